@@ -0,0 +1,833 @@
+// Long term storage for recordings. Motion, lap, telemetry and car status packets barely change
+// from one frame to the next, so an archive stores each of their fields as a column per car
+// running down the packets, each value as how it differs from the one above: integers
+// subtracted, floats XORed bit for bit. A value that hasn't changed takes a byte and the columns
+// then go through the same block compression as captures.
+//
+// Reading an archive back gives the datagrams as recorded, byte for byte, or the packets decoded
+// from them. Only 2019 format packets that the encoder writes back to the same bytes go in
+// columns. 2018 and 2020 packets have no columns, they and anything else, datagrams that aren't
+// packets included, are kept as received and shrink only as far as the chunk's compression takes
+// them. Source addresses aren't kept.
+//
+// File header, 16 bytes:
+//
+//     [u8; 8]  magic "F1UDPARC"
+//     u16      format version, 1
+//     u16      header length in bytes, 16. Readers skip anything past what they know
+//     u32      reserved, 0
+//
+// Then chunks to the end of the file. A chunk ends after CHUNK_DATAGRAMS datagrams or the datagram
+// received CHUNK_SPAN_US or more after its first, so recording live loses at most a few seconds
+// of datagrams if stopped without finishing:
+//
+//     u32      compressed length
+//     u32      decompressed length
+//     [u8]     the chunk's tables and datagrams kept as received, compressed as in compress.rs
+//
+// Each table is:
+//
+//     varint   cars in each packet, 0 for the sequence table
+//     varint   rows
+//     varint   column count
+//     [varint] byte length of each column
+//     [u8]     each column, a varint per row
+//
+// First comes the sequence table, a row per datagram with a column for its kind, 0 if kept as
+// received or 1 plus which of TABLES it's in, and one for its receive time. The motion, lap,
+// telemetry and car status tables follow with a row per packet of theirs in the sequence. Their
+// columns are the header's fields and the rest sent once per packet, in the order the field
+// lists below go through them, then the fields of each car in turn. Last come the datagrams kept
+// as received, each a varint length then its bytes.
+//
+// Varints are LEB128, 7 bits a byte starting from the lowest. Integer columns hold the
+// difference from the row above zigzag encoded, float columns the XOR of the bits with the row
+// above, the first row of a column going from 0.
+
+use std::convert::TryFrom;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+
+use crate::compress;
+use crate::encoder::Encode;
+use crate::f1_2019_net::ButtonStatus;
+use crate::f1_2019_net::CarMotion;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::CarStatusData;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::DriverStatus;
+use crate::f1_2019_net::ErsDeployMode;
+use crate::f1_2019_net::FiaFlag;
+use crate::f1_2019_net::FuelMix;
+use crate::f1_2019_net::Lap;
+use crate::f1_2019_net::LapData;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::PitStatus;
+use crate::f1_2019_net::ResultStatus;
+use crate::f1_2019_net::Telemetry;
+use crate::f1_2019_net::TyreCompound;
+use crate::f1_2019_net::VisualTyreCompound;
+use crate::file::invalid_data;
+use crate::file::read_or_end;
+use crate::parser::parse_packet;
+use crate::source::PacketSource;
+use crate::wheels::Wheels;
+
+pub const MAGIC: [u8; 8] = *b"F1UDPARC";
+pub const VERSION: u16 = 1;
+pub const FILE_HEADER_SIZE: usize = 16;
+pub const CHUNK_HEADER_SIZE: usize = 8;
+pub const CHUNK_DATAGRAMS: usize = 8192;   // Around half a minute of a race
+pub const CHUNK_SPAN_US: u64 = 5_000_000;
+
+// Packet types stored in columns, in the order their tables come in a chunk
+pub const TABLES: [PacketType; 4] = [PacketType::Motion, PacketType::Lap, PacketType::Telemetry, PacketType::CarStatus];
+
+const KIND_RAW: u8 = 0;
+const MAX_VARINT_LEN: usize = 10;
+// No chunk decompresses to more than this, every datagram in it at its longest
+const MAX_CHUNK_LEN: usize = CHUNK_DATAGRAMS * (u16::MAX as usize + MAX_VARINT_LEN);
+
+// Whether a file starting with these bytes is an archive
+pub fn is_archive(start: &[u8]) -> bool {
+    start.starts_with(&MAGIC)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(input: &[u8], at: &mut usize) -> io::Result<u64> {
+    let mut value: u64 = 0;
+    for shift in 0..MAX_VARINT_LEN {
+        let byte = *input.get(*at).ok_or_else(|| invalid_data("archive chunk ends part way through a number"))?;
+        *at += 1;
+        value |= ((byte & 0x7f) as u64) << (7 * shift);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("archive chunk has a number longer than 64 bits"))
+}
+
+// A field as it's kept in a column, its bits in a u64. Integers are sign extended so a small step
+// down is a small difference.
+trait Value: Copy {
+    const FLOAT: bool = false;
+
+    fn to_raw(self) -> u64;
+
+    fn from_raw(raw: u64) -> io::Result<Self>;
+}
+
+macro_rules! integer_values {
+    ($($int:ty),*) => {$(
+        impl Value for $int {
+            fn to_raw(self) -> u64 {
+                self as i64 as u64
+            }
+
+            fn from_raw(raw: u64) -> io::Result<Self> {
+                <$int>::try_from(raw as i64).map_err(|_| invalid_data("archive column value out of range for its field"))
+            }
+        }
+    )*};
+}
+
+integer_values!(u8, i8, u16, i16, u32);
+
+impl Value for u64 {
+    fn to_raw(self) -> u64 {
+        self
+    }
+
+    fn from_raw(raw: u64) -> io::Result<Self> {
+        Ok(raw)
+    }
+}
+
+impl Value for f32 {
+    const FLOAT: bool = true;
+
+    fn to_raw(self) -> u64 {
+        self.to_bits() as u64
+    }
+
+    fn from_raw(raw: u64) -> io::Result<Self> {
+        u32::try_from(raw).map(f32::from_bits).map_err(|_| invalid_data("archive column value out of range for its field"))
+    }
+}
+
+impl Value for ButtonStatus {
+    fn to_raw(self) -> u64 {
+        self.0.to_raw()
+    }
+
+    fn from_raw(raw: u64) -> io::Result<Self> {
+        u32::from_raw(raw).map(ButtonStatus)
+    }
+}
+
+// Coded fields, kept as the number sent
+macro_rules! coded_values {
+    ($($coded:ty as $repr:ty),*) => {$(
+        impl Value for $coded {
+            fn to_raw(self) -> u64 {
+                <$repr>::from(self).to_raw()
+            }
+
+            fn from_raw(raw: u64) -> io::Result<Self> {
+                <$repr>::from_raw(raw).map(<$coded>::from)
+            }
+        }
+    )*};
+}
+
+coded_values!(PitStatus as u8, DriverStatus as u8, ResultStatus as u8, FuelMix as u8, ErsDeployMode as u8,
+    TyreCompound as u8, VisualTyreCompound as u8, FiaFlag as i8);
+
+// Goes through the fields of a row a column at a time, filling each column from the field when
+// writing or the field from the column when reading
+trait Fields {
+    fn field<T: Value>(&mut self, value: &mut T) -> io::Result<()>;
+
+    // A column per wheel
+    fn wheels<T: Value>(&mut self, wheels: &mut Wheels<T>) -> io::Result<()> {
+        for value in wheels.iter_mut() {
+            self.field(value)?;
+        }
+        Ok(())
+    }
+}
+
+// packet_type isn't kept, it goes with the table
+fn header<F: Fields>(f: &mut F, header: &mut PacketHeader) -> io::Result<()> {
+    f.field(&mut header.packet_format)?;
+    f.field(&mut header.maj_version)?;
+    f.field(&mut header.min_version)?;
+    f.field(&mut header.packet_version)?;
+    f.field(&mut header.session_id)?;
+    f.field(&mut header.session_time)?;
+    f.field(&mut header.frame_id)?;
+    f.field(&mut header.player_car_index)?;
+    f.field(&mut header.secondary_player_car_index)
+}
+
+fn car_motion<F: Fields>(f: &mut F, car: &mut CarMotion) -> io::Result<()> {
+    f.field(&mut car.world_pos_x)?;
+    f.field(&mut car.world_pos_y)?;
+    f.field(&mut car.world_pos_z)?;
+    f.field(&mut car.world_vel_x)?;
+    f.field(&mut car.world_vel_y)?;
+    f.field(&mut car.world_vel_z)?;
+    f.field(&mut car.world_fwd_dir_x)?;
+    f.field(&mut car.world_fwd_dir_y)?;
+    f.field(&mut car.world_fwd_dir_z)?;
+    f.field(&mut car.world_right_dir_x)?;
+    f.field(&mut car.world_right_dir_y)?;
+    f.field(&mut car.world_right_dir_z)?;
+    f.field(&mut car.lateral_g)?;
+    f.field(&mut car.longitudinal_g)?;
+    f.field(&mut car.vertical_g)?;
+    f.field(&mut car.yaw)?;
+    f.field(&mut car.pitch)?;
+    f.field(&mut car.roll)
+}
+
+fn motion<F: Fields>(f: &mut F, packet: &mut MotionData) -> io::Result<()> {
+    header(f, &mut packet.header)?;
+    f.wheels(&mut packet.suspension_pos)?;
+    f.wheels(&mut packet.suspension_vel)?;
+    f.wheels(&mut packet.suspension_acc)?;
+    f.wheels(&mut packet.wheel_speed)?;
+    f.wheels(&mut packet.wheel_slip)?;
+    f.field(&mut packet.local_vel_x)?;
+    f.field(&mut packet.local_vel_y)?;
+    f.field(&mut packet.local_vel_z)?;
+    f.field(&mut packet.angular_vel_x)?;
+    f.field(&mut packet.angular_vel_y)?;
+    f.field(&mut packet.angular_vel_z)?;
+    f.field(&mut packet.angular_acc_x)?;
+    f.field(&mut packet.angular_acc_y)?;
+    f.field(&mut packet.angular_acc_z)?;
+    f.field(&mut packet.front_wheels_angle)?;
+    for car in &mut packet.car_motion_data {
+        car_motion(f, car)?;
+    }
+    Ok(())
+}
+
+fn lap_data<F: Fields>(f: &mut F, car: &mut LapData) -> io::Result<()> {
+    f.field(&mut car.last_lap)?;
+    f.field(&mut car.current_lap)?;
+    f.field(&mut car.best_lap)?;
+    f.field(&mut car.best_sec_1)?;
+    f.field(&mut car.best_sec_2)?;
+    f.field(&mut car.lap_distance)?;
+    f.field(&mut car.total_distance)?;
+    f.field(&mut car.safety_car_delta)?;
+    f.field(&mut car.position)?;
+    f.field(&mut car.lap_num)?;
+    f.field(&mut car.pit_status)?;
+    f.field(&mut car.sector)?;
+    f.field(&mut car.is_lap_valid)?;
+    f.field(&mut car.penalties)?;
+    f.field(&mut car.grid_position)?;
+    f.field(&mut car.driver_status)?;
+    f.field(&mut car.result_status)
+}
+
+fn lap<F: Fields>(f: &mut F, packet: &mut Lap) -> io::Result<()> {
+    header(f, &mut packet.header)?;
+    for car in &mut packet.lap_data {
+        lap_data(f, car)?;
+    }
+    Ok(())
+}
+
+fn car_telemetry<F: Fields>(f: &mut F, car: &mut CarTelemetry) -> io::Result<()> {
+    f.field(&mut car.car_speed)?;
+    f.field(&mut car.throttle_pos)?;
+    f.field(&mut car.steering_pos)?;
+    f.field(&mut car.brake_pos)?;
+    f.field(&mut car.clutch_pos)?;
+    f.field(&mut car.gear)?;
+    f.field(&mut car.engine_rpm)?;
+    f.field(&mut car.drs_active)?;
+    f.field(&mut car.change_light_perc)?;
+    f.wheels(&mut car.brake_temps)?;
+    f.wheels(&mut car.tyre_surface_temps)?;
+    f.wheels(&mut car.tyre_inner_temps)?;
+    f.field(&mut car.engine_temp)?;
+    f.wheels(&mut car.tyre_pressures)?;
+    f.wheels(&mut car.tyre_contact_types)
+}
+
+fn telemetry<F: Fields>(f: &mut F, packet: &mut Telemetry) -> io::Result<()> {
+    header(f, &mut packet.header)?;
+    f.field(&mut packet.button_status)?;
+    f.field(&mut packet.mfd_panel_index)?;
+    f.field(&mut packet.mfd_panel_index_secondary)?;
+    f.field(&mut packet.suggested_gear)?;
+    for car in &mut packet.car_telemetry_data {
+        car_telemetry(f, car)?;
+    }
+    Ok(())
+}
+
+fn car_status<F: Fields>(f: &mut F, car: &mut CarStatus) -> io::Result<()> {
+    f.field(&mut car.traction_control)?;
+    f.field(&mut car.anti_lock_brakes)?;
+    f.field(&mut car.fuel_mix)?;
+    f.field(&mut car.front_brake_bias)?;
+    f.field(&mut car.pit_limiter_status)?;
+    f.field(&mut car.fuel_in_tank)?;
+    f.field(&mut car.fuel_capacity)?;
+    f.field(&mut car.fuel_remaining_laps)?;
+    f.field(&mut car.max_rpm)?;
+    f.field(&mut car.idle_rpm)?;
+    f.field(&mut car.max_gears)?;
+    f.field(&mut car.drs_allowed)?;
+    f.field(&mut car.drs_activation_distance)?;
+    f.wheels(&mut car.tyres_wear)?;
+    f.field(&mut car.actual_tyre_compound)?;
+    f.field(&mut car.tyre_visual_compound)?;
+    f.field(&mut car.tyres_age_laps)?;
+    f.wheels(&mut car.tyres_damage)?;
+    f.field(&mut car.front_left_wing_damage)?;
+    f.field(&mut car.front_right_wing_damage)?;
+    f.field(&mut car.rear_wing_damage)?;
+    f.field(&mut car.drs_fault)?;
+    f.field(&mut car.engine_damage)?;
+    f.field(&mut car.gear_box_damage)?;
+    f.field(&mut car.vehicle_fia_flags)?;
+    f.field(&mut car.ers_store_energy)?;
+    f.field(&mut car.ers_deploy_mode)?;
+    f.field(&mut car.ers_harvested_this_lap_mguk)?;
+    f.field(&mut car.ers_harvested_this_lap_mguh)?;
+    f.field(&mut car.ers_deployed_this_lap)
+}
+
+fn car_status_data<F: Fields>(f: &mut F, packet: &mut CarStatusData) -> io::Result<()> {
+    header(f, &mut packet.header)?;
+    for car in &mut packet.car_status_data {
+        car_status(f, car)?;
+    }
+    Ok(())
+}
+
+// The fields of a packet of one of the TABLES
+fn packet_fields<F: Fields>(f: &mut F, packet: &mut Packet) -> io::Result<()> {
+    match packet {
+        Packet::Motion(packet)      => motion(f, packet),
+        Packet::Lap(packet)         => lap(f, packet),
+        Packet::Telemetry(packet)   => telemetry(f, packet),
+        Packet::CarStatus(packet)   => car_status_data(f, packet),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "packet type isn't kept in columns")),
+    }
+}
+
+fn cars(packet: &Packet) -> usize {
+    match packet {
+        Packet::Motion(packet)      => packet.car_motion_data.len(),
+        Packet::Lap(packet)         => packet.lap_data.len(),
+        Packet::Telemetry(packet)   => packet.car_telemetry_data.len(),
+        Packet::CarStatus(packet)   => packet.car_status_data.len(),
+        _ => 0,
+    }
+}
+
+// A packet of one of the TABLES with every field 0, to read a row into
+fn empty_packet(packet_type: PacketType, cars: usize) -> Packet {
+    let header = PacketHeader { packet_type, ..PacketHeader::default() };
+    match packet_type {
+        PacketType::Motion => Packet::Motion(MotionData {
+            header,
+            car_motion_data: vec![CarMotion::default(); cars],
+            suspension_pos: Wheels::default(),
+            suspension_vel: Wheels::default(),
+            suspension_acc: Wheels::default(),
+            wheel_speed: Wheels::default(),
+            wheel_slip: Wheels::default(),
+            local_vel_x: 0.0,
+            local_vel_y: 0.0,
+            local_vel_z: 0.0,
+            angular_vel_x: 0.0,
+            angular_vel_y: 0.0,
+            angular_vel_z: 0.0,
+            angular_acc_x: 0.0,
+            angular_acc_y: 0.0,
+            angular_acc_z: 0.0,
+            front_wheels_angle: 0.0,
+        }),
+        PacketType::Lap => Packet::Lap(Lap { header, lap_data: vec![LapData::default(); cars] }),
+        PacketType::Telemetry => Packet::Telemetry(Telemetry {
+            header,
+            car_telemetry_data: vec![CarTelemetry::default(); cars],
+            button_status: ButtonStatus::default(),
+            mfd_panel_index: 0,
+            mfd_panel_index_secondary: 0,
+            suggested_gear: 0,
+        }),
+        _ => Packet::CarStatus(CarStatusData { header, car_status_data: vec![CarStatus::default(); cars] }),
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+#[derive(Default)]
+struct Column {
+    previous:   u64,
+    bytes:      Vec<u8>,
+}
+
+// A table filling up for the chunk being written
+#[derive(Default)]
+struct TableWriter {
+    cars:       usize,
+    rows:       usize,
+    columns:    Vec<Column>,
+}
+
+impl TableWriter {
+    fn row(&mut self) -> ColumnWriter<'_> {
+        self.rows += 1;
+        ColumnWriter { columns: &mut self.columns, next: 0 }
+    }
+
+    fn write_to(&self, out: &mut Vec<u8>) {
+        write_varint(out, self.cars as u64);
+        write_varint(out, self.rows as u64);
+        write_varint(out, self.columns.len() as u64);
+        for column in &self.columns {
+            write_varint(out, column.bytes.len() as u64);
+        }
+        for column in &self.columns {
+            out.extend_from_slice(&column.bytes);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.cars = 0;
+        self.rows = 0;
+        self.columns.clear();
+    }
+}
+
+struct ColumnWriter<'a> {
+    columns:    &'a mut Vec<Column>,
+    next:       usize,
+}
+
+impl Fields for ColumnWriter<'_> {
+    fn field<T: Value>(&mut self, value: &mut T) -> io::Result<()> {
+        if self.next == self.columns.len() {
+            self.columns.push(Column::default());
+        }
+        let column = &mut self.columns[self.next];
+        let raw = value.to_raw();
+        let encoded = if T::FLOAT { raw ^ column.previous } else { zigzag(raw.wrapping_sub(column.previous) as i64) };
+        write_varint(&mut column.bytes, encoded);
+        column.previous = raw;
+        self.next += 1;
+        Ok(())
+    }
+}
+
+pub struct ArchiveWriter<W: Write> {
+    inner:      W,
+    sequence:   TableWriter,
+    tables:     [TableWriter; 4],   // One for each of TABLES
+    raw:        Vec<u8>,            // Datagrams kept as received
+    datagrams:  usize,              // In the chunk being filled
+    first_us:   u64,                // When the chunk's first datagram was received
+    columnar:   u64,                // Written to columns so far
+    encoded:    Vec<u8>,            // Reused to check packets encode back to their datagram
+    body:       Vec<u8>,            // Reused to put the chunk together
+    compressed: Vec<u8>,            // Reused to compress into
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    // Writes the file header. Call finish once done to write the last chunk.
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        header[..8].copy_from_slice(&MAGIC);
+        header[8..10].copy_from_slice(&VERSION.to_le_bytes());
+        header[10..12].copy_from_slice(&(FILE_HEADER_SIZE as u16).to_le_bytes());
+        inner.write_all(&header)?;
+        Ok(ArchiveWriter {
+            inner,
+            sequence: TableWriter::default(),
+            tables: Default::default(),
+            raw: Vec::new(),
+            datagrams: 0,
+            first_us: 0,
+            columnar: 0,
+            encoded: Vec::new(),
+            body: Vec::new(),
+            compressed: Vec::new(),
+        })
+    }
+
+    pub fn write(&mut self, received_us: u64, datagram: &[u8]) -> io::Result<()> {
+        let len = u16::try_from(datagram.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "datagram longer than 65535 bytes"))?;
+        if self.datagrams == 0 {
+            self.first_us = received_us;
+        }
+        let columnar = self.columnar(datagram);
+        let mut kind = columnar.as_ref().map_or(KIND_RAW, |(table, _)| *table as u8 + 1);
+        let mut received_us = received_us;
+        let mut row = self.sequence.row();
+        row.field(&mut kind)?;
+        row.field(&mut received_us)?;
+        match columnar {
+            Some((table, mut packet)) => {
+                let table = &mut self.tables[table];
+                table.cars = cars(&packet);
+                packet_fields(&mut table.row(), &mut packet)?;
+                self.columnar += 1;
+            },
+            None => {
+                write_varint(&mut self.raw, len as u64);
+                self.raw.extend_from_slice(datagram);
+            },
+        }
+        self.datagrams += 1;
+        if self.datagrams == CHUNK_DATAGRAMS || received_us.saturating_sub(self.first_us) >= CHUNK_SPAN_US {
+            self.end_chunk()?;
+        }
+        Ok(())
+    }
+
+    // Which of TABLES the datagram goes in and its packet, None if it has to be kept as received.
+    // Only packets that come back out of the columns as the same datagram go in them.
+    fn columnar(&mut self, datagram: &[u8]) -> Option<(usize, Packet)> {
+        let packet = parse_packet(datagram).ok()?;
+        let table = TABLES.iter().position(|&packet_type| packet_type == packet.packet_type())?;
+        if packet.encoded_len() != datagram.len() {
+            return None;
+        }
+        self.encoded.resize(datagram.len(), 0);
+        match packet.write_into(&mut self.encoded) {
+            Ok(written) if self.encoded[..written] == *datagram => Some((table, packet)),
+            _ => None,
+        }
+    }
+
+    // Datagrams written so far that went into columns
+    pub fn columnar_count(&self) -> u64 {
+        self.columnar
+    }
+
+    // Writes out the chunk being filled
+    pub fn end_chunk(&mut self) -> io::Result<()> {
+        if self.datagrams == 0 {
+            return Ok(());
+        }
+        self.body.clear();
+        self.sequence.write_to(&mut self.body);
+        for table in &self.tables {
+            table.write_to(&mut self.body);
+        }
+        self.body.extend_from_slice(&self.raw);
+        self.compressed.clear();
+        compress::compress(&self.body, &mut self.compressed);
+        let mut header = [0u8; CHUNK_HEADER_SIZE];
+        header[..4].copy_from_slice(&(self.compressed.len() as u32).to_le_bytes());
+        header[4..].copy_from_slice(&(self.body.len() as u32).to_le_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(&self.compressed)?;
+
+        self.sequence.clear();
+        for table in &mut self.tables {
+            table.clear();
+        }
+        self.raw.clear();
+        self.datagrams = 0;
+        Ok(())
+    }
+
+    // Flushes what's been written, which doesn't include the chunk being filled
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    // Writes out the last chunk and flushes
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.end_chunk()?;
+        self.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    // The unfinished chunk is lost, see finish
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+// Where reading has got to down a column of the chunk
+struct ColumnCursor {
+    at:         usize,
+    end:        usize,
+    previous:   u64,
+}
+
+#[derive(Default)]
+struct TableReader {
+    cars:       usize,
+    rows:       usize,  // Left to read
+    columns:    Vec<ColumnCursor>,
+}
+
+impl TableReader {
+    // Reads the table at the start of body[*at..], leaving at after it
+    fn read(body: &[u8], at: &mut usize) -> io::Result<TableReader> {
+        let cars = read_varint(body, at)? as usize;
+        let rows = read_varint(body, at)? as usize;
+        let count = read_varint(body, at)? as usize;
+        if cars > u8::MAX as usize || rows > CHUNK_DATAGRAMS || count > body.len() {
+            return Err(invalid_data("archive table is bigger than a chunk can hold"));
+        }
+        let mut columns = Vec::with_capacity(count);
+        let mut start: usize = 0;
+        for _ in 0..count {
+            let len = read_varint(body, at)? as usize;
+            columns.push(ColumnCursor { at: start, end: start.saturating_add(len), previous: 0 });
+            start = start.saturating_add(len);
+        }
+        let end = at.checked_add(start).filter(|&end| end <= body.len()).ok_or_else(|| invalid_data("archive chunk ends part way through a table"))?;
+        for column in &mut columns {
+            column.at += *at;
+            column.end += *at;
+        }
+        *at = end;
+        Ok(TableReader { cars, rows, columns })
+    }
+
+    // Reads the next row into the fields of the row given
+    fn row<F>(&mut self, body: &[u8], fields: F) -> io::Result<()>
+        where F: FnOnce(&mut ColumnReader) -> io::Result<()> {
+        if self.rows == 0 {
+            return Err(invalid_data("archive sequence has more rows than its table"));
+        }
+        self.rows -= 1;
+        let mut reader = ColumnReader { body, columns: &mut self.columns, next: 0 };
+        fields(&mut reader)?;
+        if reader.next != reader.columns.len() {
+            return Err(invalid_data("archive table has more columns than its packets have fields"));
+        }
+        Ok(())
+    }
+}
+
+struct ColumnReader<'a> {
+    body:       &'a [u8],
+    columns:    &'a mut [ColumnCursor],
+    next:       usize,
+}
+
+impl Fields for ColumnReader<'_> {
+    fn field<T: Value>(&mut self, value: &mut T) -> io::Result<()> {
+        let column = self.columns.get_mut(self.next).ok_or_else(|| invalid_data("archive table has fewer columns than its packets have fields"))?;
+        self.next += 1;
+        let encoded = read_varint(&self.body[..column.end], &mut column.at)?;
+        let raw = if T::FLOAT { encoded ^ column.previous } else { column.previous.wrapping_add(unzigzag(encoded) as u64) };
+        column.previous = raw;
+        *value = T::from_raw(raw)?;
+        Ok(())
+    }
+}
+
+// The next datagram of a chunk
+enum Entry {
+    Raw(usize, usize),      // Kept as received, where it is in the chunk
+    Packet(Box<Packet>),    // Read back from the columns
+}
+
+pub struct ArchiveReader<R: Read> {
+    inner:      R,
+    version:    u16,
+    compressed: Vec<u8>,            // Reused to read compressed chunks into
+    body:       Vec<u8>,            // The chunk being read
+    sequence:   TableReader,
+    tables:     Vec<TableReader>,   // One for each of TABLES
+    raw_at:     usize,              // Where the next datagram kept as received is in body
+    buf:        Vec<u8>,            // The last packet read from columns encoded again
+}
+
+impl<R: Read> ArchiveReader<R> {
+    // Reads and checks the file header
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        inner.read_exact(&mut header)?;
+        if header[..8] != MAGIC {
+            return Err(invalid_data("not an archive, bad magic"));
+        }
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        if version != VERSION {
+            return Err(invalid_data(&format!("unsupported archive version {}", version)));
+        }
+        let header_len = u16::from_le_bytes([header[10], header[11]]) as usize;
+        if header_len < FILE_HEADER_SIZE {
+            return Err(invalid_data("archive header length too small"));
+        }
+        io::copy(&mut (&mut inner).take((header_len - FILE_HEADER_SIZE) as u64), &mut io::sink())?;
+        Ok(ArchiveReader {
+            inner,
+            version,
+            compressed: Vec::new(),
+            body: Vec::new(),
+            sequence: TableReader::default(),
+            tables: Vec::new(),
+            raw_at: 0,
+            buf: Vec::new(),
+        })
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    // Next packet and when it was received, None at the end of the archive. Datagrams kept as
+    // received that aren't packets are skipped.
+    pub fn next_packet(&mut self) -> io::Result<Option<(u64, Packet)>> {
+        loop {
+            match self.next_entry()? {
+                Some((received_us, Entry::Packet(packet))) => return Ok(Some((received_us, *packet))),
+                Some((received_us, Entry::Raw(start, end))) => {
+                    if let Ok(packet) = parse_packet(&self.body[start..end]) {
+                        return Ok(Some((received_us, packet)));
+                    }
+                },
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn next_entry(&mut self) -> io::Result<Option<(u64, Entry)>> {
+        while self.sequence.rows == 0 {
+            if !self.read_chunk()? {
+                return Ok(None);
+            }
+        }
+        let mut kind = KIND_RAW;
+        let mut received_us: u64 = 0;
+        self.sequence.row(&self.body, |row| {
+            row.field(&mut kind)?;
+            row.field(&mut received_us)
+        })?;
+        if kind == KIND_RAW {
+            let len = read_varint(&self.body, &mut self.raw_at)? as usize;
+            let start = self.raw_at;
+            let end = start.checked_add(len).filter(|&end| end <= self.body.len()).ok_or_else(|| invalid_data("archive chunk ends part way through a datagram"))?;
+            self.raw_at = end;
+            return Ok(Some((received_us, Entry::Raw(start, end))));
+        }
+        let index = kind as usize - 1;
+        let table = self.tables.get_mut(index).ok_or_else(|| invalid_data(&format!("archive datagram of unknown kind {}", kind)))?;
+        let mut packet = empty_packet(TABLES[index], table.cars);
+        table.row(&self.body, |row| packet_fields(row, &mut packet))?;
+        Ok(Some((received_us, Entry::Packet(Box::new(packet)))))
+    }
+
+    // Reads and decompresses the next chunk, false at the end of the file
+    fn read_chunk(&mut self) -> io::Result<bool> {
+        let mut header = [0u8; CHUNK_HEADER_SIZE];
+        if !read_or_end(&mut self.inner, &mut header, "archive ends part way through a chunk")? {
+            return Ok(false);
+        }
+        let compressed_len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if len > MAX_CHUNK_LEN {
+            return Err(invalid_data("archive chunk longer than a chunk can be"));
+        }
+        if compressed_len > compress::max_compressed_len(len) {
+            return Err(invalid_data("archive chunk compressed longer than its length can compress to"));
+        }
+        self.compressed.resize(compressed_len, 0);
+        self.inner.read_exact(&mut self.compressed).map_err(|_| io::Error::new(io::ErrorKind::UnexpectedEof, "archive ends part way through a chunk"))?;
+        self.body.clear();
+        compress::decompress(&self.compressed, &mut self.body, len)?;
+
+        let mut at = 0;
+        self.sequence = TableReader::read(&self.body, &mut at)?;
+        self.tables.clear();
+        for _ in &TABLES {
+            self.tables.push(TableReader::read(&self.body, &mut at)?);
+        }
+        self.raw_at = at;
+        Ok(true)
+    }
+}
+
+// The datagrams as recorded, packets from the columns encoded again
+impl<R: Read> PacketSource for ArchiveReader<R> {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        match self.next_entry()? {
+            Some((received_us, Entry::Raw(start, end))) => Ok(Some((received_us, &self.body[start..end]))),
+            Some((received_us, Entry::Packet(packet))) => {
+                self.buf.resize(packet.encoded_len(), 0);
+                let written = packet.write_into(&mut self.buf).map_err(|e| invalid_data(&format!("archived packet doesn't encode: {}", e)))?;
+                Ok(Some((received_us, &self.buf[..written])))
+            },
+            None => Ok(None),
+        }
+    }
+}
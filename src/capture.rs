@@ -0,0 +1,427 @@
+// Capture files, datagrams archived exactly as received so a session can be decoded again after
+// a parser fix. Written by the logger's record command and read back as a PacketSource. All
+// integers are little endian.
+//
+// File header, 16 bytes:
+//
+//     [u8; 8]  magic "F1UDPCAP"
+//     u16      format version, 1, or 2 for block compressed
+//     u16      header length in bytes, 16. Readers skip anything past what they know
+//     u32      reserved, 0
+//
+// Then in version 1 one record per datagram to the end of the file:
+//
+//     u64      wall clock receive time, microseconds since the unix epoch
+//     u8       source address family, 0 unknown, 4 IPv4, 6 IPv6
+//     [u8; 16] source address, IPv4 in the first 4 bytes, zero when unknown
+//     u16      source port, 0 when unknown
+//     u16      datagram length
+//     [u8]     datagram
+//
+// Version 2 has blocks to the end of the file instead, each holding whole records as above
+// compressed into a raw LZ4 block, see compress.rs:
+//
+//     u32      compressed length
+//     u32      decompressed length
+//     [u8]     compressed records
+//
+// A block ends after the record taking it to 64 KiB or the record received a second or more after
+// its first, so a record never starts 64 KiB or more into a block and recording live loses at
+// most a second or so of datagrams if stopped. Records are found by position, the byte offset of
+// the record in version 1, and in version 2 the byte offset of its block shifted up 16 bits plus
+// where the record starts in the decompressed block. The index records these.
+
+use std::convert::TryFrom;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use crate::compress;
+use crate::error::ParseError;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::PacketHeader;
+use crate::file::invalid_data;
+use crate::file::read_or_end;
+use crate::parser::parse_header;
+use crate::parser::parse_packet;
+use crate::source::PacketSource;
+
+pub const MAGIC: [u8; 8] = *b"F1UDPCAP";
+pub const VERSION: u16 = 1;
+pub const COMPRESSED_VERSION: u16 = 2;
+pub const FILE_HEADER_SIZE: usize = 16;
+pub const RECORD_HEADER_SIZE: usize = 29;
+pub const BLOCK_HEADER_SIZE: usize = 8;
+pub const BLOCK_SIZE: usize = 65536;
+pub const BLOCK_SPAN_US: u64 = 1_000_000;
+
+const FAMILY_UNKNOWN: u8 = 0;
+const FAMILY_IPV4: u8 = 4;
+const FAMILY_IPV6: u8 = 6;
+
+pub fn micros_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_micros() as u64)
+}
+
+// A datagram read back from a capture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureRecord<'a> {
+    pub received_us:    u64,
+    pub source:         Option<SocketAddr>,
+    pub datagram:       &'a [u8],
+}
+
+impl<'a> CaptureRecord<'a> {
+    pub fn header(&self) -> Result<PacketHeader, ParseError> {
+        parse_header(self.datagram)
+    }
+
+    pub fn packet(&self) -> Result<Packet, ParseError> {
+        parse_packet(self.datagram)
+    }
+}
+
+fn record_header(received_us: u64, source: Option<SocketAddr>, len: u16) -> [u8; RECORD_HEADER_SIZE] {
+    let mut header = [0u8; RECORD_HEADER_SIZE];
+    header[..8].copy_from_slice(&received_us.to_le_bytes());
+    match source {
+        Some(SocketAddr::V4(address)) => {
+            header[8] = FAMILY_IPV4;
+            header[9..13].copy_from_slice(&address.ip().octets());
+        },
+        Some(SocketAddr::V6(address)) => {
+            header[8] = FAMILY_IPV6;
+            header[9..25].copy_from_slice(&address.ip().octets());
+        },
+        None => header[8] = FAMILY_UNKNOWN,
+    }
+    header[25..27].copy_from_slice(&source.map_or(0, |source| source.port()).to_le_bytes());
+    header[27..29].copy_from_slice(&len.to_le_bytes());
+    header
+}
+
+// Receive time, source and datagram length from a record header
+fn parse_record_header(header: &[u8]) -> io::Result<(u64, Option<SocketAddr>, usize)> {
+    let mut received_us = [0u8; 8];
+    received_us.copy_from_slice(&header[..8]);
+    let port = u16::from_le_bytes([header[25], header[26]]);
+    let source = match header[8] {
+        FAMILY_UNKNOWN => None,
+        FAMILY_IPV4 => Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(header[9], header[10], header[11], header[12])), port)),
+        FAMILY_IPV6 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&header[9..25]);
+            Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::from(octets)), port))
+        },
+        _ => return Err(invalid_data("capture record has an unknown address family")),
+    };
+    Ok((u64::from_le_bytes(received_us), source, u16::from_le_bytes([header[27], header[28]]) as usize))
+}
+
+// The block a compressed capture is filling
+struct Block {
+    records:        Vec<u8>,    // Uncompressed
+    first_us:       u64,        // When its first record was received
+    compressed:     Vec<u8>,    // Reused to compress into
+}
+
+impl Block {
+    fn new() -> Self {
+        Block { records: Vec::with_capacity(2 * BLOCK_SIZE), first_us: 0, compressed: Vec::new() }
+    }
+}
+
+pub struct CaptureWriter<W: Write> {
+    inner:      W,
+    offset:     u64,            // Bytes written so far
+    block:      Option<Block>,  // For compressed captures
+}
+
+impl<W: Write> CaptureWriter<W> {
+    // Writes the file header
+    pub fn new(inner: W) -> io::Result<Self> {
+        CaptureWriter::with_version(inner, VERSION, None)
+    }
+
+    // Writes the file header of a block compressed capture. Call finish once done to write the
+    // last block.
+    pub fn compressed(inner: W) -> io::Result<Self> {
+        CaptureWriter::with_version(inner, COMPRESSED_VERSION, Some(Block::new()))
+    }
+
+    // Carries on a capture finished at len bytes long, inner being at its end. compressed says
+    // which of new or compressed started it. Nothing is written before the next record.
+    pub fn append(inner: W, len: u64, compressed: bool) -> Self {
+        let block = if compressed { Some(Block::new()) } else { None };
+        CaptureWriter { inner, offset: len, block }
+    }
+
+    fn with_version(mut inner: W, version: u16, block: Option<Block>) -> io::Result<Self> {
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        header[..8].copy_from_slice(&MAGIC);
+        header[8..10].copy_from_slice(&version.to_le_bytes());
+        header[10..12].copy_from_slice(&(FILE_HEADER_SIZE as u16).to_le_bytes());
+        inner.write_all(&header)?;
+        Ok(CaptureWriter { inner, offset: FILE_HEADER_SIZE as u64, block })
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.block.is_some()
+    }
+
+    pub fn write(&mut self, received_us: u64, source: Option<SocketAddr>, datagram: &[u8]) -> io::Result<()> {
+        let len = u16::try_from(datagram.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "datagram longer than 65535 bytes"))?;
+        let header = record_header(received_us, source, len);
+        let block = match &mut self.block {
+            Some(block) => block,
+            None => {
+                self.inner.write_all(&header)?;
+                self.inner.write_all(datagram)?;
+                self.offset += (RECORD_HEADER_SIZE + datagram.len()) as u64;
+                return Ok(());
+            },
+        };
+        if block.records.is_empty() {
+            block.first_us = received_us;
+        }
+        block.records.extend_from_slice(&header);
+        block.records.extend_from_slice(datagram);
+        if block.records.len() >= BLOCK_SIZE || received_us.saturating_sub(block.first_us) >= BLOCK_SPAN_US {
+            self.end_block()?;
+        }
+        Ok(())
+    }
+
+    // Where the next record will be in the file, see the positions above
+    pub fn position(&self) -> u64 {
+        match &self.block {
+            Some(block) => self.offset << 16 | block.records.len() as u64,
+            None => self.offset,
+        }
+    }
+
+    // Records before this position have been passed to the inner writer. In a compressed capture
+    // that's those before the block being filled.
+    pub fn written_position(&self) -> u64 {
+        match &self.block {
+            Some(_) => self.offset << 16,
+            None => self.offset,
+        }
+    }
+
+    // Writes out the block being filled in a compressed capture, nothing otherwise
+    pub fn end_block(&mut self) -> io::Result<()> {
+        let block = match &mut self.block {
+            Some(block) if !block.records.is_empty() => block,
+            _ => return Ok(()),
+        };
+        block.compressed.clear();
+        compress::compress(&block.records, &mut block.compressed);
+        let mut header = [0u8; BLOCK_HEADER_SIZE];
+        header[..4].copy_from_slice(&(block.compressed.len() as u32).to_le_bytes());
+        header[4..].copy_from_slice(&(block.records.len() as u32).to_le_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(&block.compressed)?;
+        self.offset += (BLOCK_HEADER_SIZE + block.compressed.len()) as u64;
+        block.records.clear();
+        Ok(())
+    }
+
+    // Flushes what's been written, which doesn't include the block a compressed capture is filling
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    // Writes out the last block of a compressed capture and flushes
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.end_block()?;
+        self.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    // A compressed capture's unfinished block is lost, see finish
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+pub struct CaptureReader<R: Read> {
+    inner:          R,
+    version:        u16,
+    buf:            Vec<u8>,            // The last datagram read, or for compressed captures the block
+    source:         Option<SocketAddr>, // Of the last record read
+    position:       u64,                // Where the next record starts in the file, uncompressed only
+    data_start:     u64,                // Position of the first record
+    compressed:     Vec<u8>,            // Reused to read compressed blocks into
+    block_offset:   u64,                // Where the block in buf starts in the file
+    block_read:     usize,              // How far records have been read from the block in buf
+    next_block:     u64,                // Where the block after the one in buf starts in the file
+}
+
+impl<R: Read> CaptureReader<R> {
+    // Reads and checks the file header
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        inner.read_exact(&mut header).map_err(|_| invalid_data("not a capture file, too short for the header"))?;
+        if header[..8] != MAGIC {
+            return Err(invalid_data("not a capture file, bad magic"));
+        }
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        if version != VERSION && version != COMPRESSED_VERSION {
+            return Err(invalid_data(&format!("unsupported capture version {}", version)));
+        }
+        let header_len = u16::from_le_bytes([header[10], header[11]]) as usize;
+        if header_len < FILE_HEADER_SIZE {
+            return Err(invalid_data("capture header length too small"));
+        }
+        io::copy(&mut (&mut inner).take((header_len - FILE_HEADER_SIZE) as u64), &mut io::sink())?;
+        let data_start = if version == COMPRESSED_VERSION { (header_len as u64) << 16 } else { header_len as u64 };
+        Ok(CaptureReader {
+            inner,
+            version,
+            buf: Vec::new(),
+            source: None,
+            position: header_len as u64,
+            data_start,
+            compressed: Vec::new(),
+            block_offset: header_len as u64,
+            block_read: 0,
+            next_block: header_len as u64,
+        })
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.version == COMPRESSED_VERSION
+    }
+
+    // Where the next record starts, see the positions above
+    pub fn position(&self) -> u64 {
+        if !self.is_compressed() {
+            self.position
+        } else if self.block_read < self.buf.len() {
+            self.block_offset << 16 | self.block_read as u64
+        } else {
+            self.next_block << 16
+        }
+    }
+
+    // None at the end of the capture. A record cut short by the end of the file is an error.
+    pub fn next_record(&mut self) -> io::Result<Option<CaptureRecord<'_>>> {
+        if self.is_compressed() {
+            return self.next_compressed_record();
+        }
+        let mut header = [0u8; RECORD_HEADER_SIZE];
+        if !read_or_end(&mut self.inner, &mut header, "capture ends part way through a record")? {
+            return Ok(None);
+        }
+        let (received_us, source, len) = parse_record_header(&header)?;
+        self.source = source;
+        self.buf.resize(len, 0);
+        self.inner.read_exact(&mut self.buf).map_err(|_| io::Error::new(io::ErrorKind::UnexpectedEof, "capture ends part way through a record"))?;
+        self.position += (RECORD_HEADER_SIZE + len) as u64;
+        Ok(Some(CaptureRecord { received_us, source, datagram: &self.buf }))
+    }
+
+    fn next_compressed_record(&mut self) -> io::Result<Option<CaptureRecord<'_>>> {
+        while self.block_read == self.buf.len() {
+            if !self.read_block()? {
+                return Ok(None);
+            }
+        }
+        let start = self.block_read + RECORD_HEADER_SIZE;
+        let header = self.buf.get(self.block_read..start).ok_or_else(|| invalid_data("capture block ends part way through a record"))?;
+        let (received_us, source, len) = parse_record_header(header)?;
+        if start + len > self.buf.len() {
+            return Err(invalid_data("capture block ends part way through a record"));
+        }
+        self.source = source;
+        self.block_read = start + len;
+        Ok(Some(CaptureRecord { received_us, source, datagram: &self.buf[start..start + len] }))
+    }
+
+    // Reads and decompresses the next block into buf, false at the end of the file
+    fn read_block(&mut self) -> io::Result<bool> {
+        let mut header = [0u8; BLOCK_HEADER_SIZE];
+        if !read_or_end(&mut self.inner, &mut header, "capture ends part way through a block")? {
+            return Ok(false);
+        }
+        let compressed_len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if len >= BLOCK_SIZE + RECORD_HEADER_SIZE + u16::MAX as usize {
+            return Err(invalid_data("capture block longer than a block can be"));
+        }
+        if compressed_len > compress::max_compressed_len(len) {
+            return Err(invalid_data("capture block compressed longer than its length can compress to"));
+        }
+        self.compressed.resize(compressed_len, 0);
+        self.inner.read_exact(&mut self.compressed).map_err(|_| io::Error::new(io::ErrorKind::UnexpectedEof, "capture ends part way through a block"))?;
+        self.buf.clear();
+        compress::decompress(&self.compressed, &mut self.buf, len)?;
+        self.block_offset = self.next_block;
+        self.block_read = 0;
+        self.next_block += (BLOCK_HEADER_SIZE + compressed_len) as u64;
+        Ok(true)
+    }
+}
+
+impl<R: Read + Seek> CaptureReader<R> {
+    // Moves to a record starting at position, as given by position() before reading it
+    pub fn seek_to(&mut self, position: u64) -> io::Result<()> {
+        if position < self.data_start {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "position is inside the capture header"));
+        }
+        if !self.is_compressed() {
+            self.inner.seek(SeekFrom::Start(position))?;
+            self.position = position;
+            return Ok(());
+        }
+        let block_offset = position >> 16;
+        let at = (position & 0xffff) as usize;
+        // Already holding the block when seeking within it
+        if self.buf.is_empty() || block_offset != self.block_offset {
+            self.inner.seek(SeekFrom::Start(block_offset))?;
+            self.buf.clear();
+            self.block_read = 0;
+            self.block_offset = block_offset;
+            self.next_block = block_offset;
+            if at > 0 && !self.read_block()? {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "position is past the end of the capture"));
+            }
+        }
+        if at > self.buf.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "position is past the end of its block"));
+        }
+        self.block_read = at;
+        Ok(())
+    }
+
+    // Back to the first record
+    pub fn rewind(&mut self) -> io::Result<()> {
+        self.seek_to(self.data_start)
+    }
+}
+
+impl<R: Read> PacketSource for CaptureReader<R> {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        Ok(self.next_record()?.map(|record| (record.received_us, record.datagram)))
+    }
+
+    fn source_address(&self) -> Option<SocketAddr> {
+        self.source
+    }
+}
@@ -0,0 +1,455 @@
+// Command line for the logger binary. Hand rolled rather than pulling in a parser crate, the
+// binary only has a handful of subcommands sharing one set of options.
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::edit::SplitBy;
+use crate::f1_2019_net::PacketType;
+use crate::replay::SeekTarget;
+use crate::retention::Retention;
+use crate::retention::RetentionPolicy;
+
+pub const DEFAULT_BIND_ADDRESS: &str = "0.0.0.0";
+pub const DEFAULT_PORT: u16 = 20777;		//20777 default on ps4
+pub const DEFAULT_REPLAY_HOST: &str = "127.0.0.1";
+
+pub const USAGE: &str = "\
+usage: f12019_data_logger <command> [options] [recording...]
+
+commands:
+    listen      Decode packets and print them, keeping recent ones in memory
+    record      Save datagrams to a recording, needs --output
+    replay      Send a recording to --target with its original timing. While it runs type
+                pause, resume, seek <seconds>, lap <n>, session <id> or speed <multiplier>
+                and enter
+    export      Decode packets and write them out in --format
+    inspect     Print every datagram with its size and receive time
+    stats       Count packets by type
+    index       Index a capture for seeking, to <capture>.idx unless given --output
+    trim        Copy part of a recording to --output, as picked with --from, --to, --packets
+                and --drop
+    split       Write a capture per session or per lap, see --by. Named after --output, or
+                the recording without its extension, with the session and lap added
+    merge       Merge recordings of the same session made on different machines to --output,
+                keeping one of each packet by session_id, frame_id and packet type
+
+Every command but replay, index, trim, split and merge reads live from the game unless given a
+recording. Recordings can be the logger's own captures and archives or pcap and pcapng files,
+from which UDP datagrams sent to --port are read. record writes an index beside each capture,
+and commands seeking in a capture use the index beside it if there is one.
+
+options:
+    -b, --bind <address>    Address to listen on, default 0.0.0.0
+    -p, --port <port>       Port to listen on, to send to when replaying and to read from pcap
+                            files, default 20777
+    -o, --output <path>     File to write, or directory for csv, stdout if not given
+    -i, --input <path>      Recording to read, can also be given after the options
+    -f, --format <format>   text, one line per packet or car, or debug, every field. Default text.
+                            record, export, trim and merge also take pcap, the datagrams as
+                            received for Wireshark, or archive, packed down for keeping.
+                            export also takes csv, a file per packet type in the --output
+                            directory with a row per car
+    -t, --target <address>  Where replay sends to, and pcap output shows datagrams sent to.
+                            Default 127.0.0.1:<port>
+        --packets <types>   Only these packet types, comma separated. motion, session, lap, event,
+                            participants, setups, telemetry, status, classification, lobby
+        --player-only       Only the player's car from packets with one entry per car
+        --speed <x>         Replay this many times faster than recorded, default 1
+        --loop              Replay from the start again after the end
+        --from <seconds>    Start from this session time in a capture
+        --from-lap <n>      Start from the player starting this lap
+        --from-session <id> Start from this session, its session_id in hex as inspect shows
+        --to <seconds>      Stop at this session time
+        --to-lap <n>        Stop once the player finishes this lap
+        --drop <types>      Leave out these packet types, comma separated like --packets
+        --by <piece>        What split makes a capture of, session or lap. Default session
+        --compress          Block compress the captures written, well under half the size.
+                            Every command reads them the same as uncompressed ones
+        --retain <limits>   How much listen keeps in memory by type, comma separated type=limit
+                            where limit is a count, seconds of session time like 120s, session
+                            or all. Default motion, telemetry and status=120s, session=1 and
+                            the rest for the session
+    -h, --help              Show this message
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Listen,
+    Record,
+    Replay,
+    Export,
+    Inspect,
+    Stats,
+    Index,
+    Trim,
+    Split,
+    Merge,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Command> {
+        match name {
+            "listen"    => Some(Command::Listen),
+            "record"    => Some(Command::Record),
+            "replay"    => Some(Command::Replay),
+            "export"    => Some(Command::Export),
+            "inspect"   => Some(Command::Inspect),
+            "stats"     => Some(Command::Stats),
+            "index"     => Some(Command::Index),
+            "trim"      => Some(Command::Trim),
+            "split"     => Some(Command::Split),
+            "merge"     => Some(Command::Merge),
+            _           => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Listen     => "listen",
+            Command::Record     => "record",
+            Command::Replay     => "replay",
+            Command::Export     => "export",
+            Command::Inspect    => "inspect",
+            Command::Stats      => "stats",
+            Command::Index      => "index",
+            Command::Trim       => "trim",
+            Command::Split      => "split",
+            Command::Merge      => "merge",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,       // One line per packet, or per car for packets with one entry per car
+    Debug,      // Every field of the decoded packet
+    Pcap,       // Datagrams undecoded in a pcap file, for record and export
+    Archive,    // Datagrams packed down for keeping, see archive.rs
+    Csv,        // A CSV per packet type in the --output directory, for export
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "text"      => Some(OutputFormat::Text),
+            "debug"     => Some(OutputFormat::Debug),
+            "pcap"      => Some(OutputFormat::Pcap),
+            "archive"   => Some(OutputFormat::Archive),
+            "csv"       => Some(OutputFormat::Csv),
+            _           => None,
+        }
+    }
+
+    // Whether the format writes the datagrams as received rather than decoded packets
+    pub fn is_datagrams(&self) -> bool {
+        *self == OutputFormat::Pcap || *self == OutputFormat::Archive
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Text      => "text",
+            OutputFormat::Debug     => "debug",
+            OutputFormat::Pcap      => "pcap",
+            OutputFormat::Archive   => "archive",
+            OutputFormat::Csv       => "csv",
+        }
+    }
+}
+
+// Names accepted by --packets
+const PACKET_NAMES: [(&str, PacketType); 10] = [
+    ("motion",          PacketType::Motion),
+    ("session",         PacketType::Session),
+    ("lap",             PacketType::Lap),
+    ("event",           PacketType::Event),
+    ("participants",    PacketType::Participant),
+    ("setups",          PacketType::Setup),
+    ("telemetry",       PacketType::Telemetry),
+    ("status",          PacketType::CarStatus),
+    ("classification",  PacketType::FinalClassification),
+    ("lobby",           PacketType::LobbyInfo),
+];
+
+pub fn packet_type_key(packet_type: PacketType) -> &'static str {
+    PACKET_NAMES.iter()
+        .find(|(_, known)| *known == packet_type)
+        .map(|(name, _)| *name)
+        .unwrap_or("invalid")
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub command:        Command,
+    pub bind:           String,
+    pub port:           u16,
+    pub output:         Option<PathBuf>,
+    pub input:          Option<PathBuf>,
+    pub inputs:         Vec<PathBuf>,               // merge's recordings after the first
+    pub format:         OutputFormat,
+    pub target:         Option<SocketAddr>,
+    pub packets:        Option<Vec<PacketType>>,   // None for every type
+    pub player_only:    bool,
+    pub retention:      RetentionPolicy,
+    pub speed:          f64,
+    pub looped:         bool,
+    pub start:          Option<SeekTarget>,
+    pub end:            Option<SeekTarget>,         // Stop before the first packet reaching this
+    pub split_by:       SplitBy,
+    pub compress:       bool,
+}
+
+impl Options {
+    // Address the listening commands bind to
+    pub fn bind_address(&self) -> String {
+        format!("{}:{}", self.bind, self.port)
+    }
+
+    pub fn replay_target(&self) -> Result<SocketAddr, CliError> {
+        match self.target {
+            Some(target) => Ok(target),
+            None => parse_address("--target", &format!("{}:{}", DEFAULT_REPLAY_HOST, self.port)),
+        }
+    }
+
+    pub fn wants(&self, packet_type: PacketType) -> bool {
+        match &self.packets {
+            Some(packets) => packets.contains(&packet_type),
+            None => true,
+        }
+    }
+}
+
+// Why the command line couldn't be used. Help is asked for explicitly and isn't a failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    Help,
+    MissingCommand,
+    UnknownCommand(String),
+    UnknownOption(String),
+    MissingValue(&'static str),
+    InvalidValue { option: &'static str, value: String },
+    MissingInput(Command),
+    TooFewInputs(Command),
+    MissingOutput(Command),
+    UnexpectedArgument(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Help => write!(f, "help requested"),
+            CliError::MissingCommand => write!(f, "no command given"),
+            CliError::UnknownCommand(command) => write!(f, "unknown command {}", command),
+            CliError::UnknownOption(option) => write!(f, "unknown option {}", option),
+            CliError::MissingValue(option) => write!(f, "{} needs a value", option),
+            CliError::InvalidValue { option, value } => write!(f, "invalid {} value {}", option, value),
+            CliError::MissingInput(command) => write!(f, "{} needs a recording to read, give one with --input", command.name()),
+            CliError::TooFewInputs(command) => write!(f, "{} needs two or more recordings", command.name()),
+            CliError::MissingOutput(command) => write!(f, "{} needs a file to write, give one with --output", command.name()),
+            CliError::UnexpectedArgument(arg) => write!(f, "unexpected argument {}", arg),
+        }
+    }
+}
+
+// Parses the arguments after the program name
+pub fn parse_args<I>(args: I) -> Result<Options, CliError>
+    where I: IntoIterator<Item = String> {
+    let mut args = args.into_iter();
+    let command = match args.next() {
+        Some(arg) if arg == "-h" || arg == "--help" || arg == "help" => return Err(CliError::Help),
+        Some(arg) => Command::from_name(&arg).ok_or(CliError::UnknownCommand(arg))?,
+        None => return Err(CliError::MissingCommand),
+    };
+    let mut options = Options {
+        command,
+        bind:           DEFAULT_BIND_ADDRESS.to_string(),
+        port:           DEFAULT_PORT,
+        output:         None,
+        input:          None,
+        inputs:         Vec::new(),
+        format:         OutputFormat::Text,
+        target:         None,
+        packets:        None,
+        player_only:    false,
+        retention:      RetentionPolicy::default(),
+        speed:          1.0,
+        looped:         false,
+        start:          None,
+        end:            None,
+        split_by:       SplitBy::Session,
+        compress:       false,
+    };
+    let mut dropped: Vec<PacketType> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help"     => return Err(CliError::Help),
+            "-b" | "--bind"     => options.bind = value(&mut args, "--bind")?,
+            "-p" | "--port"     => options.port = parse_port(&value(&mut args, "--port")?)?,
+            "-o" | "--output"   => options.output = Some(PathBuf::from(value(&mut args, "--output")?)),
+            "-i" | "--input"    => options.input = Some(PathBuf::from(value(&mut args, "--input")?)),
+            "-f" | "--format"   => {
+                let format = value(&mut args, "--format")?;
+                options.format = OutputFormat::from_name(&format)
+                    .ok_or(CliError::InvalidValue { option: "--format", value: format })?;
+            },
+            "-t" | "--target"   => options.target = Some(parse_address("--target", &value(&mut args, "--target")?)?),
+            "--packets"         => options.packets = Some(parse_packet_types(&value(&mut args, "--packets")?)?),
+            "--player-only"     => options.player_only = true,
+            "--speed"           => options.speed = parse_speed("--speed", &value(&mut args, "--speed")?)?,
+            "--loop"            => options.looped = true,
+            "--from"            => options.start = Some(parse_session_time("--from", &value(&mut args, "--from")?)?),
+            "--from-lap"        => options.start = Some(parse_lap("--from-lap", &value(&mut args, "--from-lap")?)?),
+            "--from-session"    => options.start = Some(parse_session("--from-session", &value(&mut args, "--from-session")?)?),
+            "--to"              => options.end = Some(parse_session_time("--to", &value(&mut args, "--to")?)?),
+            "--to-lap"          => options.end = Some(parse_end_lap("--to-lap", &value(&mut args, "--to-lap")?)?),
+            "--drop"            => dropped.extend(parse_packet_types(&value(&mut args, "--drop")?)?),
+            "--compress"        => options.compress = true,
+            "--by"              => options.split_by = parse_split_by(&value(&mut args, "--by")?)?,
+            "--retain"          => options.retention = parse_retention(options.retention, &value(&mut args, "--retain")?)?,
+            _ if arg.starts_with('-') => return Err(CliError::UnknownOption(arg)),
+            _ if options.input.is_none() => options.input = Some(PathBuf::from(arg)),
+            _ if command == Command::Merge => options.inputs.push(PathBuf::from(arg)),
+            _ => return Err(CliError::UnexpectedArgument(arg)),
+        }
+    }
+    if !dropped.is_empty() {
+        let kept = options.packets.unwrap_or_else(|| PACKET_NAMES.iter().map(|(_, packet_type)| *packet_type).collect());
+        options.packets = Some(kept.into_iter().filter(|packet_type| !dropped.contains(packet_type)).collect());
+    }
+
+    // The rest run live without an input
+    match command {
+        Command::Replay | Command::Index | Command::Trim | Command::Split | Command::Merge if options.input.is_none() =>
+            Err(CliError::MissingInput(command)),
+        Command::Merge if options.inputs.is_empty() => Err(CliError::TooFewInputs(command)),
+        // Seeking needs a recording to seek in
+        _ if options.start.is_some() && options.input.is_none() => Err(CliError::MissingInput(command)),
+        Command::Record | Command::Trim | Command::Merge if options.output.is_none() => Err(CliError::MissingOutput(command)),
+        Command::Listen | Command::Replay | Command::Inspect | Command::Stats | Command::Index | Command::Split
+            if options.format.is_datagrams() =>
+            Err(CliError::InvalidValue { option: "--format", value: format!("{} for {}", options.format.name(), command.name()) }),
+        _ if options.format == OutputFormat::Csv && command != Command::Export =>
+            Err(CliError::InvalidValue { option: "--format", value: format!("csv for {}", command.name()) }),
+        _ if options.format == OutputFormat::Csv && options.output.is_none() =>
+            Err(CliError::InvalidValue { option: "--format", value: "csv without --output, the directory to write to".to_string() }),
+        _ if options.compress && options.format.is_datagrams() =>
+            Err(CliError::InvalidValue { option: "--format", value: format!("{} with --compress", options.format.name()) }),
+        _ => Ok(options),
+    }
+}
+
+fn value<I>(args: &mut I, option: &'static str) -> Result<String, CliError>
+    where I: Iterator<Item = String> {
+    args.next().ok_or(CliError::MissingValue(option))
+}
+
+fn parse_port(value: &str) -> Result<u16, CliError> {
+    value.parse::<u16>().map_err(|_| CliError::InvalidValue { option: "--port", value: value.to_string() })
+}
+
+fn parse_address(option: &'static str, value: &str) -> Result<SocketAddr, CliError> {
+    value.parse::<SocketAddr>().map_err(|_| CliError::InvalidValue { option, value: value.to_string() })
+}
+
+fn parse_speed(option: &'static str, value: &str) -> Result<f64, CliError> {
+    value.parse::<f64>().ok()
+        .filter(|speed| speed.is_finite() && *speed > 0.0)
+        .ok_or(CliError::InvalidValue { option, value: value.to_string() })
+}
+
+fn parse_session_time(option: &'static str, value: &str) -> Result<SeekTarget, CliError> {
+    value.parse::<f32>().ok()
+        .filter(|seconds| seconds.is_finite())
+        .map(SeekTarget::SessionTime)
+        .ok_or(CliError::InvalidValue { option, value: value.to_string() })
+}
+
+fn parse_lap(option: &'static str, value: &str) -> Result<SeekTarget, CliError> {
+    value.parse::<u8>().map(SeekTarget::Lap).map_err(|_| CliError::InvalidValue { option, value: value.to_string() })
+}
+
+fn parse_session(option: &'static str, value: &str) -> Result<SeekTarget, CliError> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    u64::from_str_radix(digits, 16).map(SeekTarget::Session).map_err(|_| CliError::InvalidValue { option, value: value.to_string() })
+}
+
+// Stopping once the player finishes the lap is stopping at the first lap packet of the next
+fn parse_end_lap(option: &'static str, value: &str) -> Result<SeekTarget, CliError> {
+    match parse_lap(option, value)? {
+        SeekTarget::Lap(lap) if lap < u8::MAX => Ok(SeekTarget::Lap(lap + 1)),
+        _ => Err(CliError::InvalidValue { option, value: value.to_string() }),
+    }
+}
+
+fn parse_split_by(value: &str) -> Result<SplitBy, CliError> {
+    match value {
+        "session" => Ok(SplitBy::Session),
+        "lap" => Ok(SplitBy::Lap),
+        _ => Err(CliError::InvalidValue { option: "--by", value: value.to_string() }),
+    }
+}
+
+// Typed at the terminal while replaying
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayControl {
+    Pause,
+    Resume,
+    Seek(SeekTarget),
+    Speed(f64),
+}
+
+pub fn parse_replay_control(line: &str) -> Result<ReplayControl, CliError> {
+    let mut words = line.split_whitespace();
+    let control = match (words.next(), words.next()) {
+        (Some("pause"), None) | (Some("p"), None) => ReplayControl::Pause,
+        (Some("resume"), None) | (Some("r"), None) => ReplayControl::Resume,
+        (Some("seek"), Some(seconds)) => ReplayControl::Seek(parse_session_time("seek", seconds)?),
+        (Some("lap"), Some(lap)) => ReplayControl::Seek(parse_lap("lap", lap)?),
+        (Some("session"), Some(session_id)) => ReplayControl::Seek(parse_session("session", session_id)?),
+        (Some("speed"), Some(speed)) => ReplayControl::Speed(parse_speed("speed", speed)?),
+        _ => return Err(CliError::UnexpectedArgument(line.trim().to_string())),
+    };
+    match words.next() {
+        Some(extra) => Err(CliError::UnexpectedArgument(extra.to_string())),
+        None => Ok(control),
+    }
+}
+
+fn parse_packet_type(option: &'static str, name: &str) -> Result<PacketType, CliError> {
+    PACKET_NAMES.iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, packet_type)| *packet_type)
+        .ok_or(CliError::InvalidValue { option, value: name.to_string() })
+}
+
+fn parse_packet_types(value: &str) -> Result<Vec<PacketType>, CliError> {
+    value.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| parse_packet_type("--packets", name))
+        .collect()
+}
+
+// Overrides the given policy with each type=limit, e.g. motion=120s,lap=session,event=all,session=1
+pub fn parse_retention(mut policy: RetentionPolicy, value: &str) -> Result<RetentionPolicy, CliError> {
+    let invalid = |value: &str| CliError::InvalidValue { option: "--retain", value: value.to_string() };
+    for limit in value.split(',').map(str::trim).filter(|limit| !limit.is_empty()) {
+        let (name, retention) = limit.split_once('=').ok_or_else(|| invalid(limit))?;
+        let packet_type = parse_packet_type("--retain", name.trim())?;
+        let retention = match retention.trim() {
+            "all" => Retention::All,
+            "session" => Retention::Session,
+            seconds if seconds.ends_with('s') => seconds[..seconds.len() - 1].parse::<f32>()
+                .ok()
+                .filter(|seconds| *seconds >= 0.0)
+                .map(Retention::Window)
+                .ok_or_else(|| invalid(limit))?,
+            count => Retention::Latest(count.parse::<usize>().map_err(|_| invalid(limit))?),
+        };
+        policy = policy.with(packet_type, retention);
+    }
+    Ok(policy)
+}
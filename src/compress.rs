@@ -0,0 +1,44 @@
+// Block compression for captures and archives. Blocks are raw LZ4 blocks, as in the LZ4 block
+// format without a frame around them, compressed and decompressed by lz4_flex. The capture and
+// archive headers in front of each block carry its compressed and decompressed lengths, which is
+// all the frame format would add for us. Packets from the same car repeat most of their bytes
+// frame to frame so LZ4 does well on them and decompresses fast enough to replay from.
+
+use std::io;
+
+use lz4_flex::block;
+
+use crate::file::invalid_data;
+
+// The most len bytes can compress to, when nothing in them matches. Readers check lengths
+// against this before reading a block in.
+pub fn max_compressed_len(len: usize) -> usize {
+    block::get_maximum_output_size(len)
+}
+
+// Appends the compressed input to out
+pub fn compress(input: &[u8], out: &mut Vec<u8>) {
+    let start = out.len();
+    out.resize(start + max_compressed_len(input.len()), 0);
+    // Can't fail, out has room for the most input can compress to
+    let len = block::compress_into(input, &mut out[start..]).expect("room for the compressed block");
+    out.truncate(start + len);
+}
+
+// Appends the decompressed input to out, which must come to expected_len bytes
+pub fn decompress(input: &[u8], out: &mut Vec<u8>, expected_len: usize) -> io::Result<()> {
+    let start = out.len();
+    out.resize(start + expected_len, 0);
+    let result = block::decompress_into(input, &mut out[start..]);
+    match result {
+        Ok(len) if len == expected_len => Ok(()),
+        Ok(_) => {
+            out.truncate(start);
+            Err(invalid_data("compressed block decompresses to less than its length"))
+        },
+        Err(e) => {
+            out.truncate(start);
+            Err(invalid_data(&format!("corrupt compressed block, {}", e)))
+        },
+    }
+}
@@ -0,0 +1,432 @@
+// Decoded packets as CSV for spreadsheets, a file per packet type: motion.csv, lap.csv,
+// telemetry.csv, car_status.csv, setups.csv, session.csv, events.csv and participants.csv.
+// Packets with an entry per car get a row per car, the rest a row per packet. Every row starts
+// with session_id in hex, session_time, frame_id and car_index, the car the row is about or
+// empty for session rows, and for events the car the event names if any.
+//
+// The other columns are the fields of the struct for the row by name, per wheel values split
+// into name_rl, name_rr, name_fl and name_fr, coded values like tyre compounds written as their
+// names. What motion and telemetry packets send for the player's car alone goes on the
+// player's row and is left empty on the others. Final classification and lobby packets, and
+// the 2020 weather forecasts, aren't written.
+//
+// Files are opened as the first row for them comes in and start with a line of column names,
+// a file for a packet type that never arrives isn't made.
+
+use std::fmt::Display;
+use std::fmt::Write as _;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+use crate::f1_2019_net::CarMotion;
+use crate::f1_2019_net::CarSetupData;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::EventType;
+use crate::f1_2019_net::LapData;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::ParticipantData;
+use crate::f1_2019_net::SessionData;
+use crate::f1_2019_net::Telemetry;
+use crate::sink::PacketSink;
+use crate::wheels::Wheels;
+
+pub const MOTION: &str = "motion.csv";
+pub const LAP: &str = "lap.csv";
+pub const TELEMETRY: &str = "telemetry.csv";
+pub const CAR_STATUS: &str = "car_status.csv";
+pub const SETUPS: &str = "setups.csv";
+pub const SESSION: &str = "session.csv";
+pub const EVENTS: &str = "events.csv";
+pub const PARTICIPANTS: &str = "participants.csv";
+
+// Column name suffixes in Wheels order
+const WHEEL_SUFFIXES: [&str; 4] = ["rl", "rr", "fl", "fr"];
+
+// Opens a file by name for writing
+type Open<W> = Box<dyn FnMut(&str) -> io::Result<W>>;
+
+pub struct CsvSink<W: Write> {
+    open:           Open<W>,
+    tables:         Vec<(&'static str, W)>,     // Files opened so far, their column names written
+    packets:        Option<Vec<PacketType>>,    // None for every type
+    player_only:    bool,
+}
+
+impl<W: Write> CsvSink<W> {
+    // Calls open with a file's name, motion.csv and so on, when its first row comes in
+    pub fn new<F>(open: F) -> Self
+        where F: FnMut(&str) -> io::Result<W> + 'static {
+        CsvSink {
+            open: Box::new(open),
+            tables: Vec::new(),
+            packets: None,
+            player_only: false,
+        }
+    }
+
+    // Only these packet types. None for every type
+    pub fn set_packets(&mut self, packets: Option<Vec<PacketType>>) {
+        self.packets = packets;
+    }
+
+    // Only the player's row from packets with a row per car
+    pub fn set_player_only(&mut self, player_only: bool) {
+        self.player_only = player_only;
+    }
+
+    // What's been written to the file so far, None if it hasn't been opened
+    pub fn table(&self, name: &str) -> Option<&W> {
+        self.tables.iter().find(|(known, _)| *known == name).map(|(_, out)| out)
+    }
+
+    fn write_row<F: FnOnce(&mut Row)>(&mut self, name: &'static str, fill: F) -> io::Result<()> {
+        let (index, opened) = match self.tables.iter().position(|(known, _)| *known == name) {
+            Some(index) => (index, false),
+            None => {
+                let out = (self.open)(name)?;
+                self.tables.push((name, out));
+                (self.tables.len() - 1, true)
+            },
+        };
+        let mut row = Row::new(opened);
+        fill(&mut row);
+        let out = &mut self.tables[index].1;
+        if let Some(names) = row.names {
+            writeln!(out, "{}", names)?;
+        }
+        writeln!(out, "{}", row.values)
+    }
+
+    // A row per car, or just the player's. fill is told whether the car is the player's
+    fn write_cars<T, F>(&mut self, name: &'static str, header: &PacketHeader, cars: &[T], fill: F) -> io::Result<()>
+        where F: Fn(&mut Row, &T, bool) {
+        let player = header.player_car_index as usize;
+        for (index, car) in cars.iter().enumerate() {
+            if self.player_only && index != player {
+                continue;
+            }
+            self.write_row(name, |row| {
+                prefix(row, header, Some(index));
+                fill(row, car, index == player);
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl CsvSink<BufWriter<File>> {
+    // Writes the files to dir, made if it isn't there. Files already there are replaced
+    pub fn create_in(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let dir = dir.to_path_buf();
+        Ok(CsvSink::new(move |name| Ok(BufWriter::new(File::create(dir.join(name))?))))
+    }
+}
+
+impl<W: Write> PacketSink for CsvSink<W> {
+    fn on_packet(&mut self, packet: &Packet) -> io::Result<()> {
+        if let Some(packets) = &self.packets {
+            if !packets.contains(&packet.packet_type()) {
+                return Ok(());
+            }
+        }
+        let header = packet.header();
+        match packet {
+            Packet::Motion(p) => self.write_cars(MOTION, header, &p.car_motion_data, |row, car, player| {
+                car_motion(row, car);
+                motion_player(row, if player { Some(p) } else { None });
+            }),
+            Packet::Lap(p) => self.write_cars(LAP, header, &p.lap_data, |row, car, _| lap_data(row, car)),
+            Packet::Telemetry(p) => self.write_cars(TELEMETRY, header, &p.car_telemetry_data, |row, car, player| {
+                car_telemetry(row, car);
+                telemetry_player(row, if player { Some(p) } else { None });
+            }),
+            Packet::CarStatus(p) => self.write_cars(CAR_STATUS, header, &p.car_status_data, |row, car, _| car_status(row, car)),
+            Packet::CarSetups(p) => self.write_cars(SETUPS, header, &p.car_setups, |row, car, _| car_setup(row, car)),
+            Packet::Participants(p) => self.write_cars(PARTICIPANTS, header, &p.participant_data, |row, car, _| {
+                row.field("num_cars_active", p.num_cars_active);
+                participant(row, car);
+            }),
+            Packet::Session(p) => self.write_row(SESSION, |row| {
+                prefix(row, header, None);
+                session(row, p);
+            }),
+            Packet::Event(p) => self.write_row(EVENTS, |row| {
+                prefix(row, header, p.event_type.vehicle_idx().map(usize::from));
+                event(row, &p.event_type);
+            }),
+            Packet::FinalClassification(_) | Packet::LobbyInfo(_) => Ok(()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for (_, out) in &mut self.tables {
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// One line of a file. The column names are only gathered for the first line written to it.
+struct Row {
+    names:  Option<String>,
+    values: String,
+    cells:  usize,
+}
+
+impl Row {
+    fn new(named: bool) -> Self {
+        Row { names: if named { Some(String::new()) } else { None }, values: String::new(), cells: 0 }
+    }
+
+    fn field<T: Display>(&mut self, name: &str, value: T) {
+        if let Some(names) = &mut self.names {
+            push_cell(names, self.cells, name);
+        }
+        push_cell(&mut self.values, self.cells, value);
+        self.cells += 1;
+    }
+
+    // Empty when None
+    fn maybe<T: Display>(&mut self, name: &str, value: Option<T>) {
+        match value {
+            Some(value) => self.field(name, value),
+            None => self.field(name, ""),
+        }
+    }
+
+    fn wheels<T: Display>(&mut self, name: &str, wheels: &Wheels<T>) {
+        self.maybe_wheels(name, Some(wheels));
+    }
+
+    fn maybe_wheels<T: Display>(&mut self, name: &str, wheels: Option<&Wheels<T>>) {
+        for (wheel, suffix) in WHEEL_SUFFIXES.iter().enumerate() {
+            if let Some(names) = &mut self.names {
+                push_cell(names, self.cells, format_args!("{}_{}", name, suffix));
+            }
+            match wheels {
+                Some(wheels) => push_cell(&mut self.values, self.cells, &wheels.as_array()[wheel]),
+                None => push_cell(&mut self.values, self.cells, ""),
+            }
+            self.cells += 1;
+        }
+    }
+}
+
+// Quoted if it holds a comma, quote or line break, with quotes doubled
+fn push_cell<T: Display>(line: &mut String, index: usize, value: T) {
+    if index > 0 {
+        line.push(',');
+    }
+    let start = line.len();
+    let _ = write!(line, "{}", value);
+    if line[start..].contains([',', '"', '\n', '\r']) {
+        let cell = line.split_off(start);
+        line.push('"');
+        line.push_str(&cell.replace('"', "\"\""));
+        line.push('"');
+    }
+}
+
+fn prefix(row: &mut Row, header: &PacketHeader, car_index: Option<usize>) {
+    row.field("session_id", format_args!("{:016x}", header.session_id));
+    row.field("session_time", header.session_time);
+    row.field("frame_id", header.frame_id);
+    row.maybe("car_index", car_index);
+}
+
+fn car_motion(row: &mut Row, car: &CarMotion) {
+    row.field("world_pos_x", car.world_pos_x);
+    row.field("world_pos_y", car.world_pos_y);
+    row.field("world_pos_z", car.world_pos_z);
+    row.field("world_vel_x", car.world_vel_x);
+    row.field("world_vel_y", car.world_vel_y);
+    row.field("world_vel_z", car.world_vel_z);
+    row.field("world_fwd_dir_x", car.world_fwd_dir_x);
+    row.field("world_fwd_dir_y", car.world_fwd_dir_y);
+    row.field("world_fwd_dir_z", car.world_fwd_dir_z);
+    row.field("world_right_dir_x", car.world_right_dir_x);
+    row.field("world_right_dir_y", car.world_right_dir_y);
+    row.field("world_right_dir_z", car.world_right_dir_z);
+    row.field("lateral_g", car.lateral_g);
+    row.field("longitudinal_g", car.longitudinal_g);
+    row.field("vertical_g", car.vertical_g);
+    row.field("yaw", car.yaw);
+    row.field("pitch", car.pitch);
+    row.field("roll", car.roll);
+}
+
+// Sent for the player's car alone
+fn motion_player(row: &mut Row, packet: Option<&MotionData>) {
+    row.maybe_wheels("suspension_pos", packet.map(|p| &p.suspension_pos));
+    row.maybe_wheels("suspension_vel", packet.map(|p| &p.suspension_vel));
+    row.maybe_wheels("suspension_acc", packet.map(|p| &p.suspension_acc));
+    row.maybe_wheels("wheel_speed", packet.map(|p| &p.wheel_speed));
+    row.maybe_wheels("wheel_slip", packet.map(|p| &p.wheel_slip));
+    row.maybe("local_vel_x", packet.map(|p| p.local_vel_x));
+    row.maybe("local_vel_y", packet.map(|p| p.local_vel_y));
+    row.maybe("local_vel_z", packet.map(|p| p.local_vel_z));
+    row.maybe("angular_vel_x", packet.map(|p| p.angular_vel_x));
+    row.maybe("angular_vel_y", packet.map(|p| p.angular_vel_y));
+    row.maybe("angular_vel_z", packet.map(|p| p.angular_vel_z));
+    row.maybe("angular_acc_x", packet.map(|p| p.angular_acc_x));
+    row.maybe("angular_acc_y", packet.map(|p| p.angular_acc_y));
+    row.maybe("angular_acc_z", packet.map(|p| p.angular_acc_z));
+    row.maybe("front_wheels_angle", packet.map(|p| p.front_wheels_angle));
+}
+
+fn lap_data(row: &mut Row, car: &LapData) {
+    row.field("last_lap", car.last_lap);
+    row.field("current_lap", car.current_lap);
+    row.field("best_lap", car.best_lap);
+    row.field("best_sec_1", car.best_sec_1);
+    row.field("best_sec_2", car.best_sec_2);
+    row.field("lap_distance", car.lap_distance);
+    row.field("total_distance", car.total_distance);
+    row.field("safety_car_delta", car.safety_car_delta);
+    row.field("position", car.position);
+    row.field("lap_num", car.lap_num);
+    row.field("pit_status", car.pit_status);
+    row.field("sector", car.sector);
+    row.field("is_lap_valid", car.is_lap_valid);
+    row.field("penalties", car.penalties);
+    row.field("grid_position", car.grid_position);
+    row.field("driver_status", car.driver_status);
+    row.field("result_status", car.result_status);
+}
+
+fn car_telemetry(row: &mut Row, car: &CarTelemetry) {
+    row.field("car_speed", car.car_speed);
+    row.field("throttle_pos", car.throttle_pos);
+    row.field("steering_pos", car.steering_pos);
+    row.field("brake_pos", car.brake_pos);
+    row.field("clutch_pos", car.clutch_pos);
+    row.field("gear", car.gear);
+    row.field("engine_rpm", car.engine_rpm);
+    row.field("drs_active", car.drs_active);
+    row.field("change_light_perc", car.change_light_perc);
+    row.wheels("brake_temps", &car.brake_temps);
+    row.wheels("tyre_surface_temps", &car.tyre_surface_temps);
+    row.wheels("tyre_inner_temps", &car.tyre_inner_temps);
+    row.field("engine_temp", car.engine_temp);
+    row.wheels("tyre_pressures", &car.tyre_pressures);
+    row.wheels("tyre_contact_types", &car.tyre_contact_types);
+}
+
+// Sent once per packet for the player. Buttons as their bit flags, see ButtonStatus
+fn telemetry_player(row: &mut Row, packet: Option<&Telemetry>) {
+    row.maybe("button_status", packet.map(|p| p.button_status.bits()));
+    row.maybe("mfd_panel_index", packet.map(|p| p.mfd_panel_index));
+    row.maybe("mfd_panel_index_secondary", packet.map(|p| p.mfd_panel_index_secondary));
+    row.maybe("suggested_gear", packet.map(|p| p.suggested_gear));
+}
+
+fn car_status(row: &mut Row, car: &CarStatus) {
+    row.field("traction_control", car.traction_control);
+    row.field("anti_lock_brakes", car.anti_lock_brakes);
+    row.field("fuel_mix", car.fuel_mix);
+    row.field("front_brake_bias", car.front_brake_bias);
+    row.field("pit_limiter_status", car.pit_limiter_status);
+    row.field("fuel_in_tank", car.fuel_in_tank);
+    row.field("fuel_capacity", car.fuel_capacity);
+    row.field("fuel_remaining_laps", car.fuel_remaining_laps);
+    row.field("max_rpm", car.max_rpm);
+    row.field("idle_rpm", car.idle_rpm);
+    row.field("max_gears", car.max_gears);
+    row.field("drs_allowed", car.drs_allowed);
+    row.field("drs_activation_distance", car.drs_activation_distance);
+    row.wheels("tyres_wear", &car.tyres_wear);
+    row.field("actual_tyre_compound", car.actual_tyre_compound);
+    row.field("tyre_visual_compound", car.tyre_visual_compound);
+    row.field("tyres_age_laps", car.tyres_age_laps);
+    row.wheels("tyres_damage", &car.tyres_damage);
+    row.field("front_left_wing_damage", car.front_left_wing_damage);
+    row.field("front_right_wing_damage", car.front_right_wing_damage);
+    row.field("rear_wing_damage", car.rear_wing_damage);
+    row.field("drs_fault", car.drs_fault);
+    row.field("engine_damage", car.engine_damage);
+    row.field("gear_box_damage", car.gear_box_damage);
+    row.field("vehicle_fia_flags", car.vehicle_fia_flags);
+    row.field("ers_store_energy", car.ers_store_energy);
+    row.field("ers_deploy_mode", car.ers_deploy_mode);
+    row.field("ers_harvested_this_lap_mguk", car.ers_harvested_this_lap_mguk);
+    row.field("ers_harvested_this_lap_mguh", car.ers_harvested_this_lap_mguh);
+    row.field("ers_deployed_this_lap", car.ers_deployed_this_lap);
+}
+
+fn car_setup(row: &mut Row, car: &CarSetupData) {
+    row.field("front_wing", car.front_wing);
+    row.field("rear_wing", car.rear_wing);
+    row.field("on_throttle", car.on_throttle);
+    row.field("off_throttle", car.off_throttle);
+    row.field("front_camber", car.front_camber);
+    row.field("rear_camber", car.rear_camber);
+    row.field("front_toe", car.front_toe);
+    row.field("rear_toe", car.rear_toe);
+    row.field("front_suspension", car.front_suspension);
+    row.field("rear_suspension", car.rear_suspension);
+    row.field("front_anti_roll_bar", car.front_anti_roll_bar);
+    row.field("rear_anti_roll_bar", car.rear_anti_roll_bar);
+    row.field("front_suspension_height", car.front_suspension_height);
+    row.field("rear_suspension_height", car.rear_suspension_height);
+    row.field("brake_pressure", car.brake_pressure);
+    row.field("brake_bias", car.brake_bias);
+    row.field("front_tyre_pressure", car.front_tyre_pressure);
+    row.field("rear_tyre_pressure", car.rear_tyre_pressure);
+    row.field("ballast", car.ballast);
+    row.field("fuel_load", car.fuel_load);
+}
+
+fn participant(row: &mut Row, car: &ParticipantData) {
+    row.field("ai_controlled", car.ai_controlled);
+    row.field("driver_id", car.driver_id);
+    row.field("team_id", car.team_id);
+    row.field("race_number", car.race_number);
+    row.field("nationality", car.nationality);
+    row.field("name", car.name());
+    row.field("priv_telemetry", car.priv_telemetry);
+}
+
+// Every marshal zone goes in, num_zones says how many are in use
+fn session(row: &mut Row, session: &SessionData) {
+    row.field("weather", session.weather);
+    row.field("track_temp", session.track_temp);
+    row.field("air_temp", session.air_temp);
+    row.field("total_laps", session.total_laps);
+    row.field("track_len", session.track_len);
+    row.field("session_type", session.session_type);
+    row.field("track_id", session.track_id);
+    row.field("formula", session.formual);
+    row.field("session_ttl", session.session_ttl);
+    row.field("session_len", session.session_len);
+    row.field("pit_spd_lim", session.pit_spd_lim);
+    row.field("is_paused", session.is_paused);
+    row.field("is_spectating", session.is_spectating);
+    row.field("spectator_car", session.spectator_car);
+    row.field("sli_native", session.sli_native);
+    row.field("num_zones", session.num_zones);
+    for (index, zone) in session.zones.iter().enumerate() {
+        row.field(&format!("zone_start_{}", index), zone.zone_start);
+        row.field(&format!("zone_flag_{}", index), zone.flag);
+    }
+    row.field("safety_car", session.safety_car);
+    row.field("is_network_game", session.is_network_game);
+}
+
+// The code as sent, and the lap time for fastest laps
+fn event(row: &mut Row, event_type: &EventType) {
+    row.field("code", String::from_utf8_lossy(&event_type.code()));
+    let lap_time = match event_type {
+        EventType::FastestLap { lap_time, .. } => Some(*lap_time),
+        _ => None,
+    };
+    row.maybe("lap_time", lap_time);
+}
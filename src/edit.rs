@@ -0,0 +1,200 @@
+// Cutting recordings up and putting them back together. Until ends a source at a point in the
+// session, Splitter says which piece of a recording each datagram belongs in when splitting by
+// session or lap, and Merger interleaves recordings of the same session made on different
+// machines, passing each packet on once.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+
+use crate::index::LapTracker;
+use crate::parser::parse_header;
+use crate::replay::SeekTarget;
+use crate::source::PacketSource;
+
+// Passes datagrams on from a source up to the first one reaching the end target, which isn't
+// passed on
+pub struct Until<S: PacketSource> {
+    source:     S,
+    end:        SeekTarget,
+    ended:      bool,
+}
+
+impl<S: PacketSource> Until<S> {
+    pub fn new(source: S, end: SeekTarget) -> Self {
+        Until { source, end, ended: false }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: PacketSource> PacketSource for Until<S> {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        if self.ended {
+            return Ok(None);
+        }
+        match self.source.next_datagram()? {
+            Some((_, datagram)) if self.end.reached(datagram) => {
+                self.ended = true;
+                Ok(None)
+            },
+            next => Ok(next),
+        }
+    }
+
+    fn source_address(&self) -> Option<SocketAddr> {
+        self.source.source_address()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    Session,    // A piece per session_id
+    Lap,        // A piece per player lap in each session
+}
+
+// Part of a recording when split
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub session_id:     u64,
+    pub lap:            Option<u8>,     // The player's lap when splitting by lap, 0 before the first lap packet
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "session {:016x}", self.session_id)?;
+        match self.lap {
+            Some(lap) => write!(f, " lap {}", lap),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct Splitter {
+    by:         SplitBy,
+    tracker:    LapTracker,
+    current:    Option<Piece>,
+}
+
+impl Splitter {
+    pub fn new(by: SplitBy) -> Self {
+        Splitter { by, tracker: LapTracker::new(), current: None }
+    }
+
+    // Which piece the next datagram of the recording goes in. Datagrams without a readable header
+    // stay with the piece before them, None if they come before any packet.
+    pub fn piece(&mut self, datagram: &[u8]) -> Option<Piece> {
+        if let Ok(header) = parse_header(datagram) {
+            let lap = self.tracker.player_lap(&header, datagram);
+            self.current = Some(Piece {
+                session_id: header.session_id,
+                lap: if self.by == SplitBy::Lap { Some(lap) } else { None },
+            });
+        }
+        self.current
+    }
+}
+
+// What identifies a packet between recordings, (session_id, frame_id, packet type id)
+type PacketKey = (u64, u32, u8);
+
+// A datagram read ahead from one of the recordings being merged
+struct Pending {
+    received_us:    u64,
+    datagram:       Vec<u8>,
+    source:         Option<SocketAddr>,
+}
+
+struct MergeInput<S: PacketSource> {
+    source:     S,
+    next:       Option<Pending>,
+    seen:       HashMap<PacketKey, u32>,    // Times each packet has come from this recording
+}
+
+impl<S: PacketSource> MergeInput<S> {
+    fn read_ahead(&mut self) -> io::Result<()> {
+        let mut pending = self.next.take().unwrap_or(Pending { received_us: 0, datagram: Vec::new(), source: None });
+        if let Some((received_us, datagram)) = self.source.next_datagram()? {
+            pending.received_us = received_us;
+            pending.datagram.clear();
+            pending.datagram.extend_from_slice(datagram);
+            pending.source = self.source.source_address();
+            self.next = Some(pending);
+        }
+        Ok(())
+    }
+}
+
+// Merges recordings in receive time order, earlier recordings first on a tie. A packet in more
+// than one recording is passed on once, going by its (session_id, frame_id, packet type). Each
+// recording is taken to be in order on its own, and a packet repeated within one recording, as
+// happens to frame ids after a flashback, is passed on as often as the recording with the most
+// repeats has it. Datagrams without a readable header can't be matched up and are all passed on.
+pub struct Merger<S: PacketSource> {
+    inputs:     Vec<MergeInput<S>>,
+    passed:     HashMap<PacketKey, u32>,    // Times each packet has been passed on
+    current:    Pending,
+    duplicates: u64,
+}
+
+impl<S: PacketSource> Merger<S> {
+    // Reads the first datagram of each recording
+    pub fn new(sources: Vec<S>) -> io::Result<Self> {
+        let mut inputs = Vec::with_capacity(sources.len());
+        for source in sources {
+            let mut input = MergeInput { source, next: None, seen: HashMap::new() };
+            input.read_ahead()?;
+            inputs.push(input);
+        }
+        Ok(Merger {
+            inputs,
+            passed: HashMap::new(),
+            current: Pending { received_us: 0, datagram: Vec::new(), source: None },
+            duplicates: 0,
+        })
+    }
+
+    // Packets dropped so far for having already been passed on from another recording
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+}
+
+impl<S: PacketSource> PacketSource for Merger<S> {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        loop {
+            let earliest = self.inputs.iter()
+                .enumerate()
+                .filter_map(|(index, input)| input.next.as_ref().map(|next| (next.received_us, index)))
+                .min();
+            let index = match earliest {
+                Some((_, index)) => index,
+                None => return Ok(None),
+            };
+            let input = &mut self.inputs[index];
+            let next = input.next.as_mut().expect("the earliest input has a datagram read ahead");
+            std::mem::swap(&mut self.current, next);
+            input.read_ahead()?;
+
+            let key = match parse_header(&self.current.datagram) {
+                Ok(header) => (header.session_id, header.frame_id, header.packet_type.id()),
+                Err(_) => return Ok(Some((self.current.received_us, &self.current.datagram))),
+            };
+            let seen = input.seen.entry(key).or_insert(0);
+            *seen += 1;
+            let passed = self.passed.entry(key).or_insert(0);
+            if *seen > *passed {
+                *passed = *seen;
+                return Ok(Some((self.current.received_us, &self.current.datagram)));
+            }
+            self.duplicates += 1;
+        }
+    }
+
+    fn source_address(&self) -> Option<SocketAddr> {
+        self.current.source
+    }
+}
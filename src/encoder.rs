@@ -0,0 +1,530 @@
+// Inverse of the parsers, writes packets back out in the F1 2019 UDP layout.
+//
+// Fields are written in the order the layouts in parser/f1_2019.rs list them, the same order the
+// parsers read them in, so any 2019 packet decoded by parse_packet can be encoded again to the
+// same bytes. Packets decoded from other formats are refused rather than written out in a layout
+// they didn't come from.
+
+use std::mem;
+
+use byte::ctx::Endian;
+use byte::BytesExt;
+use byte::TryWrite;
+use byte::LE;
+
+use crate::error::EncodeError;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::CarMotion;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::SessionData;
+use crate::f1_2019_net::LapData;
+use crate::f1_2019_net::Lap;
+use crate::f1_2019_net::CarSetupData;
+use crate::f1_2019_net::CarSetups;
+use crate::f1_2019_net::CarStatusData;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::ParticipantData;
+use crate::f1_2019_net::Participants;
+use crate::f1_2019_net::Telemetry;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::EventType;
+use crate::f1_2019_net::Event;
+use crate::f1_2019_net::MarshalZone;
+use crate::parser::f1_2019::*;
+use crate::wheels::Wheels;
+
+pub trait Encode {
+	// Number of bytes write_into will fill
+	fn encoded_len(&self) -> usize;
+
+	// Writes the struct to the start of buf, returns the number of bytes written
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+	// UnsupportedFormat for packets decoded from 2018 or 2020 datagrams, CarCountMismatch when a
+	// per car array isn't 20 long
+	fn to_bytes(&self) -> Result<Vec<u8>, EncodeError> {
+		let mut buf = vec![0u8; self.encoded_len()];
+		let written = self.write_into(&mut buf)?;
+		buf.truncate(written);
+		Ok(buf)
+	}
+}
+
+// Cursor over the part of buf a struct is written to, the counterpart of parser::Reader. Room
+// for the whole struct is checked when the writer is made, each write then moves past the field
+// so fields go out in the order their layout lists them rather than at offsets kept by hand.
+struct Writer<'a> {
+	buf: &'a mut [u8],
+	pos: usize,
+}
+
+impl<'a> Writer<'a> {
+	// Writer over the first len bytes of buf, BufferTooSmall if buf doesn't hold them
+	fn new(buf: &'a mut [u8], len: usize) -> Result<Self, EncodeError> {
+		if buf.len() < len {
+			return Err(EncodeError::BufferTooSmall { needed: len, got: buf.len() });
+		}
+		Ok(Writer { buf: &mut buf[..len], pos: 0 })
+	}
+
+	fn write<T>(&mut self, value: T) -> Result<(), EncodeError>
+		where T: TryWrite<Endian> {
+		let (start, len) = (self.pos, self.buf.len());
+		self.buf.write_with::<T>(&mut self.pos, value, LE).map_err(|_| EncodeError::BufferTooSmall {
+			needed: start + mem::size_of::<T>(),
+			got: len,
+		})
+	}
+
+	// 4 consecutive values, one per wheel
+	fn write_wheels<T>(&mut self, wheels: &Wheels<T>) -> Result<(), EncodeError>
+		where T: TryWrite<Endian> + Copy {
+		for value in wheels {
+			self.write::<T>(*value)?;
+		}
+		Ok(())
+	}
+
+	fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+		let end = self.pos + bytes.len();
+		if end > self.buf.len() {
+			return Err(EncodeError::BufferTooSmall { needed: end, got: self.buf.len() });
+		}
+		self.buf[self.pos..end].copy_from_slice(bytes);
+		self.pos = end;
+		Ok(())
+	}
+
+	// A nested struct, written by its own Encode
+	fn write_encoded<T: Encode>(&mut self, value: &T) -> Result<(), EncodeError> {
+		self.pos += value.write_into(&mut self.buf[self.pos..])?;
+		Ok(())
+	}
+
+	// Each element of an array (cars, marshal zones) back to back
+	fn write_all<T: Encode>(&mut self, elems: &[T]) -> Result<(), EncodeError> {
+		for elem in elems {
+			self.write_encoded(elem)?;
+		}
+		Ok(())
+	}
+
+	// Zeroes the rest, for packets sent at full size whatever they hold
+	fn pad(&mut self) {
+		for byte in &mut self.buf[self.pos..] {
+			*byte = 0;
+		}
+		self.pos = self.buf.len();
+	}
+
+	// Bytes written, which has to be the len the writer was made with
+	fn finish(self) -> Result<usize, EncodeError> {
+		debug_assert_eq!(self.pos, self.buf.len(), "fields written don't fill the layout");
+		Ok(self.pos)
+	}
+}
+
+fn check_car_count(cars: usize) -> Result<(), EncodeError> {
+	if cars != NUM_CARS {
+		return Err(EncodeError::CarCountMismatch { expected: NUM_CARS, got: cars });
+	}
+	Ok(())
+}
+
+impl Encode for PacketHeader {
+	fn encoded_len(&self) -> usize {
+		HEADER_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		if self.packet_format != PACKET_FORMAT {
+			return Err(EncodeError::UnsupportedFormat(self.packet_format));
+		}
+		let mut writer = Writer::new(buf, HEADER_SIZE)?;
+		writer.write::<u16>(self.packet_format)?;
+		writer.write::<u8>(self.maj_version)?;
+		writer.write::<u8>(self.min_version)?;
+		writer.write::<u8>(self.packet_version)?;
+		writer.write::<u8>(self.packet_type.id())?;
+		writer.write::<u64>(self.session_id)?;
+		writer.write::<f32>(self.session_time)?;
+		writer.write::<u32>(self.frame_id)?;
+		writer.write::<u8>(self.player_car_index)?;
+		writer.finish()
+	}
+}
+
+impl Encode for CarMotion {
+	fn encoded_len(&self) -> usize {
+		CAR_MOTION_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, CAR_MOTION_SIZE)?;
+		writer.write::<f32>(self.world_pos_x)?;
+		writer.write::<f32>(self.world_pos_y)?;
+		writer.write::<f32>(self.world_pos_z)?;
+		writer.write::<f32>(self.world_vel_x)?;
+		writer.write::<f32>(self.world_vel_y)?;
+		writer.write::<f32>(self.world_vel_z)?;
+		writer.write::<i16>(self.world_fwd_dir_x)?;
+		writer.write::<i16>(self.world_fwd_dir_y)?;
+		writer.write::<i16>(self.world_fwd_dir_z)?;
+		writer.write::<i16>(self.world_right_dir_x)?;
+		writer.write::<i16>(self.world_right_dir_y)?;
+		writer.write::<i16>(self.world_right_dir_z)?;
+		writer.write::<f32>(self.lateral_g)?;
+		writer.write::<f32>(self.longitudinal_g)?;
+		writer.write::<f32>(self.vertical_g)?;
+		writer.write::<f32>(self.yaw)?;
+		writer.write::<f32>(self.pitch)?;
+		writer.write::<f32>(self.roll)?;
+		writer.finish()
+	}
+}
+
+impl Encode for MotionData {
+	fn encoded_len(&self) -> usize {
+		MOTION_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, MOTION_SIZE)?;
+		check_car_count(self.car_motion_data.len())?;
+		writer.write_encoded(&self.header)?;
+		writer.write_all(&self.car_motion_data)?;
+		writer.write_wheels(&self.suspension_pos)?;
+		writer.write_wheels(&self.suspension_vel)?;
+		writer.write_wheels(&self.suspension_acc)?;
+		writer.write_wheels(&self.wheel_speed)?;
+		writer.write_wheels(&self.wheel_slip)?;
+		writer.write::<f32>(self.local_vel_x)?;
+		writer.write::<f32>(self.local_vel_y)?;
+		writer.write::<f32>(self.local_vel_z)?;
+		writer.write::<f32>(self.angular_vel_x)?;
+		writer.write::<f32>(self.angular_vel_y)?;
+		writer.write::<f32>(self.angular_vel_z)?;
+		writer.write::<f32>(self.angular_acc_x)?;
+		writer.write::<f32>(self.angular_acc_y)?;
+		writer.write::<f32>(self.angular_acc_z)?;
+		writer.write::<f32>(self.front_wheels_angle)?;
+		writer.finish()
+	}
+}
+
+impl Encode for MarshalZone {
+	fn encoded_len(&self) -> usize {
+		MARSHAL_ZONE_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, MARSHAL_ZONE_SIZE)?;
+		writer.write::<f32>(self.zone_start)?;
+		writer.write::<i8>(i8::from(self.flag))?;
+		writer.finish()
+	}
+}
+
+impl Encode for SessionData {
+	fn encoded_len(&self) -> usize {
+		SESSION_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, SESSION_SIZE)?;
+		writer.write_encoded(&self.header)?;
+		writer.write::<u8>(u8::from(self.weather))?;
+		writer.write::<i8>(self.track_temp)?;
+		writer.write::<i8>(self.air_temp)?;
+		writer.write::<u8>(self.total_laps)?;
+		writer.write::<u16>(self.track_len)?;
+		writer.write::<u8>(u8::from(self.session_type))?;
+		writer.write::<i8>(self.track_id)?;
+		writer.write::<u8>(u8::from(self.formual))?;
+		writer.write::<u16>(self.session_ttl)?;
+		writer.write::<u16>(self.session_len)?;
+		writer.write::<u8>(self.pit_spd_lim)?;
+		writer.write::<u8>(self.is_paused)?;
+		writer.write::<u8>(self.is_spectating)?;
+		writer.write::<u8>(self.spectator_car)?;
+		writer.write::<u8>(self.sli_native)?;
+		writer.write::<u8>(self.num_zones)?;
+		writer.write_all(&self.zones)?;
+		writer.write::<u8>(u8::from(self.safety_car))?;
+		writer.write::<u8>(self.is_network_game)?;
+		writer.finish()
+	}
+}
+
+impl Encode for LapData {
+	fn encoded_len(&self) -> usize {
+		CAR_LAP_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, CAR_LAP_SIZE)?;
+		writer.write::<f32>(self.last_lap)?;
+		writer.write::<f32>(self.current_lap)?;
+		writer.write::<f32>(self.best_lap)?;
+		writer.write::<f32>(self.best_sec_1)?;
+		writer.write::<f32>(self.best_sec_2)?;
+		writer.write::<f32>(self.lap_distance)?;
+		writer.write::<f32>(self.total_distance)?;
+		writer.write::<f32>(self.safety_car_delta)?;
+		writer.write::<u8>(self.position)?;
+		writer.write::<u8>(self.lap_num)?;
+		writer.write::<u8>(u8::from(self.pit_status))?;
+		writer.write::<u8>(self.sector)?;
+		writer.write::<u8>(self.is_lap_valid)?;
+		writer.write::<u8>(self.penalties)?;
+		writer.write::<u8>(self.grid_position)?;
+		writer.write::<u8>(u8::from(self.driver_status))?;
+		writer.write::<u8>(u8::from(self.result_status))?;
+		writer.finish()
+	}
+}
+
+impl Encode for Lap {
+	fn encoded_len(&self) -> usize {
+		LAP_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, LAP_SIZE)?;
+		check_car_count(self.lap_data.len())?;
+		writer.write_encoded(&self.header)?;
+		writer.write_all(&self.lap_data)?;
+		writer.finish()
+	}
+}
+
+impl Encode for Event {
+	fn encoded_len(&self) -> usize {
+		EVENT_SIZE
+	}
+
+	// Events are always sent at full size, unused detail bytes are left zeroed
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, EVENT_SIZE)?;
+		writer.write_encoded(&self.header)?;
+		writer.write_bytes(&self.event_type.code())?;
+		match self.event_type {
+			EventType::FastestLap { vehicle_idx, lap_time } => {
+				writer.write::<u8>(vehicle_idx)?;
+				writer.write::<f32>(lap_time)?;
+			},
+			EventType::Retirement { vehicle_idx }
+			| EventType::TeamMateInPits { vehicle_idx }
+			| EventType::RaceWinner { vehicle_idx } => {
+				writer.write::<u8>(vehicle_idx)?;
+			},
+			_ => {},
+		}
+		writer.pad();
+		writer.finish()
+	}
+}
+
+impl Encode for ParticipantData {
+	fn encoded_len(&self) -> usize {
+		PARTICIPANT_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, PARTICIPANT_SIZE)?;
+		writer.write::<u8>(self.ai_controlled)?;
+		writer.write::<u8>(self.driver_id)?;
+		writer.write::<u8>(self.team_id)?;
+		writer.write::<u8>(self.race_number)?;
+		writer.write::<u8>(self.nationality)?;
+		writer.write_bytes(&self.name)?;
+		writer.write::<u8>(self.priv_telemetry)?;
+		writer.finish()
+	}
+}
+
+impl Encode for Participants {
+	fn encoded_len(&self) -> usize {
+		PARTICIPANTS_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, PARTICIPANTS_SIZE)?;
+		check_car_count(self.participant_data.len())?;
+		writer.write_encoded(&self.header)?;
+		writer.write::<u8>(self.num_cars_active)?;
+		writer.write_all(&self.participant_data)?;
+		writer.finish()
+	}
+}
+
+impl Encode for CarSetupData {
+	fn encoded_len(&self) -> usize {
+		CAR_SETUP_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, CAR_SETUP_SIZE)?;
+		writer.write::<u8>(self.front_wing)?;
+		writer.write::<u8>(self.rear_wing)?;
+		writer.write::<u8>(self.on_throttle)?;
+		writer.write::<u8>(self.off_throttle)?;
+		writer.write::<f32>(self.front_camber)?;
+		writer.write::<f32>(self.rear_camber)?;
+		writer.write::<f32>(self.front_toe)?;
+		writer.write::<f32>(self.rear_toe)?;
+		writer.write::<u8>(self.front_suspension)?;
+		writer.write::<u8>(self.rear_suspension)?;
+		writer.write::<u8>(self.front_anti_roll_bar)?;
+		writer.write::<u8>(self.rear_anti_roll_bar)?;
+		writer.write::<u8>(self.front_suspension_height)?;
+		writer.write::<u8>(self.rear_suspension_height)?;
+		writer.write::<u8>(self.brake_pressure)?;
+		writer.write::<u8>(self.brake_bias)?;
+		writer.write::<f32>(self.front_tyre_pressure)?;
+		writer.write::<f32>(self.rear_tyre_pressure)?;
+		writer.write::<u8>(self.ballast)?;
+		writer.write::<f32>(self.fuel_load)?;
+		writer.finish()
+	}
+}
+
+impl Encode for CarSetups {
+	fn encoded_len(&self) -> usize {
+		CARSETUPS_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, CARSETUPS_SIZE)?;
+		check_car_count(self.car_setups.len())?;
+		writer.write_encoded(&self.header)?;
+		writer.write_all(&self.car_setups)?;
+		writer.finish()
+	}
+}
+
+impl Encode for CarTelemetry {
+	fn encoded_len(&self) -> usize {
+		CAR_TELEMETRY_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, CAR_TELEMETRY_SIZE)?;
+		writer.write::<u16>(self.car_speed)?;
+		writer.write::<f32>(self.throttle_pos)?;
+		writer.write::<f32>(self.steering_pos)?;
+		writer.write::<f32>(self.brake_pos)?;
+		writer.write::<u8>(self.clutch_pos)?;
+		writer.write::<i8>(self.gear)?;
+		writer.write::<u16>(self.engine_rpm)?;
+		writer.write::<u8>(self.drs_active)?;
+		writer.write::<u8>(self.change_light_perc)?;
+		writer.write_wheels(&self.brake_temps)?;
+		writer.write_wheels(&self.tyre_surface_temps)?;
+		writer.write_wheels(&self.tyre_inner_temps)?;
+		writer.write::<u16>(self.engine_temp)?;
+		writer.write_wheels(&self.tyre_pressures)?;
+		writer.write_wheels(&self.tyre_contact_types)?;
+		writer.finish()
+	}
+}
+
+impl Encode for Telemetry {
+	fn encoded_len(&self) -> usize {
+		TELEMETY_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, TELEMETY_SIZE)?;
+		check_car_count(self.car_telemetry_data.len())?;
+		writer.write_encoded(&self.header)?;
+		writer.write_all(&self.car_telemetry_data)?;
+		writer.write::<u32>(self.button_status.bits())?;
+		writer.finish()
+	}
+}
+
+impl Encode for CarStatus {
+	fn encoded_len(&self) -> usize {
+		CAR_STATUS_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, CAR_STATUS_SIZE)?;
+		writer.write::<u8>(self.traction_control)?;
+		writer.write::<u8>(self.anti_lock_brakes)?;
+		writer.write::<u8>(u8::from(self.fuel_mix))?;
+		writer.write::<u8>(self.front_brake_bias)?;
+		writer.write::<u8>(self.pit_limiter_status)?;
+		writer.write::<f32>(self.fuel_in_tank)?;
+		writer.write::<f32>(self.fuel_capacity)?;
+		writer.write::<f32>(self.fuel_remaining_laps)?;
+		writer.write::<u16>(self.max_rpm)?;
+		writer.write::<u16>(self.idle_rpm)?;
+		writer.write::<u8>(self.max_gears)?;
+		writer.write::<u8>(self.drs_allowed)?;
+		writer.write_wheels(&self.tyres_wear)?;
+		writer.write::<u8>(u8::from(self.actual_tyre_compound))?;
+		writer.write::<u8>(u8::from(self.tyre_visual_compound))?;
+		writer.write_wheels(&self.tyres_damage)?;
+		writer.write::<u8>(self.front_left_wing_damage)?;
+		writer.write::<u8>(self.front_right_wing_damage)?;
+		writer.write::<u8>(self.rear_wing_damage)?;
+		writer.write::<u8>(self.engine_damage)?;
+		writer.write::<u8>(self.gear_box_damage)?;
+		writer.write::<i8>(i8::from(self.vehicle_fia_flags))?;
+		writer.write::<f32>(self.ers_store_energy)?;
+		writer.write::<u8>(u8::from(self.ers_deploy_mode))?;
+		writer.write::<f32>(self.ers_harvested_this_lap_mguk)?;
+		writer.write::<f32>(self.ers_harvested_this_lap_mguh)?;
+		writer.write::<f32>(self.ers_deployed_this_lap)?;
+		writer.finish()
+	}
+}
+
+impl Encode for CarStatusData {
+	fn encoded_len(&self) -> usize {
+		STATUS_SIZE
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		let mut writer = Writer::new(buf, STATUS_SIZE)?;
+		check_car_count(self.car_status_data.len())?;
+		writer.write_encoded(&self.header)?;
+		writer.write_all(&self.car_status_data)?;
+		writer.finish()
+	}
+}
+
+impl Encode for Packet {
+	fn encoded_len(&self) -> usize {
+		match self {
+			Packet::Motion(p)       => p.encoded_len(),
+			Packet::Session(p)      => p.encoded_len(),
+			Packet::Lap(p)          => p.encoded_len(),
+			Packet::Event(p)        => p.encoded_len(),
+			Packet::Participants(p) => p.encoded_len(),
+			Packet::CarSetups(p)    => p.encoded_len(),
+			Packet::Telemetry(p)    => p.encoded_len(),
+			Packet::CarStatus(p)    => p.encoded_len(),
+			// 2020 only, nothing to write them as
+			Packet::FinalClassification(_) | Packet::LobbyInfo(_) => 0,
+		}
+	}
+
+	fn write_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+		match self {
+			Packet::Motion(p)       => p.write_into(buf),
+			Packet::Session(p)      => p.write_into(buf),
+			Packet::Lap(p)          => p.write_into(buf),
+			Packet::Event(p)        => p.write_into(buf),
+			Packet::Participants(p) => p.write_into(buf),
+			Packet::CarSetups(p)    => p.write_into(buf),
+			Packet::Telemetry(p)    => p.write_into(buf),
+			Packet::CarStatus(p)    => p.write_into(buf),
+			Packet::FinalClassification(p) => Err(EncodeError::UnsupportedFormat(p.header.packet_format)),
+			Packet::LobbyInfo(p)    => Err(EncodeError::UnsupportedFormat(p.header.packet_format)),
+		}
+	}
+}
@@ -0,0 +1,48 @@
+use std::error;
+use std::fmt;
+
+use crate::f1_2019_net::PacketType;
+
+// Reasons a datagram could not be turned into a Packet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseError {
+    TooShort { expected: usize, got: usize },       // Ran out of bytes, expected is how many were needed
+    SizeMismatch { expected: usize, got: usize },   // Size doesn't match the size given in the spec for the packet type
+    UnknownPacketType(u8),                          // Packet type byte not sent by the packet's format
+    WrongPacketType { expected: PacketType, got: PacketType }, // Valid packet, but not the type that was asked for
+    UnsupportedFormat(u16),                         // packet_format in the header isn't one we can decode
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::TooShort { expected, got } => write!(f, "packet too short, expected at least {} bytes got {}", expected, got),
+            ParseError::SizeMismatch { expected, got } => write!(f, "packet size mismatch, expected {} bytes got {}", expected, got),
+            ParseError::UnknownPacketType(id) => write!(f, "unknown packet type {}", id),
+            ParseError::WrongPacketType { expected, got } => write!(f, "expected a {} got a {}", expected.name(), got.name()),
+            ParseError::UnsupportedFormat(format) => write!(f, "unsupported packet format {}", format),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+// Reasons a packet could not be written out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EncodeError {
+    BufferTooSmall { needed: usize, got: usize },   // Destination can't hold the encoded packet
+    UnsupportedFormat(u16),                         // Only the 2019 layout can be written
+    CarCountMismatch { expected: usize, got: usize }, // Per car array isn't the length the layout expects
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::BufferTooSmall { needed, got } => write!(f, "buffer too small, needed {} bytes got {}", needed, got),
+            EncodeError::UnsupportedFormat(format) => write!(f, "can't encode packet format {}", format),
+            EncodeError::CarCountMismatch { expected, got } => write!(f, "car count mismatch, expected {} cars got {}", expected, got),
+        }
+    }
+}
+
+impl error::Error for EncodeError {}
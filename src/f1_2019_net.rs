@@ -1,7 +1,16 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
 use std::fmt;
+use std::str;
 
-#[derive(Debug, Clone, Copy)]
+use crate::registry::Driver;
+use crate::registry::Nationality;
+use crate::registry::SurfaceType;
+use crate::registry::Team;
+use crate::registry::Track;
+use crate::vector::Vec3;
+use crate::wheels::Wheels;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum PacketType {
     Motion,
     Session,
@@ -11,70 +20,112 @@ pub enum PacketType {
     Setup,
     Telemetry,  
     CarStatus,
-    InvalidPacket, 
-}
-
-impl Default for PacketType {
-    fn default() -> Self {PacketType::InvalidPacket} //ok to use as default and error state as byte can't be > 7
+    FinalClassification,    // F1 2020 onwards
+    LobbyInfo,              // F1 2020 onwards
+    #[default]
+    InvalidPacket,      //ok to use as default and error state as byte can't be > 9
 }
 
 impl PacketType {
-    pub fn getName(&self, event_type: PacketType) -> &str {
-        match event_type {
-            Motion      => return &"motion packet",
-            Session     => return &"session packet", 
-            Lap         => return &"lap packet",
-            Event       => return &"event packet",
-            Participant => return &"participant packet",
-            Setup       => return &"setup packet",
-            Telemetry   => return &"telemetry packet",
-            CarStatus   => return &"car status packet",
-            InvalidPacket => return &"invalid packet",   
+    pub fn name(&self) -> &'static str {
+        match self {
+            PacketType::Motion          => "motion packet",
+            PacketType::Session         => "session packet",
+            PacketType::Lap             => "lap packet",
+            PacketType::Event           => "event packet",
+            PacketType::Participant     => "participant packet",
+            PacketType::Setup           => "setup packet",
+            PacketType::Telemetry       => "telemetry packet",
+            PacketType::CarStatus       => "car status packet",
+            PacketType::FinalClassification => "final classification packet",
+            PacketType::LobbyInfo       => "lobby info packet",
+            PacketType::InvalidPacket   => "invalid packet",
+        }
+    }
+
+    // Packet id byte as sent in the header
+    pub fn id(&self) -> u8 {
+        match self {
+            PacketType::Motion          => 0,
+            PacketType::Session         => 1,
+            PacketType::Lap             => 2,
+            PacketType::Event           => 3,
+            PacketType::Participant     => 4,
+            PacketType::Setup           => 5,
+            PacketType::Telemetry       => 6,
+            PacketType::CarStatus       => 7,
+            PacketType::FinalClassification => 8,
+            PacketType::LobbyInfo       => 9,
+            PacketType::InvalidPacket   => 255,
         }
     }
 }
 
-//Design is that events are differentiated by way of a 4 byte array of ascii chars???
-//We have no need to read these as chars so plan to create an instance of these enum vals with the int val of the ascii chars in question 
-//Possible improvement calculate the 32 bit val of concating these bytes together as we could read the data stream as one 32 bit val rather than 4 8 bit vals
-#[derive(Debug)]
+// Events are identified on the wire by a 4 char ascii code, followed by details for the events
+// that refer to a car. Each variant carries its own details.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EventType { 
-    SessionStarted,     // Session Started "SSTA" S=83, S=83, T=84, A=65                    / TOT = 315
-    SessionEnded,       // Session Ended "SEND" S=83, E=69, N=78, D=68                      / TOT = 298 
-    FastestLap,         // Fastest Lap "FTLP" F=70, T=84, L=76, P=80                        / TOT = 310
-    Retirement,         // Retirement "RTMT" R=82, T=84, M=77, T=84                         / TOT = 327
-    DrsEnabled,         // DRS Enabled "DRSE" D=68, R=82, S=83, E=69                        / TOT = 302
-    DrsDisabled,        // DRS Disabled "DRSD" D=68, R=82, S=83, D=68                       / TOT = 301
-    TeamMateInPits,     // Team mate in the pits "TMPT" T=84, M=77, P=80, T=84              / TOT = 325
-    ChequeredFlag,      // The chequered flag has been waved "CHQF" C=67, H=72, Q=81, F=70  / TOT = 290
-    RaceWinner,         // The race winner is announced "RCWN" R=82, C=67, W=87, N=78       / TOT = 314
+    SessionStarted,                                 // "SSTA" Sent when the session starts
+    SessionEnded,                                   // "SEND" Sent when the session ends
+    FastestLap { vehicle_idx: u8, lap_time: f32 },  // "FTLP" When a driver achieves the fastest lap, lap time in seconds
+    Retirement { vehicle_idx: u8 },                 // "RTMT" When a driver retires
+    DrsEnabled,                                     // "DRSE" Race control have enabled DRS
+    DrsDisabled,                                    // "DRSD" Race control have disabled DRS
+    TeamMateInPits { vehicle_idx: u8 },             // "TMPT" Your team mate has entered the pits
+    ChequeredFlag,                                  // "CHQF" The chequered flag has been waved
+    RaceWinner { vehicle_idx: u8 },                 // "RCWN" The race winner is announced
+    Unknown([u8; 4]),                               // Any other code, kept as sent. Details aren't decoded
 }
 
 #[derive(Debug)]
 pub struct Event {
 	pub header: PacketHeader,
 	pub event_type: EventType,
-	pub car_idx: u8,		//The car if this event refers to a car
-	pub lap_time: f32,		//Lap time if this event is fastest lap
 }
 
-impl Event {
-    
+impl EventType {
+    // 4 char ascii code identifying the event on the wire
+    pub fn code(&self) -> [u8; 4] {
+        match self {
+            EventType::SessionStarted           => *b"SSTA",
+            EventType::SessionEnded             => *b"SEND",
+            EventType::FastestLap { .. }        => *b"FTLP",
+            EventType::Retirement { .. }        => *b"RTMT",
+            EventType::DrsEnabled               => *b"DRSE",
+            EventType::DrsDisabled              => *b"DRSD",
+            EventType::TeamMateInPits { .. }    => *b"TMPT",
+            EventType::ChequeredFlag            => *b"CHQF",
+            EventType::RaceWinner { .. }        => *b"RCWN",
+            EventType::Unknown(code)            => *code,
+        }
+    }
+
+    // The car the event refers to, if any
+    pub fn vehicle_idx(&self) -> Option<u8> {
+        match self {
+            EventType::FastestLap { vehicle_idx, .. }   => Some(*vehicle_idx),
+            EventType::Retirement { vehicle_idx }       => Some(*vehicle_idx),
+            EventType::TeamMateInPits { vehicle_idx }   => Some(*vehicle_idx),
+            EventType::RaceWinner { vehicle_idx }       => Some(*vehicle_idx),
+            _                                           => None,
+        }
+    }
 }
 
 //
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct PacketHeader {   
-    packet_format: 		u16,		// 2019
-    maj_version: 		u8,			// Game major version - "X.00"
-    min_version: 		u8,			// Game minor version - "1.XX"
-    packet_version: 	u8,			// Version of this packet type, all start from 1
-    packet_type:		PacketType,	// JP enum spec is u8
-    session_id: 		u64,		// Unique identifier for the session
-    session_time: 		f32,		// Session timestamp
-    frame_id:			u32,  		// Identifier for the frame the data was retrieved on. JP 2020- working from stated total packet sizes this is u32
-                                    // even though 32 bit int isn't in the data type list. Stated type is uint..? 
-    player_car_index: 	u8			// Index of player's car in the array
+    pub packet_format: 		u16,		// 2018, 2019 or 2020 - see format::PacketFormat
+    pub maj_version: 		u8,			// Game major version - "X.00", 0 for 2018 which doesn't send it
+    pub min_version: 		u8,			// Game minor version - "1.XX", 0 for 2018 which doesn't send it
+    pub packet_version: 	u8,			// Version of this packet type, all start from 1
+    pub packet_type:		PacketType,	// JP enum spec is u8
+    pub session_id: 		u64,		// Unique identifier for the session
+    pub session_time: 		f32,		// Session timestamp
+    pub frame_id:			u32,  		// Identifier for the frame the data was retrieved on. JP 2020- working from stated total packet sizes this is u32
+                                    	// even though 32 bit int isn't in the data type list. Stated type is uint..? 
+    pub player_car_index: 	u8,			// Index of player's car in the array
+    pub secondary_player_car_index: u8,	// Index of secondary player's car in splitscreen, 255 if none or pre 2020
 }
 
 impl PacketHeader {
@@ -82,6 +133,7 @@ impl PacketHeader {
         self.packet_type    
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(packet_format: u16, maj_version: u8, min_version: u8, packet_version: u8, 
             packet_type: PacketType, session_id: u64, session_time: f32, frame_id: u32, player_car_index: u8) -> Self {
         PacketHeader {
@@ -94,6 +146,7 @@ impl PacketHeader {
             session_time,
             frame_id,
             player_car_index,
+            secondary_player_car_index: 255,
         }
     }
 }
@@ -109,37 +162,55 @@ pub struct CarTelemetry {				//66 bytes
     pub engine_rpm:			u16,					// Engine RPM
     pub drs_active: 		u8,						// 0 = off, 1 = on
     pub change_light_perc: 	u8,						// Rev lights indicator (percentage)
-    pub brake_temps: 		[u16; 4],				// Brakes temperature (celsius)
-    pub tyre_surface_temps: [u16; 4],				// Tyres surface temperature (celsius)
-    pub tyre_inner_temps: 	[u16; 4],				// Tyres inner temperature (celsius)
+    pub brake_temps: 		Wheels<u16>,		// Brakes temperature (celsius)
+    pub tyre_surface_temps: Wheels<u16>,		// Tyres surface temperature (celsius)
+    pub tyre_inner_temps: 	Wheels<u16>,		// Tyres inner temperature (celsius)
     pub engine_temp: 		u16,					// Engine temperature (celsius)
-    pub tyre_pressures: 	[f32; 4], 				// Tyres pressure (PSI)
-    pub tyre_contact_types: [u8; 4] 				// Driving surface, see appendices
+    pub tyre_pressures: 	Wheels<f32>, 		// Tyres pressure (PSI)
+    pub tyre_contact_types: Wheels<u8> 		// Driving surface, see appendices
+}
+
+impl CarTelemetry {
+    // Surface under each wheel, None for ids missing from the appendix
+    pub fn surface_types(&self) -> Wheels<Option<&'static SurfaceType>> {
+        self.tyre_contact_types.map(SurfaceType::from_id)
+    }
 }
 
+#[derive(Debug)]
 pub struct Telemetry {
     pub header:             PacketHeader, 
-    pub car_telemetry_data: [CarTelemetry; 20],
-    pub button_status:      u32,               // Bit flags specifying which buttons are being pressed currently - see appendices 
+    pub car_telemetry_data: Vec<CarTelemetry>,  // One per car, 20 cars up to 2019 and 22 from 2020
+    pub button_status:      ButtonStatus,      // Bit flags specifying which buttons are being pressed currently - see appendices
+    pub mfd_panel_index:    u8,                // 2020 only - index of MFD panel open, 255 = closed or pre 2020
+    pub mfd_panel_index_secondary: u8,         // 2020 only - same as above for the secondary player
+    pub suggested_gear:     i8,                // 2020 only - suggested gear for the player (1-8), 0 if no gear suggested
+}
+
+impl Telemetry {
+    // Button presses and releases since the previous telemetry packet, e.g. to drop markers from the wheel
+    pub fn button_changes(&self, previous: &Telemetry) -> impl Iterator<Item = ButtonEvent> {
+        self.button_status.changes_since(previous.button_status)
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct MarshalZone {
 	pub zone_start:	f32,	// Fraction (0..1) of way through the lap the marshal zone starts
-	pub flag:		i8,		// -1 = invalid/unknown, 0 = none, 1 = green, 2 = blue, 3 = yellow, 4 = red   
+	pub flag:		FiaFlag,		// -1 = invalid/unknown, 0 = none, 1 = green, 2 = blue, 3 = yellow, 4 = red   
 }
 
 #[derive(Debug)]
 pub struct SessionData {
 	pub header:			PacketHeader,
-	pub weather:		u8,					// Weather - 0 = clear, 1 = light cloud, 2 = overcast, 3 = light rain, 4 = heavy rain, 5 = storm
+	pub weather:		Weather,			// Weather - 0 = clear, 1 = light cloud, 2 = overcast, 3 = light rain, 4 = heavy rain, 5 = storm
 	pub track_temp:		i8,					// Track temp. in degrees celsius
 	pub air_temp:		i8,					// Air temp. in degrees celsius
 	pub total_laps:		u8,					// Total number of laps in this race
 	pub track_len:		u16,				// Track length in metres
-	pub session_type:	u8,					// 0 = unknown, 1 = P1, 2 = P2, 3 = P3, 4 = Short, P5 = Q1, 6 = Q2, 7 = Q3, 8 = Short Q, 9 = OSQ, 10 = R, 11 = R2, 12 = Time Trial
+	pub session_type:	SessionType,		// 0 = unknown, 1 = P1, 2 = P2, 3 = P3, 4 = Short, P5 = Q1, 6 = Q2, 7 = Q3, 8 = Short Q, 9 = OSQ, 10 = R, 11 = R2, 12 = Time Trial
 	pub track_id:		i8,					// -1 for unknown, 0-21 for tracks, see appendix
-	pub formual:		u8,					// Formula, 0 = F1 Modern, 1 = F1 Classic, 2 = F2, 3 = F1 Generic
+	pub formual:		Formula,			// Formula, 0 = F1 Modern, 1 = F1 Classic, 2 = F2, 3 = F1 Generic
 	pub session_ttl:	u16,				// Time left in session in seconds
 	pub session_len:	u16,				// Session duration in seconds
 	pub pit_spd_lim:	u8,					// Pit speed limit in kilometres per hour
@@ -149,8 +220,25 @@ pub struct SessionData {
 	pub sli_native:		u8,					// SLI Pro support, 0 = inactive, 1 = active
 	pub num_zones:		u8,					// Number of marshal zones to follow
 	pub zones:			[MarshalZone; 21], 	// List of marshal zones – max 21
-	pub safety_car:		u8,					// 0 = no safety car, 1 = full safety car, 2 = virtual safety car
+	pub safety_car:		SafetyCarStatus,	// 0 = no safety car, 1 = full safety car, 2 = virtual safety car
 	pub is_network_game:u8,					// 0 = offline, 1 = online
+	pub weather_forecast_samples: Vec<WeatherForecastSample>,	// 2020 only - empty for earlier formats
+}
+
+impl SessionData {
+	pub fn track(&self) -> Option<&'static Track> {
+		Track::from_id(self.track_id)
+	}
+}
+
+// F1 2020 forecast entry from the session packet
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WeatherForecastSample {
+	pub session_type:	SessionType,	// Same values as SessionData.session_type
+	pub time_offset:	u8,		// Time in minutes the forecast is for
+	pub weather:		Weather,		// Same values as SessionData.weather
+	pub track_temp:		i8,		// Track temp. in degrees celsius
+	pub air_temp:		i8,		// Air temp. in degrees celsius
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -165,19 +253,19 @@ pub struct LapData {
 	pub safety_car_delta: f32,	// Delta in seconds for safety car
 	pub position: u8,			// Car race position
 	pub lap_num: u8,			// Current lap number
-	pub pit_status: u8,			// 0 = none, 1 = pitting, 2 = in pit area
+	pub pit_status: PitStatus,	// 0 = none, 1 = pitting, 2 = in pit area
 	pub sector: u8,				// 0 = sector1, 1 = sector2, 2 = sector3
 	pub is_lap_valid: u8,		// Current lap invalid - 0 = valid, 1 = invalid
 	pub penalties: u8,			// Accumulated time penalties in seconds to be added
 	pub grid_position: u8,		// Grid position the vehicle started the race in
-	pub driver_status: u8,		// Status of driver - 0 = in garage, 1 = flying lap, 2 = in lap, 3 = out lap, 4 = on track
-	pub result_status: u8,		// Result status - 0 = invalid, 1 = inactive, 2 = active, 3 = finished, 4 = disqualified, 5 = not classified, 6 = retired
+	pub driver_status: DriverStatus,	// Status of driver - 0 = in garage, 1 = flying lap, 2 = in lap, 3 = out lap, 4 = on track
+	pub result_status: ResultStatus,	// Result status - 0 = invalid, 1 = inactive, 2 = active, 3 = finished, 4 = disqualified, 5 = not classified, 6 = retired
 }
 
 #[derive(Debug)]
 pub struct Lap	{
     pub header:	PacketHeader,     // Header
-    pub lap_data: [LapData; 20]  // Lap data for all cars on track
+    pub lap_data: Vec<LapData>	  // Lap data for all cars on track
 }
 
 // This is a list of participants in the race. If the vehicle is controlled by AI, 
@@ -188,11 +276,7 @@ pub struct Lap	{
 //Frequency: Every 5 seconds
 //Size: 1104 bytes
 //Version: 1 
-//Can't Derive Debug due to array size
-//		arrays only have std trait implementations for lengths 0..=32
-//	  = note: required because of the requirements on the impl of `std::fmt::Debug` for `[u8; 48]`
-//    = note: required because of the requirements on the impl of `std::fmt::Debug` for `&[u8; 48]`
-//    = note: required for the cast to the object type `dyn std::fmt::Debug
+//Debug is implemented by hand so the name prints as text rather than 48 bytes
 #[derive(Clone, Copy)]
 pub struct ParticipantData {
     pub ai_controlled: 	u8,         // Whether the vehicle is AI (1) or Human (0) controlled
@@ -216,12 +300,69 @@ impl ParticipantData {
             priv_telemetry: 0,
         }
     }
+
+    // Name up to the null terminator, see decode_name
+    pub fn name(&self) -> Cow<'_, str> {
+        decode_name(&self.name)
+    }
+
+    // The game cuts long names short and ends them with …
+    pub fn is_name_truncated(&self) -> bool {
+        self.name().ends_with('\u{2026}')
+    }
+
+    pub fn team(&self) -> Option<&'static Team> {
+        Team::from_id(self.team_id)
+    }
+
+    // None for human drivers, who are sent as 255 in online games
+    pub fn driver(&self) -> Option<&'static Driver> {
+        Driver::from_id(self.driver_id)
+    }
+
+    pub fn nationality(&self) -> Option<&'static Nationality> {
+        Nationality::from_id(self.nationality)
+    }
+}
+
+impl Default for ParticipantData {
+    fn default() -> Self {
+        ParticipantData::new()
+    }
 }
 
+impl fmt::Debug for ParticipantData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParticipantData")
+            .field("ai_controlled", &self.ai_controlled)
+            .field("driver_id", &self.driver_id)
+            .field("team_id", &self.team_id)
+            .field("race_number", &self.race_number)
+            .field("nationality", &self.nationality)
+            .field("name", &self.name())
+            .field("priv_telemetry", &self.priv_telemetry)
+            .finish()
+    }
+}
+
+// Decodes a null terminated UTF-8 name field. A name filling the whole field has no terminator
+// and may end part way through a character, that partial character is dropped. Anything else
+// that isn't valid UTF-8 becomes U+FFFD.
+pub fn decode_name(bytes: &[u8]) -> Cow<'_, str> {
+    let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    let bytes = &bytes[..end];
+    match str::from_utf8(bytes) {
+        Ok(name) => Cow::Borrowed(name),
+        Err(e) if e.error_len().is_none() => String::from_utf8_lossy(&bytes[..e.valid_up_to()]),
+        Err(_) => String::from_utf8_lossy(bytes),
+    }
+}
+
+#[derive(Debug)]
 pub struct Participants {
     pub header: 			PacketHeader,			// Header
     pub num_cars_active:	u8,						// Number of active cars in the data – should match number of cars on HUD
-    pub participant_data:	[ParticipantData; 20],	// Data for all cars
+    pub participant_data:	Vec<ParticipantData>,	// Data for all cars
 }
 
 //Car Setups Packet
@@ -230,7 +371,7 @@ pub struct Participants {
 //Frequency: 2 per second
 //Size: 843 bytes
 //Version: 1
-#[derive(Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct CarSetupData {
     pub front_wing: 			u8,             // Front wing aero
     pub rear_wing:				u8,             // Rear wing aero
@@ -248,15 +389,16 @@ pub struct CarSetupData {
     pub rear_suspension_height: u8,     		// Rear ride height
     pub brake_pressure: 		u8,            	// Brake pressure (percentage)
     pub brake_bias: 			u8,             // Brake bias (percentage)
-    pub front_tyre_pressure: 	f32,       		// Front tyre pressure (PSI)
-    pub rear_tyre_pressure: 	f32,         	// Rear tyre pressure (PSI)
+    pub front_tyre_pressure: 	f32,       		// Front tyre pressure (PSI), 2020 sends per wheel so this is the mean of the fronts
+    pub rear_tyre_pressure: 	f32,         	// Rear tyre pressure (PSI), 2020 sends per wheel so this is the mean of the rears
     pub ballast: 				u8,             // Ballast
     pub fuel_load: 				f32,            // Fuel load
 }
 
+#[derive(Debug)]
 pub struct CarSetups {
     pub header: PacketHeader,
-    pub car_setups: [CarSetupData; 20],
+    pub car_setups: Vec<CarSetupData>,
 }
 
 //---------------Car Status Packet
@@ -264,16 +406,17 @@ pub struct CarSetups {
 //Frequency: Rate as specified in menus
 //Size: 1143 bytes
 //Version: 1 
+#[derive(Debug)]
 pub struct CarStatusData {
 	pub header: 			PacketHeader,			// Header
-    pub car_status_data: 	[CarStatus; 20],		// Array of car status'
+    pub car_status_data: 	Vec<CarStatus>,			// Array of car status
 }
 
-#[derive(Default, Clone, Copy)] 
+#[derive(Debug, Default, Clone, Copy)] 
 pub struct CarStatus {                      //56 bytes
     pub traction_control: 		u8,         // 0 (off) - 2 (high)
     pub anti_lock_brakes: 		u8,         // 0 (off) - 1 (on)
-    pub fuel_mix: 				u8,         // Fuel mix - 0 = lean, 1 = standard, 2 = rich, 3 = max
+    pub fuel_mix: 				FuelMix,    // Fuel mix - 0 = lean, 1 = standard, 2 = rich, 3 = max
     pub front_brake_bias: 		u8,         // Front brake bias (percentage)
     pub pit_limiter_status: 	u8,       	// Pit limiter status - 0 = off, 1 = on
     pub fuel_in_tank: 			f32,        // Current fuel mass
@@ -283,26 +426,29 @@ pub struct CarStatus {                      //56 bytes
     pub idle_rpm:				u16,        // Cars idle RPM
     pub max_gears: 				u8,         // Maximum number of gears
     pub drs_allowed: 			u8,         // 0 = not allowed, 1 = allowed, -1 = unknown
-    pub tyres_wear: 			[u8; 4],    // Tyre wear percentage
-    pub actual_tyre_compound: 	u8,	   		// F1 Modern - 16 = C5, 17 = C4, 18 = C3, 19 = C2, 20 = C1
+    pub drs_activation_distance:u16,        // 2020 only - 0 = DRS not available, non-zero = DRS will be available in [X] metres
+    pub tyres_wear: 			Wheels<u8>,  // Tyre wear percentage
+    pub actual_tyre_compound: 	TyreCompound,	// F1 Modern - 16 = C5, 17 = C4, 18 = C3, 19 = C2, 20 = C1
    					   						// 7 = inter, 8 = wet
    					   						// F1 Classic - 9 = dry, 10 = wet
    					   						// F2 – 11 = super soft, 12 = soft, 13 = medium, 14 = hard
    					   						// 15 = wet
-	pub tyre_visual_compound: 	u8,       	// F1 visual (can be different from actual compound)
+	pub tyre_visual_compound: 	VisualTyreCompound,	// F1 visual (can be different from actual compound)
    											// 16 = soft, 17 = medium, 18 = hard, 7 = inter, 8 = wet
    											// F1 Classic – same as above
    											// F2 – same as above
-    pub tyres_damage: 			[u8; 4],            	// Tyre damage (percentage)
+    pub tyres_age_laps:         u8,         // 2020 only - age in laps of the current set of tyres
+    pub tyres_damage: 			Wheels<u8>,           // Tyre damage (percentage)
     pub front_left_wing_damage: u8,      		// Front left wing damage (percentage)
     pub front_right_wing_damage:u8,     		// Front right wing damage (percentage)
     pub rear_wing_damage: 		u8,           	// Rear wing damage (percentage)
+    pub drs_fault:              u8,             // 2020 only - 0 = OK, 1 = fault
     pub engine_damage: 			u8,             		// Engine damage (percentage)
     pub gear_box_damage: 		u8,            	// Gear box damage (percentage)
-    pub vehicle_fia_flags: 		i8,	   			// -1 = invalid/unknown, 0 = none, 1 = green
+    pub vehicle_fia_flags: 		FiaFlag,		// -1 = invalid/unknown, 0 = none, 1 = green
                                             // 2 = blue, 3 = yellow, 4 = red
     pub ers_store_energy: 		f32,           	// ERS energy store in Joules
-    pub ers_deploy_mode: 		u8,            	// ERS deployment mode, 0 = none, 1 = low, 2 = medium
+    pub ers_deploy_mode: 		ErsDeployMode, 	// ERS deployment mode, 0 = none, 1 = low, 2 = medium
    					   						// 3 = high, 4 = overtake, 5 = hotlap
     pub ers_harvested_this_lap_mguk: 	f32,  	// ERS energy harvested this lap by MGU-K
     pub ers_harvested_this_lap_mguh: 	f32,  	// ERS energy harvested this lap by MGU-H
@@ -317,7 +463,7 @@ pub struct CarStatus {                      //56 bytes
 //Frequency: Rate as specified in menus
 //Size: 1343 bytes
 //Version: 1
-#[derive(Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct CarMotion {
     pub world_pos_x: f32,			//World Space pos
     pub world_pos_y: f32,
@@ -339,16 +485,72 @@ pub struct CarMotion {
     pub roll: f32,					//Radians
 }
 
+impl CarMotion {
+    // World space position in metres
+    pub fn position(&self) -> Vec3 {
+        Vec3::new(self.world_pos_x, self.world_pos_y, self.world_pos_z)
+    }
+
+    // World space velocity in metres per second
+    pub fn velocity(&self) -> Vec3 {
+        Vec3::new(self.world_vel_x, self.world_vel_y, self.world_vel_z)
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        Vec3::from_normalised(self.world_fwd_dir_x, self.world_fwd_dir_y, self.world_fwd_dir_z)
+    }
+
+    pub fn right(&self) -> Vec3 {
+        Vec3::from_normalised(self.world_right_dir_x, self.world_right_dir_y, self.world_right_dir_z)
+    }
+
+    // Not sent, forward x right gives y up for a car sitting flat
+    pub fn up(&self) -> Vec3 {
+        self.forward().cross(self.right())
+    }
+
+    // Metres per second
+    pub fn speed(&self) -> f32 {
+        self.velocity().length()
+    }
+
+    pub fn speed_kph(&self) -> f32 {
+        self.speed() * 3.6
+    }
+
+    // Direction the car points in the ground plane, radians clockwise from world +z
+    pub fn heading(&self) -> f32 {
+        let forward = self.forward();
+        forward.x.atan2(forward.z)
+    }
+
+    // World space vector in the car's frame, x right, y up, z forward
+    pub fn to_local(&self, world: Vec3) -> Vec3 {
+        Vec3::new(world.dot(self.right()), world.dot(self.up()), world.dot(self.forward()))
+    }
+
+    // Car frame vector back to world space
+    pub fn to_world(&self, local: Vec3) -> Vec3 {
+        self.right() * local.x + self.up() * local.y + self.forward() * local.z
+    }
+
+    // Velocity in the car's frame, z is forward speed and x is sideways slide
+    pub fn local_velocity(&self) -> Vec3 {
+        self.to_local(self.velocity())
+    }
+}
+
+#[derive(Debug)]
 pub struct MotionData {
     pub header: PacketHeader,               	// Header
-    pub car_motion_data: [CarMotion; 20],    	// Data for all cars on track
+    pub car_motion_data: Vec<CarMotion>,    	// Data for all cars on track
 
     //Player car only
-    pub suspension_pos: 	[f32; 4],
-    pub suspension_vel: 	[f32; 4],
-    pub suspension_acc:		[f32; 4],
-    pub wheel_speed: 		[f32; 4],
-    pub wheel_slip:			[f32; 4],
+    pub suspension_pos: 	Wheels<f32>,
+    pub suspension_vel: 	Wheels<f32>,
+    pub suspension_acc:		Wheels<f32>,
+    pub wheel_speed: 		Wheels<f32>,
+    pub wheel_slip:			Wheels<f32>,
     pub local_vel_x:		f32,		//Local space
     pub local_vel_y:		f32,
     pub local_vel_z:		f32,
@@ -361,288 +563,1080 @@ pub struct MotionData {
     pub front_wheels_angle: f32, //Radians
 }
 
-pub fn init_teams(map: &mut HashMap<usize, &str>) {
-    map.insert(0, "Mercedes");
-    map.insert(1, "Ferrari");
-    map.insert(2, "Red Bull Racing");
-    map.insert(3, "Williams");
-    map.insert(4, "Racing Point");
-    map.insert(5, "Renault");
-    map.insert(6, "Toro Rosso");
-    map.insert(7, "Haas");
-    map.insert(8, "McLaren");
-    map.insert(9, "Alfa Romeo");
-    map.insert(10, "McLaren 1988");
-    map.insert(11, "McLaren 1991");
-    map.insert(12, "Williams 1992");
-    map.insert(13, "Ferrari 1995");
-    map.insert(14, "Williams 1996");
-    map.insert(15, "McLaren 1998");
-    map.insert(16, "Ferrari 2002");
-    map.insert(17, "Ferrari 2004");
-    map.insert(18, "Renault 2006");
-    map.insert(19, "Ferrari 2007");
-    map.insert(21, "Red Bull 2010");
-    map.insert(22, "Ferrari 1976");
-    map.insert(23, "ART Grand Prix");
-    map.insert(24, "Campos Vexatec Racing");
-    map.insert(25, "Carlin");
-    map.insert(26, "Charouz Racing System");
-    map.insert(27, "DAMS");
-    map.insert(28, "Russian Time");
-    map.insert(29, "MP Motorsport");
-    map.insert(30, "Pertamina");
-    map.insert(31, "McLaren 1990");
-    map.insert(32, "Trident");
-    map.insert(33, "BWT Arden");
-    map.insert(34, "McLaren 1976");
-    map.insert(35, "Lotus 1972");
-    map.insert(36, "Ferrari 1979");
-    map.insert(37, "McLaren 1982");
-    map.insert(38, "Williams 2003");
-    map.insert(39, "Brawn 2009");
-    map.insert(40, "Lotus 1978");
-    map.insert(42, "Art GP ’19");
-    map.insert(43, "Campos ’19");
-    map.insert(44, "Carlin ’19");
-    map.insert(45, "Sauber Junior Charouz ’19");
-    map.insert(46, "Dams ’19");
-    map.insert(47, "Uni-Virtuosi ‘19");
-    map.insert(48, "MP Motorsport ‘19");
-    map.insert(49, "Prema ’19");
-    map.insert(50, "Trident ’19");
-    map.insert(51, "Arden ’19");
-    map.insert(63, "Ferrari 1990");
-    map.insert(64, "McLaren 2010");
-    map.insert(65, "Ferrari 2010");
-}   
-
-pub fn init_drivers(map: &mut HashMap<usize, &str>) {
-    map.insert(0,  "Carlos Sainz");
-    map.insert(37, "Peter Belousov");
-    map.insert(1,  "Daniil Kvyat");
-    map.insert(38, "Klimek Michalski");
-    map.insert(70, "Rashid Nair");
-    map.insert(2,  "Daniel Ricciardo");
-    map.insert(39, "Santiago Moreno");
-    map.insert(71, "Jack Tremblay");
-    map.insert(6,  "Kimi Räikkönen");
-    map.insert(40, "Benjamin Coppens");
-    map.insert(74, "Antonio Giovinazzi");
-    map.insert(7,  "Lewis Hamilton");
-    map.insert(41, "Noah Visser");
-    map.insert(75, "Robert Kubica");
-    map.insert(9,  "Max Verstappen");
-    map.insert(42, "Gert Waldmuller");
-    map.insert(78, "Nobuharu Matsushita");
-    map.insert(10, "Nico Hulkenburg");
-    map.insert(43, "Julian Quesada");
-    map.insert(79, "Nikita Mazepin");
-    map.insert(11, "Kevin Magnussen");
-    map.insert(44, "Daniel Jones");
-    map.insert(80, "Guanya Zhou");
-    map.insert(12, "Romain Grosjean");
-    map.insert(45, "Artem Markelov");
-    map.insert(81, "Mick Schumacher");
-    map.insert(13, "Sebastian Vettel");
-    map.insert(46, "Tadasuke Makino");
-    map.insert(82, "Callum Ilott");
-    map.insert(14, "Sergio Perez");
-    map.insert(47, "Sean Gelael");
-    map.insert(83, "Juan Manuel Correa");
-    map.insert(15, "Valtteri Bottas");
-    map.insert(48, "Nyck De Vries");
-    map.insert(84, "Jordan King");
-    map.insert(19, "Lance Stroll");
-    map.insert(49, "Jack Aitken");
-    map.insert(85, "Mahaveer Raghunathan");
-    map.insert(20, "Arron Barnes");
-    map.insert(50, "George Russell");
-    map.insert(86, "Tatiana Calderon");
-    map.insert(21, "Martin Giles");
-    map.insert(51, "Maximilian Günther");
-    map.insert(87, "Anthoine Hubert");
-    map.insert(22, "Alex Murray");
-    map.insert(52, "Nirei Fukuzumi");
-    map.insert(88, "Guiliano Alesi");
-    map.insert(23, "Lucas Roth");
-    map.insert(53, "Luca Ghiotto");
-    map.insert(89, "Ralph Boschung");
-    map.insert(24, "Igor Correia");
-    map.insert(54, "Lando Norris");
-    map.insert(25, "Sophie Levasseur");
-    map.insert(55, "Sérgio Sette Câmara");
-    map.insert(26, "Jonas Schiffer");
-    map.insert(56, "Louis Delétraz");
-    map.insert(27, "Alain Forest");
-    map.insert(57, "Antonio Fuoco");
-    map.insert(28, "Jay Letourneau");
-    map.insert(58, "Charles Leclerc");
-    map.insert(29, "Esto Saari");
-    map.insert(59, "Pierre Gasly");
-    map.insert(30, "Yasar Atiyeh");
-    map.insert(62, "Alexander Albon");
-    map.insert(31, "Callisto Calabresi");
-    map.insert(63, "Nicholas Latifi");
-    map.insert(32, "Naota Izum");
-    map.insert(64, "Dorian Boccolacci");
-    map.insert(33, "Howard Clarke");
-    map.insert(65, "Niko Kari");
-    map.insert(34, "Wilheim Kaufmann");
-    map.insert(66, "Roberto Merhi");
-    map.insert(35, "Marie Laursen");
-    map.insert(67, "Arjun Maini");
-    map.insert(36, "Flavio Nieves");
-    map.insert(68, "Alessio Lorandi"); 
-    map.insert(69, "Ruben Meijer");
-}
-
-pub fn init_tracks(map: &mut HashMap<usize, &str>) {
-    map.insert(0 , "Melbourne");
-    map.insert(1 , "Paul Ricard");
-    map.insert(2 , "Shanghai");
-    map.insert(3 , "Sakhir Bahrain");
-    map.insert(4 , "Catalunya");
-    map.insert(5 , "Monaco");
-    map.insert(6 , "Montreal");
-    map.insert(7 , "Silverstone");
-    map.insert(8 , "Hockenheim");
-    map.insert(9 , "Hungaroring");
-    map.insert(10, "Spa");
-    map.insert(11, "Monza");
-    map.insert(12, "Singapore");
-    map.insert(13, "Suzuka");
-    map.insert(14, "Abu Dhabi");
-    map.insert(15, "Texas");
-    map.insert(16, "Brazil");
-    map.insert(17, "Austria");
-    map.insert(18, "Sochi");
-    map.insert(19, "Mexico");
-    map.insert(20, "Baku Azerbaijan");
-    map.insert(21, "Sakhir Short");
-    map.insert(22, "Silverstone Short");
-    map.insert(23, "Texas Short");
-    map.insert(24, "Suzuka Short");
-}
-
-pub fn init_countries(map: &mut HashMap<usize, &str>) { 
-    map.insert(1 ,  "American");
-    map.insert(31,  "Greek");
-    map.insert(61,  "Panamanian");
-    map.insert(2 ,  "Argentinean");
-    map.insert(32,  "Guatemalan");
-    map.insert(62,  "Paraguayan");
-    map.insert(3 ,  "Australian");
-    map.insert(33,  "Honduran");
-    map.insert(63,  "Peruvian");
-    map.insert(4 ,  "Austrian");
-    map.insert(34,  "Hong Konger");
-    map.insert(64,  "Polish");
-    map.insert(5 ,  "Azerbaijani");
-    map.insert(35,  "Hungarian");
-    map.insert(65,  "Portuguese");
-    map.insert(6 ,  "Bahraini");
-    map.insert(36,  "Icelander");
-    map.insert(66,  "Qatari");
-    map.insert(7 ,  "Belgian");
-    map.insert(37,  "Indian");
-    map.insert(67,  "Romanian");
-    map.insert(8 ,  "Bolivian");
-    map.insert(38,  "Indonesian");
-    map.insert(68,  "Russian");
-    map.insert(9 ,  "Brazilian");
-    map.insert(39,  "Irish");
-    map.insert(69,  "Salvadoran");
-    map.insert(10,  "British");
-    map.insert(40,  "Israeli");
-    map.insert(70,  "Saudi");
-    map.insert(11,  "Bulgarian");
-    map.insert(41,  "Italian");
-    map.insert(71,  "Scottish");
-    map.insert(12,  "Cameroonian");
-    map.insert(42,  "Jamaican");
-    map.insert(72,  "Serbian");
-    map.insert(13,  "Canadian");
-    map.insert(43,  "Japanese");
-    map.insert(73,  "Singaporean");
-    map.insert(14,  "Chilean");
-    map.insert(44,  "Jordanian");
-    map.insert(74,  "Slovakian");
-    map.insert(15,  "Chinese");
-    map.insert(45,  "Kuwaiti");
-    map.insert(75,  "Slovenian");
-    map.insert(16,  "Colombian");
-    map.insert(46,  "Latvian");
-    map.insert(76,  "South Korean");
-    map.insert(17,  "Costa Rican");
-    map.insert(47,  "Lebanese");
-    map.insert(77,  "South African");
-    map.insert(18,  "Croatian");
-    map.insert(48,  "Lithuanian");
-    map.insert(78,  "Spanish");
-    map.insert(19,  "Cypriot");
-    map.insert(49,  "Luxembourger");
-    map.insert(79,  "Swedish");
-    map.insert(20,  "Czech");
-    map.insert(50,  "Malaysian");
-    map.insert(80,  "Swiss");
-    map.insert(21,  "Danish");
-    map.insert(51,  "Maltese");
-    map.insert(81,  "Thai");
-    map.insert(22,  "Dutch");
-    map.insert(52,  "Mexican");
-    map.insert(82,  "Turkish");
-    map.insert(23,  "Ecuadorian");
-    map.insert(53,  "Monegasque");
-    map.insert(83,  "Uruguayan");
-    map.insert(24,  "English");
-    map.insert(54,  "New Zealander");
-    map.insert(84,  "Ukrainian");
-    map.insert(25,  "Emirian");
-    map.insert(55,  "Nicaraguan");
-    map.insert(85,  "Venezuelan");
-    map.insert(26,  "Estonian");
-    map.insert(56,  "North Korean");
-    map.insert(86,  "Welsh");
-    map.insert(27,  "Finnish");
-    map.insert(57,  "Northern Irish");
-    map.insert(28,  "French");
-    map.insert(58,  "Norwegian");
-    map.insert(29,  "German");
-    map.insert(59,  "Omani");
-    map.insert(30,  "Ghanaian");
-    map.insert(60,  "Pakistani");
-}
-
-pub fn init_surfaces(map: &mut HashMap<usize, &str>) {
-    map.insert(0 , "Tarmac");
-    map.insert(1 , "Rumble strip");
-    map.insert(2 , "Concrete");
-    map.insert(3 , "Rock");
-    map.insert(4 , "Gravel");
-    map.insert(5 , "Mud");
-    map.insert(6 , "Sand");
-    map.insert(7 , "Grass");
-    map.insert(8 , "Water");
-    map.insert(9 , "Cobblestone");
-    map.insert(10, "Metal"); 
-    map.insert(11, "Ridged");
-}
-
-pub fn init_button_flags(map: &mut HashMap<&str, &str>) { //buttons being pressed by player
-    map.insert("0x0001",  "Cross or A");
-    map.insert("0x0002",  "Triangle or Y");
-    map.insert("0x0004",  "Circle or B");
-    map.insert("0x0008",  "Square or X");
-    map.insert("0x0010",  "D-pad Left");
-    map.insert("0x0020",  "D-pad Right");
-    map.insert("0x0040",  "D-pad Up");
-    map.insert("0x0080",  "D-pad Down");
-    map.insert("0x0100",  "Options or Menu");
-    map.insert("0x0200",  "L1 or LB");
-    map.insert("0x0400",  "R1 or RB");
-    map.insert("0x0800",  "L2 or LT");
-    map.insert("0x1000",  "R2 or RT");
-    map.insert("0x2000",  "Left Stick Click");
-    map.insert("0x4000",  "Right Stick Click");
-}  
\ No newline at end of file
+impl MotionData {
+    // Player car velocity in its own frame
+    pub fn local_velocity(&self) -> Vec3 {
+        Vec3::new(self.local_vel_x, self.local_vel_y, self.local_vel_z)
+    }
+
+    pub fn angular_velocity(&self) -> Vec3 {
+        Vec3::new(self.angular_vel_x, self.angular_vel_y, self.angular_vel_z)
+    }
+
+    pub fn angular_acceleration(&self) -> Vec3 {
+        Vec3::new(self.angular_acc_x, self.angular_acc_y, self.angular_acc_z)
+    }
+}
+
+//-------------Final Classification Packet (F1 2020 onwards)
+//This packet details the final classification at the end of the race.
+//Frequency: Once at the end of a race
+//Size: 839 bytes
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FinalClassificationData {
+    pub position:           u8,         // Finishing position
+    pub num_laps:           u8,         // Number of laps completed
+    pub grid_position:      u8,         // Grid position of the car
+    pub points:             u8,         // Number of points scored
+    pub num_pit_stops:      u8,         // Number of pit stops made
+    pub result_status:      ResultStatus, // Same values as LapData.result_status
+    pub best_lap_time:      f32,        // Best lap time of the session in seconds
+    pub total_race_time:    f64,        // Total race time in seconds without penalties
+    pub penalties_time:     u8,         // Total penalties accumulated in seconds
+    pub num_penalties:      u8,         // Number of penalties applied to this driver
+    pub num_tyre_stints:    u8,         // Number of tyres stints up to maximum
+    pub tyre_stints_actual: [u8; 8],    // Actual tyres used by this driver
+    pub tyre_stints_visual: [u8; 8],    // Visual tyres used by this driver
+}
+
+#[derive(Debug)]
+pub struct FinalClassification {
+    pub header:                 PacketHeader,
+    pub num_cars:               u8,                             // Number of cars in the final classification
+    pub classification_data:    Vec<FinalClassificationData>,
+}
+
+//-------------Lobby Info Packet (F1 2020 onwards)
+//This packet details the players currently in a multiplayer lobby.
+//Frequency: Two every second when in the lobby
+//Size: 1169 bytes
+#[derive(Clone, Copy)]
+pub struct LobbyInfoData {
+    pub ai_controlled:  u8,         // Whether the vehicle is AI (1) or Human (0) controlled
+    pub team_id:        u8,         // Team id - see appendix (255 if no team currently selected)
+    pub nationality:    u8,         // Nationality of the driver
+    pub name:           [u8; 48],   // Name of participant in UTF-8 format – null terminated
+    pub ready_status:   u8,         // 0 = not ready, 1 = ready, 2 = spectating
+}
+
+impl LobbyInfoData {
+    pub fn new() -> Self {
+        LobbyInfoData {
+            ai_controlled:  0,
+            team_id:        0,
+            nationality:    0,
+            name:           [0u8; 48],
+            ready_status:   0,
+        }
+    }
+
+    pub fn name(&self) -> Cow<'_, str> {
+        decode_name(&self.name)
+    }
+
+    // None until a team is picked
+    pub fn team(&self) -> Option<&'static Team> {
+        Team::from_id(self.team_id)
+    }
+
+    pub fn nationality(&self) -> Option<&'static Nationality> {
+        Nationality::from_id(self.nationality)
+    }
+}
+
+impl Default for LobbyInfoData {
+    fn default() -> Self {
+        LobbyInfoData::new()
+    }
+}
+
+impl fmt::Debug for LobbyInfoData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LobbyInfoData")
+            .field("ai_controlled", &self.ai_controlled)
+            .field("team_id", &self.team_id)
+            .field("nationality", &self.nationality)
+            .field("name", &self.name())
+            .field("ready_status", &self.ready_status)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct LobbyInfo {
+    pub header:         PacketHeader,
+    pub num_players:    u8,                     // Number of players in the lobby data
+    pub lobby_players:  Vec<LobbyInfoData>,
+}
+
+// Coded values. A number the tables below don't list, from a later game or a corrupt datagram,
+// decodes to Other holding the number sent and encodes back to it, rather than failing the
+// whole packet over one car's field.
+
+// Weather in the session packet and forecasts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    #[default]
+    Clear,
+    LightCloud,
+    Overcast,
+    LightRain,
+    HeavyRain,
+    Storm,
+    Other(u8),
+}
+
+impl From<u8> for Weather {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => Weather::Clear,
+            1   => Weather::LightCloud,
+            2   => Weather::Overcast,
+            3   => Weather::LightRain,
+            4   => Weather::HeavyRain,
+            5   => Weather::Storm,
+            _   => Weather::Other(value),
+        }
+    }
+}
+
+impl From<Weather> for u8 {
+    fn from(value: Weather) -> Self {
+        match value {
+            Weather::Clear        => 0,
+            Weather::LightCloud   => 1,
+            Weather::Overcast     => 2,
+            Weather::LightRain    => 3,
+            Weather::HeavyRain    => 4,
+            Weather::Storm        => 5,
+            Weather::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Weather::Clear        => "clear",
+            Weather::LightCloud   => "light cloud",
+            Weather::Overcast     => "overcast",
+            Weather::LightRain    => "light rain",
+            Weather::HeavyRain    => "heavy rain",
+            Weather::Storm        => "storm",
+            Weather::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SessionType {
+    #[default]
+    Unknown,
+    P1,
+    P2,
+    P3,
+    ShortPractice,
+    Q1,
+    Q2,
+    Q3,
+    ShortQualifying,
+    OneShotQualifying,
+    Race,
+    Race2,
+    TimeTrial,
+    Other(u8),
+}
+
+impl From<u8> for SessionType {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => SessionType::Unknown,
+            1   => SessionType::P1,
+            2   => SessionType::P2,
+            3   => SessionType::P3,
+            4   => SessionType::ShortPractice,
+            5   => SessionType::Q1,
+            6   => SessionType::Q2,
+            7   => SessionType::Q3,
+            8   => SessionType::ShortQualifying,
+            9   => SessionType::OneShotQualifying,
+            10  => SessionType::Race,
+            11  => SessionType::Race2,
+            12  => SessionType::TimeTrial,
+            _   => SessionType::Other(value),
+        }
+    }
+}
+
+impl From<SessionType> for u8 {
+    fn from(value: SessionType) -> Self {
+        match value {
+            SessionType::Unknown           => 0,
+            SessionType::P1                => 1,
+            SessionType::P2                => 2,
+            SessionType::P3                => 3,
+            SessionType::ShortPractice     => 4,
+            SessionType::Q1                => 5,
+            SessionType::Q2                => 6,
+            SessionType::Q3                => 7,
+            SessionType::ShortQualifying   => 8,
+            SessionType::OneShotQualifying => 9,
+            SessionType::Race              => 10,
+            SessionType::Race2             => 11,
+            SessionType::TimeTrial         => 12,
+            SessionType::Other(value)      => value,
+        }
+    }
+}
+
+impl fmt::Display for SessionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SessionType::Unknown           => "unknown",
+            SessionType::P1                => "P1",
+            SessionType::P2                => "P2",
+            SessionType::P3                => "P3",
+            SessionType::ShortPractice     => "short practice",
+            SessionType::Q1                => "Q1",
+            SessionType::Q2                => "Q2",
+            SessionType::Q3                => "Q3",
+            SessionType::ShortQualifying   => "short qualifying",
+            SessionType::OneShotQualifying => "one shot qualifying",
+            SessionType::Race              => "race",
+            SessionType::Race2             => "race 2",
+            SessionType::TimeTrial         => "time trial",
+            SessionType::Other(value)      => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// 2018 only sends modern or classic
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Formula {
+    #[default]
+    F1Modern,
+    F1Classic,
+    F2,
+    F1Generic,
+    Other(u8),
+}
+
+impl From<u8> for Formula {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => Formula::F1Modern,
+            1   => Formula::F1Classic,
+            2   => Formula::F2,
+            3   => Formula::F1Generic,
+            _   => Formula::Other(value),
+        }
+    }
+}
+
+impl From<Formula> for u8 {
+    fn from(value: Formula) -> Self {
+        match value {
+            Formula::F1Modern     => 0,
+            Formula::F1Classic    => 1,
+            Formula::F2           => 2,
+            Formula::F1Generic    => 3,
+            Formula::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Formula::F1Modern     => "F1 modern",
+            Formula::F1Classic    => "F1 classic",
+            Formula::F2           => "F2",
+            Formula::F1Generic    => "F1 generic",
+            Formula::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SafetyCarStatus {
+    #[default]
+    NoSafetyCar,
+    Full,
+    Virtual,
+    Other(u8),
+}
+
+impl From<u8> for SafetyCarStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => SafetyCarStatus::NoSafetyCar,
+            1   => SafetyCarStatus::Full,
+            2   => SafetyCarStatus::Virtual,
+            _   => SafetyCarStatus::Other(value),
+        }
+    }
+}
+
+impl From<SafetyCarStatus> for u8 {
+    fn from(value: SafetyCarStatus) -> Self {
+        match value {
+            SafetyCarStatus::NoSafetyCar  => 0,
+            SafetyCarStatus::Full         => 1,
+            SafetyCarStatus::Virtual      => 2,
+            SafetyCarStatus::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for SafetyCarStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SafetyCarStatus::NoSafetyCar  => "no safety car",
+            SafetyCarStatus::Full         => "full safety car",
+            SafetyCarStatus::Virtual      => "virtual safety car",
+            SafetyCarStatus::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PitStatus {
+    #[default]
+    None,
+    Pitting,
+    InPitArea,
+    Other(u8),
+}
+
+impl From<u8> for PitStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => PitStatus::None,
+            1   => PitStatus::Pitting,
+            2   => PitStatus::InPitArea,
+            _   => PitStatus::Other(value),
+        }
+    }
+}
+
+impl From<PitStatus> for u8 {
+    fn from(value: PitStatus) -> Self {
+        match value {
+            PitStatus::None         => 0,
+            PitStatus::Pitting      => 1,
+            PitStatus::InPitArea    => 2,
+            PitStatus::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for PitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PitStatus::None         => "none",
+            PitStatus::Pitting      => "pitting",
+            PitStatus::InPitArea    => "in pit area",
+            PitStatus::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DriverStatus {
+    #[default]
+    InGarage,
+    FlyingLap,
+    InLap,
+    OutLap,
+    OnTrack,
+    Other(u8),
+}
+
+impl From<u8> for DriverStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => DriverStatus::InGarage,
+            1   => DriverStatus::FlyingLap,
+            2   => DriverStatus::InLap,
+            3   => DriverStatus::OutLap,
+            4   => DriverStatus::OnTrack,
+            _   => DriverStatus::Other(value),
+        }
+    }
+}
+
+impl From<DriverStatus> for u8 {
+    fn from(value: DriverStatus) -> Self {
+        match value {
+            DriverStatus::InGarage     => 0,
+            DriverStatus::FlyingLap    => 1,
+            DriverStatus::InLap        => 2,
+            DriverStatus::OutLap       => 3,
+            DriverStatus::OnTrack      => 4,
+            DriverStatus::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for DriverStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            DriverStatus::InGarage     => "in garage",
+            DriverStatus::FlyingLap    => "flying lap",
+            DriverStatus::InLap        => "in lap",
+            DriverStatus::OutLap       => "out lap",
+            DriverStatus::OnTrack      => "on track",
+            DriverStatus::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResultStatus {
+    #[default]
+    Invalid,
+    Inactive,
+    Active,
+    Finished,
+    Disqualified,
+    NotClassified,
+    Retired,
+    Other(u8),
+}
+
+impl From<u8> for ResultStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => ResultStatus::Invalid,
+            1   => ResultStatus::Inactive,
+            2   => ResultStatus::Active,
+            3   => ResultStatus::Finished,
+            4   => ResultStatus::Disqualified,
+            5   => ResultStatus::NotClassified,
+            6   => ResultStatus::Retired,
+            _   => ResultStatus::Other(value),
+        }
+    }
+}
+
+impl From<ResultStatus> for u8 {
+    fn from(value: ResultStatus) -> Self {
+        match value {
+            ResultStatus::Invalid       => 0,
+            ResultStatus::Inactive      => 1,
+            ResultStatus::Active        => 2,
+            ResultStatus::Finished      => 3,
+            ResultStatus::Disqualified  => 4,
+            ResultStatus::NotClassified => 5,
+            ResultStatus::Retired       => 6,
+            ResultStatus::Other(value)  => value,
+        }
+    }
+}
+
+impl fmt::Display for ResultStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ResultStatus::Invalid       => "invalid",
+            ResultStatus::Inactive      => "inactive",
+            ResultStatus::Active        => "active",
+            ResultStatus::Finished      => "finished",
+            ResultStatus::Disqualified  => "disqualified",
+            ResultStatus::NotClassified => "not classified",
+            ResultStatus::Retired       => "retired",
+            ResultStatus::Other(value)  => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FuelMix {
+    #[default]
+    Lean,
+    Standard,
+    Rich,
+    Max,
+    Other(u8),
+}
+
+impl From<u8> for FuelMix {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => FuelMix::Lean,
+            1   => FuelMix::Standard,
+            2   => FuelMix::Rich,
+            3   => FuelMix::Max,
+            _   => FuelMix::Other(value),
+        }
+    }
+}
+
+impl From<FuelMix> for u8 {
+    fn from(value: FuelMix) -> Self {
+        match value {
+            FuelMix::Lean         => 0,
+            FuelMix::Standard     => 1,
+            FuelMix::Rich         => 2,
+            FuelMix::Max          => 3,
+            FuelMix::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for FuelMix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FuelMix::Lean         => "lean",
+            FuelMix::Standard     => "standard",
+            FuelMix::Rich         => "rich",
+            FuelMix::Max          => "max",
+            FuelMix::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// 2018 and 2019 values, 2020 dropped low and high and renumbered - see from_2020
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErsDeployMode {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Overtake,
+    Hotlap,
+    Other(u8),
+}
+
+impl From<u8> for ErsDeployMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => ErsDeployMode::None,
+            1   => ErsDeployMode::Low,
+            2   => ErsDeployMode::Medium,
+            3   => ErsDeployMode::High,
+            4   => ErsDeployMode::Overtake,
+            5   => ErsDeployMode::Hotlap,
+            _   => ErsDeployMode::Other(value),
+        }
+    }
+}
+
+impl From<ErsDeployMode> for u8 {
+    fn from(value: ErsDeployMode) -> Self {
+        match value {
+            ErsDeployMode::None         => 0,
+            ErsDeployMode::Low          => 1,
+            ErsDeployMode::Medium       => 2,
+            ErsDeployMode::High         => 3,
+            ErsDeployMode::Overtake     => 4,
+            ErsDeployMode::Hotlap       => 5,
+            ErsDeployMode::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for ErsDeployMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ErsDeployMode::None         => "none",
+            ErsDeployMode::Low          => "low",
+            ErsDeployMode::Medium       => "medium",
+            ErsDeployMode::High         => "high",
+            ErsDeployMode::Overtake     => "overtake",
+            ErsDeployMode::Hotlap       => "hotlap",
+            ErsDeployMode::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Actual compound fitted. 0-8 are the 2018 ids for modern cars. 2018 classic cars send 0-6 for
+// dry and 7-8 for wet, which can't be told apart from the modern ids so they decode as modern
+// compounds too. 2019 gives classic cars their own ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TyreCompound {
+    HyperSoft,
+    UltraSoft,
+    SuperSoft,
+    Soft,
+    Medium,
+    Hard,
+    SuperHard,
+    Inter,
+    Wet,
+    ClassicDry,
+    ClassicWet,
+    F2SuperSoft,
+    F2Soft,
+    F2Medium,
+    F2Hard,
+    F2Wet,
+    C5,
+    C4,
+    C3,
+    C2,
+    C1,
+    Other(u8),
+}
+
+// 255 isn't sent by any edition, so a status that wasn't filled in doesn't read as a real compound
+impl Default for TyreCompound {
+    fn default() -> Self {
+        TyreCompound::Other(255)
+    }
+}
+
+impl From<u8> for TyreCompound {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => TyreCompound::HyperSoft,
+            1   => TyreCompound::UltraSoft,
+            2   => TyreCompound::SuperSoft,
+            3   => TyreCompound::Soft,
+            4   => TyreCompound::Medium,
+            5   => TyreCompound::Hard,
+            6   => TyreCompound::SuperHard,
+            7   => TyreCompound::Inter,
+            8   => TyreCompound::Wet,
+            9   => TyreCompound::ClassicDry,
+            10  => TyreCompound::ClassicWet,
+            11  => TyreCompound::F2SuperSoft,
+            12  => TyreCompound::F2Soft,
+            13  => TyreCompound::F2Medium,
+            14  => TyreCompound::F2Hard,
+            15  => TyreCompound::F2Wet,
+            16  => TyreCompound::C5,
+            17  => TyreCompound::C4,
+            18  => TyreCompound::C3,
+            19  => TyreCompound::C2,
+            20  => TyreCompound::C1,
+            _   => TyreCompound::Other(value),
+        }
+    }
+}
+
+impl From<TyreCompound> for u8 {
+    fn from(value: TyreCompound) -> Self {
+        match value {
+            TyreCompound::HyperSoft    => 0,
+            TyreCompound::UltraSoft    => 1,
+            TyreCompound::SuperSoft    => 2,
+            TyreCompound::Soft         => 3,
+            TyreCompound::Medium       => 4,
+            TyreCompound::Hard         => 5,
+            TyreCompound::SuperHard    => 6,
+            TyreCompound::Inter        => 7,
+            TyreCompound::Wet          => 8,
+            TyreCompound::ClassicDry   => 9,
+            TyreCompound::ClassicWet   => 10,
+            TyreCompound::F2SuperSoft  => 11,
+            TyreCompound::F2Soft       => 12,
+            TyreCompound::F2Medium     => 13,
+            TyreCompound::F2Hard       => 14,
+            TyreCompound::F2Wet        => 15,
+            TyreCompound::C5           => 16,
+            TyreCompound::C4           => 17,
+            TyreCompound::C3           => 18,
+            TyreCompound::C2           => 19,
+            TyreCompound::C1           => 20,
+            TyreCompound::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for TyreCompound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TyreCompound::HyperSoft    => "hyper soft",
+            TyreCompound::UltraSoft    => "ultra soft",
+            TyreCompound::SuperSoft    => "super soft",
+            TyreCompound::Soft         => "soft",
+            TyreCompound::Medium       => "medium",
+            TyreCompound::Hard         => "hard",
+            TyreCompound::SuperHard    => "super hard",
+            TyreCompound::Inter        => "inter",
+            TyreCompound::Wet          => "wet",
+            TyreCompound::ClassicDry   => "classic dry",
+            TyreCompound::ClassicWet   => "classic wet",
+            TyreCompound::F2SuperSoft  => "F2 super soft",
+            TyreCompound::F2Soft       => "F2 soft",
+            TyreCompound::F2Medium     => "F2 medium",
+            TyreCompound::F2Hard       => "F2 hard",
+            TyreCompound::F2Wet        => "F2 wet",
+            TyreCompound::C5           => "C5",
+            TyreCompound::C4           => "C4",
+            TyreCompound::C3           => "C3",
+            TyreCompound::C2           => "C2",
+            TyreCompound::C1           => "C1",
+            TyreCompound::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Compound shown in game, can be different from the actual compound. 2018 only sent the actual
+// compound, see from_2018. F2 specific visual compounds are 2020 onwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisualTyreCompound {
+    HyperSoft,
+    UltraSoft,
+    SuperSoft,
+    SuperHard,
+    Inter,
+    Wet,
+    F2Wet,
+    Soft,
+    Medium,
+    Hard,
+    F2SuperSoft,
+    F2Soft,
+    F2Medium,
+    F2Hard,
+    Other(u8),
+}
+
+// Same as TyreCompound
+impl Default for VisualTyreCompound {
+    fn default() -> Self {
+        VisualTyreCompound::Other(255)
+    }
+}
+
+impl From<u8> for VisualTyreCompound {
+    fn from(value: u8) -> Self {
+        match value {
+            0   => VisualTyreCompound::HyperSoft,
+            1   => VisualTyreCompound::UltraSoft,
+            2   => VisualTyreCompound::SuperSoft,
+            6   => VisualTyreCompound::SuperHard,
+            7   => VisualTyreCompound::Inter,
+            8   => VisualTyreCompound::Wet,
+            15  => VisualTyreCompound::F2Wet,
+            16  => VisualTyreCompound::Soft,
+            17  => VisualTyreCompound::Medium,
+            18  => VisualTyreCompound::Hard,
+            19  => VisualTyreCompound::F2SuperSoft,
+            20  => VisualTyreCompound::F2Soft,
+            21  => VisualTyreCompound::F2Medium,
+            22  => VisualTyreCompound::F2Hard,
+            _   => VisualTyreCompound::Other(value),
+        }
+    }
+}
+
+impl From<VisualTyreCompound> for u8 {
+    fn from(value: VisualTyreCompound) -> Self {
+        match value {
+            VisualTyreCompound::HyperSoft    => 0,
+            VisualTyreCompound::UltraSoft    => 1,
+            VisualTyreCompound::SuperSoft    => 2,
+            VisualTyreCompound::SuperHard    => 6,
+            VisualTyreCompound::Inter        => 7,
+            VisualTyreCompound::Wet          => 8,
+            VisualTyreCompound::F2Wet        => 15,
+            VisualTyreCompound::Soft         => 16,
+            VisualTyreCompound::Medium       => 17,
+            VisualTyreCompound::Hard         => 18,
+            VisualTyreCompound::F2SuperSoft  => 19,
+            VisualTyreCompound::F2Soft       => 20,
+            VisualTyreCompound::F2Medium     => 21,
+            VisualTyreCompound::F2Hard       => 22,
+            VisualTyreCompound::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for VisualTyreCompound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            VisualTyreCompound::HyperSoft    => "hyper soft",
+            VisualTyreCompound::UltraSoft    => "ultra soft",
+            VisualTyreCompound::SuperSoft    => "super soft",
+            VisualTyreCompound::SuperHard    => "super hard",
+            VisualTyreCompound::Inter        => "inter",
+            VisualTyreCompound::Wet          => "wet",
+            VisualTyreCompound::F2Wet        => "F2 wet",
+            VisualTyreCompound::Soft         => "soft",
+            VisualTyreCompound::Medium       => "medium",
+            VisualTyreCompound::Hard         => "hard",
+            VisualTyreCompound::F2SuperSoft  => "F2 super soft",
+            VisualTyreCompound::F2Soft       => "F2 soft",
+            VisualTyreCompound::F2Medium     => "F2 medium",
+            VisualTyreCompound::F2Hard       => "F2 hard",
+            VisualTyreCompound::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Flag shown to a car or in a marshal zone
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FiaFlag {
+    Unknown,
+    #[default]
+    None,
+    Green,
+    Blue,
+    Yellow,
+    Red,
+    Other(i8),
+}
+
+impl From<i8> for FiaFlag {
+    fn from(value: i8) -> Self {
+        match value {
+            -1  => FiaFlag::Unknown,
+            0   => FiaFlag::None,
+            1   => FiaFlag::Green,
+            2   => FiaFlag::Blue,
+            3   => FiaFlag::Yellow,
+            4   => FiaFlag::Red,
+            _   => FiaFlag::Other(value),
+        }
+    }
+}
+
+impl From<FiaFlag> for i8 {
+    fn from(value: FiaFlag) -> Self {
+        match value {
+            FiaFlag::Unknown      => -1,
+            FiaFlag::None         => 0,
+            FiaFlag::Green        => 1,
+            FiaFlag::Blue         => 2,
+            FiaFlag::Yellow       => 3,
+            FiaFlag::Red          => 4,
+            FiaFlag::Other(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for FiaFlag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FiaFlag::Unknown      => "unknown",
+            FiaFlag::None         => "none",
+            FiaFlag::Green        => "green",
+            FiaFlag::Blue         => "blue",
+            FiaFlag::Yellow       => "yellow",
+            FiaFlag::Red          => "red",
+            FiaFlag::Other(value) => return write!(f, "other ({})", value),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl ErsDeployMode {
+    // 2020 sends 0 = none, 1 = medium, 2 = overtake, 3 = hotlap. Other keeps the 2020 number
+    pub fn from_2020(value: u8) -> Self {
+        match value {
+            0   => ErsDeployMode::None,
+            1   => ErsDeployMode::Medium,
+            2   => ErsDeployMode::Overtake,
+            3   => ErsDeployMode::Hotlap,
+            _   => ErsDeployMode::Other(value),
+        }
+    }
+}
+
+impl VisualTyreCompound {
+    // 2018 only sends the actual compound, its soft, medium and hard are the ones shown in game
+    pub fn from_2018(value: u8) -> Self {
+        match value {
+            3   => VisualTyreCompound::Soft,
+            4   => VisualTyreCompound::Medium,
+            5   => VisualTyreCompound::Hard,
+            _   => VisualTyreCompound::from(value),
+        }
+    }
+}
+
+// Telemetry.button_status, one bit per button held down when the packet was sent.
+// Single buttons are the associated constants, combine them with | to test for several.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ButtonStatus(pub u32);
+
+impl ButtonStatus {
+    pub const CROSS: ButtonStatus              = ButtonStatus(0x0001);   // Cross or A
+    pub const TRIANGLE: ButtonStatus           = ButtonStatus(0x0002);   // Triangle or Y
+    pub const CIRCLE: ButtonStatus             = ButtonStatus(0x0004);   // Circle or B
+    pub const SQUARE: ButtonStatus             = ButtonStatus(0x0008);   // Square or X
+    pub const DPAD_LEFT: ButtonStatus          = ButtonStatus(0x0010);
+    pub const DPAD_RIGHT: ButtonStatus         = ButtonStatus(0x0020);
+    pub const DPAD_UP: ButtonStatus            = ButtonStatus(0x0040);
+    pub const DPAD_DOWN: ButtonStatus          = ButtonStatus(0x0080);
+    pub const OPTIONS: ButtonStatus            = ButtonStatus(0x0100);   // Options or Menu
+    pub const L1: ButtonStatus                 = ButtonStatus(0x0200);   // L1 or LB
+    pub const R1: ButtonStatus                 = ButtonStatus(0x0400);   // R1 or RB
+    pub const L2: ButtonStatus                 = ButtonStatus(0x0800);   // L2 or LT
+    pub const R2: ButtonStatus                 = ButtonStatus(0x1000);   // R2 or RT
+    pub const LEFT_STICK_CLICK: ButtonStatus   = ButtonStatus(0x2000);
+    pub const RIGHT_STICK_CLICK: ButtonStatus  = ButtonStatus(0x4000);
+
+    // Xbox names for the same bits
+    pub const A: ButtonStatus  = ButtonStatus::CROSS;
+    pub const Y: ButtonStatus  = ButtonStatus::TRIANGLE;
+    pub const B: ButtonStatus  = ButtonStatus::CIRCLE;
+    pub const X: ButtonStatus  = ButtonStatus::SQUARE;
+    pub const MENU: ButtonStatus = ButtonStatus::OPTIONS;
+    pub const LB: ButtonStatus = ButtonStatus::L1;
+    pub const RB: ButtonStatus = ButtonStatus::R1;
+    pub const LT: ButtonStatus = ButtonStatus::L2;
+    pub const RT: ButtonStatus = ButtonStatus::R2;
+
+    pub const NONE: ButtonStatus = ButtonStatus(0);
+
+    // Every button in the appendix, in bit order
+    const BUTTONS: [(ButtonStatus, &'static str); 15] = [
+        (ButtonStatus::CROSS,             "Cross or A"),
+        (ButtonStatus::TRIANGLE,          "Triangle or Y"),
+        (ButtonStatus::CIRCLE,            "Circle or B"),
+        (ButtonStatus::SQUARE,            "Square or X"),
+        (ButtonStatus::DPAD_LEFT,         "D-pad Left"),
+        (ButtonStatus::DPAD_RIGHT,        "D-pad Right"),
+        (ButtonStatus::DPAD_UP,           "D-pad Up"),
+        (ButtonStatus::DPAD_DOWN,         "D-pad Down"),
+        (ButtonStatus::OPTIONS,           "Options or Menu"),
+        (ButtonStatus::L1,                "L1 or LB"),
+        (ButtonStatus::R1,                "R1 or RB"),
+        (ButtonStatus::L2,                "L2 or LT"),
+        (ButtonStatus::R2,                "R2 or RT"),
+        (ButtonStatus::LEFT_STICK_CLICK,  "Left Stick Click"),
+        (ButtonStatus::RIGHT_STICK_CLICK, "Right Stick Click"),
+    ];
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    // True if every button in other is held
+    pub fn contains(self, other: ButtonStatus) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    // Appendix name for a single button, None for combinations and unlisted bits
+    pub fn name(self) -> Option<&'static str> {
+        ButtonStatus::BUTTONS.iter()
+            .find(|(button, _)| *button == self)
+            .map(|(_, name)| *name)
+    }
+
+    // Each held button on its own, bits missing from the appendix are skipped
+    pub fn iter(self) -> impl Iterator<Item = ButtonStatus> {
+        ButtonStatus::BUTTONS.iter()
+            .map(|(button, _)| *button)
+            .filter(move |button| self.contains(*button))
+    }
+
+    // Buttons held now that weren't in previous
+    pub fn pressed_since(self, previous: ButtonStatus) -> ButtonStatus {
+        ButtonStatus(self.0 & !previous.0)
+    }
+
+    // Buttons held in previous that have been let go
+    pub fn released_since(self, previous: ButtonStatus) -> ButtonStatus {
+        ButtonStatus(previous.0 & !self.0)
+    }
+
+    // Presses then releases between two consecutive states, one event per button
+    pub fn changes_since(self, previous: ButtonStatus) -> impl Iterator<Item = ButtonEvent> {
+        self.pressed_since(previous).iter().map(ButtonEvent::Pressed)
+            .chain(self.released_since(previous).iter().map(ButtonEvent::Released))
+    }
+}
+
+impl std::ops::BitOr for ButtonStatus {
+    type Output = ButtonStatus;
+
+    fn bitor(self, other: ButtonStatus) -> ButtonStatus {
+        ButtonStatus(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitAnd for ButtonStatus {
+    type Output = ButtonStatus;
+
+    fn bitand(self, other: ButtonStatus) -> ButtonStatus {
+        ButtonStatus(self.0 & other.0)
+    }
+}
+
+impl From<u32> for ButtonStatus {
+    fn from(bits: u32) -> Self {
+        ButtonStatus(bits)
+    }
+}
+
+// Held buttons by name, comma separated
+impl fmt::Display for ButtonStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for button in self.iter() {
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", button.name().unwrap_or("unknown"))?;
+            first = false;
+        }
+        if first {
+            write!(f, "none")?;
+        }
+        Ok(())
+    }
+}
+
+// A single button changing state between two Telemetry packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonEvent {
+    Pressed(ButtonStatus),
+    Released(ButtonStatus),
+}
+
+// Any decoded packet. Returned by parse_packet so consumers can match on a single type
+// rather than calling the per packet parsers themselves.
+#[derive(Debug)]
+pub enum Packet {
+    Motion(MotionData),
+    Session(SessionData),
+    Lap(Lap),
+    Event(Event),
+    Participants(Participants),
+    CarSetups(CarSetups),
+    Telemetry(Telemetry),
+    CarStatus(CarStatusData),
+    FinalClassification(FinalClassification),
+    LobbyInfo(LobbyInfo),
+}
+
+impl Packet {
+    pub fn header(&self) -> &PacketHeader {
+        match self {
+            Packet::Motion(p)       => &p.header,
+            Packet::Session(p)      => &p.header,
+            Packet::Lap(p)          => &p.header,
+            Packet::Event(p)        => &p.header,
+            Packet::Participants(p) => &p.header,
+            Packet::CarSetups(p)    => &p.header,
+            Packet::Telemetry(p)    => &p.header,
+            Packet::CarStatus(p)    => &p.header,
+            Packet::FinalClassification(p) => &p.header,
+            Packet::LobbyInfo(p)    => &p.header,
+        }
+    }
+
+    pub fn packet_type(&self) -> PacketType {
+        self.header().packet_type
+    }
+}
@@ -0,0 +1,24 @@
+// Reading helpers shared by the file formats, captures, pcaps, indexes and archives.
+
+use std::io;
+use std::io::Read;
+
+pub fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+// Fills buf, false if inner was already at its end. Ending part way through buf is an
+// UnexpectedEof error carrying message.
+pub fn read_or_end<R: Read>(inner: &mut R, buf: &mut [u8], message: &str) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match inner.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, message.to_string())),
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
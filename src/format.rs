@@ -0,0 +1,99 @@
+use std::convert::TryFrom;
+
+use crate::error::ParseError;
+use crate::f1_2019_net::PacketType;
+use crate::parser::f1_2018;
+use crate::parser::f1_2019;
+use crate::parser::f1_2020;
+
+// Game editions we can decode, picked from packet_format, the first two bytes of every header.
+// Each edition decodes into the same f1_2019_net structs, fields an edition doesn't send are
+// left at their defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketFormat {
+    F2018,
+    F2019,
+    F2020,
+}
+
+impl PacketFormat {
+    pub fn year(&self) -> u16 {
+        match self {
+            PacketFormat::F2018 => 2018,
+            PacketFormat::F2019 => 2019,
+            PacketFormat::F2020 => 2020,
+        }
+    }
+
+    pub fn header_size(&self) -> usize {
+        match self {
+            PacketFormat::F2018 => f1_2018::HEADER_SIZE,
+            PacketFormat::F2019 => f1_2019::HEADER_SIZE,
+            PacketFormat::F2020 => f1_2020::HEADER_SIZE,
+        }
+    }
+
+    // Length of the per car arrays
+    pub fn num_cars(&self) -> usize {
+        match self {
+            PacketFormat::F2018 => f1_2018::NUM_CARS,
+            PacketFormat::F2019 => f1_2019::NUM_CARS,
+            PacketFormat::F2020 => f1_2020::NUM_CARS,
+        }
+    }
+
+    // Size to spec of a packet type in this edition, None if the edition doesn't send it.
+    // Events are the largest event for the edition, shorter ones may be sent.
+    pub fn packet_size(&self, packet_type: PacketType) -> Option<usize> {
+        match self {
+            PacketFormat::F2018 => match packet_type {
+                PacketType::Motion      => Some(f1_2018::MOTION_SIZE),
+                PacketType::Session     => Some(f1_2018::SESSION_SIZE),
+                PacketType::Lap         => Some(f1_2018::LAP_SIZE),
+                PacketType::Event       => Some(f1_2018::EVENT_SIZE),
+                PacketType::Participant => Some(f1_2018::PARTICIPANTS_SIZE),
+                PacketType::Setup       => Some(f1_2018::CARSETUPS_SIZE),
+                PacketType::Telemetry   => Some(f1_2018::TELEMETRY_SIZE),
+                PacketType::CarStatus   => Some(f1_2018::STATUS_SIZE),
+                _                       => None,
+            },
+            PacketFormat::F2019 => match packet_type {
+                PacketType::Motion      => Some(f1_2019::MOTION_SIZE),
+                PacketType::Session     => Some(f1_2019::SESSION_SIZE),
+                PacketType::Lap         => Some(f1_2019::LAP_SIZE),
+                PacketType::Event       => Some(f1_2019::EVENT_SIZE),
+                PacketType::Participant => Some(f1_2019::PARTICIPANTS_SIZE),
+                PacketType::Setup       => Some(f1_2019::CARSETUPS_SIZE),
+                PacketType::Telemetry   => Some(f1_2019::TELEMETY_SIZE),
+                PacketType::CarStatus   => Some(f1_2019::STATUS_SIZE),
+                _                       => None,
+            },
+            PacketFormat::F2020 => match packet_type {
+                PacketType::Motion      => Some(f1_2020::MOTION_SIZE),
+                PacketType::Session     => Some(f1_2020::SESSION_SIZE),
+                PacketType::Lap         => Some(f1_2020::LAP_SIZE),
+                PacketType::Event       => Some(f1_2020::EVENT_SIZE),
+                PacketType::Participant => Some(f1_2020::PARTICIPANTS_SIZE),
+                PacketType::Setup       => Some(f1_2020::CARSETUPS_SIZE),
+                PacketType::Telemetry   => Some(f1_2020::TELEMETRY_SIZE),
+                PacketType::CarStatus   => Some(f1_2020::STATUS_SIZE),
+                PacketType::FinalClassification => Some(f1_2020::FINAL_CLASSIFICATION_SIZE),
+                PacketType::LobbyInfo   => Some(f1_2020::LOBBY_INFO_SIZE),
+                PacketType::InvalidPacket => None,
+            },
+        }
+    }
+}
+
+impl TryFrom<u16> for PacketFormat {
+    type Error = ParseError;
+
+    fn try_from(packet_format: u16) -> Result<Self, Self::Error> {
+        match packet_format {
+            2018 => Ok(PacketFormat::F2018),
+            2019 => Ok(PacketFormat::F2019),
+            2020 => Ok(PacketFormat::F2020),
+            _ => Err(ParseError::UnsupportedFormat(packet_format)),
+        }
+    }
+}
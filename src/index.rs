@@ -0,0 +1,376 @@
+// Capture indexes, for finding a lap or a point in session time in an hour long capture without
+// reading up to it. An index sits beside its capture as <capture>.idx, written by record as it
+// goes or afterwards by the index command, with an entry per record that has a readable header.
+// All integers are little endian.
+//
+// File header, 16 bytes:
+//
+//     [u8; 8]  magic "F1UDPIDX"
+//     u16      format version, 1
+//     u16      header length in bytes, 16. Readers skip anything past what they know
+//     u32      reserved, 0
+//
+// Then one entry per record, in capture order:
+//
+//     u64      where the record starts in the capture
+//     u64      session_id
+//     f32      session_time
+//     u32      frame_id
+//     u8       packet type id
+//     u8       player's lap_num in the session's latest lap packet, 0 before the first
+//
+// An entry cut short at the end of the file, from a record command that was stopped, is ignored.
+// Seeks find the first record reaching the target the same as reading through the capture would,
+// by binary search over the latest session time and lap reached at each entry.
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::capture::CaptureReader;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::PacketType;
+use crate::file::invalid_data;
+use crate::file::read_or_end;
+use crate::parser::get_packet_type;
+use crate::parser::parse_header;
+use crate::parser::view::LapView;
+use crate::replay::SeekTarget;
+
+pub const MAGIC: [u8; 8] = *b"F1UDPIDX";
+pub const VERSION: u16 = 1;
+pub const FILE_HEADER_SIZE: usize = 16;
+pub const ENTRY_SIZE: usize = 26;
+
+// Where the index for a capture is looked for, the capture's path with .idx added
+pub fn sidecar_path(capture: &Path) -> PathBuf {
+    let mut path = OsString::from(capture.as_os_str());
+    path.push(".idx");
+    PathBuf::from(path)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexEntry {
+    pub position:       u64,        // Where the record starts in the capture
+    pub session_id:     u64,
+    pub session_time:   f32,
+    pub frame_id:       u32,
+    pub packet_type:    PacketType,
+    pub player_lap:     u8,         // From the session's latest lap packet, 0 before the first
+}
+
+impl IndexEntry {
+    fn to_bytes(self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0u8; ENTRY_SIZE];
+        bytes[..8].copy_from_slice(&self.position.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.session_id.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.session_time.to_le_bytes());
+        bytes[20..24].copy_from_slice(&self.frame_id.to_le_bytes());
+        bytes[24] = self.packet_type.id();
+        bytes[25] = self.player_lap;
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; ENTRY_SIZE]) -> io::Result<IndexEntry> {
+        let field = |start: usize| {
+            let mut field = [0u8; 8];
+            field.copy_from_slice(&bytes[start..start + 8]);
+            u64::from_le_bytes(field)
+        };
+        let packet_type = get_packet_type(bytes[24]).map_err(|_| invalid_data("index entry has an unknown packet type"))?;
+        Ok(IndexEntry {
+            position: field(0),
+            session_id: field(8),
+            session_time: f32::from_le_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]),
+            frame_id: u32::from_le_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]),
+            packet_type,
+            player_lap: bytes[25],
+        })
+    }
+}
+
+// Follows the player's lap through the records of a capture, the lap of each record being the
+// player's lap_num in the session's latest lap packet, 0 before the first
+#[derive(Debug, Default, Clone)]
+pub struct LapTracker {
+    session_id:     Option<u64>,
+    player_lap:     u8,
+}
+
+impl LapTracker {
+    pub fn new() -> Self {
+        LapTracker::default()
+    }
+
+    // The lap of the next record, whose header has been parsed from the datagram
+    pub fn player_lap(&mut self, header: &PacketHeader, datagram: &[u8]) -> u8 {
+        if self.session_id != Some(header.session_id) {
+            self.session_id = Some(header.session_id);
+            self.player_lap = 0;
+        }
+        if header.packet_type == PacketType::Lap {
+            if let Some(car) = LapView::new(datagram).ok().and_then(|view| view.player_car()) {
+                self.player_lap = car.lap_num();
+            }
+        }
+        self.player_lap
+    }
+
+    // None for datagrams without a readable header
+    fn entry(&mut self, position: u64, datagram: &[u8]) -> Option<IndexEntry> {
+        let header = parse_header(datagram).ok()?;
+        Some(IndexEntry {
+            position,
+            session_id: header.session_id,
+            session_time: header.session_time,
+            frame_id: header.frame_id,
+            packet_type: header.packet_type,
+            player_lap: self.player_lap(&header, datagram),
+        })
+    }
+}
+
+// An index held in memory for seeking
+#[derive(Debug, Default)]
+pub struct CaptureIndex {
+    entries:        Vec<IndexEntry>,
+    time_reached:   Vec<f32>,           // Latest session_time up to each entry
+    lap_reached:    Vec<Option<u8>>,    // Latest player lap in a lap packet up to each entry
+    sessions:       Vec<(u64, usize)>,  // Each session_id and its first entry, in capture order
+}
+
+impl CaptureIndex {
+    pub fn new() -> Self {
+        CaptureIndex::default()
+    }
+
+    // Indexes the records from the reader's position to the end of the capture
+    pub fn build<R: Read>(reader: &mut CaptureReader<R>) -> io::Result<Self> {
+        let mut index = CaptureIndex::new();
+        let mut tracker = LapTracker::default();
+        loop {
+            let position = reader.position();
+            match reader.next_record()? {
+                Some(record) => {
+                    if let Some(entry) = tracker.entry(position, record.datagram) {
+                        index.push(entry);
+                    }
+                },
+                None => return Ok(index),
+            }
+        }
+    }
+
+    // Reads an index file as written by IndexWriter
+    pub fn read<R: Read>(mut inner: R) -> io::Result<Self> {
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        inner.read_exact(&mut header).map_err(|_| invalid_data("not a capture index, too short for the header"))?;
+        if header[..8] != MAGIC {
+            return Err(invalid_data("not a capture index, bad magic"));
+        }
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        if version != VERSION {
+            return Err(invalid_data(&format!("unsupported capture index version {}", version)));
+        }
+        let header_len = u16::from_le_bytes([header[10], header[11]]) as usize;
+        if header_len < FILE_HEADER_SIZE {
+            return Err(invalid_data("capture index header length too small"));
+        }
+        io::copy(&mut (&mut inner).take((header_len - FILE_HEADER_SIZE) as u64), &mut io::sink())?;
+
+        let mut index = CaptureIndex::new();
+        let mut bytes = [0u8; ENTRY_SIZE];
+        loop {
+            match read_or_end(&mut inner, &mut bytes, "capture index ends part way through an entry") {
+                Ok(true) => index.push(IndexEntry::from_bytes(&bytes)?),
+                Ok(false) => return Ok(index),
+                // A record command stopped part way through writing an entry, the entries
+                // before it are still good
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(index),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // The index beside the capture if there is one
+    pub fn open(capture: &Path) -> io::Result<Option<Self>> {
+        match File::open(sidecar_path(capture)) {
+            Ok(file) => CaptureIndex::read(BufReader::new(file)).map(Some),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Entries must come in capture order
+    pub fn push(&mut self, entry: IndexEntry) {
+        let time_reached = self.time_reached.last().map_or(entry.session_time, |&reached| reached.max(entry.session_time));
+        let lap = if entry.packet_type == PacketType::Lap { Some(entry.player_lap) } else { None };
+        let lap_reached = self.lap_reached.last().map_or(lap, |&reached| reached.max(lap));
+        if self.sessions.iter().all(|&(session_id, _)| session_id != entry.session_id) {
+            self.sessions.push((entry.session_id, self.entries.len()));
+        }
+        self.entries.push(entry);
+        self.time_reached.push(time_reached);
+        self.lap_reached.push(lap_reached);
+    }
+
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Session ids in the order they first appear
+    pub fn sessions(&self) -> impl Iterator<Item = u64> + '_ {
+        self.sessions.iter().map(|&(session_id, _)| session_id)
+    }
+
+    // The first record reaching the target, None if no indexed record does
+    pub fn find(&self, target: SeekTarget) -> Option<&IndexEntry> {
+        let found = match target {
+            // NaN never reaches anything, as when comparing while reading through
+            SeekTarget::SessionTime(session_time) => self.time_reached.partition_point(|&reached| {
+                reached < session_time || reached.is_nan() || session_time.is_nan()
+            }),
+            SeekTarget::Lap(lap) => self.lap_reached.partition_point(|&reached| reached < Some(lap)),
+            SeekTarget::Session(wanted) => self.sessions.iter()
+                .find(|&&(session_id, _)| session_id == wanted)
+                .map_or(self.entries.len(), |&(_, first)| first),
+        };
+        self.entries.get(found)
+    }
+}
+
+// Moves the reader to the first record reaching the target. With an index that's looked up, and
+// if the index doesn't have it the capture is read on from the last record indexed in case the
+// index stops short. Without one the capture is read from the start. False if the capture never
+// reaches the target, the reader then being at the end.
+pub fn seek<R: Read + Seek>(reader: &mut CaptureReader<R>, index: Option<&CaptureIndex>, target: SeekTarget) -> io::Result<bool> {
+    let last = index.and_then(|index| {
+        index.find(target).map(|entry| (entry, true)).or_else(|| index.entries().last().map(|entry| (entry, false)))
+    });
+    match last {
+        Some((entry, found)) => {
+            reader.seek_to(entry.position)?;
+            check_entry(reader, entry)?;
+            reader.seek_to(entry.position)?;
+            if found {
+                return Ok(true);
+            }
+        },
+        None => reader.rewind()?,
+    }
+    loop {
+        let position = reader.position();
+        match reader.next_record()? {
+            Some(record) if target.reached(record.datagram) => {
+                reader.seek_to(position)?;
+                return Ok(true);
+            },
+            Some(_) => {},
+            None => return Ok(false),
+        }
+    }
+}
+
+// That the record at the reader's position is the one the entry was made from
+fn check_entry<R: Read>(reader: &mut CaptureReader<R>, entry: &IndexEntry) -> io::Result<()> {
+    let matches = match reader.next_record() {
+        Ok(Some(record)) => record.header().is_ok_and(|header| {
+            header.session_id == entry.session_id && header.frame_id == entry.frame_id && header.packet_type == entry.packet_type
+        }),
+        Ok(None) | Err(_) => false,
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(invalid_data("capture index doesn't match the capture, rebuild it with the index command"))
+    }
+}
+
+// Writes an index a record at a time, alongside the capture it's for
+pub struct IndexWriter<W: Write> {
+    inner:      W,
+    tracker:    LapTracker,
+    held:       Vec<IndexEntry>,    // For records the capture hasn't written yet
+    entries:    u64,
+}
+
+impl<W: Write> IndexWriter<W> {
+    // Writes the file header
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        header[..8].copy_from_slice(&MAGIC);
+        header[8..10].copy_from_slice(&VERSION.to_le_bytes());
+        header[10..12].copy_from_slice(&(FILE_HEADER_SIZE as u16).to_le_bytes());
+        inner.write_all(&header)?;
+        Ok(IndexWriter { inner, tracker: LapTracker::default(), held: Vec::new(), entries: 0 })
+    }
+
+    // Carries on an index that was finished, inner being at its end. tracker is the one the
+    // writer before left off with, so records go on getting the lap they were on.
+    pub fn append(inner: W, tracker: LapTracker) -> Self {
+        IndexWriter { inner, tracker, held: Vec::new(), entries: 0 }
+    }
+
+    // Indexes the datagram of a record starting at position in the capture. Datagrams without a
+    // readable header aren't indexed. The entry is held until write_up_to or finish so the index
+    // never gets ahead of a compressed capture's blocks.
+    pub fn add(&mut self, position: u64, datagram: &[u8]) {
+        if let Some(entry) = self.tracker.entry(position, datagram) {
+            self.held.push(entry);
+            self.entries += 1;
+        }
+    }
+
+    // Writes the entries held for records before written, as from CaptureWriter::written_position
+    pub fn write_up_to(&mut self, written: u64) -> io::Result<()> {
+        let ready = self.held.iter().take_while(|entry| entry.position < written).count();
+        for entry in self.held.drain(..ready) {
+            self.inner.write_all(&entry.to_bytes())?;
+        }
+        Ok(())
+    }
+
+    // Entries added by this writer
+    pub fn len(&self) -> u64 {
+        self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries == 0
+    }
+
+    // Where the player's lap has got to, for carrying on with append
+    pub fn tracker(&self) -> &LapTracker {
+        &self.tracker
+    }
+
+    // Flushes the entries written, not those held
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    // Writes every entry held and flushes, for once the capture is finished
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.write_up_to(u64::MAX)?;
+        self.flush()
+    }
+
+    // Entries still held are lost, see finish
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
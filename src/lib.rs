@@ -0,0 +1,48 @@
+// Decoder for the CodeMasters F1 UDP telemetry formats, 2018 through 2020.
+//
+// parse_packet takes a received datagram, picks the parser for its packet_format and returns
+// one of the Packet variants. Every format decodes into the same f1_2019_net structs, the
+// per format parse_* functions live under parser::f1_2018/f1_2019/f1_2020 for anyone wanting
+// a single packet type. parser::view has borrowed views over the high rate packets for reading
+// a few fields without decoding every car. The Encode trait goes the other way, writing packets
+// back out in the 2019 layout.
+//
+// Around the decoder, source::PacketSource yields datagrams live from a socket, from a
+// capture file or from memory, index::CaptureIndex finds laps and session times in a capture,
+// edit splits, trims and merges recordings, archive::ArchiveWriter packs recordings down for
+// keeping with the high rate packets stored a column per field, sink::Dispatcher hands each
+// decoded packet to whatever PacketSinks were set up, csv::CsvSink among them writing a CSV per
+// packet type, and retention::PacketStore holds recent packets with a limit per type. cli, output
+// and stats are the logger binary's command line, packet printing and stats command.
+
+pub mod f1_2019_net;
+pub mod archive;
+pub mod capture;
+pub mod cli;
+pub mod compress;
+pub mod csv;
+pub mod edit;
+pub mod error;
+mod file;
+pub mod format;
+pub mod index;
+pub mod output;
+pub mod parser;
+pub mod pcap;
+pub mod encoder;
+pub mod registry;
+pub mod replay;
+pub mod retention;
+pub mod sink;
+pub mod source;
+pub mod stats;
+pub mod vector;
+pub mod wheels;
+
+pub use error::ParseError;
+pub use error::EncodeError;
+pub use encoder::Encode;
+pub use f1_2019_net::Packet;
+pub use format::PacketFormat;
+pub use parser::parse_packet;
+pub use parser::MAX_PACKET_SIZE;
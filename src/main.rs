@@ -1,654 +1,649 @@
+use std::net::SocketAddr;
 use std::net::UdpSocket;
-use byte::*;
-use std::collections::VecDeque;
-use std::collections::HashMap;
-
-/*
-@TODO convert name array to utf-8
-*/
-
-mod f1_2019_net;
-
-use f1_2019_net::PacketType;
-use f1_2019_net::PacketHeader;
-use f1_2019_net::CarMotion;
-use f1_2019_net::MotionData;
-use f1_2019_net::SessionData;
-use f1_2019_net::LapData;
-use f1_2019_net::Lap;
-use f1_2019_net::CarSetupData;
-use f1_2019_net::CarSetups;
-use f1_2019_net::CarStatusData;
-use f1_2019_net::CarStatus;
-use f1_2019_net::ParticipantData;
-use f1_2019_net::Participants;
-use f1_2019_net::Telemetry;
-use f1_2019_net::CarTelemetry;
-use f1_2019_net::EventType;
-use f1_2019_net::Event;
-use f1_2019_net::MarshalZone;
-
-
-const MAX_PACKET_SIZE: usize = 1347;					//Max packet size to spec
-const DEFAULT_SOCKET_BINDING: &str = "0.0.0.0:20777";	//20777 default on ps4
-const MIN_PAYLOAD_SIZE: usize = 32;
-const MIN_PACKET_SIZE: usize = HEADER_SIZE + MIN_PAYLOAD_SIZE;
-
-//Header part offsets
-const PACKET_FORMAT_OFFSET: usize = 0;
-const MAJ_VERSION_OFFSET: usize = 2;
-const MIN_VERSION_OFFSET: usize = 3;
-const PACKET_VERSION_OFFSET: usize = 4;
-const PACKET_TYPE_OFFSET: usize = 5;
-const SESSION_ID_OFFSET: usize = 6;
-const SESSION_TIME_OFFSET: usize = 14;
-const FRAME_ID_OFFSET: usize = 18;
-const PLAYER_CAR_INDEX_OFFESET: usize = 22;
-
-//Packset Sizes for v basic check vs packet type byte
-const HEADER_SIZE: usize = 23;
-const MOTION_SIZE: usize = 1343;
-const SESSION_SIZE: usize = 149;
-const LAP_SIZE: usize = 843;
-const EVENT_SIZE: usize = 43; // Might not be able to use this as event packet changes with event type
-const PARTICIPANTS_SIZE: usize = 1104;
-const CARSETUPS_SIZE: usize = 843;
-const TELEMETY_SIZE: usize = MAX_PACKET_SIZE;
-const STATUS_SIZE: usize = 1143;
-
-//Sub type sizes
-const MARSHAL_ZONE_SIZE: usize = 5;
-const CAR_MOTION_SIZE: usize = 60;
-const CAR_LAP_SIZE: usize = 41;
-const PARTICIPANT_SIZE: usize = 54;
-const CAR_SETUP_SIZE: usize = 41;
-const CAR_TELEMETRY_SIZE: usize = 66;
-const CAR_STATUS_SIZE: usize = 56;
-
-//Nums of elems
-const NUM_MARSHAL_ZONES: usize = 21;
-const NUM_CARS: usize = 20;
-const NUM_WHEELS: usize = 4;
-const NUM_TEAMS: usize = 53;
-const NUM_TRACKS: usize = 25;
-const NUM_DRIVERS: usize = 77;
-const NUM_COUNTRIES: usize = 86;
-const NUM_BUTTONS: usize = 15;
+use std::cell::Cell;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::parser::parse_header;
+use f12019_data_logger::archive;
+use f12019_data_logger::archive::ArchiveReader;
+use f12019_data_logger::archive::ArchiveWriter;
+use f12019_data_logger::capture;
+use f12019_data_logger::capture::CaptureReader;
+use f12019_data_logger::capture::CaptureWriter;
+use f12019_data_logger::cli;
+use f12019_data_logger::cli::CliError;
+use f12019_data_logger::cli::Command;
+use f12019_data_logger::cli::Options;
+use f12019_data_logger::cli::OutputFormat;
+use f12019_data_logger::cli::ReplayControl;
+use f12019_data_logger::csv::CsvSink;
+use f12019_data_logger::edit::Merger;
+use f12019_data_logger::edit::Piece;
+use f12019_data_logger::edit::Splitter;
+use f12019_data_logger::edit::Until;
+use f12019_data_logger::index;
+use f12019_data_logger::index::CaptureIndex;
+use f12019_data_logger::index::IndexWriter;
+use f12019_data_logger::index::LapTracker;
+use f12019_data_logger::output::WriterSink;
+use f12019_data_logger::pcap;
+use f12019_data_logger::pcap::PcapReader;
+use f12019_data_logger::pcap::PcapWriter;
+use f12019_data_logger::replay::Replayer;
+use f12019_data_logger::retention::PacketStore;
+use f12019_data_logger::sink::Dispatcher;
+use f12019_data_logger::source::is_timeout;
+use f12019_data_logger::source::PacketSource;
+use f12019_data_logger::source::UdpSource;
+use f12019_data_logger::stats::Stats;
+
+const LIVE_STATS_INTERVAL: Duration = Duration::from_secs(5);
+const MEMORY_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+const ERROR_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
 
 fn main() {
-    let mut motion_data: VecDeque<MotionData> 			= VecDeque::with_capacity(32);		// update spd 20-60hz
-    let mut session_data: VecDeque<SessionData> 		= VecDeque::with_capacity(2);		// every 2 seconds
-    let mut lap_data: VecDeque<Lap> 					= VecDeque::with_capacity(32);		// update spd 20-60hz
-    let mut event_data:	VecDeque<Event> 				= VecDeque::with_capacity(4);		// as events happen
-    let mut setup_data:	VecDeque<CarSetups> 			= VecDeque::with_capacity(2);		// every 2 seconds	
-    let mut car_status_data: VecDeque<CarStatusData> 	= VecDeque::with_capacity(32); 		// update spd 20-60hz
-    let mut participant_data: VecDeque<Participants> 	= VecDeque::with_capacity(2); 		// every 5 seconds
-    let mut telemetry_data: VecDeque<Telemetry> 		= VecDeque::with_capacity(32);		// update spd
-
-    let mut teams: HashMap<usize, &str> 	  = HashMap::with_capacity(NUM_TEAMS);
-    let mut drivers: HashMap<usize, &str> 	  = HashMap::with_capacity(NUM_DRIVERS);
-    let mut tracks: HashMap<usize, &str> 	  = HashMap::with_capacity(NUM_TRACKS);
-    let mut countries: HashMap<usize, &str>   = HashMap::with_capacity(NUM_COUNTRIES);
-    let mut button_press: HashMap<&str, &str> = HashMap::with_capacity(NUM_BUTTONS);
-    
-    f1_2019_net::init_teams(&mut teams);
-    f1_2019_net::init_tracks(&mut tracks);
-    f1_2019_net::init_drivers(&mut drivers);
-    f1_2019_net::init_countries(&mut countries);
-    f1_2019_net::init_button_flags(&mut button_press);
-	
-	let mut header: PacketHeader = Default::default(); 
-	let mut buf = [0u8; MAX_PACKET_SIZE]; 
-    
-    let socket = UdpSocket::bind(DEFAULT_SOCKET_BINDING).expect("failed to bind to socket");
-    
-    loop {
-    	let num_bytes = match socket.recv(&mut buf) {
-    		Ok(num_bytes) => num_bytes,
-    		Err(_) => continue,
-    	};
-		match parse_header(&buf, num_bytes) {
-			Some(header) => match header.get_type() {
-						PacketType::Motion => match parse_motion_data(&buf, header, num_bytes) {
-												Some(p) => motion_data.push_back(p),
-												None => {},
-											},
-						PacketType::Session => match parse_session_data(&buf, header, num_bytes)  {
-			    								Some(p) => session_data.push_back(p),
-			    								None => {},
-			    							},
-						PacketType::Lap => match parse_lap_data(&buf, header, num_bytes) {
-			    								Some(p) => lap_data.push_back(p),
-			    								None => {},
-			    							},
-						PacketType::Event => match parse_event_data(&buf, header) {
-			    								Some(p) => event_data.push_back(p),
-			    								None => {},
-			    							},
-			    		PacketType::Setup => match parse_setups(&buf, header, num_bytes) {
-			    								Some(p) => setup_data.push_back(p),
-			    								None => {},
-			    							},
-			    		PacketType::CarStatus => match parse_car_status_data(&buf, header, num_bytes)  {
-			    								Some(p) => car_status_data.push_back(p),
-			    								None => {},
-			    							},
-						PacketType::Participant => match parse_participant_data(&buf, header, num_bytes) {
-			    								Some(p) => participant_data.push_back(p),
-			    								None => {},
-			    							},
-						PacketType::Telemetry => match parse_telemetry_data(&buf, header, num_bytes) {
-			    								Some(p) => telemetry_data.push_back(p),
-			    								None => {},
-			    							},
-					 	PacketType::InvalidPacket => {},
-					},
-			None => continue,
-		}
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(CliError::Help) => {
+            print!("{}", cli::USAGE);
+            return;
+        },
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        },
+    };
+    let result = match options.command {
+        Command::Listen     => listen(&options),
+        Command::Record     => record(&options),
+        Command::Replay     => replay(&options),
+        Command::Export     => export(&options),
+        Command::Inspect    => inspect(&options),
+        Command::Stats      => stats(&options),
+        Command::Index      => build_index(&options),
+        Command::Trim       => record(&options),
+        Command::Split      => split(&options),
+        Command::Merge      => merge(&options),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
     }
 }
 
-fn parse_header(buf: &[u8; MAX_PACKET_SIZE], num_bytes: usize) -> Option<PacketHeader> {
-	if num_bytes >= MIN_PACKET_SIZE {
-		let header = PacketHeader::new ( 
-			buf.read_with::<u16>(&mut PACKET_FORMAT_OFFSET, LE).expect("error reading from buffer!"),
-    		buf.read_with::<u8>(&mut MAJ_VERSION_OFFSET, LE).expect("error reading from buffer!"),
-	    	buf.read_with::<u8>(&mut MIN_VERSION_OFFSET, LE).expect("error reading from buffer!"),
-    		buf.read_with::<u8>(&mut PACKET_VERSION_OFFSET, LE).expect("error reading from buffer!"),
-    		get_packet_type(buf.read_with::<u8>(&mut PACKET_TYPE_OFFSET, LE).expect("error reading from buffer!")),
-    		buf.read_with::<u64>(&mut SESSION_ID_OFFSET, LE).expect("error reading from buffer!"),
-    		buf.read_with::<f32>(&mut SESSION_TIME_OFFSET, LE).expect("error reading from buffer!"),
-    		buf.read_with::<u32>(&mut FRAME_ID_OFFSET, LE).expect("error reading from buffer!"),
-    		buf.read_with::<u8>(&mut PLAYER_CAR_INDEX_OFFESET, LE).expect("error reading from buffer!")
-    		);
-    	return Some(header)
-	} else {
-		return None;
-	}
-}	
-
-fn get_packet_type(packet_type_byte: u8) -> PacketType {
-	match packet_type_byte {
-		0 => PacketType::Motion,
-		1 => PacketType::Session,
-		2 => PacketType::Lap,
-		3 => PacketType::Event,
-		4 => PacketType::Participant,
-		5 => PacketType::Setup,
-		6 => PacketType::Telemetry,
-		7 => PacketType::CarStatus,
-		_ => PacketType::InvalidPacket,
-	}
-} 
-
-fn parse_motion_data(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader, num_bytes: usize) -> Option<MotionData> {
-	if num_bytes != MOTION_SIZE {
-		return None;
-	}
-	let mut car_data = [CarMotion::default(); NUM_CARS];
-	parse_car_motion(&buf, &mut car_data, NUM_CARS);
-	let mut sus_pos 	= [0.0f32; NUM_WHEELS];
-	let mut sus_vel 	= [0.0f32; NUM_WHEELS];
-	let mut sus_acc 	= [0.0f32; NUM_WHEELS];
-	let mut wheel_spd 	= [0.0f32; NUM_WHEELS];
-	let mut wheel_slp 	= [0.0f32; NUM_WHEELS];
-	parse_wheel_array_f32(&mut sus_pos, &buf[HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)..HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+16], NUM_WHEELS);
-	parse_wheel_array_f32(&mut sus_vel, &buf[HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+16..HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+32], NUM_WHEELS);
-	parse_wheel_array_f32(&mut sus_acc, &buf[HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+48..HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+64], NUM_WHEELS);
-	parse_wheel_array_f32(&mut wheel_spd, &buf[HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+80..HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+96], NUM_WHEELS);
-	parse_wheel_array_f32(&mut wheel_slp, &buf[HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+112..HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+128], NUM_WHEELS);
-	let mut offset = HEADER_SIZE+(CAR_MOTION_SIZE*NUM_CARS)+128;
-	return Some ( MotionData {
-				header,
-				car_motion_data: 	car_data,
-				suspension_pos: 	sus_pos,
-    			suspension_vel: 	sus_vel,
-    			suspension_acc:		sus_acc,
-    			wheel_speed: 		wheel_spd,
-    			wheel_slip:			wheel_slp,
-    			local_vel_x:		buf.read_with::<f32>(&mut (offset), LE).unwrap(),			//Local space
-    			local_vel_y:		buf.read_with::<f32>(&mut (offset+4), LE).unwrap(),
-    			local_vel_z:		buf.read_with::<f32>(&mut (offset+8), LE).unwrap(),
-    			angular_vel_x:		buf.read_with::<f32>(&mut (offset+12), LE).unwrap(),
-    			angular_vel_y:		buf.read_with::<f32>(&mut (offset+16), LE).unwrap(),
-    			angular_vel_z:		buf.read_with::<f32>(&mut (offset+22), LE).unwrap(),
-    			angular_acc_x:		buf.read_with::<f32>(&mut (offset+26), LE).unwrap(),
-    			angular_acc_y:		buf.read_with::<f32>(&mut (offset+30), LE).unwrap(),
-    			angular_acc_z:		buf.read_with::<f32>(&mut (offset+34), LE).unwrap(),
-    			front_wheels_angle: buf.read_with::<f32>(&mut (offset+38), LE).unwrap(),
-		})
+fn open_output(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    match path {
+        Some(path) => Ok(Box::new(BufWriter::new(File::create(path)?))),
+        None => Ok(Box::new(BufWriter::new(io::stdout()))),
+    }
 }
 
-fn parse_car_motion(buf: &[u8; MAX_PACKET_SIZE], car_data: &mut [CarMotion; 20], count: usize) {
-	let index: usize = NUM_CARS - count;
-	let mut offset = index * CAR_MOTION_SIZE;
-	let car_data = CarMotion {
-		world_pos_x: buf.read_with::<f32>(&mut (HEADER_SIZE+offset), LE).unwrap(),			
-    	world_pos_y: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+4), LE).unwrap(),
-    	world_pos_z: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+8), LE).unwrap(),
-    	world_vel_x: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+12), LE).unwrap(),			
-    	world_vel_y: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+16), LE).unwrap(),
-    	world_vel_z: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+20), LE).unwrap(),
-    	world_fwd_dir_x: buf.read_with::<i16>(&mut (HEADER_SIZE+offset+24), LE).unwrap(),	 	
-    	world_fwd_dir_y: buf.read_with::<i16>(&mut (HEADER_SIZE+offset+26), LE).unwrap(),		
-    	world_fwd_dir_z: buf.read_with::<i16>(&mut (HEADER_SIZE+offset+28), LE).unwrap(),		
-    	world_right_dir_x: buf.read_with::<i16>(&mut (HEADER_SIZE+offset+30), LE).unwrap(),		
-    	world_right_dir_y: buf.read_with::<i16>(&mut (HEADER_SIZE+offset+32), LE).unwrap(),		
-    	world_right_dir_z: buf.read_with::<i16>(&mut (HEADER_SIZE+offset+34), LE).unwrap(),		
-    	lateral_g: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+36), LE).unwrap(),
-    	longitudinal_g: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+40), LE).unwrap(),
-    	vertical_g: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+44), LE).unwrap(),
-    	pitch: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+48), LE).unwrap(),					
-    	yaw: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+52), LE).unwrap(),					
-    	roll: buf.read_with::<f32>(&mut (HEADER_SIZE+offset+56), LE).unwrap(),	
-	};
-
+// An existing file to write on the end of, with how long it is
+fn open_append(path: &Path) -> io::Result<(Box<dyn Write>, u64)> {
+    let file = OpenOptions::new().append(true).open(path)?;
+    let len = file.metadata()?.len();
+    Ok((Box::new(BufWriter::new(file)), len))
 }
 
-fn parse_wheel_array_f32(array_to_fill: &mut [f32; NUM_WHEELS], bytes: &[u8], count: usize) {
-	let mut index = NUM_WHEELS - count;
-	array_to_fill[index] = bytes.read_with::<f32>(&mut (index * 4), LE).unwrap(); // *4 4 bytes per f32
-	if count <= 0 {
-		return;
-	} else {
-		parse_wheel_array_f32(array_to_fill, bytes, count-1);
-	}
+// The recording given with --input, otherwise packets live from the game, up to --to if given
+fn open_source(options: &Options) -> io::Result<Box<dyn PacketSource>> {
+    let source: Box<dyn PacketSource> = match options.input.as_deref() {
+        Some(path) => open_recording(path, options)?,
+        None => Box::new(bind(options)?),
+    };
+    match options.end {
+        Some(end) => Ok(Box::new(Until::new(source, end))),
+        None => Ok(source),
+    }
 }
 
-fn parse_wheel_array_u16(array_to_fill: &mut [u16; NUM_WHEELS], bytes: &[u8], count: usize) {
-	let mut index = NUM_WHEELS - count;
-	array_to_fill[index] = bytes.read_with::<u16>(&mut (index * 2), LE).unwrap(); // *2 2 bytes per f32
-	if count <= 0 {
-		return;
-	} else {
-		parse_wheel_array_u16(array_to_fill, bytes, count-1);
-	}
+// A capture or a pcap file. Captures start from --from and the like, found with the index beside
+// them if there is one.
+fn open_recording(path: &Path, options: &Options) -> io::Result<Box<dyn PacketSource>> {
+    let mut file = BufReader::new(File::open(path)?);
+    if pcap::is_pcap(file.fill_buf()?) {
+        if options.start.is_some() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "can only start part way through a capture, record the pcap file to one first"));
+        }
+        return Ok(Box::new(PcapReader::new(file, options.port)?));
+    }
+    if archive::is_archive(file.fill_buf()?) {
+        if options.start.is_some() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "can only start part way through a capture, trim the archive to one first"));
+        }
+        return Ok(Box::new(ArchiveReader::new(file)?));
+    }
+    let mut reader = CaptureReader::new(file)?;
+    if let Some(start) = options.start {
+        if !index::seek(&mut reader, CaptureIndex::open(path)?.as_ref(), start)? {
+            eprintln!("recording never reaches {}", start);
+        }
+    }
+    Ok(Box::new(reader))
 }
 
-fn parse_wheel_array_u8(array_to_fill: &mut [u8; NUM_WHEELS], bytes: &[u8], count: usize) {
-	let mut index = NUM_WHEELS - count;
-	array_to_fill[index] = bytes.read_with::<u8>(&mut index, LE).unwrap();
-	if count <= 0 {
-		return;
-	} else {
-		parse_wheel_array_u8(array_to_fill, bytes, count-1);
-	}
+// Where record and export --format pcap or archive write datagrams. Captures written to a file
+// get an index beside them, compressed with --compress.
+enum DatagramWriter {
+    Capture(CaptureWriter<Box<dyn Write>>, Option<IndexWriter<Box<dyn Write>>>),
+    Pcap(PcapWriter<Box<dyn Write>>),
+    Archive(ArchiveWriter<Box<dyn Write>>),
 }
 
-fn parse_session_data(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader, num_bytes: usize) -> Option<SessionData> {
-	//crappy check 
-	if num_bytes != SESSION_SIZE {
-		return None;
-	}
-	let mut zones = [MarshalZone{zone_start: 0.0, flag: 0}; NUM_MARSHAL_ZONES];
-	parse_marshal_zones(&mut zones, &buf[HEADER_SIZE+19..], NUM_MARSHAL_ZONES);
-	return Some( SessionData {
-		header,			
-		weather:		buf.read_with::<u8>(&mut HEADER_SIZE, LE).unwrap(),		
-		track_temp:		buf.read_with::<i8>(&mut (HEADER_SIZE+1), LE).unwrap(),	
-		air_temp:		buf.read_with::<i8>(&mut (HEADER_SIZE+2), LE).unwrap(),	
-		total_laps:		buf.read_with::<u8>(&mut (HEADER_SIZE+3), LE).unwrap(),	
-		track_len:		buf.read_with::<u16>(&mut (HEADER_SIZE+4), LE).unwrap(),	
-		session_type:	buf.read_with::<u8>(&mut (HEADER_SIZE+6), LE).unwrap(),	
-		track_id:		buf.read_with::<i8>(&mut (HEADER_SIZE+7), LE).unwrap(),
-		formual:		buf.read_with::<u8>(&mut (HEADER_SIZE+8), LE).unwrap(),
-		session_ttl:	buf.read_with::<u16>(&mut (HEADER_SIZE+9), LE).unwrap(),
-		session_len:	buf.read_with::<u16>(&mut (HEADER_SIZE+11), LE).unwrap(),
-		pit_spd_lim:	buf.read_with::<u8>(&mut (HEADER_SIZE+13), LE).unwrap(),
-		is_paused:		buf.read_with::<u8>(&mut (HEADER_SIZE+14), LE).unwrap(),
-		is_spectating:	buf.read_with::<u8>(&mut (HEADER_SIZE+15), LE).unwrap(),
-		spectator_car:	buf.read_with::<u8>(&mut (HEADER_SIZE+16), LE).unwrap(),
-		sli_native:		buf.read_with::<u8>(&mut (HEADER_SIZE+17), LE).unwrap(),
-		num_zones:		buf.read_with::<u8>(&mut (HEADER_SIZE+18), LE).unwrap(),
-		zones:			zones,
-		safety_car:		buf.read_with::<u8>(&mut (HEADER_SIZE+124), LE).unwrap(), // HEADER_SIZE + 19 (previous offset) + 21*5 (Marshal Zone is 1xi8 & 1xf32)
-		is_network_game:buf.read_with::<u8>(&mut (HEADER_SIZE+125), LE).unwrap(), // HEADER_SIZE + 125 = 148 giving tot packet size 149
-	})	
-}
+impl DatagramWriter {
+    fn create(options: &Options) -> io::Result<DatagramWriter> {
+        match (options.format, options.output.as_deref()) {
+            (OutputFormat::Pcap, output) => {
+                let destination = options.replay_target().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+                Ok(DatagramWriter::Pcap(PcapWriter::new(open_output(output)?, destination)?))
+            },
+            (OutputFormat::Archive, output) => Ok(DatagramWriter::Archive(ArchiveWriter::new(open_output(output)?)?)),
+            (_, Some(path)) => DatagramWriter::capture(path, options.compress),
+            (_, None) => Ok(DatagramWriter::Capture(capture_writer(open_output(None)?, options.compress)?, None)),
+        }
+    }
 
-fn parse_marshal_zones(zones: &mut [MarshalZone; 21], bytes: &[u8], count: usize) {
-	let mut index = NUM_MARSHAL_ZONES - count;
-	let zone = MarshalZone {
-		zone_start: bytes.read_with::<f32>(&mut (0+(5*index)), LE).unwrap(),	
-		flag:		bytes.read_with::<i8>(&mut (4+(5*index)), LE).unwrap(), 
-	};	
-	zones[index] = zone;
-	if count <= 0 {
-		return;
-	} else {
-		parse_marshal_zones(zones, bytes, count-1);
-	}
-}
+    // A capture file with its index
+    fn capture(path: &Path, compress: bool) -> io::Result<DatagramWriter> {
+        let index = IndexWriter::new(open_output(Some(&index::sidecar_path(path)))?)?;
+        Ok(DatagramWriter::Capture(capture_writer(open_output(Some(path))?, compress)?, Some(index)))
+    }
 
-fn parse_lap_data(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader, num_bytes: usize) -> Option<Lap> {
-	if num_bytes != LAP_SIZE {
-		return None;
-	}
-	let mut car_laps = [LapData::default(); 20];
-	parse_car_laps(&mut car_laps, &buf[HEADER_SIZE..], NUM_CARS);
-	return Some (Lap {
-		header,
-		lap_data: car_laps,
-	})
-}
+    // Carries on a capture and its index left by close
+    fn append_capture(path: &Path, compress: bool, tracker: LapTracker) -> io::Result<DatagramWriter> {
+        let (index, _) = open_append(&index::sidecar_path(path))?;
+        let (out, len) = open_append(path)?;
+        Ok(DatagramWriter::Capture(CaptureWriter::append(out, len, compress), Some(IndexWriter::append(index, tracker))))
+    }
 
-fn parse_car_laps(car_laps: &mut [LapData; 20], bytes: &[u8], count: usize) { 
-	let mut index = NUM_CARS - count;
-	let mut offset: usize = index * CAR_LAP_SIZE;
-	let car_lap_data = LapData {
-		last_lap: 		bytes.read_with::<f32>(&mut offset, LE).unwrap(),			
-		current_lap: 	bytes.read_with::<f32>(&mut (offset+4), LE).unwrap(),		
-		best_lap: 		bytes.read_with::<f32>(&mut (offset+8), LE).unwrap(),			
-		best_sec_1: 	bytes.read_with::<f32>(&mut (offset+12), LE).unwrap(),		
-		best_sec_2: 	bytes.read_with::<f32>(&mut (offset+16), LE).unwrap(),		
-		lap_distance: 	bytes.read_with::<f32>(&mut (offset+20), LE).unwrap(),		
-		total_distance: bytes.read_with::<f32>(&mut (offset+24), LE).unwrap(),	
-		safety_car_delta: bytes.read_with::<f32>(&mut (offset+28), LE).unwrap(),	
-		position: 		bytes.read_with::<u8>(&mut (offset+32), LE).unwrap(),			
-		lap_num: 		bytes.read_with::<u8>(&mut (offset+33), LE).unwrap(),			
-		pit_status: 	bytes.read_with::<u8>(&mut (offset+34), LE).unwrap(),			
-		sector: 		bytes.read_with::<u8>(&mut (offset+35), LE).unwrap(),				
-		is_lap_valid: 	bytes.read_with::<u8>(&mut (offset+36), LE).unwrap(),		
-		penalties: 		bytes.read_with::<u8>(&mut (offset+37), LE).unwrap(),			
-		grid_position: 	bytes.read_with::<u8>(&mut (offset+38), LE).unwrap(),		
-		driver_status: 	bytes.read_with::<u8>(&mut (offset+39), LE).unwrap(),		
-		result_status: 	bytes.read_with::<u8>(&mut (offset+40), LE).unwrap(),		
-	};
-	car_laps[index] = car_lap_data;
-	if count <= 0 {
-		return;
-	} else {
-		parse_car_laps(car_laps, bytes, count-1);
-	}
+    fn write(&mut self, received_us: u64, source: Option<SocketAddr>, datagram: &[u8]) -> io::Result<()> {
+        match self {
+            DatagramWriter::Capture(out, index) => {
+                let position = out.position();
+                out.write(received_us, source, datagram)?;
+                match index {
+                    Some(index) => {
+                        index.add(position, datagram);
+                        index.write_up_to(out.written_position())
+                    },
+                    None => Ok(()),
+                }
+            },
+            DatagramWriter::Pcap(out) => out.write(received_us, source, datagram),
+            DatagramWriter::Archive(out) => out.write(received_us, datagram),
+        }
+    }
 
-}
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            // The capture first so the index never refers past its end
+            DatagramWriter::Capture(out, index) => {
+                out.flush()?;
+                match index {
+                    Some(index) => index.flush(),
+                    None => Ok(()),
+                }
+            },
+            DatagramWriter::Pcap(out) => out.flush(),
+            DatagramWriter::Archive(out) => out.flush(),
+        }
+    }
+
+    // Writes out anything held back and flushes
+    fn finish(&mut self) -> io::Result<()> {
+        match self {
+            DatagramWriter::Capture(out, index) => {
+                out.finish()?;
+                match index {
+                    Some(index) => index.finish(),
+                    None => Ok(()),
+                }
+            },
+            DatagramWriter::Pcap(out) => out.flush(),
+            DatagramWriter::Archive(out) => out.finish(),
+        }
+    }
 
-fn parse_event_data(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader) -> Option<Event> {
-	//Can't naively check event packet size as this varies with event type
-	let evt_slice = &buf[HEADER_SIZE..];
-	let event_type = match parse_event_identifier(evt_slice) {
-		Some(e) => e,
-		_ => return None,
-	};
-	match event_type {
-		EventType::SessionStarted 	=> return Some(Event {
-													header, 
-													event_type: EventType::SessionStarted, 
-													car_idx: 255, 
-													lap_time: 0.0}), 
-		EventType::SessionEnded 	=> return Some(Event {
-													header, 
-													event_type: EventType::SessionEnded, 
-													car_idx: 255, 
-													lap_time: 0.0}),
-		EventType::FastestLap		=> return parse_multipart_event(evt_slice, event_type, header),
-		EventType::Retirement		=> return parse_multipart_event(evt_slice, event_type, header),
-		EventType::DrsEnabled		=> return Some(Event {
-													header, 
-													event_type: EventType::DrsEnabled, 
-													car_idx: 255, 
-													lap_time: 0.0}),
-		EventType::DrsDisabled		=> return Some(Event {
-													header, 
-													event_type: EventType::DrsDisabled, 
-													car_idx: 255, 
-													lap_time: 0.0}),
-		EventType::TeamMateInPits	=> return parse_multipart_event(evt_slice, event_type, header),
-		EventType::ChequeredFlag	=> return Some(Event {
-													header, 
-													event_type: EventType::ChequeredFlag, 
-													car_idx: 255, 
-													lap_time: 0.0}),
-		EventType::RaceWinner		=> return parse_multipart_event(evt_slice, event_type, header),
-		_							=> return None,
-	}
-	
+    // Finishes and closes the files, giving what append_capture needs to carry on the index
+    fn close(mut self) -> io::Result<LapTracker> {
+        self.finish()?;
+        match self {
+            DatagramWriter::Capture(_, Some(index)) => Ok(index.tracker().clone()),
+            _ => Ok(LapTracker::new()),
+        }
+    }
 }
 
-fn parse_multipart_event(bytes: &[u8], event_type: EventType, header: PacketHeader) -> Option<Event> {
-	let mut offset = 4; //skip over event type identifier bytes
-	//first byte is always car index
-	let car_idx = bytes.read_with::<u8>(&mut offset, LE).unwrap();
-	match event_type {
-		EventType::FastestLap => return 
-			Some(Event{
-			header, 
-			event_type,
-			car_idx,
-			lap_time: bytes.read_with::<f32>(&mut (offset+1), LE).unwrap(), 
-		}),
-		_ => return 
-			Some(Event{
-			header, 
-			event_type,
-			car_idx,
-			lap_time: 0.0, 
-		}) 	
-	}
+fn capture_writer<W: Write>(out: W, compress: bool) -> io::Result<CaptureWriter<W>> {
+    if compress {
+        CaptureWriter::compressed(out)
+    } else {
+        CaptureWriter::new(out)
+    }
 }
 
-fn parse_event_identifier(ascii_bytes: &[u8]) -> Option<EventType> {
-	// Instead of converting bytes to ascii and then comparing strings we add up the byte vals 
-	// as each 4 char combined val is unique. Could have compared first two bytes but two type share first 3 chars.
-	// I'm confused by this way of id-ing event packet types, maybe this was used for readability and cost not too 
-	// high as event packets are infrequent
-	let mut array = [0u8; 4];
-	array[0] = ascii_bytes.read_with::<u8>(&mut 0, LE).unwrap();
-	array[1] = ascii_bytes.read_with::<u8>(&mut 1, LE).unwrap();
-	array[2] = ascii_bytes.read_with::<u8>(&mut 2, LE).unwrap();
-	array[3] = ascii_bytes.read_with::<u8>(&mut 3, LE).unwrap();
-	let added_bytes: u32 = u32::from_ne_bytes(array);
-	match added_bytes {
-		315 => return Some(EventType::SessionStarted),
-		298 => return Some(EventType::SessionEnded),
-		310 => return Some(EventType::FastestLap),
-		327 => return Some(EventType::Retirement),
-		302 => return Some(EventType::DrsEnabled),
-		301 => return Some(EventType::DrsDisabled),
-		325 => return Some(EventType::TeamMateInPits),
-		290 => return Some(EventType::ChequeredFlag),
-		314 => return Some(EventType::RaceWinner),
-		_ => return None,
-	}
+fn bind(options: &Options) -> io::Result<UdpSource> {
+    let address = options.bind_address();
+    UdpSource::bind(&address).map_err(|e| io::Error::new(e.kind(), format!("failed to bind to {}: {}", address, e)))
 }
 
-fn parse_participant_data(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader, num_bytes: usize) -> Option<Participants> {
-	if num_bytes != PARTICIPANTS_SIZE {
-		return None;
-	}
-	let mut participants = [ParticipantData::new(); 20];
-	parse_participant(&mut participants, &buf[HEADER_SIZE+1..], NUM_CARS); //+1 due to u8 num cars active
-	return Some(Participants{
-			header,
-			num_cars_active: buf.read_with::<u8>(&mut (HEADER_SIZE+1), LE).unwrap(),
-			participant_data: participants,
-		});
+// Filters on the header alone for commands passing datagrams on undecoded. Ones without a
+// readable header only pass when every type is wanted.
+fn wants_datagram(options: &Options, datagram: &[u8]) -> bool {
+    options.packets.is_none() || parse_header(datagram).is_ok_and(|header| options.wants(header.packet_type))
 }
 
-fn parse_participant(participants: &mut [ParticipantData; NUM_CARS], bytes: &[u8], count: usize) {
-	let index = NUM_CARS - count;
-	let mut offset = index * PARTICIPANT_SIZE;
-	let participant = ParticipantData {
-		ai_controlled: 	bytes.read_with::<u8>(&mut offset, LE).unwrap(),
-    	driver_id: 		bytes.read_with::<u8>(&mut (offset+1), LE).unwrap(),
-    	team_id: 		bytes.read_with::<u8>(&mut (offset+2), LE).unwrap(),
-    	race_number: 	bytes.read_with::<u8>(&mut (offset+3), LE).unwrap(),
-    	nationality: 	bytes.read_with::<u8>(&mut (offset+4), LE).unwrap(),
-    	name: 			get_name_bytes(&bytes[offset+5..offset+52]),
-    	priv_telemetry: bytes.read_with::<u8>(&mut (offset+53), LE).unwrap(),
-	};
-	participants[index] = participant;
-	if count <= 0 {
-		return;
-	} else {
-		parse_participant(participants, bytes, count-1);
-	}
+// Sinks every decoded packet goes to, set up from the options. csv writes its files to the
+// --output directory
+fn sinks(options: &Options) -> io::Result<Dispatcher> {
+    if let (OutputFormat::Csv, Some(dir)) = (options.format, options.output.as_deref()) {
+        let mut csv = CsvSink::create_in(dir)?;
+        csv.set_packets(options.packets.clone());
+        csv.set_player_only(options.player_only);
+        return Ok(Dispatcher::new().with(csv));
+    }
+    let out = open_output(options.output.as_deref())?;
+    Ok(Dispatcher::new().with(WriterSink::new(out, options)))
+}
 
+// Decodes packets and passes them to the sinks as they arrive, flushing them every second.
+// Packets are kept in memory as far as --retain allows, with how much that is reported every
+// minute. Datagrams that can't be parsed are counted and reported every few seconds at most.
+fn listen(options: &Options) -> io::Result<()> {
+    let mut store = PacketStore::new(options.retention.clone());
+    let evicted = Rc::new(Cell::new(0u64));
+    let evicted_count = Rc::clone(&evicted);
+    store.set_evict_handler(move |_| evicted_count.set(evicted_count.get() + 1));
+
+    let mut bad_packets: u64 = 0;           // Datagrams that failed to parse since they were last reported
+    let mut last_error_report: Option<Instant> = None;
+    let mut last_report = Instant::now();
+    let mut last_flush = Instant::now();
+
+    let mut source = open_source(options)?;
+    let mut sinks = sinks(options)?;
+
+    while let Some((_, datagram)) = source.next_datagram()? {
+        let packet = match parse_packet(datagram) {
+            Ok(packet) => packet,
+            Err(e) => {
+                bad_packets += 1;
+                if last_error_report.is_none_or(|reported| reported.elapsed() >= ERROR_REPORT_INTERVAL) {
+                    eprintln!("dropped {} packets that couldn't be parsed, the last: {}", bad_packets, e);
+                    bad_packets = 0;
+                    last_error_report = Some(Instant::now());
+                }
+                continue;
+            },
+        };
+        sinks.dispatch(&packet)?;
+        store.push(packet);
+        if last_flush.elapsed() >= FLUSH_INTERVAL {
+            sinks.flush()?;
+            last_flush = Instant::now();
+        }
+        if last_report.elapsed() >= MEMORY_REPORT_INTERVAL {
+            let usage = store.total_memory_usage();
+            eprintln!("holding {} packets in {} KiB, {} evicted", usage.packets, usage.bytes / 1024, evicted.get());
+            last_report = Instant::now();
+        }
+    }
+    if bad_packets > 0 {
+        eprintln!("dropped {} packets that couldn't be parsed", bad_packets);
+    }
+    sinks.close()
 }
 
-fn get_name_bytes(slice: &[u8]) -> [u8; 48]{
-	let mut name_array = [0u8; 48];
-	for x in 0..47 {
-		name_array[x] = slice[x];
-	}
-	name_array
+// Saves datagrams as received, to a capture or with --format pcap or archive to a pcap file or an
+// archive. Ones that can't be parsed are kept too so parser fixes can be tried on them later,
+// unless only some packet types are wanted. Given a recording, copies the packets asked for out of it, as trim does.
+fn record(options: &Options) -> io::Result<()> {
+    copy(open_source(options)?, options)
 }
 
-fn parse_setups(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader, num_bytes: usize) -> Option<CarSetups> {
-	if  num_bytes != CARSETUPS_SIZE {
-		return None;
-	}
-	let mut setup_data = [CarSetupData::default(); NUM_CARS];
-	parse_car_setup(&mut setup_data, &buf[HEADER_SIZE..], NUM_CARS);
-	return Some(CarSetups{
-		header,
-		car_setups: setup_data,
-	});
+// Writes the datagrams wanted from the source out as record does
+fn copy<S: PacketSource>(mut source: S, options: &Options) -> io::Result<()> {
+    let mut out = DatagramWriter::create(options)?;
+    let live = options.input.is_none();
+    let mut datagram: Vec<u8> = Vec::new();     // Copied out so the source can be asked who sent it
+    let mut recorded: u64 = 0;
+    while let Some((received_us, received)) = source.next_datagram()? {
+        if !wants_datagram(options, received) {
+            continue;
+        }
+        datagram.clear();
+        datagram.extend_from_slice(received);
+        out.write(received_us, source.source_address(), &datagram)?;
+        recorded += 1;
+        if live {
+            out.flush()?;
+            if recorded.is_multiple_of(1000) {
+                eprintln!("{} packets recorded", recorded);
+            }
+        }
+    }
+    out.finish()
 }
 
-fn parse_car_setup(setup_data: &mut [CarSetupData; NUM_CARS], bytes: &[u8], count: usize) {
-	let index = NUM_CARS - count;
-	let mut offset = index * CAR_SETUP_SIZE;
-	let setup = CarSetupData {
-		 		front_wing: 			bytes.read_with::<u8>(&mut offset, LE).unwrap(), 			
-    			rear_wing:				bytes.read_with::<u8>(&mut (offset+1), LE).unwrap(),			
-    			on_throttle: 			bytes.read_with::<u8>(&mut (offset+2), LE).unwrap(),			
-    			off_throttle: 			bytes.read_with::<u8>(&mut (offset+3), LE).unwrap(),		
-    			front_camber: 			bytes.read_with::<f32>(&mut (offset+4), LE).unwrap(),		
-    			rear_camber: 			bytes.read_with::<f32>(&mut (offset+8), LE).unwrap(),		
-    			front_toe: 				bytes.read_with::<f32>(&mut (offset+12), LE).unwrap(),		
-    			rear_toe: 				bytes.read_with::<f32>(&mut (offset+16), LE).unwrap(),	
-    			front_suspension: 		bytes.read_with::<u8>(&mut (offset+20), LE).unwrap(),	
-    			rear_suspension:		bytes.read_with::<u8>(&mut (offset+21), LE).unwrap(),	
-    			front_anti_roll_bar: 	bytes.read_with::<u8>(&mut (offset+22), LE).unwrap(),	
-    			rear_anti_roll_bar: 	bytes.read_with::<u8>(&mut (offset+23), LE).unwrap(),
-    			front_suspension_height:bytes.read_with::<u8>(&mut (offset+24), LE).unwrap(),
-    			rear_suspension_height: bytes.read_with::<u8>(&mut (offset+25), LE).unwrap(),
-    			brake_pressure: 		bytes.read_with::<u8>(&mut (offset+26), LE).unwrap(),
-    			brake_bias: 			bytes.read_with::<u8>(&mut (offset+27), LE).unwrap(),
-    			front_tyre_pressure: 	bytes.read_with::<f32>(&mut (offset+28), LE).unwrap(),
-    			rear_tyre_pressure: 	bytes.read_with::<f32>(&mut (offset+32), LE).unwrap(),
-    			ballast: 				bytes.read_with::<u8>(&mut (offset+36), LE).unwrap(),	
-    			fuel_load: 				bytes.read_with::<f32>(&mut (offset+37), LE).unwrap(),
-			};
-	setup_data[index] = setup;
-	if count <= 0 {
-		return; 
-	} else {
-		parse_car_setup(setup_data, bytes, count-1);
-	}
+// Sends each datagram to the target, waiting out the gaps between them as they were received,
+// scaled by --speed. Pause, resume, seek and speed can be typed in while it runs.
+fn replay(options: &Options) -> io::Result<()> {
+    let target = options.replay_target().map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    let path = options.input.as_deref().expect("parse_args checks replay has an input");
+    let mut replayer = Replayer::new(CaptureReader::new(BufReader::new(File::open(path)?))?);
+    replayer.set_index(CaptureIndex::open(path)?);
+    replayer.set_speed(options.speed);
+    replayer.set_loop(options.looped);
+    replayer.set_packets(options.packets.clone());
+    replayer.set_end(options.end);
+    let mut more = match options.start {
+        Some(start) if !replayer.seek(start)? => {
+            eprintln!("recording never reaches {}", start);
+            false
+        },
+        Some(_) => true,
+        None => replayer.advance()?,
+    };
+
+    let controls = replay_controls();
+    let mut controls_open = true;
+    let mut sent: u64 = 0;
+    while more {
+        let control = if !controls_open {
+            thread::sleep(replayer.due().saturating_duration_since(Instant::now()));
+            None
+        } else if replayer.is_paused() {
+            controls.recv().ok()
+        } else {
+            match controls.recv_timeout(replayer.due().saturating_duration_since(Instant::now())) {
+                Ok(control) => Some(control),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => {
+                    controls_open = false;
+                    continue;
+                },
+            }
+        };
+        match control {
+            Some(ReplayControl::Pause) => replayer.pause(),
+            Some(ReplayControl::Resume) => replayer.resume(),
+            Some(ReplayControl::Seek(target)) => {
+                if !replayer.seek(target)? {
+                    eprintln!("recording never reaches {}", target);
+                }
+            },
+            Some(ReplayControl::Speed(speed)) => replayer.set_speed(speed),
+            // Stdin closed while paused, nothing can resume it so carry on
+            None if replayer.is_paused() => {
+                controls_open = false;
+                replayer.resume();
+            },
+            None => {
+                socket.send_to(replayer.datagram(), target)?;
+                sent += 1;
+                more = replayer.advance()?;
+            },
+        }
+    }
+    eprintln!("sent {} packets to {}", sent, target);
+    Ok(())
 }
 
-fn parse_telemetry_data(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader, num_bytes: usize) -> Option<Telemetry> {
-	if num_bytes != TELEMETY_SIZE {
-		return None;
-	}
-	let mut car_telemetry = [CarTelemetry::default(); NUM_CARS];
-	parse_car_telemetry(&mut car_telemetry, &buf[HEADER_SIZE..], NUM_CARS);
-	return Some(Telemetry{
-				header,
-				car_telemetry_data: car_telemetry,
-				button_status: buf.read_with::<u32>(&mut (HEADER_SIZE+(NUM_CARS*CAR_TELEMETRY_SIZE)), LE).unwrap(),
-			});
+// Lines typed while replaying, parsed. Ones that don't parse are reported and dropped.
+fn replay_controls() -> Receiver<ReplayControl> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let line = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            match cli::parse_replay_control(&line) {
+                Ok(control) => {
+                    if sender.send(control).is_err() {
+                        break;
+                    }
+                },
+                Err(e) => eprintln!("{}, expected pause, resume, seek <seconds>, lap <n>, session <id> or speed <multiplier>", e),
+            }
+        }
+    });
+    receiver
+}
 
+// Decodes packets and passes them to the sinks, or with --format pcap or archive copies the
+// datagrams out. Live from the game the sinks are flushed every second as listen does.
+fn export(options: &Options) -> io::Result<()> {
+    if options.format.is_datagrams() {
+        return record(options);
+    }
+    let mut source = open_source(options)?;
+    let mut sinks = sinks(options)?;
+    let live = options.input.is_none();
+    let mut bad_packets: u64 = 0;
+    let mut last_flush = Instant::now();
+    while let Some((_, datagram)) = source.next_datagram()? {
+        match parse_packet(datagram) {
+            Ok(packet) => sinks.dispatch(&packet)?,
+            Err(_) => bad_packets += 1,
+        }
+        if live && last_flush.elapsed() >= FLUSH_INTERVAL {
+            sinks.flush()?;
+            last_flush = Instant::now();
+        }
+    }
+    if bad_packets > 0 {
+        eprintln!("skipped {} packets that couldn't be parsed", bad_packets);
+    }
+    sinks.close()
 }
 
-fn parse_car_telemetry(car_telemetry: &mut [CarTelemetry; NUM_CARS], bytes: &[u8], count: usize) {
-	let index = NUM_CARS - count;
-	let mut offset = index * CAR_TELEMETRY_SIZE;
-	let mut brake_temps		= [0u16; NUM_WHEELS];	
-	let mut tyre_s_temps 	= [0u16; NUM_WHEELS];
-	let mut tyre_ic_temps 	= [0u16; NUM_WHEELS];
-	let mut tyre_pres 		= [0f32; NUM_WHEELS];
-	let mut tyre_contacts 	= [0u8; NUM_WHEELS];
-	parse_wheel_array_u16(&mut brake_temps, &bytes[offset+20..], NUM_WHEELS);
-	parse_wheel_array_u16(&mut tyre_s_temps, &bytes[offset+28..], NUM_WHEELS);
-	parse_wheel_array_u16(&mut tyre_ic_temps, &bytes[offset+36..], NUM_WHEELS);
-	parse_wheel_array_f32(&mut tyre_pres, &bytes[offset+46..], NUM_WHEELS);
-	parse_wheel_array_u8(&mut tyre_contacts, &bytes[offset+62..], NUM_WHEELS);
-	let telemetry =			 
-		CarTelemetry {
-					car_speed: 			bytes.read_with::<u16>(&mut (offset), LE).unwrap(),			
-				    throttle_pos: 		bytes.read_with::<f32>(&mut (offset+2), LE).unwrap(),	
-				    steering_pos:		bytes.read_with::<f32>(&mut (offset+6), LE).unwrap(),	
-				    brake_pos: 			bytes.read_with::<f32>(&mut (offset+10), LE).unwrap(),	
-				    clutch_pos:			bytes.read_with::<u8>(&mut (offset+14), LE).unwrap(),	
-				    gear: 				bytes.read_with::<i8>(&mut (offset+15), LE).unwrap(),	
-				    engine_rpm:			bytes.read_with::<u16>(&mut (offset+16), LE).unwrap(),	
-				    drs_active: 		bytes.read_with::<u8>(&mut (offset+18), LE).unwrap(),	
-				    change_light_perc: 	bytes.read_with::<u8>(&mut (offset+19), LE).unwrap(),	
-				    brake_temps: 		brake_temps,
-				    tyre_surface_temps:	tyre_s_temps,
-				    tyre_inner_temps: 	tyre_ic_temps,	
-				    engine_temp: 		bytes.read_with::<u16>(&mut (offset+44), LE).unwrap(),	
-				    tyre_pressures: 	tyre_pres,
-				    tyre_contact_types: tyre_contacts,
-				};
-	car_telemetry[index] = telemetry;
-	if count <= 0 {
-		return;
-	} else {
-		parse_car_telemetry(car_telemetry, bytes, count-1);
-	}
+// A line per datagram with when it arrived relative to the first and what its header says, and
+// why it couldn't be parsed if it couldn't
+fn inspect(options: &Options) -> io::Result<()> {
+    let mut source = open_source(options)?;
+    let mut out = open_output(options.output.as_deref())?;
+    let mut first_us: Option<u64> = None;
+    let mut index: u64 = 0;
+    while let Some((received_us, datagram)) = source.next_datagram()? {
+        let offset = received_us.saturating_sub(*first_us.get_or_insert(received_us)) as f64 / 1_000_000.0;
+        let packet = parse_packet(datagram);
+        let wanted = match &packet {
+            Ok(packet) => options.wants(packet.packet_type()),
+            Err(_) => options.packets.is_none(),
+        };
+        if wanted {
+            write!(out, "{:>8} {:>10.3}s {:>5} bytes ", index, offset, datagram.len())?;
+            match packet {
+                Ok(packet) => {
+                    let header = packet.header();
+                    writeln!(out, "{} {:<14} session {:016x} time {:.3} frame {}",
+                        header.packet_format, cli::packet_type_key(header.packet_type), header.session_id, header.session_time, header.frame_id)?;
+                },
+                Err(e) => writeln!(out, "unparsable: {}", e)?,
+            }
+            if options.input.is_none() {
+                out.flush()?;
+            }
+        }
+        index += 1;
+    }
+    out.flush()
 }
 
-fn parse_car_status_data(buf: &[u8; MAX_PACKET_SIZE], header: PacketHeader, num_bytes: usize) -> Option<CarStatusData> {
-	if num_bytes != STATUS_SIZE {
-		return None;
-	} 
-	let mut status_data = [CarStatus::default(); NUM_CARS];
-	parse_car_status(&mut status_data, &buf[HEADER_SIZE..], NUM_CARS);
-	return Some(CarStatusData{
-		header, 
-		car_status_data: status_data,
-	});
+// Counts packets in a recording, or live from the game every few seconds if no recording is given
+fn stats(options: &Options) -> io::Result<()> {
+    let mut stats = Stats::new();
+    let mut out = open_output(options.output.as_deref())?;
+    if options.input.is_some() {
+        let mut source = open_source(options)?;
+        while let Some((received_us, datagram)) = source.next_datagram()? {
+            count(&mut stats, options, received_us, datagram);
+        }
+        stats.write(&mut out)?;
+        return out.flush();
+    }
+
+    let mut source = bind(options)?;
+    let mut last_report = Instant::now();
+    source.set_read_timeout(Some(LIVE_STATS_INTERVAL))?;
+    loop {
+        match source.next_datagram() {
+            Ok(Some((received_us, datagram))) => count(&mut stats, options, received_us, datagram),
+            Ok(None) => return out.flush(),
+            Err(e) if is_timeout(&e) => {},
+            Err(e) => return Err(e),
+        }
+        if last_report.elapsed() >= LIVE_STATS_INTERVAL {
+            stats.write(&mut out)?;
+            writeln!(out)?;
+            out.flush()?;
+            last_report = Instant::now();
+        }
+    }
 }
 
-fn parse_car_status(status_data: &mut [CarStatus; NUM_CARS], bytes: &[u8], count: usize) {
-	let index = NUM_CARS - count;
-	let mut offset = index * CAR_STATUS_SIZE;
-	let mut tyre_wear 	= [0u8; NUM_WHEELS];
-	let mut tyre_damage = [0u8; NUM_WHEELS];
-	parse_wheel_array_u8(&mut tyre_wear, &bytes[offset+23..], NUM_WHEELS);
-	parse_wheel_array_u8(&mut tyre_damage, &bytes[offset+29..], NUM_WHEELS);
-	let car_status_data = 
-		CarStatus {
-			    traction_control: 	bytes.read_with::<u8>(&mut (offset), LE).unwrap(),		
-			    anti_lock_brakes: 	bytes.read_with::<u8>(&mut (offset+1), LE).unwrap(),		
-			    fuel_mix: 			bytes.read_with::<u8>(&mut (offset+2), LE).unwrap(),	
-			    front_brake_bias: 	bytes.read_with::<u8>(&mut (offset+3), LE).unwrap(),	
-			    pit_limiter_status: bytes.read_with::<u8>(&mut (offset+4), LE).unwrap(),	
-			    fuel_in_tank: 		bytes.read_with::<f32>(&mut (offset+5), LE).unwrap(),	
-				fuel_capacity: 		bytes.read_with::<f32>(&mut (offset+9), LE).unwrap(),	
-				fuel_remaining_laps:bytes.read_with::<f32>(&mut (offset+13), LE).unwrap(),	
-			    max_rpm: 			bytes.read_with::<u16>(&mut (offset+17), LE).unwrap(),	
-			    idle_rpm:			bytes.read_with::<u16>(&mut (offset+19), LE).unwrap(),	
-			    max_gears: 			bytes.read_with::<u8>(&mut (offset+21), LE).unwrap(),	
-			    drs_allowed: 		bytes.read_with::<u8>(&mut (offset+22), LE).unwrap(),	
-			    tyres_wear: 			tyre_wear,	
-			    actual_tyre_compound: 	bytes.read_with::<u8>(&mut (offset+27), LE).unwrap(),	
-				tyre_visual_compound: 	bytes.read_with::<u8>(&mut (offset+28), LE).unwrap(),      										
-			    tyres_damage: 			tyre_damage,
-			    front_left_wing_damage: bytes.read_with::<u8>(&mut (offset+33), LE).unwrap(),
-			    front_right_wing_damage:bytes.read_with::<u8>(&mut (offset+34), LE).unwrap(),
-			    rear_wing_damage: 		bytes.read_with::<u8>(&mut (offset+35), LE).unwrap(),
-			    engine_damage: 			bytes.read_with::<u8>(&mut (offset+36), LE).unwrap(),
-			    gear_box_damage: 		bytes.read_with::<u8>(&mut (offset+37), LE).unwrap(),
-			    vehicle_fia_flags: 		bytes.read_with::<i8>(&mut (offset+38), LE).unwrap(),                   
-			    ers_store_energy: 		bytes.read_with::<f32>(&mut (offset+39), LE).unwrap(),
-			    ers_deploy_mode: 		bytes.read_with::<u8>(&mut (offset+43), LE).unwrap(),			
-			    ers_harvested_this_lap_mguk:	bytes.read_with::<f32>(&mut (offset+44), LE).unwrap(),
-			    ers_harvested_this_lap_mguh:	bytes.read_with::<f32>(&mut (offset+48), LE).unwrap(),
-			    ers_deployed_this_lap: 			bytes.read_with::<f32>(&mut (offset+52), LE).unwrap(),
-		};
-	status_data[index] = car_status_data;
-	if count <= 0 {
-		return;
-	} else {
-		parse_car_status(status_data, bytes, count-1);
-	}
+fn count(stats: &mut Stats, options: &Options, received_us: u64, datagram: &[u8]) {
+    match parse_packet(datagram) {
+        Ok(packet) if options.wants(packet.packet_type()) => stats.add(received_us, datagram.len(), Ok(packet.header())),
+        Ok(_) => {},
+        Err(e) => stats.add(received_us, datagram.len(), Err(&e)),
+    }
 }
 
+// Writes the index for a capture, for captures recorded before record wrote them or whose index
+// was lost
+fn build_index(options: &Options) -> io::Result<()> {
+    let path = options.input.as_deref().expect("parse_args checks index has an input");
+    let mut file = BufReader::new(File::open(path)?);
+    if pcap::is_pcap(file.fill_buf()?) || archive::is_archive(file.fill_buf()?) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "only captures can be indexed, trim the recording to one first"));
+    }
+    let mut reader = CaptureReader::new(file)?;
+    let output = options.output.clone().unwrap_or_else(|| index::sidecar_path(path));
+    let mut index = IndexWriter::new(open_output(Some(&output))?)?;
+    let mut records: u64 = 0;
+    loop {
+        let position = reader.position();
+        match reader.next_record()? {
+            Some(record) => index.add(position, record.datagram),
+            None => break,
+        }
+        index.write_up_to(u64::MAX)?;
+        records += 1;
+    }
+    index.finish()?;
+    eprintln!("indexed {} of {} records to {}", index.len(), records, output.display());
+    Ok(())
+}
 
-	
+// Writes each piece of the recording to its own capture, named after the output or the recording
+// with the piece added
+fn split(options: &Options) -> io::Result<()> {
+    let path = options.input.as_deref().expect("parse_args checks split has an input");
+    let prefix = options.output.clone().unwrap_or_else(|| path.with_extension(""));
+    // Pieces are captures, with the recording's extension if it's one too
+    let is_capture = BufReader::new(File::open(path)?).fill_buf()?.starts_with(&capture::MAGIC);
+    let extension = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if is_capture => extension,
+        _ => "rec",
+    };
+    let mut source = open_source(options)?;
+    let mut splitter = Splitter::new(options.split_by);
+    // Each piece's lap tracker is kept once it's closed, for carrying on its index
+    let mut pieces: Vec<(Piece, PathBuf, Option<LapTracker>, u64)> = Vec::new();
+    // Only the piece being written is open, a piece left is closed and reopened if it comes back
+    let mut open: Option<(usize, DatagramWriter)> = None;
+    let mut datagram: Vec<u8> = Vec::new();     // Copied out so the source can be asked who sent it
+    let mut skipped: u64 = 0;
+    while let Some((received_us, received)) = source.next_datagram()? {
+        let piece = match splitter.piece(received) {
+            Some(piece) => piece,
+            None => {
+                skipped += 1;
+                continue;
+            },
+        };
+        if !wants_datagram(options, received) {
+            continue;
+        }
+        datagram.clear();
+        datagram.extend_from_slice(received);
+        // Usually the piece last written to, a lap can come round again after a flashback
+        let found = pieces.iter().rposition(|(written, _, _, _)| *written == piece);
+        let index = match found {
+            Some(index) => index,
+            None => {
+                let mut name = prefix.clone().into_os_string();
+                name.push(format!("-{:016x}", piece.session_id));
+                if let Some(lap) = piece.lap {
+                    name.push(format!("-lap{:02}", lap));
+                }
+                name.push(".");
+                name.push(extension);
+                pieces.push((piece, PathBuf::from(name), None, 0));
+                pieces.len() - 1
+            },
+        };
+        if open.as_ref().is_none_or(|(open_index, _)| *open_index != index) {
+            if let Some((closed, out)) = open.take() {
+                pieces[closed].2 = Some(out.close()?);
+            }
+            let (_, path, tracker, _) = &mut pieces[index];
+            let out = match tracker.take() {
+                Some(tracker) => DatagramWriter::append_capture(path, options.compress, tracker)?,
+                None => DatagramWriter::capture(path, options.compress)?,
+            };
+            open = Some((index, out));
+        }
+        let (_, out) = open.as_mut().expect("opened above");
+        out.write(received_us, source.source_address(), &datagram)?;
+        pieces[index].3 += 1;
+    }
+    if let Some((_, out)) = open {
+        out.close()?;
+    }
+    for (piece, path, _, written) in pieces {
+        eprintln!("{}: {} packets to {}", piece, written, path.display());
+    }
+    if skipped > 0 {
+        eprintln!("skipped {} datagrams before the first packet", skipped);
+    }
+    Ok(())
+}
 
+// Merges the recordings into one, each packet once
+fn merge(options: &Options) -> io::Result<()> {
+    let mut sources = Vec::new();
+    for path in options.input.iter().chain(options.inputs.iter()) {
+        let source = open_recording(path, options)?;
+        sources.push(match options.end {
+            Some(end) => Box::new(Until::new(source, end)) as Box<dyn PacketSource>,
+            None => source,
+        });
+    }
+    let mut merger = Merger::new(sources)?;
+    copy(&mut merger, options)?;
+    eprintln!("dropped {} packets already merged from another recording", merger.duplicates());
+    Ok(())
+}
@@ -0,0 +1,177 @@
+// Writes decoded packets for listen and export. Text is one line per packet, or one per car for
+// packets with an entry per car, prefixed with the header fields that place it in the session.
+// Debug writes every field of the decoded packet.
+
+use std::fmt::Debug;
+use std::io;
+use std::io::Write;
+
+use crate::Packet;
+use crate::f1_2019_net::PacketType;
+use crate::sink::PacketSink;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::EventType;
+use crate::f1_2019_net::CarMotion;
+use crate::f1_2019_net::LapData;
+use crate::f1_2019_net::ParticipantData;
+use crate::f1_2019_net::CarSetupData;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::FinalClassificationData;
+use crate::f1_2019_net::LobbyInfoData;
+
+use crate::cli::packet_type_key;
+use crate::cli::OutputFormat;
+use crate::cli::Options;
+
+// Writes the packets the options ask for to out
+pub struct WriterSink {
+    out:            Box<dyn Write>,
+    format:         OutputFormat,
+    packets:        Option<Vec<PacketType>>,
+    player_only:    bool,
+}
+
+impl WriterSink {
+    pub fn new(out: Box<dyn Write>, options: &Options) -> Self {
+        WriterSink {
+            out,
+            format: options.format,
+            packets: options.packets.clone(),
+            player_only: options.player_only,
+        }
+    }
+}
+
+impl PacketSink for WriterSink {
+    fn on_packet(&mut self, packet: &Packet) -> io::Result<()> {
+        match &self.packets {
+            Some(packets) if !packets.contains(&packet.packet_type()) => Ok(()),
+            _ => write_packet(&mut self.out, packet, self.format, self.player_only),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+pub fn write_packet(out: &mut dyn Write, packet: &Packet, format: OutputFormat, player_only: bool) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => write_text(out, packet, player_only),
+        OutputFormat::Debug => write_debug(out, packet, player_only),
+        OutputFormat::Pcap | OutputFormat::Archive => Err(io::Error::new(io::ErrorKind::InvalidInput,
+            format!("{} holds datagrams as received, not decoded packets", format.name()))),
+        OutputFormat::Csv => Err(io::Error::new(io::ErrorKind::InvalidInput, "csv is written a file per packet type by CsvSink")),
+    }
+}
+
+fn write_text(out: &mut dyn Write, packet: &Packet, player_only: bool) -> io::Result<()> {
+    let header = packet.header();
+    let prefix = format!("{} {:>10.3} {:>8} {:<14}",
+        header.packet_format, header.session_time, header.frame_id, packet_type_key(header.packet_type));
+    match packet {
+        Packet::Motion(p)       => write_cars(out, &prefix, header, &p.car_motion_data, player_only, describe_motion),
+        Packet::Lap(p)          => write_cars(out, &prefix, header, &p.lap_data, player_only, describe_lap),
+        Packet::Participants(p) => write_cars(out, &prefix, header, &p.participant_data, player_only, describe_participant),
+        Packet::CarSetups(p)    => write_cars(out, &prefix, header, &p.car_setups, player_only, describe_setup),
+        Packet::Telemetry(p)    => write_cars(out, &prefix, header, &p.car_telemetry_data, player_only, describe_telemetry),
+        Packet::CarStatus(p)    => write_cars(out, &prefix, header, &p.car_status_data, player_only, describe_status),
+        Packet::FinalClassification(p) => write_cars(out, &prefix, header, &p.classification_data, player_only, describe_classification),
+        Packet::LobbyInfo(p)    => write_cars(out, &prefix, header, &p.lobby_players, player_only, describe_lobby_player),
+        Packet::Session(p)      => {
+            let track = p.track().map_or("unknown track", |track| track.name);
+            writeln!(out, "{} {} {}, {}, track {}C air {}C, {} laps, {}s left",
+                prefix, track, p.session_type, p.weather, p.track_temp, p.air_temp, p.total_laps, p.session_ttl)
+        },
+        Packet::Event(p)        => {
+            let code = p.event_type.code();
+            let code = String::from_utf8_lossy(&code);
+            match p.event_type {
+                EventType::FastestLap { vehicle_idx, lap_time } => writeln!(out, "{} {} car {} {:.3}", prefix, code, vehicle_idx, lap_time),
+                event_type => match event_type.vehicle_idx() {
+                    Some(vehicle_idx) => writeln!(out, "{} {} car {}", prefix, code, vehicle_idx),
+                    None => writeln!(out, "{} {}", prefix, code),
+                },
+            }
+        },
+    }
+}
+
+// A line per car, or just the player's
+fn write_cars<T>(out: &mut dyn Write, prefix: &str, header: &PacketHeader, cars: &[T], player_only: bool,
+        describe: fn(&T) -> String) -> io::Result<()> {
+    for (index, car) in cars.iter().enumerate() {
+        if player_only && index != header.player_car_index as usize {
+            continue;
+        }
+        writeln!(out, "{} car {:>2} {}", prefix, index, describe(car))?;
+    }
+    Ok(())
+}
+
+fn describe_motion(car: &CarMotion) -> String {
+    format!("pos {} {:.1} km/h heading {:.3} g lat {:.2} long {:.2}",
+        car.position(), car.speed_kph(), car.heading(), car.lateral_g, car.longitudinal_g)
+}
+
+fn describe_lap(car: &LapData) -> String {
+    format!("P{} lap {} last {:.3} current {:.3} best {:.3} {} {}",
+        car.position, car.lap_num, car.last_lap, car.current_lap, car.best_lap, car.driver_status, car.pit_status)
+}
+
+fn describe_participant(car: &ParticipantData) -> String {
+    let team = car.team().map_or("unknown team", |team| team.name);
+    format!("#{} {} {}", car.race_number, car.name(), team)
+}
+
+fn describe_setup(car: &CarSetupData) -> String {
+    format!("wings {}/{} diff {}/{} brake bias {} fuel {:.1}",
+        car.front_wing, car.rear_wing, car.on_throttle, car.off_throttle, car.brake_bias, car.fuel_load)
+}
+
+fn describe_telemetry(car: &CarTelemetry) -> String {
+    format!("{} km/h gear {} rpm {} throttle {:.2} brake {:.2} steer {:.2} drs {}",
+        car.car_speed, car.gear, car.engine_rpm, car.throttle_pos, car.brake_pos, car.steering_pos, car.drs_active)
+}
+
+fn describe_status(car: &CarStatus) -> String {
+    format!("fuel {:.2} {} tyres {} wear {:?} ers {:.0} {}",
+        car.fuel_in_tank, car.fuel_mix, car.tyre_visual_compound, car.tyres_wear.as_array(), car.ers_store_energy, car.ers_deploy_mode)
+}
+
+fn describe_classification(car: &FinalClassificationData) -> String {
+    format!("P{} laps {} points {} best {:.3} total {:.3} {}",
+        car.position, car.num_laps, car.points, car.best_lap_time, car.total_race_time, car.result_status)
+}
+
+fn describe_lobby_player(player: &LobbyInfoData) -> String {
+    format!("{} ready {}", player.name(), player.ready_status)
+}
+
+fn write_debug(out: &mut dyn Write, packet: &Packet, player_only: bool) -> io::Result<()> {
+    if !player_only {
+        return writeln!(out, "{:#?}", packet);
+    }
+    let header = packet.header();
+    let player = header.player_car_index as usize;
+    match packet {
+        Packet::Motion(p)       => write_player(out, header, p.car_motion_data.get(player)),
+        Packet::Lap(p)          => write_player(out, header, p.lap_data.get(player)),
+        Packet::Participants(p) => write_player(out, header, p.participant_data.get(player)),
+        Packet::CarSetups(p)    => write_player(out, header, p.car_setups.get(player)),
+        Packet::Telemetry(p)    => write_player(out, header, p.car_telemetry_data.get(player)),
+        Packet::CarStatus(p)    => write_player(out, header, p.car_status_data.get(player)),
+        Packet::FinalClassification(p) => write_player(out, header, p.classification_data.get(player)),
+        Packet::LobbyInfo(p)    => write_player(out, header, p.lobby_players.get(player)),
+        _                       => writeln!(out, "{:#?}", packet),
+    }
+}
+
+fn write_player<T: Debug>(out: &mut dyn Write, header: &PacketHeader, car: Option<&T>) -> io::Result<()> {
+    writeln!(out, "{:#?}", header)?;
+    match car {
+        Some(car) => writeln!(out, "{:#?}", car),
+        None => writeln!(out, "no car at player index {}", header.player_car_index),
+    }
+}
@@ -0,0 +1,317 @@
+// F1 2018 layout. The header has no game version bytes, telemetry packs throttle, brake and
+// steering into single bytes and car status has one tyre compound and no fuel laps, otherwise
+// the payloads match 2019 and reuse its parsers.
+
+use crate::error::ParseError;
+use crate::format::PacketFormat;
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::SessionData;
+use crate::f1_2019_net::Lap;
+use crate::f1_2019_net::Event;
+use crate::f1_2019_net::CarSetups;
+use crate::f1_2019_net::CarStatusData;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::ParticipantData;
+use crate::f1_2019_net::Participants;
+use crate::f1_2019_net::Telemetry;
+use crate::f1_2019_net::ButtonStatus;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::FuelMix;
+use crate::f1_2019_net::ErsDeployMode;
+use crate::f1_2019_net::TyreCompound;
+use crate::f1_2019_net::VisualTyreCompound;
+use crate::f1_2019_net::FiaFlag;
+use crate::wheels::Wheels;
+use super::f1_2019;
+use super::layout::Field;
+use super::layout::FieldType;
+use super::layout::Layout;
+use super::Reader;
+use super::check_size;
+use super::get_format_packet_type;
+
+pub const PACKET_FORMAT: u16 = 2018;
+
+//Layouts that differ from 2019
+pub const HEADER_LAYOUT: Layout = Layout::new("PacketHeader", &[
+	Field::new("packet_format", 	FieldType::U16),
+	Field::new("packet_version", 	FieldType::U8),
+	Field::new("packet_type", 		FieldType::U8),
+	Field::new("session_id", 		FieldType::U64),
+	Field::new("session_time", 		FieldType::F32),
+	Field::new("frame_id", 			FieldType::U32),
+	Field::new("player_car_index", 	FieldType::U8),
+]);
+
+// No your_telemetry byte
+pub const PARTICIPANT_LAYOUT: Layout = Layout::new("ParticipantData", &[
+	Field::new("ai_controlled", 	FieldType::U8),
+	Field::new("driver_id", 		FieldType::U8),
+	Field::new("team_id", 			FieldType::U8),
+	Field::new("race_number", 		FieldType::U8),
+	Field::new("nationality", 		FieldType::U8),
+	Field::array("name", 			FieldType::U8, f1_2019::NAME_SIZE),
+]);
+
+// Throttle, steering and brake are single bytes and there's no surface type
+pub const CAR_TELEMETRY_LAYOUT: Layout = Layout::new("CarTelemetry", &[
+	Field::new("car_speed", 		FieldType::U16),
+	Field::new("throttle_pos", 		FieldType::U8),
+	Field::new("steering_pos", 		FieldType::I8),
+	Field::new("brake_pos", 		FieldType::U8),
+	Field::new("clutch_pos", 		FieldType::U8),
+	Field::new("gear", 				FieldType::I8),
+	Field::new("engine_rpm", 		FieldType::U16),
+	Field::new("drs_active", 		FieldType::U8),
+	Field::new("change_light_perc", FieldType::U8),
+	Field::wheels("brake_temps", 	FieldType::U16),
+	Field::wheels("tyre_surface_temps", FieldType::U16),
+	Field::wheels("tyre_inner_temps", FieldType::U16),
+	Field::new("engine_temp", 		FieldType::U16),
+	Field::wheels("tyre_pressures", FieldType::F32),
+]);
+
+// One tyre compound, no fuel laps and an exhaust damage byte
+pub const CAR_STATUS_LAYOUT: Layout = Layout::new("CarStatus", &[
+	Field::new("traction_control", 	FieldType::U8),
+	Field::new("anti_lock_brakes", 	FieldType::U8),
+	Field::new("fuel_mix", 			FieldType::U8),
+	Field::new("front_brake_bias", 	FieldType::U8),
+	Field::new("pit_limiter_status",FieldType::U8),
+	Field::new("fuel_in_tank", 		FieldType::F32),
+	Field::new("fuel_capacity", 	FieldType::F32),
+	Field::new("max_rpm", 			FieldType::U16),
+	Field::new("idle_rpm", 			FieldType::U16),
+	Field::new("max_gears", 		FieldType::U8),
+	Field::new("drs_allowed", 		FieldType::U8),
+	Field::wheels("tyres_wear", 	FieldType::U8),
+	Field::new("tyre_compound", 	FieldType::U8),
+	Field::wheels("tyres_damage", 	FieldType::U8),
+	Field::new("front_left_wing_damage", FieldType::U8),
+	Field::new("front_right_wing_damage", FieldType::U8),
+	Field::new("rear_wing_damage", 	FieldType::U8),
+	Field::new("engine_damage", 	FieldType::U8),
+	Field::new("gear_box_damage", 	FieldType::U8),
+	Field::new("exhaust_damage", 	FieldType::U8),
+	Field::new("vehicle_fia_flags", FieldType::I8),
+	Field::new("ers_store_energy", 	FieldType::F32),
+	Field::new("ers_deploy_mode", 	FieldType::U8),
+	Field::new("ers_harvested_this_lap_mguk", FieldType::F32),
+	Field::new("ers_harvested_this_lap_mguh", FieldType::F32),
+	Field::new("ers_deployed_this_lap", FieldType::F32),
+]);
+
+//Header part offsets
+pub const PACKET_VERSION_OFFSET: usize = HEADER_LAYOUT.offset_at(1);
+pub const PACKET_TYPE_OFFSET: usize = HEADER_LAYOUT.offset_at(2);
+pub const SESSION_ID_OFFSET: usize = HEADER_LAYOUT.offset_at(3);
+pub const SESSION_TIME_OFFSET: usize = HEADER_LAYOUT.offset_at(4);
+pub const FRAME_ID_OFFSET: usize = HEADER_LAYOUT.offset_at(5);
+pub const PLAYER_CAR_INDEX_OFFSET: usize = HEADER_LAYOUT.offset_at(6);
+
+//Sub type sizes that differ from 2019
+pub const PARTICIPANT_SIZE: usize = PARTICIPANT_LAYOUT.size();
+pub const CAR_TELEMETRY_SIZE: usize = CAR_TELEMETRY_LAYOUT.size();
+pub const CAR_STATUS_SIZE: usize = CAR_STATUS_LAYOUT.size();
+
+//Nums of elems
+pub const NUM_CARS: usize = 20;
+
+//Packet sizes
+pub const HEADER_SIZE: usize = HEADER_LAYOUT.size();
+pub const MOTION_SIZE: usize = HEADER_SIZE + NUM_CARS*f1_2019::CAR_MOTION_SIZE + f1_2019::MOTION_EXTRAS_LAYOUT.size();
+pub const SESSION_SIZE: usize = HEADER_SIZE + f1_2019::SESSION_LAYOUT.size();
+pub const LAP_SIZE: usize = HEADER_SIZE + NUM_CARS*f1_2019::CAR_LAP_SIZE;
+pub const EVENT_SIZE: usize = 25;		// Header + 4 byte code, 2018 events carry no details
+pub const PARTICIPANTS_SIZE: usize = HEADER_SIZE + 1 + NUM_CARS*PARTICIPANT_SIZE;	// +1 for num cars active
+pub const CARSETUPS_SIZE: usize = HEADER_SIZE + NUM_CARS*f1_2019::CAR_SETUP_SIZE;
+pub const TELEMETRY_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_TELEMETRY_SIZE + f1_2019::TELEMETRY_EXTRAS_LAYOUT.size();
+pub const STATUS_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_STATUS_SIZE;
+
+pub fn parse_packet(buf: &[u8]) -> Result<Packet, ParseError> {
+	let header = parse_header(buf)?;
+	match header.get_type() {
+		PacketType::Motion 		=> parse_motion_data(buf, header).map(Packet::Motion),
+		PacketType::Session 	=> parse_session_data(buf, header).map(Packet::Session),
+		PacketType::Lap 		=> parse_lap_data(buf, header).map(Packet::Lap),
+		PacketType::Event 		=> parse_event_data(buf, header).map(Packet::Event),
+		PacketType::Participant => parse_participant_data(buf, header).map(Packet::Participants),
+		PacketType::Setup 		=> parse_setups(buf, header).map(Packet::CarSetups),
+		PacketType::Telemetry 	=> parse_telemetry_data(buf, header).map(Packet::Telemetry),
+		PacketType::CarStatus 	=> parse_car_status_data(buf, header).map(Packet::CarStatus),
+		other 					=> Err(ParseError::UnknownPacketType(other.id())), // parse_header already rejects these
+	}
+}
+
+// 2018 has no game version bytes so maj/min version are left as 0
+pub fn parse_header(buf: &[u8]) -> Result<PacketHeader, ParseError> {
+	let mut reader = Reader::new(buf, 0, HEADER_SIZE)?;
+	let packet_format = reader.read::<u16>()?;
+	if packet_format != PACKET_FORMAT {
+		return Err(ParseError::UnsupportedFormat(packet_format));
+	}
+	Ok(PacketHeader::new(
+		packet_format,
+		0,
+		0,
+		reader.read::<u8>()?,
+		get_format_packet_type(PacketFormat::F2018, reader.read::<u8>()?)?,
+		reader.read::<u64>()?,
+		reader.read::<f32>()?,
+		reader.read::<u32>()?,
+		reader.read::<u8>()?
+	))
+}
+
+pub fn parse_motion_data(buf: &[u8], header: PacketHeader) -> Result<MotionData, ParseError> {
+	check_size(buf, MOTION_SIZE)?;
+	f1_2019::parse_motion_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub fn parse_session_data(buf: &[u8], header: PacketHeader) -> Result<SessionData, ParseError> {
+	check_size(buf, SESSION_SIZE)?;
+	f1_2019::parse_session_payload(buf, HEADER_SIZE, header)
+}
+
+pub fn parse_lap_data(buf: &[u8], header: PacketHeader) -> Result<Lap, ParseError> {
+	check_size(buf, LAP_SIZE)?;
+	f1_2019::parse_lap_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub fn parse_event_data(buf: &[u8], header: PacketHeader) -> Result<Event, ParseError> {
+	f1_2019::parse_event_payload(buf, HEADER_SIZE, header)
+}
+
+pub fn parse_setups(buf: &[u8], header: PacketHeader) -> Result<CarSetups, ParseError> {
+	check_size(buf, CARSETUPS_SIZE)?;
+	f1_2019::parse_setups_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub fn parse_participant_data(buf: &[u8], header: PacketHeader) -> Result<Participants, ParseError> {
+	check_size(buf, PARTICIPANTS_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, PARTICIPANTS_SIZE - HEADER_SIZE)?;
+	let num_cars_active = reader.read::<u8>()?;
+	let mut participants = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		participants.push(ParticipantData {
+			ai_controlled: 	reader.read::<u8>()?,
+			driver_id: 		reader.read::<u8>()?,
+			team_id: 		reader.read::<u8>()?,
+			race_number: 	reader.read::<u8>()?,
+			nationality: 	reader.read::<u8>()?,
+			name: 			reader.read_bytes::<{ f1_2019::NAME_SIZE }>()?,
+			priv_telemetry: 1,			// No telemetry restrictions before 2019
+		});
+	}
+	Ok(Participants {
+		header,
+		num_cars_active,
+		participant_data: participants,
+	})
+}
+
+// Throttle and brake are sent as 0 - 100 and steering as -100 - 100, scaled to match 2019's floats
+pub fn parse_telemetry_data(buf: &[u8], header: PacketHeader) -> Result<Telemetry, ParseError> {
+	check_size(buf, TELEMETRY_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, TELEMETRY_SIZE - HEADER_SIZE)?;
+	let mut car_telemetry = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		car_telemetry.push(CarTelemetry {
+			car_speed: 			reader.read::<u16>()?,
+			throttle_pos: 		f32::from(reader.read::<u8>()?) / 100.0,
+			steering_pos:		f32::from(reader.read::<i8>()?) / 100.0,
+			brake_pos: 			f32::from(reader.read::<u8>()?) / 100.0,
+			clutch_pos:			reader.read::<u8>()?,
+			gear: 				reader.read::<i8>()?,
+			engine_rpm:			reader.read::<u16>()?,
+			drs_active: 		reader.read::<u8>()?,
+			change_light_perc: 	reader.read::<u8>()?,
+			brake_temps:		reader.read_wheels::<u16>()?,
+			tyre_surface_temps:	reader.read_wheels::<u16>()?,
+			tyre_inner_temps: 	reader.read_wheels::<u16>()?,
+			engine_temp: 		reader.read::<u16>()?,
+			tyre_pressures: 	reader.read_wheels::<f32>()?,
+			tyre_contact_types: Wheels::default(),	// Not sent in 2018
+		});
+	}
+	Ok(Telemetry {
+		header,
+		car_telemetry_data: car_telemetry,
+		button_status: ButtonStatus(reader.read::<u32>()?),
+		mfd_panel_index: 255,
+		mfd_panel_index_secondary: 255,
+		suggested_gear: 0,
+	})
+}
+
+// 2018 sends a single tyre compound, used for both the actual and visual compound
+pub fn parse_car_status_data(buf: &[u8], header: PacketHeader) -> Result<CarStatusData, ParseError> {
+	check_size(buf, STATUS_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, NUM_CARS*CAR_STATUS_SIZE)?;
+	let mut status_data = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		status_data.push(read_car_status(&mut reader)?);
+	}
+	Ok(CarStatusData {
+		header,
+		car_status_data: status_data,
+	})
+}
+
+fn read_car_status(reader: &mut Reader) -> Result<CarStatus, ParseError> {
+	let traction_control = reader.read::<u8>()?;
+	let anti_lock_brakes = reader.read::<u8>()?;
+	let fuel_mix = reader.read_coded::<u8, FuelMix>()?;
+	let front_brake_bias = reader.read::<u8>()?;
+	let pit_limiter_status = reader.read::<u8>()?;
+	let fuel_in_tank = reader.read::<f32>()?;
+	let fuel_capacity = reader.read::<f32>()?;
+	let max_rpm = reader.read::<u16>()?;
+	let idle_rpm = reader.read::<u16>()?;
+	let max_gears = reader.read::<u8>()?;
+	let drs_allowed = reader.read::<u8>()?;
+	let tyres_wear = reader.read_wheels::<u8>()?;
+	let tyre_compound = reader.read::<u8>()?;
+	let tyres_damage = reader.read_wheels::<u8>()?;
+	let front_left_wing_damage = reader.read::<u8>()?;
+	let front_right_wing_damage = reader.read::<u8>()?;
+	let rear_wing_damage = reader.read::<u8>()?;
+	let engine_damage = reader.read::<u8>()?;
+	let gear_box_damage = reader.read::<u8>()?;
+	reader.skip(1)?;	// Exhaust damage, dropped in 2019 and not kept
+	Ok(CarStatus {
+		traction_control,
+		anti_lock_brakes,
+		fuel_mix,
+		front_brake_bias,
+		pit_limiter_status,
+		fuel_in_tank,
+		fuel_capacity,
+		fuel_remaining_laps:	0.0,
+		max_rpm,
+		idle_rpm,
+		max_gears,
+		drs_allowed,
+		drs_activation_distance: 0,
+		tyres_wear,
+		actual_tyre_compound: 	TyreCompound::from(tyre_compound),
+		tyre_visual_compound: 	VisualTyreCompound::from_2018(tyre_compound),
+		tyres_age_laps: 		0,
+		tyres_damage,
+		front_left_wing_damage,
+		front_right_wing_damage,
+		rear_wing_damage,
+		drs_fault: 				0,
+		engine_damage,
+		gear_box_damage,
+		vehicle_fia_flags: 		reader.read_coded::<i8, FiaFlag>()?,
+		ers_store_energy: 		reader.read::<f32>()?,
+		ers_deploy_mode: 		reader.read_coded::<u8, ErsDeployMode>()?,
+		ers_harvested_this_lap_mguk:	reader.read::<f32>()?,
+		ers_harvested_this_lap_mguh:	reader.read::<f32>()?,
+		ers_deployed_this_lap: 			reader.read::<f32>()?,
+	})
+}
@@ -0,0 +1,629 @@
+// F1 2019 layout. 2018 and 2020 share several of these payloads, so the *_payload functions take
+// the offset the payload starts at and the number of cars rather than assuming the 2019 header.
+
+use crate::error::ParseError;
+use crate::format::PacketFormat;
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::CarMotion;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::SessionData;
+use crate::f1_2019_net::LapData;
+use crate::f1_2019_net::Lap;
+use crate::f1_2019_net::CarSetupData;
+use crate::f1_2019_net::CarSetups;
+use crate::f1_2019_net::CarStatusData;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::ParticipantData;
+use crate::f1_2019_net::Participants;
+use crate::f1_2019_net::Telemetry;
+use crate::f1_2019_net::ButtonStatus;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::EventType;
+use crate::f1_2019_net::Event;
+use crate::f1_2019_net::MarshalZone;
+use crate::f1_2019_net::Weather;
+use crate::f1_2019_net::SessionType;
+use crate::f1_2019_net::Formula;
+use crate::f1_2019_net::SafetyCarStatus;
+use crate::f1_2019_net::PitStatus;
+use crate::f1_2019_net::DriverStatus;
+use crate::f1_2019_net::ResultStatus;
+use crate::f1_2019_net::FuelMix;
+use crate::f1_2019_net::ErsDeployMode;
+use crate::f1_2019_net::TyreCompound;
+use crate::f1_2019_net::VisualTyreCompound;
+use crate::f1_2019_net::FiaFlag;
+use super::layout::Field;
+use super::layout::FieldType;
+use super::layout::Layout;
+use super::Reader;
+use super::check_size;
+use super::get_format_packet_type;
+use super::NUM_MARSHAL_ZONES;
+
+pub const PACKET_FORMAT: u16 = 2019;
+
+//Layouts, fields in wire order. Sizes and offsets below are worked out from these
+pub const HEADER_LAYOUT: Layout = Layout::new("PacketHeader", &[
+	Field::new("packet_format", 	FieldType::U16),
+	Field::new("maj_version", 		FieldType::U8),
+	Field::new("min_version", 		FieldType::U8),
+	Field::new("packet_version", 	FieldType::U8),
+	Field::new("packet_type", 		FieldType::U8),
+	Field::new("session_id", 		FieldType::U64),
+	Field::new("session_time", 		FieldType::F32),
+	Field::new("frame_id", 			FieldType::U32),
+	Field::new("player_car_index", 	FieldType::U8),
+]);
+
+pub const CAR_MOTION_LAYOUT: Layout = Layout::new("CarMotion", &[
+	Field::new("world_pos_x", 		FieldType::F32),
+	Field::new("world_pos_y", 		FieldType::F32),
+	Field::new("world_pos_z", 		FieldType::F32),
+	Field::new("world_vel_x", 		FieldType::F32),
+	Field::new("world_vel_y", 		FieldType::F32),
+	Field::new("world_vel_z", 		FieldType::F32),
+	Field::new("world_fwd_dir_x", 	FieldType::I16),
+	Field::new("world_fwd_dir_y", 	FieldType::I16),
+	Field::new("world_fwd_dir_z", 	FieldType::I16),
+	Field::new("world_right_dir_x", FieldType::I16),
+	Field::new("world_right_dir_y", FieldType::I16),
+	Field::new("world_right_dir_z", FieldType::I16),
+	Field::new("lateral_g", 		FieldType::F32),
+	Field::new("longitudinal_g", 	FieldType::F32),
+	Field::new("vertical_g", 		FieldType::F32),
+	Field::new("yaw", 				FieldType::F32),
+	Field::new("pitch", 			FieldType::F32),
+	Field::new("roll", 				FieldType::F32),
+]);
+
+// Player car only, follows the car array in the motion packet
+pub const MOTION_EXTRAS_LAYOUT: Layout = Layout::new("MotionData", &[
+	Field::wheels("suspension_pos", FieldType::F32),
+	Field::wheels("suspension_vel", FieldType::F32),
+	Field::wheels("suspension_acc", FieldType::F32),
+	Field::wheels("wheel_speed", 	FieldType::F32),
+	Field::wheels("wheel_slip", 	FieldType::F32),
+	Field::new("local_vel_x", 		FieldType::F32),
+	Field::new("local_vel_y", 		FieldType::F32),
+	Field::new("local_vel_z", 		FieldType::F32),
+	Field::new("angular_vel_x", 	FieldType::F32),
+	Field::new("angular_vel_y", 	FieldType::F32),
+	Field::new("angular_vel_z", 	FieldType::F32),
+	Field::new("angular_acc_x", 	FieldType::F32),
+	Field::new("angular_acc_y", 	FieldType::F32),
+	Field::new("angular_acc_z", 	FieldType::F32),
+	Field::new("front_wheels_angle",FieldType::F32),
+]);
+
+pub const MARSHAL_ZONE_LAYOUT: Layout = Layout::new("MarshalZone", &[
+	Field::new("zone_start", 		FieldType::F32),
+	Field::new("flag", 				FieldType::I8),
+]);
+
+// Everything after the header
+pub const SESSION_LAYOUT: Layout = Layout::new("SessionData", &[
+	Field::new("weather", 			FieldType::U8),
+	Field::new("track_temp", 		FieldType::I8),
+	Field::new("air_temp", 			FieldType::I8),
+	Field::new("total_laps", 		FieldType::U8),
+	Field::new("track_len", 		FieldType::U16),
+	Field::new("session_type", 		FieldType::U8),
+	Field::new("track_id", 			FieldType::I8),
+	Field::new("formual", 			FieldType::U8),
+	Field::new("session_ttl", 		FieldType::U16),
+	Field::new("session_len", 		FieldType::U16),
+	Field::new("pit_spd_lim", 		FieldType::U8),
+	Field::new("is_paused", 		FieldType::U8),
+	Field::new("is_spectating", 	FieldType::U8),
+	Field::new("spectator_car", 	FieldType::U8),
+	Field::new("sli_native", 		FieldType::U8),
+	Field::new("num_zones", 		FieldType::U8),
+	Field::array("zones", 			FieldType::Record(&MARSHAL_ZONE_LAYOUT), NUM_MARSHAL_ZONES),
+	Field::new("safety_car", 		FieldType::U8),
+	Field::new("is_network_game", 	FieldType::U8),
+]);
+
+pub const CAR_LAP_LAYOUT: Layout = Layout::new("LapData", &[
+	Field::new("last_lap", 			FieldType::F32),
+	Field::new("current_lap", 		FieldType::F32),
+	Field::new("best_lap", 			FieldType::F32),
+	Field::new("best_sec_1", 		FieldType::F32),
+	Field::new("best_sec_2", 		FieldType::F32),
+	Field::new("lap_distance", 		FieldType::F32),
+	Field::new("total_distance", 	FieldType::F32),
+	Field::new("safety_car_delta", 	FieldType::F32),
+	Field::new("position", 			FieldType::U8),
+	Field::new("lap_num", 			FieldType::U8),
+	Field::new("pit_status", 		FieldType::U8),
+	Field::new("sector", 			FieldType::U8),
+	Field::new("is_lap_valid", 		FieldType::U8),
+	Field::new("penalties", 		FieldType::U8),
+	Field::new("grid_position", 	FieldType::U8),
+	Field::new("driver_status", 	FieldType::U8),
+	Field::new("result_status", 	FieldType::U8),
+]);
+
+pub const PARTICIPANT_LAYOUT: Layout = Layout::new("ParticipantData", &[
+	Field::new("ai_controlled", 	FieldType::U8),
+	Field::new("driver_id", 		FieldType::U8),
+	Field::new("team_id", 			FieldType::U8),
+	Field::new("race_number", 		FieldType::U8),
+	Field::new("nationality", 		FieldType::U8),
+	Field::array("name", 			FieldType::U8, NAME_SIZE),
+	Field::new("priv_telemetry", 	FieldType::U8),
+]);
+
+pub const CAR_SETUP_LAYOUT: Layout = Layout::new("CarSetupData", &[
+	Field::new("front_wing", 		FieldType::U8),
+	Field::new("rear_wing", 		FieldType::U8),
+	Field::new("on_throttle", 		FieldType::U8),
+	Field::new("off_throttle", 		FieldType::U8),
+	Field::new("front_camber", 		FieldType::F32),
+	Field::new("rear_camber", 		FieldType::F32),
+	Field::new("front_toe", 		FieldType::F32),
+	Field::new("rear_toe", 			FieldType::F32),
+	Field::new("front_suspension", 	FieldType::U8),
+	Field::new("rear_suspension", 	FieldType::U8),
+	Field::new("front_anti_roll_bar", FieldType::U8),
+	Field::new("rear_anti_roll_bar", FieldType::U8),
+	Field::new("front_suspension_height", FieldType::U8),
+	Field::new("rear_suspension_height", FieldType::U8),
+	Field::new("brake_pressure", 	FieldType::U8),
+	Field::new("brake_bias", 		FieldType::U8),
+	Field::new("front_tyre_pressure", FieldType::F32),
+	Field::new("rear_tyre_pressure", FieldType::F32),
+	Field::new("ballast", 			FieldType::U8),
+	Field::new("fuel_load", 		FieldType::F32),
+]);
+
+pub const CAR_TELEMETRY_LAYOUT: Layout = Layout::new("CarTelemetry", &[
+	Field::new("car_speed", 		FieldType::U16),
+	Field::new("throttle_pos", 		FieldType::F32),
+	Field::new("steering_pos", 		FieldType::F32),
+	Field::new("brake_pos", 		FieldType::F32),
+	Field::new("clutch_pos", 		FieldType::U8),
+	Field::new("gear", 				FieldType::I8),
+	Field::new("engine_rpm", 		FieldType::U16),
+	Field::new("drs_active", 		FieldType::U8),
+	Field::new("change_light_perc", FieldType::U8),
+	Field::wheels("brake_temps", 	FieldType::U16),
+	Field::wheels("tyre_surface_temps", FieldType::U16),
+	Field::wheels("tyre_inner_temps", FieldType::U16),
+	Field::new("engine_temp", 		FieldType::U16),
+	Field::wheels("tyre_pressures", FieldType::F32),
+	Field::wheels("tyre_contact_types", FieldType::U8),
+]);
+
+// Follows the car array in the telemetry packet
+pub const TELEMETRY_EXTRAS_LAYOUT: Layout = Layout::new("Telemetry", &[
+	Field::new("button_status", 	FieldType::U32),
+]);
+
+pub const CAR_STATUS_LAYOUT: Layout = Layout::new("CarStatus", &[
+	Field::new("traction_control", 	FieldType::U8),
+	Field::new("anti_lock_brakes", 	FieldType::U8),
+	Field::new("fuel_mix", 			FieldType::U8),
+	Field::new("front_brake_bias", 	FieldType::U8),
+	Field::new("pit_limiter_status",FieldType::U8),
+	Field::new("fuel_in_tank", 		FieldType::F32),
+	Field::new("fuel_capacity", 	FieldType::F32),
+	Field::new("fuel_remaining_laps", FieldType::F32),
+	Field::new("max_rpm", 			FieldType::U16),
+	Field::new("idle_rpm", 			FieldType::U16),
+	Field::new("max_gears", 		FieldType::U8),
+	Field::new("drs_allowed", 		FieldType::U8),
+	Field::wheels("tyres_wear", 	FieldType::U8),
+	Field::new("actual_tyre_compound", FieldType::U8),
+	Field::new("tyre_visual_compound", FieldType::U8),
+	Field::wheels("tyres_damage", 	FieldType::U8),
+	Field::new("front_left_wing_damage", FieldType::U8),
+	Field::new("front_right_wing_damage", FieldType::U8),
+	Field::new("rear_wing_damage", 	FieldType::U8),
+	Field::new("engine_damage", 	FieldType::U8),
+	Field::new("gear_box_damage", 	FieldType::U8),
+	Field::new("vehicle_fia_flags", FieldType::I8),
+	Field::new("ers_store_energy", 	FieldType::F32),
+	Field::new("ers_deploy_mode", 	FieldType::U8),
+	Field::new("ers_harvested_this_lap_mguk", FieldType::F32),
+	Field::new("ers_harvested_this_lap_mguh", FieldType::F32),
+	Field::new("ers_deployed_this_lap", FieldType::F32),
+]);
+
+//Header part offsets
+pub const MAJ_VERSION_OFFSET: usize = HEADER_LAYOUT.offset_at(1);
+pub const MIN_VERSION_OFFSET: usize = HEADER_LAYOUT.offset_at(2);
+pub const PACKET_VERSION_OFFSET: usize = HEADER_LAYOUT.offset_at(3);
+pub const PACKET_TYPE_OFFSET: usize = HEADER_LAYOUT.offset_at(4);
+pub const SESSION_ID_OFFSET: usize = HEADER_LAYOUT.offset_at(5);
+pub const SESSION_TIME_OFFSET: usize = HEADER_LAYOUT.offset_at(6);
+pub const FRAME_ID_OFFSET: usize = HEADER_LAYOUT.offset_at(7);
+pub const PLAYER_CAR_INDEX_OFFESET: usize = HEADER_LAYOUT.offset_at(8);
+
+//Sub type sizes
+pub const MARSHAL_ZONE_SIZE: usize = MARSHAL_ZONE_LAYOUT.size();
+pub const CAR_MOTION_SIZE: usize = CAR_MOTION_LAYOUT.size();
+pub const CAR_LAP_SIZE: usize = CAR_LAP_LAYOUT.size();
+pub const PARTICIPANT_SIZE: usize = PARTICIPANT_LAYOUT.size();
+pub const CAR_SETUP_SIZE: usize = CAR_SETUP_LAYOUT.size();
+pub const CAR_TELEMETRY_SIZE: usize = CAR_TELEMETRY_LAYOUT.size();
+pub const CAR_STATUS_SIZE: usize = CAR_STATUS_LAYOUT.size();
+pub const NAME_SIZE: usize = 48;
+
+//Nums of elems
+pub const NUM_CARS: usize = 20;
+
+//Packset Sizes for v basic check vs packet type byte
+pub const HEADER_SIZE: usize = HEADER_LAYOUT.size();
+pub const MOTION_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_MOTION_SIZE + MOTION_EXTRAS_LAYOUT.size();
+pub const SESSION_SIZE: usize = HEADER_SIZE + SESSION_LAYOUT.size();
+pub const LAP_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_LAP_SIZE;
+pub const EVENT_SIZE: usize = 32; // Header + 4 byte code + largest event details (fastest lap u8 + f32)
+pub const EVENT_CODE_SIZE: usize = 4;
+pub const PARTICIPANTS_SIZE: usize = HEADER_SIZE + 1 + NUM_CARS*PARTICIPANT_SIZE; // +1 for num cars active
+pub const CARSETUPS_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_SETUP_SIZE;
+pub const TELEMETY_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_TELEMETRY_SIZE + TELEMETRY_EXTRAS_LAYOUT.size();
+pub const STATUS_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_STATUS_SIZE;
+
+// Reads the header, checks the packet type byte and hands the buffer to the matching parser
+pub fn parse_packet(buf: &[u8]) -> Result<Packet, ParseError> {
+	let header = parse_header(buf)?;
+	match header.get_type() {
+		PacketType::Motion 		=> parse_motion_data(buf, header).map(Packet::Motion),
+		PacketType::Session 	=> parse_session_data(buf, header).map(Packet::Session),
+		PacketType::Lap 		=> parse_lap_data(buf, header).map(Packet::Lap),
+		PacketType::Event 		=> parse_event_data(buf, header).map(Packet::Event),
+		PacketType::Participant => parse_participant_data(buf, header).map(Packet::Participants),
+		PacketType::Setup 		=> parse_setups(buf, header).map(Packet::CarSetups),
+		PacketType::Telemetry 	=> parse_telemetry_data(buf, header).map(Packet::Telemetry),
+		PacketType::CarStatus 	=> parse_car_status_data(buf, header).map(Packet::CarStatus),
+		other 					=> Err(ParseError::UnknownPacketType(other.id())), // parse_header already rejects these
+	}
+}
+
+pub fn parse_header(buf: &[u8]) -> Result<PacketHeader, ParseError> {
+	let mut reader = Reader::new(buf, 0, HEADER_SIZE)?;
+	let packet_format = reader.read::<u16>()?;
+	if packet_format != PACKET_FORMAT {
+		return Err(ParseError::UnsupportedFormat(packet_format));
+	}
+	Ok(PacketHeader::new(
+		packet_format,
+		reader.read::<u8>()?,
+		reader.read::<u8>()?,
+		reader.read::<u8>()?,
+		get_format_packet_type(PacketFormat::F2019, reader.read::<u8>()?)?,
+		reader.read::<u64>()?,
+		reader.read::<f32>()?,
+		reader.read::<u32>()?,
+		reader.read::<u8>()?
+	))
+}
+
+pub fn parse_motion_data(buf: &[u8], header: PacketHeader) -> Result<MotionData, ParseError> {
+	check_size(buf, MOTION_SIZE)?;
+	parse_motion_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub(crate) fn parse_motion_payload(buf: &[u8], base: usize, num_cars: usize, header: PacketHeader) -> Result<MotionData, ParseError> {
+	let mut reader = Reader::new(buf, base, num_cars*CAR_MOTION_SIZE + MOTION_EXTRAS_LAYOUT.size())?;
+	let mut car_data = Vec::with_capacity(num_cars);
+	for _ in 0..num_cars {
+		car_data.push(read_car_motion(&mut reader)?);
+	}
+	// Player car only extras follow the car array
+	Ok(MotionData {
+		header,
+		car_motion_data: 	car_data,
+		suspension_pos: 	reader.read_wheels::<f32>()?,
+		suspension_vel: 	reader.read_wheels::<f32>()?,
+		suspension_acc:		reader.read_wheels::<f32>()?,
+		wheel_speed: 		reader.read_wheels::<f32>()?,
+		wheel_slip:			reader.read_wheels::<f32>()?,
+		local_vel_x:		reader.read::<f32>()?,			//Local space
+		local_vel_y:		reader.read::<f32>()?,
+		local_vel_z:		reader.read::<f32>()?,
+		angular_vel_x:		reader.read::<f32>()?,
+		angular_vel_y:		reader.read::<f32>()?,
+		angular_vel_z:		reader.read::<f32>()?,
+		angular_acc_x:		reader.read::<f32>()?,
+		angular_acc_y:		reader.read::<f32>()?,
+		angular_acc_z:		reader.read::<f32>()?,
+		front_wheels_angle: reader.read::<f32>()?,
+	})
+}
+
+fn read_car_motion(reader: &mut Reader) -> Result<CarMotion, ParseError> {
+	Ok(CarMotion {
+		world_pos_x: 		reader.read::<f32>()?,
+		world_pos_y: 		reader.read::<f32>()?,
+		world_pos_z: 		reader.read::<f32>()?,
+		world_vel_x: 		reader.read::<f32>()?,
+		world_vel_y: 		reader.read::<f32>()?,
+		world_vel_z: 		reader.read::<f32>()?,
+		world_fwd_dir_x: 	reader.read::<i16>()?,
+		world_fwd_dir_y: 	reader.read::<i16>()?,
+		world_fwd_dir_z: 	reader.read::<i16>()?,
+		world_right_dir_x: 	reader.read::<i16>()?,
+		world_right_dir_y: 	reader.read::<i16>()?,
+		world_right_dir_z: 	reader.read::<i16>()?,
+		lateral_g: 			reader.read::<f32>()?,
+		longitudinal_g: 	reader.read::<f32>()?,
+		vertical_g: 		reader.read::<f32>()?,
+		yaw: 				reader.read::<f32>()?,
+		pitch: 				reader.read::<f32>()?,
+		roll: 				reader.read::<f32>()?,
+	})
+}
+
+pub fn parse_session_data(buf: &[u8], header: PacketHeader) -> Result<SessionData, ParseError> {
+	check_size(buf, SESSION_SIZE)?;
+	parse_session_payload(buf, HEADER_SIZE, header)
+}
+
+pub(crate) fn parse_session_payload(buf: &[u8], base: usize, header: PacketHeader) -> Result<SessionData, ParseError> {
+	let mut reader = Reader::new(buf, base, SESSION_LAYOUT.size())?;
+	Ok(SessionData {
+		header,
+		weather:		reader.read_coded::<u8, Weather>()?,
+		track_temp:		reader.read::<i8>()?,
+		air_temp:		reader.read::<i8>()?,
+		total_laps:		reader.read::<u8>()?,
+		track_len:		reader.read::<u16>()?,
+		session_type:	reader.read_coded::<u8, SessionType>()?,
+		track_id:		reader.read::<i8>()?,
+		formual:		reader.read_coded::<u8, Formula>()?,
+		session_ttl:	reader.read::<u16>()?,
+		session_len:	reader.read::<u16>()?,
+		pit_spd_lim:	reader.read::<u8>()?,
+		is_paused:		reader.read::<u8>()?,
+		is_spectating:	reader.read::<u8>()?,
+		spectator_car:	reader.read::<u8>()?,
+		sli_native:		reader.read::<u8>()?,
+		num_zones:		reader.read::<u8>()?,
+		zones:			read_marshal_zones(&mut reader)?,
+		safety_car:		reader.read_coded::<u8, SafetyCarStatus>()?,
+		is_network_game:reader.read::<u8>()?,
+		weather_forecast_samples: Vec::new(),
+	})
+}
+
+fn read_marshal_zones(reader: &mut Reader) -> Result<[MarshalZone; NUM_MARSHAL_ZONES], ParseError> {
+	let mut zones = [MarshalZone::default(); NUM_MARSHAL_ZONES];
+	for zone in zones.iter_mut() {
+		*zone = MarshalZone {
+			zone_start: reader.read::<f32>()?,
+			flag:		reader.read_coded::<i8, FiaFlag>()?,
+		};
+	}
+	Ok(zones)
+}
+
+pub fn parse_lap_data(buf: &[u8], header: PacketHeader) -> Result<Lap, ParseError> {
+	check_size(buf, LAP_SIZE)?;
+	parse_lap_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub(crate) fn parse_lap_payload(buf: &[u8], base: usize, num_cars: usize, header: PacketHeader) -> Result<Lap, ParseError> {
+	let mut reader = Reader::new(buf, base, num_cars*CAR_LAP_SIZE)?;
+	let mut car_laps = Vec::with_capacity(num_cars);
+	for _ in 0..num_cars {
+		car_laps.push(read_car_lap(&mut reader)?);
+	}
+	Ok(Lap {
+		header,
+		lap_data: car_laps,
+	})
+}
+
+fn read_car_lap(reader: &mut Reader) -> Result<LapData, ParseError> {
+	Ok(LapData {
+		last_lap: 		reader.read::<f32>()?,
+		current_lap: 	reader.read::<f32>()?,
+		best_lap: 		reader.read::<f32>()?,
+		best_sec_1: 	reader.read::<f32>()?,
+		best_sec_2: 	reader.read::<f32>()?,
+		lap_distance: 	reader.read::<f32>()?,
+		total_distance: reader.read::<f32>()?,
+		safety_car_delta: reader.read::<f32>()?,
+		position: 		reader.read::<u8>()?,
+		lap_num: 		reader.read::<u8>()?,
+		pit_status: 	reader.read_coded::<u8, PitStatus>()?,
+		sector: 		reader.read::<u8>()?,
+		is_lap_valid: 	reader.read::<u8>()?,
+		penalties: 		reader.read::<u8>()?,
+		grid_position: 	reader.read::<u8>()?,
+		driver_status: 	reader.read_coded::<u8, DriverStatus>()?,
+		result_status: 	reader.read_coded::<u8, ResultStatus>()?,
+	})
+}
+
+pub fn parse_event_data(buf: &[u8], header: PacketHeader) -> Result<Event, ParseError> {
+	parse_event_payload(buf, HEADER_SIZE, header)
+}
+
+pub(crate) fn parse_event_payload(buf: &[u8], base: usize, header: PacketHeader) -> Result<Event, ParseError> {
+	//Can't naively check event packet size as this varies with event type, but the code is always there
+	let code = Reader::new(buf, base, EVENT_CODE_SIZE)?.read_bytes::<EVENT_CODE_SIZE>()?;
+	let details = base + EVENT_CODE_SIZE;
+	let mut reader = Reader::new(buf, details, buf.len() - details)?;
+	let event_type = match &code {
+		b"SSTA" => EventType::SessionStarted,
+		b"SEND" => EventType::SessionEnded,
+		b"FTLP" => EventType::FastestLap {
+			vehicle_idx: 	reader.read::<u8>()?,
+			lap_time: 		reader.read::<f32>()?,
+		},
+		b"RTMT" => EventType::Retirement { vehicle_idx: reader.read::<u8>()? },
+		b"DRSE" => EventType::DrsEnabled,
+		b"DRSD" => EventType::DrsDisabled,
+		b"TMPT" => EventType::TeamMateInPits { vehicle_idx: reader.read::<u8>()? },
+		b"CHQF" => EventType::ChequeredFlag,
+		b"RCWN" => EventType::RaceWinner { vehicle_idx: reader.read::<u8>()? },
+		_ 		=> EventType::Unknown(code),
+	};
+	Ok(Event {
+		header,
+		event_type,
+	})
+}
+
+pub fn parse_participant_data(buf: &[u8], header: PacketHeader) -> Result<Participants, ParseError> {
+	check_size(buf, PARTICIPANTS_SIZE)?;
+	parse_participant_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub(crate) fn parse_participant_payload(buf: &[u8], base: usize, num_cars: usize, header: PacketHeader) -> Result<Participants, ParseError> {
+	let mut reader = Reader::new(buf, base, 1 + num_cars*PARTICIPANT_SIZE)?;
+	let num_cars_active = reader.read::<u8>()?;
+	let mut participants = Vec::with_capacity(num_cars);
+	for _ in 0..num_cars {
+		participants.push(read_participant(&mut reader)?);
+	}
+	Ok(Participants {
+		header,
+		num_cars_active,
+		participant_data: participants,
+	})
+}
+
+fn read_participant(reader: &mut Reader) -> Result<ParticipantData, ParseError> {
+	Ok(ParticipantData {
+		ai_controlled: 	reader.read::<u8>()?,
+		driver_id: 		reader.read::<u8>()?,
+		team_id: 		reader.read::<u8>()?,
+		race_number: 	reader.read::<u8>()?,
+		nationality: 	reader.read::<u8>()?,
+		name: 			reader.read_bytes::<NAME_SIZE>()?,
+		priv_telemetry: reader.read::<u8>()?,
+	})
+}
+
+pub fn parse_setups(buf: &[u8], header: PacketHeader) -> Result<CarSetups, ParseError> {
+	check_size(buf, CARSETUPS_SIZE)?;
+	parse_setups_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub(crate) fn parse_setups_payload(buf: &[u8], base: usize, num_cars: usize, header: PacketHeader) -> Result<CarSetups, ParseError> {
+	let mut reader = Reader::new(buf, base, num_cars*CAR_SETUP_SIZE)?;
+	let mut setup_data = Vec::with_capacity(num_cars);
+	for _ in 0..num_cars {
+		setup_data.push(read_car_setup(&mut reader)?);
+	}
+	Ok(CarSetups {
+		header,
+		car_setups: setup_data,
+	})
+}
+
+fn read_car_setup(reader: &mut Reader) -> Result<CarSetupData, ParseError> {
+	Ok(CarSetupData {
+		front_wing: 			reader.read::<u8>()?,
+		rear_wing:				reader.read::<u8>()?,
+		on_throttle: 			reader.read::<u8>()?,
+		off_throttle: 			reader.read::<u8>()?,
+		front_camber: 			reader.read::<f32>()?,
+		rear_camber: 			reader.read::<f32>()?,
+		front_toe: 				reader.read::<f32>()?,
+		rear_toe: 				reader.read::<f32>()?,
+		front_suspension: 		reader.read::<u8>()?,
+		rear_suspension:		reader.read::<u8>()?,
+		front_anti_roll_bar: 	reader.read::<u8>()?,
+		rear_anti_roll_bar: 	reader.read::<u8>()?,
+		front_suspension_height:reader.read::<u8>()?,
+		rear_suspension_height: reader.read::<u8>()?,
+		brake_pressure: 		reader.read::<u8>()?,
+		brake_bias: 			reader.read::<u8>()?,
+		front_tyre_pressure: 	reader.read::<f32>()?,
+		rear_tyre_pressure: 	reader.read::<f32>()?,
+		ballast: 				reader.read::<u8>()?,
+		fuel_load: 				reader.read::<f32>()?,
+	})
+}
+
+pub fn parse_telemetry_data(buf: &[u8], header: PacketHeader) -> Result<Telemetry, ParseError> {
+	check_size(buf, TELEMETY_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, TELEMETY_SIZE - HEADER_SIZE)?;
+	let mut car_telemetry = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		car_telemetry.push(read_car_telemetry(&mut reader)?);
+	}
+	Ok(Telemetry {
+		header,
+		car_telemetry_data: car_telemetry,
+		button_status: ButtonStatus(reader.read::<u32>()?),
+		mfd_panel_index: 255,
+		mfd_panel_index_secondary: 255,
+		suggested_gear: 0,
+	})
+}
+
+fn read_car_telemetry(reader: &mut Reader) -> Result<CarTelemetry, ParseError> {
+	Ok(CarTelemetry {
+		car_speed: 			reader.read::<u16>()?,
+		throttle_pos: 		reader.read::<f32>()?,
+		steering_pos:		reader.read::<f32>()?,
+		brake_pos: 			reader.read::<f32>()?,
+		clutch_pos:			reader.read::<u8>()?,
+		gear: 				reader.read::<i8>()?,
+		engine_rpm:			reader.read::<u16>()?,
+		drs_active: 		reader.read::<u8>()?,
+		change_light_perc: 	reader.read::<u8>()?,
+		brake_temps:		reader.read_wheels::<u16>()?,
+		tyre_surface_temps:	reader.read_wheels::<u16>()?,
+		tyre_inner_temps: 	reader.read_wheels::<u16>()?,
+		engine_temp: 		reader.read::<u16>()?,
+		tyre_pressures: 	reader.read_wheels::<f32>()?,
+		tyre_contact_types: reader.read_wheels::<u8>()?,
+	})
+}
+
+pub fn parse_car_status_data(buf: &[u8], header: PacketHeader) -> Result<CarStatusData, ParseError> {
+	check_size(buf, STATUS_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, NUM_CARS*CAR_STATUS_SIZE)?;
+	let mut status_data = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		status_data.push(read_car_status(&mut reader)?);
+	}
+	Ok(CarStatusData {
+		header,
+		car_status_data: status_data,
+	})
+}
+
+fn read_car_status(reader: &mut Reader) -> Result<CarStatus, ParseError> {
+	Ok(CarStatus {
+		traction_control: 	reader.read::<u8>()?,
+		anti_lock_brakes: 	reader.read::<u8>()?,
+		fuel_mix: 			reader.read_coded::<u8, FuelMix>()?,
+		front_brake_bias: 	reader.read::<u8>()?,
+		pit_limiter_status: reader.read::<u8>()?,
+		fuel_in_tank: 		reader.read::<f32>()?,
+		fuel_capacity: 		reader.read::<f32>()?,
+		fuel_remaining_laps:reader.read::<f32>()?,
+		max_rpm: 			reader.read::<u16>()?,
+		idle_rpm:			reader.read::<u16>()?,
+		max_gears: 			reader.read::<u8>()?,
+		drs_allowed: 		reader.read::<u8>()?,
+		drs_activation_distance: 0,
+		tyres_wear: 			reader.read_wheels::<u8>()?,
+		actual_tyre_compound: 	reader.read_coded::<u8, TyreCompound>()?,
+		tyre_visual_compound: 	reader.read_coded::<u8, VisualTyreCompound>()?,
+		tyres_age_laps: 		0,
+		tyres_damage: 			reader.read_wheels::<u8>()?,
+		front_left_wing_damage: reader.read::<u8>()?,
+		front_right_wing_damage:reader.read::<u8>()?,
+		rear_wing_damage: 		reader.read::<u8>()?,
+		drs_fault: 				0,
+		engine_damage: 			reader.read::<u8>()?,
+		gear_box_damage: 		reader.read::<u8>()?,
+		vehicle_fia_flags: 		reader.read_coded::<i8, FiaFlag>()?,
+		ers_store_energy: 		reader.read::<f32>()?,
+		ers_deploy_mode: 		reader.read_coded::<u8, ErsDeployMode>()?,
+		ers_harvested_this_lap_mguk:	reader.read::<f32>()?,
+		ers_harvested_this_lap_mguh:	reader.read::<f32>()?,
+		ers_deployed_this_lap: 			reader.read::<f32>()?,
+	})
+}
@@ -0,0 +1,544 @@
+// F1 2020 layout. 22 cars, a secondary player index in the header and two new packet types,
+// final classification and lobby info. Motion and participants match 2019 with the bigger grid,
+// session appends weather forecasts, the rest of the per car structs gained fields.
+
+use crate::error::ParseError;
+use crate::format::PacketFormat;
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::SessionData;
+use crate::f1_2019_net::WeatherForecastSample;
+use crate::f1_2019_net::LapData;
+use crate::f1_2019_net::Lap;
+use crate::f1_2019_net::Event;
+use crate::f1_2019_net::CarSetupData;
+use crate::f1_2019_net::CarSetups;
+use crate::f1_2019_net::CarStatusData;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::Participants;
+use crate::f1_2019_net::Telemetry;
+use crate::f1_2019_net::ButtonStatus;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::FinalClassificationData;
+use crate::f1_2019_net::FinalClassification;
+use crate::f1_2019_net::LobbyInfoData;
+use crate::f1_2019_net::LobbyInfo;
+use crate::f1_2019_net::Weather;
+use crate::f1_2019_net::SessionType;
+use crate::f1_2019_net::PitStatus;
+use crate::f1_2019_net::DriverStatus;
+use crate::f1_2019_net::ResultStatus;
+use crate::f1_2019_net::FuelMix;
+use crate::f1_2019_net::ErsDeployMode;
+use crate::f1_2019_net::TyreCompound;
+use crate::f1_2019_net::VisualTyreCompound;
+use crate::f1_2019_net::FiaFlag;
+use super::f1_2019;
+use super::layout::Field;
+use super::layout::FieldType;
+use super::layout::Layout;
+use super::Reader;
+use super::check_size;
+use super::get_format_packet_type;
+
+pub const PACKET_FORMAT: u16 = 2020;
+
+//Layouts that differ from 2019
+pub const HEADER_LAYOUT: Layout = Layout::new("PacketHeader", &[
+	Field::new("packet_format", 	FieldType::U16),
+	Field::new("maj_version", 		FieldType::U8),
+	Field::new("min_version", 		FieldType::U8),
+	Field::new("packet_version", 	FieldType::U8),
+	Field::new("packet_type", 		FieldType::U8),
+	Field::new("session_id", 		FieldType::U64),
+	Field::new("session_time", 		FieldType::F32),
+	Field::new("frame_id", 			FieldType::U32),
+	Field::new("player_car_index", 	FieldType::U8),
+	Field::new("secondary_player_car_index", FieldType::U8),
+]);
+
+pub const WEATHER_FORECAST_SAMPLE_LAYOUT: Layout = Layout::new("WeatherForecastSample", &[
+	Field::new("session_type", 		FieldType::U8),
+	Field::new("time_offset", 		FieldType::U8),
+	Field::new("weather", 			FieldType::U8),
+	Field::new("track_temp", 		FieldType::I8),
+	Field::new("air_temp", 			FieldType::I8),
+]);
+
+// Follows the 2019 session fields
+pub const SESSION_EXTRAS_LAYOUT: Layout = Layout::new("SessionData", &[
+	Field::new("num_weather_forecast_samples", FieldType::U8),
+	Field::array("weather_forecast_samples", FieldType::Record(&WEATHER_FORECAST_SAMPLE_LAYOUT), NUM_WEATHER_FORECAST_SAMPLES),
+]);
+
+pub const CAR_LAP_LAYOUT: Layout = Layout::new("LapData", &[
+	Field::new("last_lap", 			FieldType::F32),
+	Field::new("current_lap", 		FieldType::F32),
+	Field::new("best_sec_1", 		FieldType::U16),	// Current sector times, ms
+	Field::new("best_sec_2", 		FieldType::U16),
+	Field::new("best_lap", 			FieldType::F32),
+	Field::new("best_lap_num", 		FieldType::U8),
+	Field::new("best_lap_sector1_time_ms", FieldType::U16),
+	Field::new("best_lap_sector2_time_ms", FieldType::U16),
+	Field::new("best_lap_sector3_time_ms", FieldType::U16),
+	Field::new("best_overall_sector1_time_ms", FieldType::U16),
+	Field::new("best_overall_sector1_lap_num", FieldType::U8),
+	Field::new("best_overall_sector2_time_ms", FieldType::U16),
+	Field::new("best_overall_sector2_lap_num", FieldType::U8),
+	Field::new("best_overall_sector3_time_ms", FieldType::U16),
+	Field::new("best_overall_sector3_lap_num", FieldType::U8),
+	Field::new("lap_distance", 		FieldType::F32),
+	Field::new("total_distance", 	FieldType::F32),
+	Field::new("safety_car_delta", 	FieldType::F32),
+	Field::new("position", 			FieldType::U8),
+	Field::new("lap_num", 			FieldType::U8),
+	Field::new("pit_status", 		FieldType::U8),
+	Field::new("sector", 			FieldType::U8),
+	Field::new("is_lap_valid", 		FieldType::U8),
+	Field::new("penalties", 		FieldType::U8),
+	Field::new("grid_position", 	FieldType::U8),
+	Field::new("driver_status", 	FieldType::U8),
+	Field::new("result_status", 	FieldType::U8),
+]);
+
+pub const CAR_SETUP_LAYOUT: Layout = Layout::new("CarSetupData", &[
+	Field::new("front_wing", 		FieldType::U8),
+	Field::new("rear_wing", 		FieldType::U8),
+	Field::new("on_throttle", 		FieldType::U8),
+	Field::new("off_throttle", 		FieldType::U8),
+	Field::new("front_camber", 		FieldType::F32),
+	Field::new("rear_camber", 		FieldType::F32),
+	Field::new("front_toe", 		FieldType::F32),
+	Field::new("rear_toe", 			FieldType::F32),
+	Field::new("front_suspension", 	FieldType::U8),
+	Field::new("rear_suspension", 	FieldType::U8),
+	Field::new("front_anti_roll_bar", FieldType::U8),
+	Field::new("rear_anti_roll_bar", FieldType::U8),
+	Field::new("front_suspension_height", FieldType::U8),
+	Field::new("rear_suspension_height", FieldType::U8),
+	Field::new("brake_pressure", 	FieldType::U8),
+	Field::new("brake_bias", 		FieldType::U8),
+	Field::wheels("tyre_pressures", FieldType::F32),
+	Field::new("ballast", 			FieldType::U8),
+	Field::new("fuel_load", 		FieldType::F32),
+]);
+
+pub const CAR_TELEMETRY_LAYOUT: Layout = Layout::new("CarTelemetry", &[
+	Field::new("car_speed", 		FieldType::U16),
+	Field::new("throttle_pos", 		FieldType::F32),
+	Field::new("steering_pos", 		FieldType::F32),
+	Field::new("brake_pos", 		FieldType::F32),
+	Field::new("clutch_pos", 		FieldType::U8),
+	Field::new("gear", 				FieldType::I8),
+	Field::new("engine_rpm", 		FieldType::U16),
+	Field::new("drs_active", 		FieldType::U8),
+	Field::new("change_light_perc", FieldType::U8),
+	Field::wheels("brake_temps", 	FieldType::U16),
+	Field::wheels("tyre_surface_temps", FieldType::U8),
+	Field::wheels("tyre_inner_temps", FieldType::U8),
+	Field::new("engine_temp", 		FieldType::U16),
+	Field::wheels("tyre_pressures", FieldType::F32),
+	Field::wheels("tyre_contact_types", FieldType::U8),
+]);
+
+// Follows the car array in the telemetry packet
+pub const TELEMETRY_EXTRAS_LAYOUT: Layout = Layout::new("Telemetry", &[
+	Field::new("button_status", 	FieldType::U32),
+	Field::new("mfd_panel_index", 	FieldType::U8),
+	Field::new("mfd_panel_index_secondary", FieldType::U8),
+	Field::new("suggested_gear", 	FieldType::I8),
+]);
+
+pub const CAR_STATUS_LAYOUT: Layout = Layout::new("CarStatus", &[
+	Field::new("traction_control", 	FieldType::U8),
+	Field::new("anti_lock_brakes", 	FieldType::U8),
+	Field::new("fuel_mix", 			FieldType::U8),
+	Field::new("front_brake_bias", 	FieldType::U8),
+	Field::new("pit_limiter_status",FieldType::U8),
+	Field::new("fuel_in_tank", 		FieldType::F32),
+	Field::new("fuel_capacity", 	FieldType::F32),
+	Field::new("fuel_remaining_laps", FieldType::F32),
+	Field::new("max_rpm", 			FieldType::U16),
+	Field::new("idle_rpm", 			FieldType::U16),
+	Field::new("max_gears", 		FieldType::U8),
+	Field::new("drs_allowed", 		FieldType::U8),
+	Field::new("drs_activation_distance", FieldType::U16),
+	Field::wheels("tyres_wear", 	FieldType::U8),
+	Field::new("actual_tyre_compound", FieldType::U8),
+	Field::new("tyre_visual_compound", FieldType::U8),
+	Field::new("tyres_age_laps", 	FieldType::U8),
+	Field::wheels("tyres_damage", 	FieldType::U8),
+	Field::new("front_left_wing_damage", FieldType::U8),
+	Field::new("front_right_wing_damage", FieldType::U8),
+	Field::new("rear_wing_damage", 	FieldType::U8),
+	Field::new("drs_fault", 		FieldType::U8),
+	Field::new("engine_damage", 	FieldType::U8),
+	Field::new("gear_box_damage", 	FieldType::U8),
+	Field::new("vehicle_fia_flags", FieldType::I8),
+	Field::new("ers_store_energy", 	FieldType::F32),
+	Field::new("ers_deploy_mode", 	FieldType::U8),
+	Field::new("ers_harvested_this_lap_mguk", FieldType::F32),
+	Field::new("ers_harvested_this_lap_mguh", FieldType::F32),
+	Field::new("ers_deployed_this_lap", FieldType::F32),
+]);
+
+pub const FINAL_CLASSIFICATION_DATA_LAYOUT: Layout = Layout::new("FinalClassificationData", &[
+	Field::new("position", 			FieldType::U8),
+	Field::new("num_laps", 			FieldType::U8),
+	Field::new("grid_position", 	FieldType::U8),
+	Field::new("points", 			FieldType::U8),
+	Field::new("num_pit_stops", 	FieldType::U8),
+	Field::new("result_status", 	FieldType::U8),
+	Field::new("best_lap_time", 	FieldType::F32),
+	Field::new("total_race_time", 	FieldType::F64),
+	Field::new("penalties_time", 	FieldType::U8),
+	Field::new("num_penalties", 	FieldType::U8),
+	Field::new("num_tyre_stints", 	FieldType::U8),
+	Field::array("tyre_stints_actual", FieldType::U8, NUM_TYRE_STINTS),
+	Field::array("tyre_stints_visual", FieldType::U8, NUM_TYRE_STINTS),
+]);
+
+pub const LOBBY_INFO_DATA_LAYOUT: Layout = Layout::new("LobbyInfoData", &[
+	Field::new("ai_controlled", 	FieldType::U8),
+	Field::new("team_id", 			FieldType::U8),
+	Field::new("nationality", 		FieldType::U8),
+	Field::array("name", 			FieldType::U8, f1_2019::NAME_SIZE),
+	Field::new("ready_status", 		FieldType::U8),
+]);
+
+//Header part offsets, same as 2019 plus the secondary player
+pub const MAJ_VERSION_OFFSET: usize = HEADER_LAYOUT.offset_at(1);
+pub const MIN_VERSION_OFFSET: usize = HEADER_LAYOUT.offset_at(2);
+pub const PACKET_VERSION_OFFSET: usize = HEADER_LAYOUT.offset_at(3);
+pub const PACKET_TYPE_OFFSET: usize = HEADER_LAYOUT.offset_at(4);
+pub const SESSION_ID_OFFSET: usize = HEADER_LAYOUT.offset_at(5);
+pub const SESSION_TIME_OFFSET: usize = HEADER_LAYOUT.offset_at(6);
+pub const FRAME_ID_OFFSET: usize = HEADER_LAYOUT.offset_at(7);
+pub const PLAYER_CAR_INDEX_OFFSET: usize = HEADER_LAYOUT.offset_at(8);
+pub const SECONDARY_PLAYER_CAR_INDEX_OFFSET: usize = HEADER_LAYOUT.offset_at(9);
+
+//Sub type sizes that differ from 2019
+pub const WEATHER_FORECAST_SAMPLE_SIZE: usize = WEATHER_FORECAST_SAMPLE_LAYOUT.size();
+pub const CAR_LAP_SIZE: usize = CAR_LAP_LAYOUT.size();
+pub const CAR_SETUP_SIZE: usize = CAR_SETUP_LAYOUT.size();
+pub const CAR_TELEMETRY_SIZE: usize = CAR_TELEMETRY_LAYOUT.size();
+pub const CAR_STATUS_SIZE: usize = CAR_STATUS_LAYOUT.size();
+pub const FINAL_CLASSIFICATION_DATA_SIZE: usize = FINAL_CLASSIFICATION_DATA_LAYOUT.size();
+pub const LOBBY_INFO_DATA_SIZE: usize = LOBBY_INFO_DATA_LAYOUT.size();
+
+//Nums of elems
+pub const NUM_CARS: usize = 22;
+pub const NUM_WEATHER_FORECAST_SAMPLES: usize = 20;
+pub const NUM_TYRE_STINTS: usize = 8;
+
+//Packet sizes
+pub const HEADER_SIZE: usize = HEADER_LAYOUT.size();
+pub const MOTION_SIZE: usize = HEADER_SIZE + NUM_CARS*f1_2019::CAR_MOTION_SIZE + f1_2019::MOTION_EXTRAS_LAYOUT.size();
+pub const SESSION_SIZE: usize = HEADER_SIZE + f1_2019::SESSION_LAYOUT.size() + SESSION_EXTRAS_LAYOUT.size();
+pub const LAP_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_LAP_SIZE;
+pub const EVENT_SIZE: usize = 35;		// Header + 4 byte code + largest event details (penalty, 7 bytes)
+pub const PARTICIPANTS_SIZE: usize = HEADER_SIZE + 1 + NUM_CARS*f1_2019::PARTICIPANT_SIZE;	// +1 for num cars active
+pub const CARSETUPS_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_SETUP_SIZE;
+pub const TELEMETRY_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_TELEMETRY_SIZE + TELEMETRY_EXTRAS_LAYOUT.size();
+pub const STATUS_SIZE: usize = HEADER_SIZE + NUM_CARS*CAR_STATUS_SIZE;
+pub const FINAL_CLASSIFICATION_SIZE: usize = HEADER_SIZE + 1 + NUM_CARS*FINAL_CLASSIFICATION_DATA_SIZE;	// +1 for num cars
+pub const LOBBY_INFO_SIZE: usize = HEADER_SIZE + 1 + NUM_CARS*LOBBY_INFO_DATA_SIZE;	// +1 for num players
+
+pub fn parse_packet(buf: &[u8]) -> Result<Packet, ParseError> {
+	let header = parse_header(buf)?;
+	match header.get_type() {
+		PacketType::Motion 		=> parse_motion_data(buf, header).map(Packet::Motion),
+		PacketType::Session 	=> parse_session_data(buf, header).map(Packet::Session),
+		PacketType::Lap 		=> parse_lap_data(buf, header).map(Packet::Lap),
+		PacketType::Event 		=> parse_event_data(buf, header).map(Packet::Event),
+		PacketType::Participant => parse_participant_data(buf, header).map(Packet::Participants),
+		PacketType::Setup 		=> parse_setups(buf, header).map(Packet::CarSetups),
+		PacketType::Telemetry 	=> parse_telemetry_data(buf, header).map(Packet::Telemetry),
+		PacketType::CarStatus 	=> parse_car_status_data(buf, header).map(Packet::CarStatus),
+		PacketType::FinalClassification => parse_final_classification_data(buf, header).map(Packet::FinalClassification),
+		PacketType::LobbyInfo 	=> parse_lobby_info_data(buf, header).map(Packet::LobbyInfo),
+		PacketType::InvalidPacket => Err(ParseError::UnknownPacketType(PacketType::InvalidPacket.id())), // parse_header already rejects these
+	}
+}
+
+pub fn parse_header(buf: &[u8]) -> Result<PacketHeader, ParseError> {
+	let mut reader = Reader::new(buf, 0, HEADER_SIZE)?;
+	let packet_format = reader.read::<u16>()?;
+	if packet_format != PACKET_FORMAT {
+		return Err(ParseError::UnsupportedFormat(packet_format));
+	}
+	let mut header = PacketHeader::new(
+		packet_format,
+		reader.read::<u8>()?,
+		reader.read::<u8>()?,
+		reader.read::<u8>()?,
+		get_format_packet_type(PacketFormat::F2020, reader.read::<u8>()?)?,
+		reader.read::<u64>()?,
+		reader.read::<f32>()?,
+		reader.read::<u32>()?,
+		reader.read::<u8>()?
+	);
+	header.secondary_player_car_index = reader.read::<u8>()?;
+	Ok(header)
+}
+
+pub fn parse_motion_data(buf: &[u8], header: PacketHeader) -> Result<MotionData, ParseError> {
+	check_size(buf, MOTION_SIZE)?;
+	f1_2019::parse_motion_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+// Same as 2019 followed by the forecast samples
+pub fn parse_session_data(buf: &[u8], header: PacketHeader) -> Result<SessionData, ParseError> {
+	check_size(buf, SESSION_SIZE)?;
+	let mut session = f1_2019::parse_session_payload(buf, HEADER_SIZE, header)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE + f1_2019::SESSION_LAYOUT.size(), SESSION_EXTRAS_LAYOUT.size())?;
+	let num_samples = (reader.read::<u8>()? as usize).min(NUM_WEATHER_FORECAST_SAMPLES);
+	for _ in 0..num_samples {
+		session.weather_forecast_samples.push(WeatherForecastSample {
+			session_type:	reader.read_coded::<u8, SessionType>()?,
+			time_offset:	reader.read::<u8>()?,
+			weather:		reader.read_coded::<u8, Weather>()?,
+			track_temp:		reader.read::<i8>()?,
+			air_temp:		reader.read::<i8>()?,
+		});
+	}
+	Ok(session)
+}
+
+pub fn parse_lap_data(buf: &[u8], header: PacketHeader) -> Result<Lap, ParseError> {
+	check_size(buf, LAP_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, NUM_CARS*CAR_LAP_SIZE)?;
+	let mut car_laps = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		car_laps.push(read_car_lap(&mut reader)?);
+	}
+	Ok(Lap {
+		header,
+		lap_data: car_laps,
+	})
+}
+
+// Sector times are sent in ms, converted to seconds to match 2019. The new best lap and best
+// sector breakdowns aren't kept in the common model.
+fn read_car_lap(reader: &mut Reader) -> Result<LapData, ParseError> {
+	let last_lap = reader.read::<f32>()?;
+	let current_lap = reader.read::<f32>()?;
+	let best_sec_1 = f32::from(reader.read::<u16>()?) / 1000.0;
+	let best_sec_2 = f32::from(reader.read::<u16>()?) / 1000.0;
+	let best_lap = reader.read::<f32>()?;
+	reader.skip(CAR_LAP_LAYOUT.offset_at(15) - CAR_LAP_LAYOUT.offset_at(5))?;	// Best lap number to best overall sector 3 lap
+	Ok(LapData {
+		last_lap,
+		current_lap,
+		best_lap,
+		best_sec_1,
+		best_sec_2,
+		lap_distance: 	reader.read::<f32>()?,
+		total_distance: reader.read::<f32>()?,
+		safety_car_delta: reader.read::<f32>()?,
+		position: 		reader.read::<u8>()?,
+		lap_num: 		reader.read::<u8>()?,
+		pit_status: 	reader.read_coded::<u8, PitStatus>()?,
+		sector: 		reader.read::<u8>()?,
+		is_lap_valid: 	reader.read::<u8>()?,
+		penalties: 		reader.read::<u8>()?,
+		grid_position: 	reader.read::<u8>()?,
+		driver_status: 	reader.read_coded::<u8, DriverStatus>()?,
+		result_status: 	reader.read_coded::<u8, ResultStatus>()?,
+	})
+}
+
+pub fn parse_event_data(buf: &[u8], header: PacketHeader) -> Result<Event, ParseError> {
+	f1_2019::parse_event_payload(buf, HEADER_SIZE, header)
+}
+
+pub fn parse_participant_data(buf: &[u8], header: PacketHeader) -> Result<Participants, ParseError> {
+	check_size(buf, PARTICIPANTS_SIZE)?;
+	f1_2019::parse_participant_payload(buf, HEADER_SIZE, NUM_CARS, header)
+}
+
+pub fn parse_setups(buf: &[u8], header: PacketHeader) -> Result<CarSetups, ParseError> {
+	check_size(buf, CARSETUPS_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, NUM_CARS*CAR_SETUP_SIZE)?;
+	let mut setup_data = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		setup_data.push(read_car_setup(&mut reader)?);
+	}
+	Ok(CarSetups {
+		header,
+		car_setups: setup_data,
+	})
+}
+
+// Tyre pressures are sent per wheel, averaged per axle to match 2019
+fn read_car_setup(reader: &mut Reader) -> Result<CarSetupData, ParseError> {
+	let front_wing = reader.read::<u8>()?;
+	let rear_wing = reader.read::<u8>()?;
+	let on_throttle = reader.read::<u8>()?;
+	let off_throttle = reader.read::<u8>()?;
+	let front_camber = reader.read::<f32>()?;
+	let rear_camber = reader.read::<f32>()?;
+	let front_toe = reader.read::<f32>()?;
+	let rear_toe = reader.read::<f32>()?;
+	let front_suspension = reader.read::<u8>()?;
+	let rear_suspension = reader.read::<u8>()?;
+	let front_anti_roll_bar = reader.read::<u8>()?;
+	let rear_anti_roll_bar = reader.read::<u8>()?;
+	let front_suspension_height = reader.read::<u8>()?;
+	let rear_suspension_height = reader.read::<u8>()?;
+	let brake_pressure = reader.read::<u8>()?;
+	let brake_bias = reader.read::<u8>()?;
+	let tyre_pressures = reader.read_wheels::<f32>()?;
+	Ok(CarSetupData {
+		front_wing,
+		rear_wing,
+		on_throttle,
+		off_throttle,
+		front_camber,
+		rear_camber,
+		front_toe,
+		rear_toe,
+		front_suspension,
+		rear_suspension,
+		front_anti_roll_bar,
+		rear_anti_roll_bar,
+		front_suspension_height,
+		rear_suspension_height,
+		brake_pressure,
+		brake_bias,
+		front_tyre_pressure: 	tyre_pressures.front_mean(),
+		rear_tyre_pressure: 	tyre_pressures.rear_mean(),
+		ballast: 				reader.read::<u8>()?,
+		fuel_load: 				reader.read::<f32>()?,
+	})
+}
+
+// Tyre surface and inner temps shrank to u8, widened back to the common u16
+pub fn parse_telemetry_data(buf: &[u8], header: PacketHeader) -> Result<Telemetry, ParseError> {
+	check_size(buf, TELEMETRY_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, TELEMETRY_SIZE - HEADER_SIZE)?;
+	let mut car_telemetry = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		car_telemetry.push(CarTelemetry {
+			car_speed: 			reader.read::<u16>()?,
+			throttle_pos: 		reader.read::<f32>()?,
+			steering_pos:		reader.read::<f32>()?,
+			brake_pos: 			reader.read::<f32>()?,
+			clutch_pos:			reader.read::<u8>()?,
+			gear: 				reader.read::<i8>()?,
+			engine_rpm:			reader.read::<u16>()?,
+			drs_active: 		reader.read::<u8>()?,
+			change_light_perc: 	reader.read::<u8>()?,
+			brake_temps:		reader.read_wheels::<u16>()?,
+			tyre_surface_temps:	reader.read_wheels::<u8>()?.map(u16::from),
+			tyre_inner_temps: 	reader.read_wheels::<u8>()?.map(u16::from),
+			engine_temp: 		reader.read::<u16>()?,
+			tyre_pressures: 	reader.read_wheels::<f32>()?,
+			tyre_contact_types: reader.read_wheels::<u8>()?,
+		});
+	}
+	Ok(Telemetry {
+		header,
+		car_telemetry_data: car_telemetry,
+		button_status: 				ButtonStatus(reader.read::<u32>()?),
+		mfd_panel_index: 			reader.read::<u8>()?,
+		mfd_panel_index_secondary: 	reader.read::<u8>()?,
+		suggested_gear: 			reader.read::<i8>()?,
+	})
+}
+
+pub fn parse_car_status_data(buf: &[u8], header: PacketHeader) -> Result<CarStatusData, ParseError> {
+	check_size(buf, STATUS_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, NUM_CARS*CAR_STATUS_SIZE)?;
+	let mut status_data = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		status_data.push(CarStatus {
+			traction_control: 	reader.read::<u8>()?,
+			anti_lock_brakes: 	reader.read::<u8>()?,
+			fuel_mix: 			reader.read_coded::<u8, FuelMix>()?,
+			front_brake_bias: 	reader.read::<u8>()?,
+			pit_limiter_status: reader.read::<u8>()?,
+			fuel_in_tank: 		reader.read::<f32>()?,
+			fuel_capacity: 		reader.read::<f32>()?,
+			fuel_remaining_laps:reader.read::<f32>()?,
+			max_rpm: 			reader.read::<u16>()?,
+			idle_rpm:			reader.read::<u16>()?,
+			max_gears: 			reader.read::<u8>()?,
+			drs_allowed: 		reader.read::<u8>()?,
+			drs_activation_distance: reader.read::<u16>()?,
+			tyres_wear: 			reader.read_wheels::<u8>()?,
+			actual_tyre_compound: 	reader.read_coded::<u8, TyreCompound>()?,
+			tyre_visual_compound: 	reader.read_coded::<u8, VisualTyreCompound>()?,
+			tyres_age_laps: 		reader.read::<u8>()?,
+			tyres_damage: 			reader.read_wheels::<u8>()?,
+			front_left_wing_damage: reader.read::<u8>()?,
+			front_right_wing_damage:reader.read::<u8>()?,
+			rear_wing_damage: 		reader.read::<u8>()?,
+			drs_fault: 				reader.read::<u8>()?,
+			engine_damage: 			reader.read::<u8>()?,
+			gear_box_damage: 		reader.read::<u8>()?,
+			vehicle_fia_flags: 		reader.read_coded::<i8, FiaFlag>()?,
+			ers_store_energy: 		reader.read::<f32>()?,
+			ers_deploy_mode: 		ErsDeployMode::from_2020(reader.read::<u8>()?),
+			ers_harvested_this_lap_mguk:	reader.read::<f32>()?,
+			ers_harvested_this_lap_mguh:	reader.read::<f32>()?,
+			ers_deployed_this_lap: 			reader.read::<f32>()?,
+		});
+	}
+	Ok(CarStatusData {
+		header,
+		car_status_data: status_data,
+	})
+}
+
+pub fn parse_final_classification_data(buf: &[u8], header: PacketHeader) -> Result<FinalClassification, ParseError> {
+	check_size(buf, FINAL_CLASSIFICATION_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, FINAL_CLASSIFICATION_SIZE - HEADER_SIZE)?;
+	let num_cars = reader.read::<u8>()?;
+	let mut classification_data = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		classification_data.push(FinalClassificationData {
+			position: 			reader.read::<u8>()?,
+			num_laps: 			reader.read::<u8>()?,
+			grid_position: 		reader.read::<u8>()?,
+			points: 			reader.read::<u8>()?,
+			num_pit_stops: 		reader.read::<u8>()?,
+			result_status: 		reader.read_coded::<u8, ResultStatus>()?,
+			best_lap_time: 		reader.read::<f32>()?,
+			total_race_time: 	reader.read::<f64>()?,
+			penalties_time: 	reader.read::<u8>()?,
+			num_penalties: 		reader.read::<u8>()?,
+			num_tyre_stints: 	reader.read::<u8>()?,
+			tyre_stints_actual: reader.read_bytes::<NUM_TYRE_STINTS>()?,
+			tyre_stints_visual: reader.read_bytes::<NUM_TYRE_STINTS>()?,
+		});
+	}
+	Ok(FinalClassification {
+		header,
+		num_cars,
+		classification_data,
+	})
+}
+
+pub fn parse_lobby_info_data(buf: &[u8], header: PacketHeader) -> Result<LobbyInfo, ParseError> {
+	check_size(buf, LOBBY_INFO_SIZE)?;
+	let mut reader = Reader::new(buf, HEADER_SIZE, LOBBY_INFO_SIZE - HEADER_SIZE)?;
+	let num_players = reader.read::<u8>()?;
+	let mut lobby_players = Vec::with_capacity(NUM_CARS);
+	for _ in 0..NUM_CARS {
+		lobby_players.push(LobbyInfoData {
+			ai_controlled: 	reader.read::<u8>()?,
+			team_id: 		reader.read::<u8>()?,
+			nationality: 	reader.read::<u8>()?,
+			name: 			reader.read_bytes::<{ f1_2019::NAME_SIZE }>()?,
+			ready_status: 	reader.read::<u8>()?,
+		});
+	}
+	Ok(LobbyInfo {
+		header,
+		num_players,
+		lobby_players,
+	})
+}
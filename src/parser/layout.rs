@@ -0,0 +1,130 @@
+// Wire layouts, the fields of each struct in the order the spec lists them.
+//
+// Parsers read fields one after another with a Reader, so a layout is the single place the
+// order and width of each field is written down. Struct and packet sizes are worked out from
+// the layouts rather than kept by hand, and offset_of gives where a field lands for anyone
+// checking against the spec.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    U64,
+    F32,
+    F64,
+    Record(&'static Layout),    // Nested struct, e.g. a car inside a packet
+}
+
+impl FieldType {
+    pub const fn size(&self) -> usize {
+        match self {
+            FieldType::U8 | FieldType::I8   => 1,
+            FieldType::U16 | FieldType::I16 => 2,
+            FieldType::U32 | FieldType::F32 => 4,
+            FieldType::U64 | FieldType::F64 => 8,
+            FieldType::Record(layout)       => layout.size(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub name:       &'static str,   // Struct field name where one exists, otherwise the spec's name
+    pub field_type: FieldType,
+    pub count:      usize,          // Number of values in a row, 1 unless the field is an array
+}
+
+impl Field {
+    pub const fn new(name: &'static str, field_type: FieldType) -> Self {
+        Field { name, field_type, count: 1 }
+    }
+
+    pub const fn array(name: &'static str, field_type: FieldType, count: usize) -> Self {
+        Field { name, field_type, count }
+    }
+
+    // One value per wheel, RL, RR, FL, FR
+    pub const fn wheels(name: &'static str, field_type: FieldType) -> Self {
+        Field::array(name, field_type, super::NUM_WHEELS)
+    }
+
+    pub const fn record(name: &'static str, layout: &'static Layout) -> Self {
+        Field::new(name, FieldType::Record(layout))
+    }
+
+    pub const fn size(&self) -> usize {
+        self.field_type.size() * self.count
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub name:   &'static str,
+    pub fields: &'static [Field],
+}
+
+impl Layout {
+    pub const fn new(name: &'static str, fields: &'static [Field]) -> Self {
+        Layout { name, fields }
+    }
+
+    pub const fn size(&self) -> usize {
+        self.offset_at(self.fields.len())
+    }
+
+    // Offset of the field at index, the size of the layout if index is past the end
+    pub const fn offset_at(&self, index: usize) -> usize {
+        let mut offset = 0;
+        let mut i = 0;
+        while i < index && i < self.fields.len() {
+            offset += self.fields[i].size();
+            i += 1;
+        }
+        offset
+    }
+
+    pub fn field(&self, name: &str) -> Option<&'static Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    // const so offsets can be looked up by name when compiling, see view.rs
+    pub const fn offset_of(&self, name: &str) -> Option<usize> {
+        let mut i = 0;
+        while i < self.fields.len() {
+            if same_name(self.fields[i].name, name) {
+                return Some(self.offset_at(i));
+            }
+            i += 1;
+        }
+        None
+    }
+
+    // Each field with the offset it starts at
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &'static Field)> {
+        let fields: &'static [Field] = self.fields;
+        fields.iter().scan(0, |offset, field| {
+            let start = *offset;
+            *offset += field.size();
+            Some((start, field))
+        })
+    }
+}
+
+// str's == isn't const
+const fn same_name(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -0,0 +1,155 @@
+// Parsers for each supported game edition. parse_packet reads packet_format from the first two
+// bytes and hands the datagram to the matching module, all of which decode into the common
+// f1_2019_net structs.
+
+use std::convert::TryFrom;
+use std::mem;
+
+use byte::ctx::Endian;
+use byte::BytesExt;
+use byte::TryRead;
+use byte::LE;
+
+use crate::error::ParseError;
+use crate::format::PacketFormat;
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::Packet;
+use crate::wheels::Wheels;
+
+pub mod f1_2018;
+pub mod f1_2019;
+pub mod f1_2020;
+pub mod layout;
+pub mod view;
+
+pub const MAX_PACKET_SIZE: usize = f1_2020::MOTION_SIZE;	//Largest packet across supported editions
+pub const PACKET_FORMAT_OFFSET: usize = 0;					//Same position in every edition
+
+//Nums of elems shared by every edition
+pub const NUM_MARSHAL_ZONES: usize = 21;
+pub const NUM_WHEELS: usize = 4;
+
+// Single entry point for a received datagram. Works out the edition from packet_format then
+// hands the buffer to that edition's parser.
+pub fn parse_packet(buf: &[u8]) -> Result<Packet, ParseError> {
+	match get_packet_format(buf)? {
+		PacketFormat::F2018 => f1_2018::parse_packet(buf),
+		PacketFormat::F2019 => f1_2019::parse_packet(buf),
+		PacketFormat::F2020 => f1_2020::parse_packet(buf),
+	}
+}
+
+pub fn parse_header(buf: &[u8]) -> Result<PacketHeader, ParseError> {
+	match get_packet_format(buf)? {
+		PacketFormat::F2018 => f1_2018::parse_header(buf),
+		PacketFormat::F2019 => f1_2019::parse_header(buf),
+		PacketFormat::F2020 => f1_2020::parse_header(buf),
+	}
+}
+
+pub fn get_packet_format(buf: &[u8]) -> Result<PacketFormat, ParseError> {
+	PacketFormat::try_from(read_field::<u16>(buf, PACKET_FORMAT_OFFSET)?)
+}
+
+pub fn get_packet_type(packet_type_byte: u8) -> Result<PacketType, ParseError> {
+	match packet_type_byte {
+		0 => Ok(PacketType::Motion),
+		1 => Ok(PacketType::Session),
+		2 => Ok(PacketType::Lap),
+		3 => Ok(PacketType::Event),
+		4 => Ok(PacketType::Participant),
+		5 => Ok(PacketType::Setup),
+		6 => Ok(PacketType::Telemetry),
+		7 => Ok(PacketType::CarStatus),
+		8 => Ok(PacketType::FinalClassification),
+		9 => Ok(PacketType::LobbyInfo),
+		_ => Err(ParseError::UnknownPacketType(packet_type_byte)),
+	}
+}
+
+// Packet type for an edition, rejecting ids the edition doesn't send
+pub(crate) fn get_format_packet_type(format: PacketFormat, packet_type_byte: u8) -> Result<PacketType, ParseError> {
+	let packet_type = get_packet_type(packet_type_byte)?;
+	match format.packet_size(packet_type) {
+		Some(_) => Ok(packet_type),
+		None => Err(ParseError::UnknownPacketType(packet_type_byte)),
+	}
+}
+
+// Bounds checked read of a single little endian field at offset
+pub(crate) fn read_field<'a, T>(bytes: &'a [u8], offset: usize) -> Result<T, ParseError>
+	where T: TryRead<'a, Endian> {
+	let mut pos = offset;
+	bytes.read_with::<T>(&mut pos, LE).map_err(|_| ParseError::TooShort {
+		expected: offset + mem::size_of::<T>(),
+		got: bytes.len(),
+	})
+}
+
+// Cursor over part of a datagram. The length is checked once when the reader is made, each read
+// then moves past the field so parsers list fields in wire order instead of by offset.
+pub(crate) struct Reader<'a> {
+	buf: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Reader<'a> {
+	// Reader over len bytes of buf from start, TooShort if buf doesn't hold them
+	pub(crate) fn new(buf: &'a [u8], start: usize, len: usize) -> Result<Self, ParseError> {
+		check_min_size(buf, start + len)?;
+		Ok(Reader { buf: &buf[..start + len], pos: start })
+	}
+
+	pub(crate) fn read<T>(&mut self) -> Result<T, ParseError>
+		where T: TryRead<'a, Endian> {
+		let value = read_field::<T>(self.buf, self.pos)?;
+		self.pos += mem::size_of::<T>();
+		Ok(value)
+	}
+
+	// Reads a coded field and converts it to its enum, R is the type on the wire
+	pub(crate) fn read_coded<R, T>(&mut self) -> Result<T, ParseError>
+		where R: TryRead<'a, Endian>, T: From<R> {
+		self.read::<R>().map(T::from)
+	}
+
+	// Reads 4 consecutive values, one per wheel
+	pub(crate) fn read_wheels<T>(&mut self) -> Result<Wheels<T>, ParseError>
+		where T: TryRead<'a, Endian> + Default + Copy {
+		let mut array = [T::default(); NUM_WHEELS];
+		for value in array.iter_mut() {
+			*value = self.read::<T>()?;
+		}
+		Ok(Wheels::from(array))
+	}
+
+	pub(crate) fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+		check_min_size(self.buf, self.pos + N)?;
+		let mut array = [0u8; N];
+		array.copy_from_slice(&self.buf[self.pos..self.pos + N]);
+		self.pos += N;
+		Ok(array)
+	}
+
+	// Moves past fields the common model doesn't keep
+	pub(crate) fn skip(&mut self, len: usize) -> Result<(), ParseError> {
+		check_min_size(self.buf, self.pos + len)?;
+		self.pos += len;
+		Ok(())
+	}
+}
+
+pub(crate) fn check_size(buf: &[u8], expected: usize) -> Result<(), ParseError> {
+	if buf.len() != expected {
+		return Err(ParseError::SizeMismatch { expected, got: buf.len() });
+	}
+	Ok(())
+}
+
+pub(crate) fn check_min_size(buf: &[u8], expected: usize) -> Result<(), ParseError> {
+	if buf.len() < expected {
+		return Err(ParseError::TooShort { expected, got: buf.len() });
+	}
+	Ok(())
+}
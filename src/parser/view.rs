@@ -0,0 +1,679 @@
+// Borrowed views over the high rate packets, motion, lap, telemetry and car status.
+//
+// A view checks the header and size once when it's made, then each accessor reads its field
+// straight out of the datagram at an offset worked out from the edition's layout when compiling.
+// Nothing is copied until asked for, so reading the player's speed from a telemetry packet
+// touches a handful of bytes rather than decoding every car. Values come back converted the same
+// way the owned parsers convert them. Fields only some editions send come back as Option, None
+// where the packet's edition doesn't send them. decode gives the owned packet when everything is
+// wanted.
+
+use std::convert::TryFrom;
+use std::mem;
+
+use byte::ctx::Endian;
+use byte::TryRead;
+
+use crate::error::ParseError;
+use crate::format::PacketFormat;
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::Lap;
+use crate::f1_2019_net::Telemetry;
+use crate::f1_2019_net::CarStatusData;
+use crate::f1_2019_net::ButtonStatus;
+use crate::f1_2019_net::PitStatus;
+use crate::f1_2019_net::DriverStatus;
+use crate::f1_2019_net::ResultStatus;
+use crate::f1_2019_net::FuelMix;
+use crate::f1_2019_net::ErsDeployMode;
+use crate::f1_2019_net::TyreCompound;
+use crate::f1_2019_net::VisualTyreCompound;
+use crate::f1_2019_net::FiaFlag;
+use crate::vector::Vec3;
+use crate::wheels::Wheels;
+use super::f1_2018;
+use super::f1_2019;
+use super::f1_2020;
+use super::layout::Layout;
+use super::check_size;
+use super::read_field;
+use super::NUM_WHEELS;
+
+// Layouts of a record for 2018, 2019 and 2020, indexed by edition()
+type Layouts = [&'static Layout; EDITIONS];
+
+const EDITIONS: usize = 3;
+
+const CAR_MOTION: Layouts = [&f1_2019::CAR_MOTION_LAYOUT; EDITIONS];
+const MOTION_EXTRAS: Layouts = [&f1_2019::MOTION_EXTRAS_LAYOUT; EDITIONS];
+const CAR_LAP: Layouts = [&f1_2019::CAR_LAP_LAYOUT, &f1_2019::CAR_LAP_LAYOUT, &f1_2020::CAR_LAP_LAYOUT];
+const CAR_TELEMETRY: Layouts = [&f1_2018::CAR_TELEMETRY_LAYOUT, &f1_2019::CAR_TELEMETRY_LAYOUT, &f1_2020::CAR_TELEMETRY_LAYOUT];
+const TELEMETRY_EXTRAS: Layouts = [&f1_2019::TELEMETRY_EXTRAS_LAYOUT, &f1_2019::TELEMETRY_EXTRAS_LAYOUT, &f1_2020::TELEMETRY_EXTRAS_LAYOUT];
+const CAR_STATUS: Layouts = [&f1_2018::CAR_STATUS_LAYOUT, &f1_2019::CAR_STATUS_LAYOUT, &f1_2020::CAR_STATUS_LAYOUT];
+
+fn edition(format: PacketFormat) -> usize {
+    match format {
+        PacketFormat::F2018 => 0,
+        PacketFormat::F2019 => 1,
+        PacketFormat::F2020 => 2,
+    }
+}
+
+// Offset of a field every edition sends. Used in consts, so naming a field one of the layouts
+// doesn't have fails to compile
+const fn sent(offset: Option<usize>) -> usize {
+    match offset {
+        Some(offset) => offset,
+        None => panic!("field missing from a layout"),
+    }
+}
+
+const fn sent_by_all(layouts: Layouts, name: &str) -> [usize; EDITIONS] {
+    [sent(layouts[0].offset_of(name)), sent(layouts[1].offset_of(name)), sent(layouts[2].offset_of(name))]
+}
+
+// Offset in each edition for a field only some send, None for the rest
+const fn sent_by_some(layouts: Layouts, name: &str) -> [Option<usize>; EDITIONS] {
+    let offsets = [layouts[0].offset_of(name), layouts[1].offset_of(name), layouts[2].offset_of(name)];
+    if offsets[0].is_none() && offsets[1].is_none() && offsets[2].is_none() {
+        panic!("field missing from every layout");
+    }
+    offsets
+}
+
+// Accessors for fields, each reading at the offsets for its name worked out when compiling.
+// record is the method giving the Record the fields are in, read the Record method to read with
+macro_rules! fields {
+    ($record:ident, $layouts:ident, $read:ident; $($name:ident: $type:ty),* $(,)?) => {$(
+        pub fn $name(&self) -> $type {
+            const OFFSETS: [usize; EDITIONS] = sent_by_all($layouts, stringify!($name));
+            let record = self.$record();
+            record.$read(OFFSETS[record.edition])
+        }
+    )*};
+}
+
+// As fields, for ones only some editions send. None for the rest
+macro_rules! some_fields {
+    ($record:ident, $layouts:ident, $read:ident; $($name:ident: $type:ty),* $(,)?) => {$(
+        pub fn $name(&self) -> Option<$type> {
+            const OFFSETS: [Option<usize>; EDITIONS] = sent_by_some($layouts, stringify!($name));
+            let record = self.$record();
+            OFFSETS[record.edition].map(|offset| record.$read(offset))
+        }
+    )*};
+}
+
+// Header checked and size matched to spec for the packet type the view expects
+#[derive(Debug, Clone, Copy)]
+struct Datagram<'a> {
+    buf:    &'a [u8],
+    format: PacketFormat,
+    header: PacketHeader,
+}
+
+impl<'a> Datagram<'a> {
+    fn new(buf: &'a [u8], packet_type: PacketType) -> Result<Self, ParseError> {
+        let header = super::parse_header(buf)?;
+        if header.get_type() != packet_type {
+            return Err(ParseError::WrongPacketType { expected: packet_type, got: header.get_type() });
+        }
+        let format = PacketFormat::try_from(header.packet_format)?;
+        if let Some(size) = format.packet_size(packet_type) {
+            check_size(buf, size)?;
+        }
+        Ok(Datagram { buf, format, header })
+    }
+
+    // Record index of an array of layouts starting straight after the header
+    fn car(&self, layouts: Layouts, index: usize) -> Option<Record<'a>> {
+        if index >= self.format.num_cars() {
+            return None;
+        }
+        let layout = layouts[edition(self.format)];
+        Some(self.record(self.format.header_size() + index*layout.size(), layout))
+    }
+
+    // Player car only fields following the car array
+    fn extras(&self, car_layouts: Layouts, layouts: Layouts) -> Record<'a> {
+        let car_size = car_layouts[edition(self.format)].size();
+        self.record(self.format.header_size() + self.format.num_cars()*car_size, layouts[edition(self.format)])
+    }
+
+    fn record(&self, start: usize, layout: &'static Layout) -> Record<'a> {
+        Record { bytes: &self.buf[start..start + layout.size()], edition: edition(self.format) }
+    }
+}
+
+// One struct within the datagram, read at offsets per edition from the same layouts the record
+// was cut to. The datagram's size was checked, so every read is in bounds.
+#[derive(Debug, Clone, Copy)]
+struct Record<'a> {
+    bytes:   &'a [u8],
+    edition: usize,
+}
+
+impl<'a> Record<'a> {
+    // Offset for this record's edition
+    fn offset(&self, offsets: [usize; EDITIONS]) -> usize {
+        offsets[self.edition]
+    }
+
+    fn read<T>(&self, offset: usize) -> T
+        where T: TryRead<'a, Endian> {
+        read_field::<T>(self.bytes, offset).expect("view record shorter than its layout")
+    }
+
+    fn read_wheels<T>(&self, offset: usize) -> Wheels<T>
+        where T: TryRead<'a, Endian> + Default + Copy {
+        let mut array = [T::default(); NUM_WHEELS];
+        for (wheel, value) in array.iter_mut().enumerate() {
+            *value = self.read(offset + wheel*mem::size_of::<T>());
+        }
+        Wheels::from(array)
+    }
+}
+
+//-------------Motion
+
+#[derive(Debug, Clone, Copy)]
+pub struct MotionDataView<'a> {
+    datagram: Datagram<'a>,
+}
+
+impl<'a> MotionDataView<'a> {
+    pub fn new(buf: &'a [u8]) -> Result<Self, ParseError> {
+        Ok(MotionDataView { datagram: Datagram::new(buf, PacketType::Motion)? })
+    }
+
+    pub fn header(&self) -> &PacketHeader {
+        &self.datagram.header
+    }
+
+    pub fn num_cars(&self) -> usize {
+        self.datagram.format.num_cars()
+    }
+
+    pub fn car(&self, index: usize) -> Option<CarMotionView<'a>> {
+        self.datagram.car(CAR_MOTION, index).map(|record| CarMotionView { record })
+    }
+
+    pub fn player_car(&self) -> Option<CarMotionView<'a>> {
+        self.car(self.header().player_car_index as usize)
+    }
+
+    pub fn cars(&self) -> impl Iterator<Item = CarMotionView<'a>> {
+        let view = *self;
+        (0..self.num_cars()).filter_map(move |index| view.car(index))
+    }
+
+    //Player car only
+    fields!(extras, MOTION_EXTRAS, read_wheels;
+        suspension_pos: Wheels<f32>,
+        suspension_vel: Wheels<f32>,
+        suspension_acc: Wheels<f32>,
+        wheel_speed: Wheels<f32>,
+        wheel_slip: Wheels<f32>,
+    );
+    fields!(extras, MOTION_EXTRAS, read;
+        local_vel_x: f32,
+        local_vel_y: f32,
+        local_vel_z: f32,
+        angular_vel_x: f32,
+        angular_vel_y: f32,
+        angular_vel_z: f32,
+        angular_acc_x: f32,
+        angular_acc_y: f32,
+        angular_acc_z: f32,
+        front_wheels_angle: f32,
+    );
+
+    // Velocity in the car's local space
+    pub fn local_velocity(&self) -> Vec3 {
+        Vec3::new(self.local_vel_x(), self.local_vel_y(), self.local_vel_z())
+    }
+
+    pub fn angular_velocity(&self) -> Vec3 {
+        Vec3::new(self.angular_vel_x(), self.angular_vel_y(), self.angular_vel_z())
+    }
+
+    pub fn angular_acceleration(&self) -> Vec3 {
+        Vec3::new(self.angular_acc_x(), self.angular_acc_y(), self.angular_acc_z())
+    }
+
+    // Copies every car out into the owned packet
+    pub fn decode(&self) -> Result<MotionData, ParseError> {
+        let Datagram { buf, format, header } = self.datagram;
+        match format {
+            PacketFormat::F2018 => f1_2018::parse_motion_data(buf, header),
+            PacketFormat::F2019 => f1_2019::parse_motion_data(buf, header),
+            PacketFormat::F2020 => f1_2020::parse_motion_data(buf, header),
+        }
+    }
+
+    fn extras(&self) -> Record<'a> {
+        self.datagram.extras(CAR_MOTION, MOTION_EXTRAS)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CarMotionView<'a> {
+    record: Record<'a>,
+}
+
+impl<'a> CarMotionView<'a> {
+    fields!(record, CAR_MOTION, read;
+        world_pos_x: f32,
+        world_pos_y: f32,
+        world_pos_z: f32,
+        world_vel_x: f32,
+        world_vel_y: f32,
+        world_vel_z: f32,
+        world_fwd_dir_x: i16,
+        world_fwd_dir_y: i16,
+        world_fwd_dir_z: i16,
+        world_right_dir_x: i16,
+        world_right_dir_y: i16,
+        world_right_dir_z: i16,
+        lateral_g: f32,
+        longitudinal_g: f32,
+        vertical_g: f32,
+        yaw: f32,
+        pitch: f32,
+        roll: f32,
+    );
+
+    pub fn position(&self) -> Vec3 {
+        Vec3::new(self.world_pos_x(), self.world_pos_y(), self.world_pos_z())
+    }
+
+    pub fn velocity(&self) -> Vec3 {
+        Vec3::new(self.world_vel_x(), self.world_vel_y(), self.world_vel_z())
+    }
+
+    // Metres per second
+    pub fn speed(&self) -> f32 {
+        self.velocity().length()
+    }
+
+    fn record(&self) -> Record<'a> {
+        self.record
+    }
+}
+
+//-------------Lap
+
+#[derive(Debug, Clone, Copy)]
+pub struct LapView<'a> {
+    datagram: Datagram<'a>,
+}
+
+impl<'a> LapView<'a> {
+    pub fn new(buf: &'a [u8]) -> Result<Self, ParseError> {
+        Ok(LapView { datagram: Datagram::new(buf, PacketType::Lap)? })
+    }
+
+    pub fn header(&self) -> &PacketHeader {
+        &self.datagram.header
+    }
+
+    pub fn num_cars(&self) -> usize {
+        self.datagram.format.num_cars()
+    }
+
+    pub fn car(&self, index: usize) -> Option<LapDataView<'a>> {
+        self.datagram.car(CAR_LAP, index).map(|record| LapDataView { record, format: self.datagram.format })
+    }
+
+    pub fn player_car(&self) -> Option<LapDataView<'a>> {
+        self.car(self.header().player_car_index as usize)
+    }
+
+    pub fn cars(&self) -> impl Iterator<Item = LapDataView<'a>> {
+        let view = *self;
+        (0..self.num_cars()).filter_map(move |index| view.car(index))
+    }
+
+    pub fn decode(&self) -> Result<Lap, ParseError> {
+        let Datagram { buf, format, header } = self.datagram;
+        match format {
+            PacketFormat::F2018 => f1_2018::parse_lap_data(buf, header),
+            PacketFormat::F2019 => f1_2019::parse_lap_data(buf, header),
+            PacketFormat::F2020 => f1_2020::parse_lap_data(buf, header),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LapDataView<'a> {
+    record: Record<'a>,
+    format: PacketFormat,
+}
+
+impl<'a> LapDataView<'a> {
+    fields!(record, CAR_LAP, read;
+        last_lap: f32,
+        current_lap: f32,
+        best_lap: f32,
+        lap_distance: f32,
+        total_distance: f32,
+        safety_car_delta: f32,
+        position: u8,
+        lap_num: u8,
+        sector: u8,
+        is_lap_valid: u8,
+        penalties: u8,
+        grid_position: u8,
+    );
+
+    pub fn best_sec_1(&self) -> f32 {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_LAP, "best_sec_1");
+        self.sector_time(OFFSETS)
+    }
+
+    pub fn best_sec_2(&self) -> f32 {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_LAP, "best_sec_2");
+        self.sector_time(OFFSETS)
+    }
+
+    pub fn pit_status(&self) -> PitStatus {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_LAP, "pit_status");
+        PitStatus::from(self.record.read::<u8>(self.record.offset(OFFSETS)))
+    }
+
+    pub fn driver_status(&self) -> DriverStatus {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_LAP, "driver_status");
+        DriverStatus::from(self.record.read::<u8>(self.record.offset(OFFSETS)))
+    }
+
+    pub fn result_status(&self) -> ResultStatus {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_LAP, "result_status");
+        ResultStatus::from(self.record.read::<u8>(self.record.offset(OFFSETS)))
+    }
+
+    // 2020 sends sector times in ms, seconds to match 2019
+    fn sector_time(&self, offsets: [usize; EDITIONS]) -> f32 {
+        let offset = self.record.offset(offsets);
+        match self.format {
+            PacketFormat::F2020 => f32::from(self.record.read::<u16>(offset)) / 1000.0,
+            _ => self.record.read(offset),
+        }
+    }
+
+    fn record(&self) -> Record<'a> {
+        self.record
+    }
+}
+
+//-------------Telemetry
+
+#[derive(Debug, Clone, Copy)]
+pub struct TelemetryView<'a> {
+    datagram: Datagram<'a>,
+}
+
+impl<'a> TelemetryView<'a> {
+    pub fn new(buf: &'a [u8]) -> Result<Self, ParseError> {
+        Ok(TelemetryView { datagram: Datagram::new(buf, PacketType::Telemetry)? })
+    }
+
+    pub fn header(&self) -> &PacketHeader {
+        &self.datagram.header
+    }
+
+    pub fn num_cars(&self) -> usize {
+        self.datagram.format.num_cars()
+    }
+
+    pub fn car(&self, index: usize) -> Option<CarTelemetryView<'a>> {
+        self.datagram.car(CAR_TELEMETRY, index).map(|record| CarTelemetryView { record, format: self.datagram.format })
+    }
+
+    pub fn player_car(&self) -> Option<CarTelemetryView<'a>> {
+        self.car(self.header().player_car_index as usize)
+    }
+
+    pub fn cars(&self) -> impl Iterator<Item = CarTelemetryView<'a>> {
+        let view = *self;
+        (0..self.num_cars()).filter_map(move |index| view.car(index))
+    }
+
+    pub fn button_status(&self) -> ButtonStatus {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(TELEMETRY_EXTRAS, "button_status");
+        let extras = self.extras();
+        ButtonStatus(extras.read(extras.offset(OFFSETS)))
+    }
+
+    // 2020 only. The panel index is 255 when closed, suggested gear 0 if no gear is suggested
+    some_fields!(extras, TELEMETRY_EXTRAS, read;
+        mfd_panel_index: u8,
+        mfd_panel_index_secondary: u8,
+        suggested_gear: i8,
+    );
+
+    pub fn decode(&self) -> Result<Telemetry, ParseError> {
+        let Datagram { buf, format, header } = self.datagram;
+        match format {
+            PacketFormat::F2018 => f1_2018::parse_telemetry_data(buf, header),
+            PacketFormat::F2019 => f1_2019::parse_telemetry_data(buf, header),
+            PacketFormat::F2020 => f1_2020::parse_telemetry_data(buf, header),
+        }
+    }
+
+    fn extras(&self) -> Record<'a> {
+        self.datagram.extras(CAR_TELEMETRY, TELEMETRY_EXTRAS)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CarTelemetryView<'a> {
+    record: Record<'a>,
+    format: PacketFormat,
+}
+
+impl<'a> CarTelemetryView<'a> {
+    fields!(record, CAR_TELEMETRY, read;
+        car_speed: u16,
+        clutch_pos: u8,
+        gear: i8,
+        engine_rpm: u16,
+        drs_active: u8,
+        change_light_perc: u8,
+        engine_temp: u16,
+    );
+    fields!(record, CAR_TELEMETRY, read_wheels;
+        brake_temps: Wheels<u16>,
+        tyre_pressures: Wheels<f32>,
+    );
+
+    // Not sent in 2018
+    some_fields!(record, CAR_TELEMETRY, read_wheels;
+        tyre_contact_types: Wheels<u8>,
+    );
+
+    pub fn throttle_pos(&self) -> f32 {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_TELEMETRY, "throttle_pos");
+        self.pedal(OFFSETS)
+    }
+
+    pub fn brake_pos(&self) -> f32 {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_TELEMETRY, "brake_pos");
+        self.pedal(OFFSETS)
+    }
+
+    pub fn tyre_surface_temps(&self) -> Wheels<u16> {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_TELEMETRY, "tyre_surface_temps");
+        self.tyre_temps(OFFSETS)
+    }
+
+    pub fn tyre_inner_temps(&self) -> Wheels<u16> {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_TELEMETRY, "tyre_inner_temps");
+        self.tyre_temps(OFFSETS)
+    }
+
+    // 2018 sends -100 - 100, scaled to match 2019's -1.0 - 1.0
+    pub fn steering_pos(&self) -> f32 {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_TELEMETRY, "steering_pos");
+        let offset = self.record.offset(OFFSETS);
+        match self.format {
+            PacketFormat::F2018 => f32::from(self.record.read::<i8>(offset)) / 100.0,
+            _ => self.record.read(offset),
+        }
+    }
+
+    // 2018 sends 0 - 100, scaled to match 2019's 0.0 - 1.0
+    fn pedal(&self, offsets: [usize; EDITIONS]) -> f32 {
+        let offset = self.record.offset(offsets);
+        match self.format {
+            PacketFormat::F2018 => f32::from(self.record.read::<u8>(offset)) / 100.0,
+            _ => self.record.read(offset),
+        }
+    }
+
+    // 2020 shrank tyre temps to u8
+    fn tyre_temps(&self, offsets: [usize; EDITIONS]) -> Wheels<u16> {
+        let offset = self.record.offset(offsets);
+        match self.format {
+            PacketFormat::F2020 => self.record.read_wheels::<u8>(offset).map(u16::from),
+            _ => self.record.read_wheels(offset),
+        }
+    }
+
+    fn record(&self) -> Record<'a> {
+        self.record
+    }
+}
+
+//-------------Car status
+
+#[derive(Debug, Clone, Copy)]
+pub struct CarStatusDataView<'a> {
+    datagram: Datagram<'a>,
+}
+
+impl<'a> CarStatusDataView<'a> {
+    pub fn new(buf: &'a [u8]) -> Result<Self, ParseError> {
+        Ok(CarStatusDataView { datagram: Datagram::new(buf, PacketType::CarStatus)? })
+    }
+
+    pub fn header(&self) -> &PacketHeader {
+        &self.datagram.header
+    }
+
+    pub fn num_cars(&self) -> usize {
+        self.datagram.format.num_cars()
+    }
+
+    pub fn car(&self, index: usize) -> Option<CarStatusView<'a>> {
+        self.datagram.car(CAR_STATUS, index).map(|record| CarStatusView { record, format: self.datagram.format })
+    }
+
+    pub fn player_car(&self) -> Option<CarStatusView<'a>> {
+        self.car(self.header().player_car_index as usize)
+    }
+
+    pub fn cars(&self) -> impl Iterator<Item = CarStatusView<'a>> {
+        let view = *self;
+        (0..self.num_cars()).filter_map(move |index| view.car(index))
+    }
+
+    pub fn decode(&self) -> Result<CarStatusData, ParseError> {
+        let Datagram { buf, format, header } = self.datagram;
+        match format {
+            PacketFormat::F2018 => f1_2018::parse_car_status_data(buf, header),
+            PacketFormat::F2019 => f1_2019::parse_car_status_data(buf, header),
+            PacketFormat::F2020 => f1_2020::parse_car_status_data(buf, header),
+        }
+    }
+}
+
+// 2018 sends a single compound, used for both the actual and visual compound
+const ACTUAL_TYRE_COMPOUND: [usize; EDITIONS] = [
+    sent(f1_2018::CAR_STATUS_LAYOUT.offset_of("tyre_compound")),
+    sent(f1_2019::CAR_STATUS_LAYOUT.offset_of("actual_tyre_compound")),
+    sent(f1_2020::CAR_STATUS_LAYOUT.offset_of("actual_tyre_compound")),
+];
+const TYRE_VISUAL_COMPOUND: [usize; EDITIONS] = [
+    sent(f1_2018::CAR_STATUS_LAYOUT.offset_of("tyre_compound")),
+    sent(f1_2019::CAR_STATUS_LAYOUT.offset_of("tyre_visual_compound")),
+    sent(f1_2020::CAR_STATUS_LAYOUT.offset_of("tyre_visual_compound")),
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct CarStatusView<'a> {
+    record: Record<'a>,
+    format: PacketFormat,
+}
+
+impl<'a> CarStatusView<'a> {
+    fields!(record, CAR_STATUS, read;
+        traction_control: u8,
+        anti_lock_brakes: u8,
+        front_brake_bias: u8,
+        pit_limiter_status: u8,
+        fuel_in_tank: f32,
+        fuel_capacity: f32,
+        max_rpm: u16,
+        idle_rpm: u16,
+        max_gears: u8,
+        drs_allowed: u8,
+        front_left_wing_damage: u8,
+        front_right_wing_damage: u8,
+        rear_wing_damage: u8,
+        engine_damage: u8,
+        gear_box_damage: u8,
+        ers_store_energy: f32,
+        ers_harvested_this_lap_mguk: f32,
+        ers_harvested_this_lap_mguh: f32,
+        ers_deployed_this_lap: f32,
+    );
+    fields!(record, CAR_STATUS, read_wheels;
+        tyres_wear: Wheels<u8>,
+        tyres_damage: Wheels<u8>,
+    );
+
+    // Fuel laps from 2019, the rest 2020 only
+    some_fields!(record, CAR_STATUS, read;
+        fuel_remaining_laps: f32,
+        drs_activation_distance: u16,
+        tyres_age_laps: u8,
+        drs_fault: u8,
+    );
+
+    pub fn fuel_mix(&self) -> FuelMix {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_STATUS, "fuel_mix");
+        FuelMix::from(self.record.read::<u8>(self.record.offset(OFFSETS)))
+    }
+
+    pub fn actual_tyre_compound(&self) -> TyreCompound {
+        TyreCompound::from(self.record.read::<u8>(self.record.offset(ACTUAL_TYRE_COMPOUND)))
+    }
+
+    pub fn tyre_visual_compound(&self) -> VisualTyreCompound {
+        let compound = self.record.read::<u8>(self.record.offset(TYRE_VISUAL_COMPOUND));
+        match self.format {
+            PacketFormat::F2018 => VisualTyreCompound::from_2018(compound),
+            _ => VisualTyreCompound::from(compound),
+        }
+    }
+
+    pub fn vehicle_fia_flags(&self) -> FiaFlag {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_STATUS, "vehicle_fia_flags");
+        FiaFlag::from(self.record.read::<i8>(self.record.offset(OFFSETS)))
+    }
+
+    pub fn ers_deploy_mode(&self) -> ErsDeployMode {
+        const OFFSETS: [usize; EDITIONS] = sent_by_all(CAR_STATUS, "ers_deploy_mode");
+        let mode = self.record.read::<u8>(self.record.offset(OFFSETS));
+        match self.format {
+            PacketFormat::F2020 => ErsDeployMode::from_2020(mode),
+            _ => ErsDeployMode::from(mode),
+        }
+    }
+
+    fn record(&self) -> Record<'a> {
+        self.record
+    }
+}
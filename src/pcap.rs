@@ -0,0 +1,459 @@
+// Reads pcap and pcapng files, e.g. from tcpdump or Wireshark, pulling out the UDP payloads sent
+// to the F1 port, and writes datagrams out as pcap so a recording opens in Wireshark.
+//
+// The reader takes either byte order, microsecond or nanosecond pcap, and pcapng with any
+// if_tsresol. Frames can be ethernet (with VLAN tags), BSD loopback, linux cooked (v1 and v2) or
+// bare IP, carrying IPv4 or IPv6. Fragmented and truncated datagrams are skipped, as are IPv6
+// packets with extension headers before the UDP header.
+//
+// The writer uses LINKTYPE_RAW, every frame an IP packet holding one UDP datagram, with
+// microsecond timestamps in little endian.
+
+use std::convert::TryFrom;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::ops::Range;
+
+use crate::capture::CaptureRecord;
+use crate::file::invalid_data;
+use crate::file::read_or_end;
+use crate::source::PacketSource;
+
+const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const PCAP_HEADER_SIZE: usize = 24;
+const PCAP_RECORD_HEADER_SIZE: usize = 16;
+
+const PCAPNG_SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+const PCAPNG_OBSOLETE_PACKET: u32 = 2;
+const PCAPNG_SIMPLE_PACKET: u32 = 3;
+const PCAPNG_ENHANCED_PACKET: u32 = 6;
+const PCAPNG_OPTION_END: u16 = 0;
+const PCAPNG_OPTION_TSRESOL: u16 = 9;
+
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LOOP: u32 = 108;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_IPV6: u32 = 229;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+// Loopback address families, IPv6 differs between the BSDs
+const AF_INET: u32 = 2;
+const AF_INET6_BSD: u32 = 24;
+const AF_INET6_FREEBSD: u32 = 28;
+const AF_INET6_DARWIN: u32 = 30;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88a8;
+
+const IP_PROTOCOL_UDP: u8 = 17;
+const IPV4_HEADER_SIZE: usize = 20;
+const IPV6_HEADER_SIZE: usize = 40;
+const UDP_HEADER_SIZE: usize = 8;
+const MICROS_PER_SECOND: u64 = 1_000_000;
+const MAX_FRAME_SIZE: usize = 256 * 1024;  // Well past any snap length, guards against a corrupt length
+
+// Whether a file starting with these bytes is pcap or pcapng
+pub fn is_pcap(start: &[u8]) -> bool {
+    if start.len() < 4 {
+        return false;
+    }
+    let magic = u32::from_le_bytes([start[0], start[1], start[2], start[3]]);
+    [PCAP_MAGIC_MICROS, PCAP_MAGIC_NANOS, PCAPNG_SECTION_HEADER].iter()
+        .any(|known| magic == *known || magic.swap_bytes() == *known)
+}
+
+fn u16_at(bytes: &[u8], offset: usize, big_endian: bool) -> u16 {
+    let field = [bytes[offset], bytes[offset + 1]];
+    if big_endian { u16::from_be_bytes(field) } else { u16::from_le_bytes(field) }
+}
+
+fn u32_at(bytes: &[u8], offset: usize, big_endian: bool) -> u32 {
+    let field = [bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]];
+    if big_endian { u32::from_be_bytes(field) } else { u32::from_le_bytes(field) }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_type:          u32,
+    units_per_second:   u64,    // Timestamp resolution
+}
+
+enum Format {
+    Pcap { big_endian: bool, interface: Interface },
+    PcapNg { big_endian: bool, interfaces: Vec<Interface> },
+}
+
+pub struct PcapReader<R: Read> {
+    inner:      R,
+    port:       u16,                // Only datagrams sent to this port
+    format:     Format,
+    frame:      Vec<u8>,            // The last frame read, whole block for pcapng
+    source:     Option<SocketAddr>, // Of the last datagram
+    last_us:    u64,                // Of the last frame, for pcapng blocks without a timestamp
+}
+
+impl<R: Read> PcapReader<R> {
+    // Reads the file header. port is the F1 port the game sent to, usually 20777.
+    pub fn new(mut inner: R, port: u16) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        inner.read_exact(&mut magic).map_err(|_| invalid_data("not a pcap file, too short"))?;
+        let format = match u32::from_le_bytes(magic) {
+            PCAPNG_SECTION_HEADER => {
+                let big_endian = read_section_header(&mut inner)?;
+                Format::PcapNg { big_endian, interfaces: Vec::new() }
+            },
+            magic => {
+                let (big_endian, nanos) = match magic {
+                    PCAP_MAGIC_MICROS => (false, false),
+                    PCAP_MAGIC_NANOS => (false, true),
+                    _ if magic.swap_bytes() == PCAP_MAGIC_MICROS => (true, false),
+                    _ if magic.swap_bytes() == PCAP_MAGIC_NANOS => (true, true),
+                    _ => return Err(invalid_data("not a pcap or pcapng file, bad magic")),
+                };
+                let mut header = [0u8; PCAP_HEADER_SIZE - 4];
+                inner.read_exact(&mut header).map_err(|_| invalid_data("pcap file too short for its header"))?;
+                let link_type = u32_at(&header, 16, big_endian) & 0x0fff_ffff;
+                let units_per_second = if nanos { 1_000_000_000 } else { MICROS_PER_SECOND };
+                Format::Pcap { big_endian, interface: Interface { link_type, units_per_second } }
+            },
+        };
+        Ok(PcapReader { inner, port, format, frame: Vec::new(), source: None, last_us: 0 })
+    }
+
+    // Next UDP datagram sent to the port, None at the end of the file
+    pub fn next_record(&mut self) -> io::Result<Option<CaptureRecord<'_>>> {
+        loop {
+            let (received_us, interface, frame) = match self.next_frame()? {
+                Some(frame) => frame,
+                None => return Ok(None),
+            };
+            self.last_us = received_us;
+            if let Some((source, port, payload)) = udp_datagram(interface.link_type, &self.frame[frame.clone()]) {
+                if port == self.port {
+                    self.source = Some(source);
+                    let payload = frame.start + payload.start..frame.start + payload.end;
+                    return Ok(Some(CaptureRecord { received_us, source: self.source, datagram: &self.frame[payload] }));
+                }
+            }
+        }
+    }
+
+    // Receive time, interface and where the frame sits in self.frame
+    fn next_frame(&mut self) -> io::Result<Option<(u64, Interface, Range<usize>)>> {
+        match &mut self.format {
+            Format::Pcap { big_endian, interface } => {
+                let mut header = [0u8; PCAP_RECORD_HEADER_SIZE];
+                if !read_or_end(&mut self.inner, &mut header, "pcap ends part way through a packet")? {
+                    return Ok(None);
+                }
+                let seconds = u32_at(&header, 0, *big_endian) as u64;
+                let fraction = u32_at(&header, 4, *big_endian) as u64;
+                let len = u32_at(&header, 8, *big_endian) as usize;
+                if len > MAX_FRAME_SIZE {
+                    return Err(invalid_data("pcap packet has a bad length"));
+                }
+                self.frame.resize(len, 0);
+                self.inner.read_exact(&mut self.frame)?;
+                let received_us = seconds * MICROS_PER_SECOND + fraction * MICROS_PER_SECOND / interface.units_per_second;
+                Ok(Some((received_us, *interface, 0..len)))
+            },
+            Format::PcapNg { big_endian, interfaces } => loop {
+                let mut header = [0u8; 8];
+                if !read_or_end(&mut self.inner, &mut header, "pcap ends part way through a packet")? {
+                    return Ok(None);
+                }
+                // A new section, possibly in the other byte order, with its own interfaces
+                if u32::from_le_bytes([header[0], header[1], header[2], header[3]]) == PCAPNG_SECTION_HEADER {
+                    *big_endian = read_section_header(&mut (&header[4..]).chain(&mut self.inner))?;
+                    interfaces.clear();
+                    continue;
+                }
+                let block_type = u32_at(&header, 0, *big_endian);
+                let len = u32_at(&header, 4, *big_endian) as usize;
+                if !(12..=MAX_FRAME_SIZE).contains(&len) || !len.is_multiple_of(4) {
+                    return Err(invalid_data("pcapng block has a bad length"));
+                }
+                self.frame.resize(len - 8, 0);
+                self.inner.read_exact(&mut self.frame)?;
+                let body = &self.frame[..len - 12];
+                let big_endian = *big_endian;
+                let timestamp = |interface: &Interface, offset: usize| {
+                    let units = (u32_at(body, offset, big_endian) as u128) << 32 | u32_at(body, offset + 4, big_endian) as u128;
+                    (units * MICROS_PER_SECOND as u128 / interface.units_per_second as u128) as u64
+                };
+                match block_type {
+                    PCAPNG_INTERFACE_DESCRIPTION if body.len() >= 8 => interfaces.push(read_interface(body, big_endian)),
+                    PCAPNG_ENHANCED_PACKET | PCAPNG_OBSOLETE_PACKET if body.len() >= 20 => {
+                        let interface_id = match block_type {
+                            PCAPNG_ENHANCED_PACKET => u32_at(body, 0, big_endian) as usize,
+                            _ => u16_at(body, 0, big_endian) as usize,
+                        };
+                        let interface = *interfaces.get(interface_id).ok_or_else(|| invalid_data("pcapng packet for an undescribed interface"))?;
+                        let captured = u32_at(body, 12, big_endian) as usize;
+                        if 20 + captured > body.len() {
+                            return Err(invalid_data("pcapng packet longer than its block"));
+                        }
+                        return Ok(Some((timestamp(&interface, 4), interface, 20..20 + captured)));
+                    },
+                    PCAPNG_SIMPLE_PACKET if body.len() >= 4 => {
+                        let interface = *interfaces.first().ok_or_else(|| invalid_data("pcapng packet for an undescribed interface"))?;
+                        let captured = (u32_at(body, 0, big_endian) as usize).min(body.len() - 4);
+                        return Ok(Some((self.last_us, interface, 4..4 + captured)));
+                    },
+                    // Name resolution, statistics and anything newer
+                    _ => {},
+                }
+            },
+        }
+    }
+}
+
+impl<R: Read> PacketSource for PcapReader<R> {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        Ok(self.next_record()?.map(|record| (record.received_us, record.datagram)))
+    }
+
+    fn source_address(&self) -> Option<SocketAddr> {
+        self.source
+    }
+}
+
+// Reads the rest of a section header block after its type, returns whether the section is big
+// endian
+fn read_section_header<R: Read>(inner: &mut R) -> io::Result<bool> {
+    let mut header = [0u8; 8];
+    inner.read_exact(&mut header).map_err(|_| invalid_data("pcapng section header cut short"))?;
+    let big_endian = match u32::from_le_bytes([header[4], header[5], header[6], header[7]]) {
+        PCAPNG_BYTE_ORDER_MAGIC => false,
+        magic if magic.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC => true,
+        _ => return Err(invalid_data("pcapng section header has a bad byte order magic")),
+    };
+    let len = u32_at(&header, 0, big_endian) as usize;
+    if len < 28 || !len.is_multiple_of(4) {
+        return Err(invalid_data("pcapng section header has a bad length"));
+    }
+    io::copy(&mut inner.take((len - 12) as u64), &mut io::sink())?;
+    Ok(big_endian)
+}
+
+fn read_interface(body: &[u8], big_endian: bool) -> Interface {
+    let mut interface = Interface { link_type: u16_at(body, 0, big_endian) as u32, units_per_second: MICROS_PER_SECOND };
+    let mut offset = 8;
+    while offset + 4 <= body.len() {
+        let code = u16_at(body, offset, big_endian);
+        let len = u16_at(body, offset + 2, big_endian) as usize;
+        if code == PCAPNG_OPTION_END || offset + 4 + len > body.len() {
+            break;
+        }
+        if code == PCAPNG_OPTION_TSRESOL && len >= 1 {
+            let resolution = body[offset + 4];
+            let units = match resolution & 0x80 {
+                0 => 10u64.checked_pow(resolution as u32),
+                _ => 2u64.checked_pow((resolution & 0x7f) as u32),
+            };
+            interface.units_per_second = units.unwrap_or(MICROS_PER_SECOND);
+        }
+        offset += 4 + len.div_ceil(4) * 4;
+    }
+    interface
+}
+
+// Where the IP packet starts in a frame of the given link type
+fn ip_offset(link_type: u32, frame: &[u8]) -> Option<usize> {
+    let ethertype = |offset: usize| frame.get(offset..offset + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+    match link_type {
+        LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => Some(0),
+        // Address family in the byte order of the machine that captured it
+        LINKTYPE_NULL | LINKTYPE_LOOP if frame.len() >= 4 => {
+            let families = [AF_INET, AF_INET6_BSD, AF_INET6_FREEBSD, AF_INET6_DARWIN];
+            match families.contains(&u32_at(frame, 0, false)) || families.contains(&u32_at(frame, 0, true)) {
+                true => Some(4),
+                false => None,
+            }
+        },
+        LINKTYPE_ETHERNET => {
+            let mut offset = 12;
+            while let Some(ETHERTYPE_VLAN) | Some(ETHERTYPE_QINQ) = ethertype(offset) {
+                offset += 4;
+            }
+            match ethertype(offset)? {
+                ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => Some(offset + 2),
+                _ => None,
+            }
+        },
+        LINKTYPE_LINUX_SLL => match ethertype(14)? {
+            ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => Some(16),
+            _ => None,
+        },
+        LINKTYPE_LINUX_SLL2 => match ethertype(0)? {
+            ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => Some(20),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Sender, destination port and where the payload sits in the frame, for a whole UDP datagram
+fn udp_datagram(link_type: u32, frame: &[u8]) -> Option<(SocketAddr, u16, Range<usize>)> {
+    let ip = ip_offset(link_type, frame)?;
+    let be16 = |offset: usize| frame.get(offset..offset + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+    let (source, udp, ip_end) = match frame.get(ip)? >> 4 {
+        4 => {
+            let header_len = (frame[ip] & 0x0f) as usize * 4;
+            let fragment = be16(ip + 6)? & 0x3fff;     // More fragments flag or an offset
+            if header_len < IPV4_HEADER_SIZE || fragment != 0 || *frame.get(ip + 9)? != IP_PROTOCOL_UDP {
+                return None;
+            }
+            let address = frame.get(ip + 12..ip + 16)?;
+            let source = IpAddr::V4(Ipv4Addr::new(address[0], address[1], address[2], address[3]));
+            (source, ip + header_len, ip + be16(ip + 2)? as usize)
+        },
+        6 => {
+            if *frame.get(ip + 6)? != IP_PROTOCOL_UDP {
+                return None;
+            }
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(frame.get(ip + 8..ip + 24)?);
+            (IpAddr::V6(Ipv6Addr::from(octets)), ip + IPV6_HEADER_SIZE, ip + IPV6_HEADER_SIZE + be16(ip + 4)? as usize)
+        },
+        _ => return None,
+    };
+    let source_port = be16(udp)?;
+    let port = be16(udp + 2)?;
+    let len = be16(udp + 4)? as usize;
+    if len < UDP_HEADER_SIZE || udp + len > ip_end || udp + len > frame.len() {
+        return None;
+    }
+    Some((SocketAddr::new(source, source_port), port, udp + UDP_HEADER_SIZE..udp + len))
+}
+
+pub struct PcapWriter<W: Write> {
+    inner:          W,
+    destination:    SocketAddr, // Where the datagrams are shown as sent to
+    frame:          Vec<u8>,
+}
+
+impl<W: Write> PcapWriter<W> {
+    // Writes the file header
+    pub fn new(mut inner: W, destination: SocketAddr) -> io::Result<Self> {
+        let mut header = [0u8; PCAP_HEADER_SIZE];
+        header[0..4].copy_from_slice(&PCAP_MAGIC_MICROS.to_le_bytes());
+        header[4..6].copy_from_slice(&2u16.to_le_bytes());
+        header[6..8].copy_from_slice(&4u16.to_le_bytes());
+        header[16..20].copy_from_slice(&(u16::MAX as u32).to_le_bytes());   // Snap length
+        header[20..24].copy_from_slice(&LINKTYPE_RAW.to_le_bytes());
+        inner.write_all(&header)?;
+        Ok(PcapWriter { inner, destination, frame: Vec::new() })
+    }
+
+    // Writes the datagram as a UDP packet from source, or an unspecified address if not known
+    pub fn write(&mut self, received_us: u64, source: Option<SocketAddr>, datagram: &[u8]) -> io::Result<()> {
+        let source = source.unwrap_or_else(|| match self.destination {
+            SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+        });
+        self.frame.clear();
+        match (source.ip(), self.destination.ip()) {
+            (IpAddr::V4(from), IpAddr::V4(to)) => write_ipv4(&mut self.frame, from, to, datagram.len())?,
+            (from, to) => write_ipv6(&mut self.frame, to_ipv6(from), to_ipv6(to), datagram.len())?,
+        }
+        let udp = self.frame.len();
+        self.frame.extend_from_slice(&source.port().to_be_bytes());
+        self.frame.extend_from_slice(&self.destination.port().to_be_bytes());
+        self.frame.extend_from_slice(&((UDP_HEADER_SIZE + datagram.len()) as u16).to_be_bytes());
+        self.frame.extend_from_slice(&[0, 0]);
+        self.frame.extend_from_slice(datagram);
+        let checksum = udp_checksum(source.ip(), self.destination.ip(), &self.frame[udp..]);
+        self.frame[udp + 6..udp + 8].copy_from_slice(&checksum.to_be_bytes());
+
+        let mut header = [0u8; PCAP_RECORD_HEADER_SIZE];
+        let seconds = u32::try_from(received_us / MICROS_PER_SECOND).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "receive time past 2106"))?;
+        header[0..4].copy_from_slice(&seconds.to_le_bytes());
+        header[4..8].copy_from_slice(&((received_us % MICROS_PER_SECOND) as u32).to_le_bytes());
+        header[8..12].copy_from_slice(&(self.frame.len() as u32).to_le_bytes());
+        header[12..16].copy_from_slice(&(self.frame.len() as u32).to_le_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(&self.frame)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+fn to_ipv6(address: IpAddr) -> Ipv6Addr {
+    match address {
+        IpAddr::V4(address) => address.to_ipv6_mapped(),
+        IpAddr::V6(address) => address,
+    }
+}
+
+fn too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "datagram too long for a UDP packet")
+}
+
+fn write_ipv4(frame: &mut Vec<u8>, source: Ipv4Addr, destination: Ipv4Addr, payload_len: usize) -> io::Result<()> {
+    let total_len = u16::try_from(IPV4_HEADER_SIZE + UDP_HEADER_SIZE + payload_len).map_err(|_| too_long())?;
+    frame.extend_from_slice(&[0x45, 0]);
+    frame.extend_from_slice(&total_len.to_be_bytes());
+    frame.extend_from_slice(&[0, 0, 0x40, 0, 64, IP_PROTOCOL_UDP, 0, 0]);  // Id, don't fragment, ttl, protocol, checksum
+    frame.extend_from_slice(&source.octets());
+    frame.extend_from_slice(&destination.octets());
+    let checksum = !fold(sum(&frame[..IPV4_HEADER_SIZE]));
+    frame[10..12].copy_from_slice(&checksum.to_be_bytes());
+    Ok(())
+}
+
+fn write_ipv6(frame: &mut Vec<u8>, source: Ipv6Addr, destination: Ipv6Addr, payload_len: usize) -> io::Result<()> {
+    let udp_len = u16::try_from(UDP_HEADER_SIZE + payload_len).map_err(|_| too_long())?;
+    frame.extend_from_slice(&[0x60, 0, 0, 0]);
+    frame.extend_from_slice(&udp_len.to_be_bytes());
+    frame.extend_from_slice(&[IP_PROTOCOL_UDP, 64]);
+    frame.extend_from_slice(&source.octets());
+    frame.extend_from_slice(&destination.octets());
+    Ok(())
+}
+
+// Ones complement sum of big endian u16s, odd lengths padded with a zero
+fn sum(bytes: &[u8]) -> u32 {
+    bytes.chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+        .fold(0u32, |total, word| {
+            let total = total + word;
+            (total & 0xffff) + (total >> 16)
+        })
+}
+
+fn fold(mut total: u32) -> u16 {
+    while total > 0xffff {
+        total = (total & 0xffff) + (total >> 16);
+    }
+    total as u16
+}
+
+fn udp_checksum(source: IpAddr, destination: IpAddr, udp: &[u8]) -> u16 {
+    let pseudo = match (source, destination) {
+        (IpAddr::V4(from), IpAddr::V4(to)) => sum(&from.octets()) + sum(&to.octets()),
+        (from, to) => sum(&to_ipv6(from).octets()) + sum(&to_ipv6(to).octets()),
+    };
+    let checksum = !fold(pseudo + IP_PROTOCOL_UDP as u32 + udp.len() as u32 + sum(udp));
+    // Zero means no checksum, send all ones instead
+    if checksum == 0 { 0xffff } else { checksum }
+}
@@ -0,0 +1,368 @@
+// Reference data from the appendices of the UDP spec, looked up by the ids sent in packets.
+// Names follow the F1 2019 appendix, later editions reuse most ids. Lookups return None for ids
+// the appendix doesn't list, e.g. 255 for a human driver.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Team {
+    pub id:     u8,
+    pub name:   &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Driver {
+    pub id:     u8,
+    pub name:   &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Track {
+    pub id:         i8,
+    pub name:       &'static str,
+    pub country:    &'static str,
+    pub length_m:   Option<u16>,    // Race layout length in metres, None for short layouts. SessionData.track_len has what the game sent
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Nationality {
+    pub id:     u8,
+    pub name:   &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceType {
+    pub id:     u8,
+    pub name:   &'static str,
+}
+
+impl Team {
+    pub fn from_id(id: u8) -> Option<&'static Team> {
+        TEAMS.iter().find(|team| team.id == id)
+    }
+}
+
+impl Driver {
+    pub fn from_id(id: u8) -> Option<&'static Driver> {
+        DRIVERS.iter().find(|driver| driver.id == id)
+    }
+}
+
+impl Track {
+    // Track id is -1 when unknown
+    pub fn from_id(id: i8) -> Option<&'static Track> {
+        TRACKS.iter().find(|track| track.id == id)
+    }
+}
+
+impl Nationality {
+    pub fn from_id(id: u8) -> Option<&'static Nationality> {
+        NATIONALITIES.iter().find(|nationality| nationality.id == id)
+    }
+}
+
+impl SurfaceType {
+    pub fn from_id(id: u8) -> Option<&'static SurfaceType> {
+        SURFACE_TYPES.iter().find(|surface| surface.id == id)
+    }
+}
+
+impl fmt::Display for Team {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Display for Driver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Display for Track {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Display for Nationality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl fmt::Display for SurfaceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+static TEAMS: [Team; 53] = [
+    Team { id: 0,   name: "Mercedes" },
+    Team { id: 1,   name: "Ferrari" },
+    Team { id: 2,   name: "Red Bull Racing" },
+    Team { id: 3,   name: "Williams" },
+    Team { id: 4,   name: "Racing Point" },
+    Team { id: 5,   name: "Renault" },
+    Team { id: 6,   name: "Toro Rosso" },
+    Team { id: 7,   name: "Haas" },
+    Team { id: 8,   name: "McLaren" },
+    Team { id: 9,   name: "Alfa Romeo" },
+    Team { id: 10,  name: "McLaren 1988" },
+    Team { id: 11,  name: "McLaren 1991" },
+    Team { id: 12,  name: "Williams 1992" },
+    Team { id: 13,  name: "Ferrari 1995" },
+    Team { id: 14,  name: "Williams 1996" },
+    Team { id: 15,  name: "McLaren 1998" },
+    Team { id: 16,  name: "Ferrari 2002" },
+    Team { id: 17,  name: "Ferrari 2004" },
+    Team { id: 18,  name: "Renault 2006" },
+    Team { id: 19,  name: "Ferrari 2007" },
+    Team { id: 21,  name: "Red Bull 2010" },
+    Team { id: 22,  name: "Ferrari 1976" },
+    Team { id: 23,  name: "ART Grand Prix" },
+    Team { id: 24,  name: "Campos Vexatec Racing" },
+    Team { id: 25,  name: "Carlin" },
+    Team { id: 26,  name: "Charouz Racing System" },
+    Team { id: 27,  name: "DAMS" },
+    Team { id: 28,  name: "Russian Time" },
+    Team { id: 29,  name: "MP Motorsport" },
+    Team { id: 30,  name: "Pertamina" },
+    Team { id: 31,  name: "McLaren 1990" },
+    Team { id: 32,  name: "Trident" },
+    Team { id: 33,  name: "BWT Arden" },
+    Team { id: 34,  name: "McLaren 1976" },
+    Team { id: 35,  name: "Lotus 1972" },
+    Team { id: 36,  name: "Ferrari 1979" },
+    Team { id: 37,  name: "McLaren 1982" },
+    Team { id: 38,  name: "Williams 2003" },
+    Team { id: 39,  name: "Brawn 2009" },
+    Team { id: 40,  name: "Lotus 1978" },
+    Team { id: 42,  name: "Art GP ’19" },
+    Team { id: 43,  name: "Campos ’19" },
+    Team { id: 44,  name: "Carlin ’19" },
+    Team { id: 45,  name: "Sauber Junior Charouz ’19" },
+    Team { id: 46,  name: "Dams ’19" },
+    Team { id: 47,  name: "Uni-Virtuosi ‘19" },
+    Team { id: 48,  name: "MP Motorsport ‘19" },
+    Team { id: 49,  name: "Prema ’19" },
+    Team { id: 50,  name: "Trident ’19" },
+    Team { id: 51,  name: "Arden ’19" },
+    Team { id: 63,  name: "Ferrari 1990" },
+    Team { id: 64,  name: "McLaren 2010" },
+    Team { id: 65,  name: "Ferrari 2010" },
+];
+
+static DRIVERS: [Driver; 77] = [
+    Driver { id: 0,   name: "Carlos Sainz" },
+    Driver { id: 1,   name: "Daniil Kvyat" },
+    Driver { id: 2,   name: "Daniel Ricciardo" },
+    Driver { id: 6,   name: "Kimi Räikkönen" },
+    Driver { id: 7,   name: "Lewis Hamilton" },
+    Driver { id: 9,   name: "Max Verstappen" },
+    Driver { id: 10,  name: "Nico Hulkenburg" },
+    Driver { id: 11,  name: "Kevin Magnussen" },
+    Driver { id: 12,  name: "Romain Grosjean" },
+    Driver { id: 13,  name: "Sebastian Vettel" },
+    Driver { id: 14,  name: "Sergio Perez" },
+    Driver { id: 15,  name: "Valtteri Bottas" },
+    Driver { id: 19,  name: "Lance Stroll" },
+    Driver { id: 20,  name: "Arron Barnes" },
+    Driver { id: 21,  name: "Martin Giles" },
+    Driver { id: 22,  name: "Alex Murray" },
+    Driver { id: 23,  name: "Lucas Roth" },
+    Driver { id: 24,  name: "Igor Correia" },
+    Driver { id: 25,  name: "Sophie Levasseur" },
+    Driver { id: 26,  name: "Jonas Schiffer" },
+    Driver { id: 27,  name: "Alain Forest" },
+    Driver { id: 28,  name: "Jay Letourneau" },
+    Driver { id: 29,  name: "Esto Saari" },
+    Driver { id: 30,  name: "Yasar Atiyeh" },
+    Driver { id: 31,  name: "Callisto Calabresi" },
+    Driver { id: 32,  name: "Naota Izum" },
+    Driver { id: 33,  name: "Howard Clarke" },
+    Driver { id: 34,  name: "Wilheim Kaufmann" },
+    Driver { id: 35,  name: "Marie Laursen" },
+    Driver { id: 36,  name: "Flavio Nieves" },
+    Driver { id: 37,  name: "Peter Belousov" },
+    Driver { id: 38,  name: "Klimek Michalski" },
+    Driver { id: 39,  name: "Santiago Moreno" },
+    Driver { id: 40,  name: "Benjamin Coppens" },
+    Driver { id: 41,  name: "Noah Visser" },
+    Driver { id: 42,  name: "Gert Waldmuller" },
+    Driver { id: 43,  name: "Julian Quesada" },
+    Driver { id: 44,  name: "Daniel Jones" },
+    Driver { id: 45,  name: "Artem Markelov" },
+    Driver { id: 46,  name: "Tadasuke Makino" },
+    Driver { id: 47,  name: "Sean Gelael" },
+    Driver { id: 48,  name: "Nyck De Vries" },
+    Driver { id: 49,  name: "Jack Aitken" },
+    Driver { id: 50,  name: "George Russell" },
+    Driver { id: 51,  name: "Maximilian Günther" },
+    Driver { id: 52,  name: "Nirei Fukuzumi" },
+    Driver { id: 53,  name: "Luca Ghiotto" },
+    Driver { id: 54,  name: "Lando Norris" },
+    Driver { id: 55,  name: "Sérgio Sette Câmara" },
+    Driver { id: 56,  name: "Louis Delétraz" },
+    Driver { id: 57,  name: "Antonio Fuoco" },
+    Driver { id: 58,  name: "Charles Leclerc" },
+    Driver { id: 59,  name: "Pierre Gasly" },
+    Driver { id: 62,  name: "Alexander Albon" },
+    Driver { id: 63,  name: "Nicholas Latifi" },
+    Driver { id: 64,  name: "Dorian Boccolacci" },
+    Driver { id: 65,  name: "Niko Kari" },
+    Driver { id: 66,  name: "Roberto Merhi" },
+    Driver { id: 67,  name: "Arjun Maini" },
+    Driver { id: 68,  name: "Alessio Lorandi" },
+    Driver { id: 69,  name: "Ruben Meijer" },
+    Driver { id: 70,  name: "Rashid Nair" },
+    Driver { id: 71,  name: "Jack Tremblay" },
+    Driver { id: 74,  name: "Antonio Giovinazzi" },
+    Driver { id: 75,  name: "Robert Kubica" },
+    Driver { id: 78,  name: "Nobuharu Matsushita" },
+    Driver { id: 79,  name: "Nikita Mazepin" },
+    Driver { id: 80,  name: "Guanya Zhou" },
+    Driver { id: 81,  name: "Mick Schumacher" },
+    Driver { id: 82,  name: "Callum Ilott" },
+    Driver { id: 83,  name: "Juan Manuel Correa" },
+    Driver { id: 84,  name: "Jordan King" },
+    Driver { id: 85,  name: "Mahaveer Raghunathan" },
+    Driver { id: 86,  name: "Tatiana Calderon" },
+    Driver { id: 87,  name: "Anthoine Hubert" },
+    Driver { id: 88,  name: "Guiliano Alesi" },
+    Driver { id: 89,  name: "Ralph Boschung" },
+];
+
+static TRACKS: [Track; 27] = [
+    Track { id: 0,   name: "Melbourne",          country: "Australia",             length_m: Some(5303) },
+    Track { id: 1,   name: "Paul Ricard",        country: "France",                length_m: Some(5842) },
+    Track { id: 2,   name: "Shanghai",           country: "China",                 length_m: Some(5451) },
+    Track { id: 3,   name: "Sakhir Bahrain",     country: "Bahrain",               length_m: Some(5412) },
+    Track { id: 4,   name: "Catalunya",          country: "Spain",                 length_m: Some(4655) },
+    Track { id: 5,   name: "Monaco",             country: "Monaco",                length_m: Some(3337) },
+    Track { id: 6,   name: "Montreal",           country: "Canada",                length_m: Some(4361) },
+    Track { id: 7,   name: "Silverstone",        country: "United Kingdom",        length_m: Some(5891) },
+    Track { id: 8,   name: "Hockenheim",         country: "Germany",               length_m: Some(4574) },
+    Track { id: 9,   name: "Hungaroring",        country: "Hungary",               length_m: Some(4381) },
+    Track { id: 10,  name: "Spa",                country: "Belgium",               length_m: Some(7004) },
+    Track { id: 11,  name: "Monza",              country: "Italy",                 length_m: Some(5793) },
+    Track { id: 12,  name: "Singapore",          country: "Singapore",             length_m: Some(5063) },
+    Track { id: 13,  name: "Suzuka",             country: "Japan",                 length_m: Some(5807) },
+    Track { id: 14,  name: "Abu Dhabi",          country: "United Arab Emirates",  length_m: Some(5554) },
+    Track { id: 15,  name: "Texas",              country: "United States",         length_m: Some(5513) },
+    Track { id: 16,  name: "Brazil",             country: "Brazil",                length_m: Some(4309) },
+    Track { id: 17,  name: "Austria",            country: "Austria",               length_m: Some(4318) },
+    Track { id: 18,  name: "Sochi",              country: "Russia",                length_m: Some(5848) },
+    Track { id: 19,  name: "Mexico",             country: "Mexico",                length_m: Some(4304) },
+    Track { id: 20,  name: "Baku Azerbaijan",    country: "Azerbaijan",            length_m: Some(6003) },
+    Track { id: 21,  name: "Sakhir Short",       country: "Bahrain",               length_m: None },
+    Track { id: 22,  name: "Silverstone Short",  country: "United Kingdom",        length_m: None },
+    Track { id: 23,  name: "Texas Short",        country: "United States",         length_m: None },
+    Track { id: 24,  name: "Suzuka Short",       country: "Japan",                 length_m: None },
+    Track { id: 25,  name: "Hanoi",              country: "Vietnam",               length_m: Some(5607) },
+    Track { id: 26,  name: "Zandvoort",          country: "Netherlands",           length_m: Some(4259) },
+];
+
+static NATIONALITIES: [Nationality; 86] = [
+    Nationality { id: 1,   name: "American" },
+    Nationality { id: 2,   name: "Argentinean" },
+    Nationality { id: 3,   name: "Australian" },
+    Nationality { id: 4,   name: "Austrian" },
+    Nationality { id: 5,   name: "Azerbaijani" },
+    Nationality { id: 6,   name: "Bahraini" },
+    Nationality { id: 7,   name: "Belgian" },
+    Nationality { id: 8,   name: "Bolivian" },
+    Nationality { id: 9,   name: "Brazilian" },
+    Nationality { id: 10,  name: "British" },
+    Nationality { id: 11,  name: "Bulgarian" },
+    Nationality { id: 12,  name: "Cameroonian" },
+    Nationality { id: 13,  name: "Canadian" },
+    Nationality { id: 14,  name: "Chilean" },
+    Nationality { id: 15,  name: "Chinese" },
+    Nationality { id: 16,  name: "Colombian" },
+    Nationality { id: 17,  name: "Costa Rican" },
+    Nationality { id: 18,  name: "Croatian" },
+    Nationality { id: 19,  name: "Cypriot" },
+    Nationality { id: 20,  name: "Czech" },
+    Nationality { id: 21,  name: "Danish" },
+    Nationality { id: 22,  name: "Dutch" },
+    Nationality { id: 23,  name: "Ecuadorian" },
+    Nationality { id: 24,  name: "English" },
+    Nationality { id: 25,  name: "Emirian" },
+    Nationality { id: 26,  name: "Estonian" },
+    Nationality { id: 27,  name: "Finnish" },
+    Nationality { id: 28,  name: "French" },
+    Nationality { id: 29,  name: "German" },
+    Nationality { id: 30,  name: "Ghanaian" },
+    Nationality { id: 31,  name: "Greek" },
+    Nationality { id: 32,  name: "Guatemalan" },
+    Nationality { id: 33,  name: "Honduran" },
+    Nationality { id: 34,  name: "Hong Konger" },
+    Nationality { id: 35,  name: "Hungarian" },
+    Nationality { id: 36,  name: "Icelander" },
+    Nationality { id: 37,  name: "Indian" },
+    Nationality { id: 38,  name: "Indonesian" },
+    Nationality { id: 39,  name: "Irish" },
+    Nationality { id: 40,  name: "Israeli" },
+    Nationality { id: 41,  name: "Italian" },
+    Nationality { id: 42,  name: "Jamaican" },
+    Nationality { id: 43,  name: "Japanese" },
+    Nationality { id: 44,  name: "Jordanian" },
+    Nationality { id: 45,  name: "Kuwaiti" },
+    Nationality { id: 46,  name: "Latvian" },
+    Nationality { id: 47,  name: "Lebanese" },
+    Nationality { id: 48,  name: "Lithuanian" },
+    Nationality { id: 49,  name: "Luxembourger" },
+    Nationality { id: 50,  name: "Malaysian" },
+    Nationality { id: 51,  name: "Maltese" },
+    Nationality { id: 52,  name: "Mexican" },
+    Nationality { id: 53,  name: "Monegasque" },
+    Nationality { id: 54,  name: "New Zealander" },
+    Nationality { id: 55,  name: "Nicaraguan" },
+    Nationality { id: 56,  name: "North Korean" },
+    Nationality { id: 57,  name: "Northern Irish" },
+    Nationality { id: 58,  name: "Norwegian" },
+    Nationality { id: 59,  name: "Omani" },
+    Nationality { id: 60,  name: "Pakistani" },
+    Nationality { id: 61,  name: "Panamanian" },
+    Nationality { id: 62,  name: "Paraguayan" },
+    Nationality { id: 63,  name: "Peruvian" },
+    Nationality { id: 64,  name: "Polish" },
+    Nationality { id: 65,  name: "Portuguese" },
+    Nationality { id: 66,  name: "Qatari" },
+    Nationality { id: 67,  name: "Romanian" },
+    Nationality { id: 68,  name: "Russian" },
+    Nationality { id: 69,  name: "Salvadoran" },
+    Nationality { id: 70,  name: "Saudi" },
+    Nationality { id: 71,  name: "Scottish" },
+    Nationality { id: 72,  name: "Serbian" },
+    Nationality { id: 73,  name: "Singaporean" },
+    Nationality { id: 74,  name: "Slovakian" },
+    Nationality { id: 75,  name: "Slovenian" },
+    Nationality { id: 76,  name: "South Korean" },
+    Nationality { id: 77,  name: "South African" },
+    Nationality { id: 78,  name: "Spanish" },
+    Nationality { id: 79,  name: "Swedish" },
+    Nationality { id: 80,  name: "Swiss" },
+    Nationality { id: 81,  name: "Thai" },
+    Nationality { id: 82,  name: "Turkish" },
+    Nationality { id: 83,  name: "Uruguayan" },
+    Nationality { id: 84,  name: "Ukrainian" },
+    Nationality { id: 85,  name: "Venezuelan" },
+    Nationality { id: 86,  name: "Welsh" },
+];
+
+static SURFACE_TYPES: [SurfaceType; 12] = [
+    SurfaceType { id: 0,   name: "Tarmac" },
+    SurfaceType { id: 1,   name: "Rumble strip" },
+    SurfaceType { id: 2,   name: "Concrete" },
+    SurfaceType { id: 3,   name: "Rock" },
+    SurfaceType { id: 4,   name: "Gravel" },
+    SurfaceType { id: 5,   name: "Mud" },
+    SurfaceType { id: 6,   name: "Sand" },
+    SurfaceType { id: 7,   name: "Grass" },
+    SurfaceType { id: 8,   name: "Water" },
+    SurfaceType { id: 9,   name: "Cobblestone" },
+    SurfaceType { id: 10,  name: "Metal" },
+    SurfaceType { id: 11,  name: "Ridged" },
+];
@@ -0,0 +1,234 @@
+// Plays a capture back with its original timing, for sending on to a dashboard without the game
+// running. The Replayer holds the current record and says when it's due, scaled by the speed,
+// leaving the sending and waiting to the caller so it can take pause and seek requests while it
+// waits. Seeking goes through the capture's index when given one, otherwise it reads the capture
+// again from the start.
+
+use std::fmt;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::capture::CaptureReader;
+use crate::capture::CaptureRecord;
+use crate::f1_2019_net::PacketType;
+use crate::index;
+use crate::index::CaptureIndex;
+use crate::parser::parse_header;
+use crate::parser::view::LapView;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeekTarget {
+    SessionTime(f32),   // First packet at or after this session_time
+    Lap(u8),            // First lap packet with the player on this lap or later
+    Session(u64),       // First packet of the session with this session_id
+}
+
+impl fmt::Display for SeekTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeekTarget::SessionTime(session_time) => write!(f, "session time {:.3}s", session_time),
+            SeekTarget::Lap(lap) => write!(f, "lap {}", lap),
+            SeekTarget::Session(session_id) => write!(f, "session {:016x}", session_id),
+        }
+    }
+}
+
+impl SeekTarget {
+    // Whether the datagram is the target or past it
+    pub fn reached(&self, datagram: &[u8]) -> bool {
+        match *self {
+            SeekTarget::SessionTime(session_time) => parse_header(datagram).is_ok_and(|header| header.session_time >= session_time),
+            SeekTarget::Lap(lap) => LapView::new(datagram).ok()
+                .and_then(|view| view.player_car())
+                .is_some_and(|car| car.lap_num() >= lap),
+            SeekTarget::Session(session_id) => parse_header(datagram).is_ok_and(|header| header.session_id == session_id),
+        }
+    }
+}
+
+pub struct Replayer<R: Read + Seek> {
+    reader:             CaptureReader<R>,
+    index:              Option<CaptureIndex>,
+    speed:              f64,
+    looped:             bool,
+    packets:            Option<Vec<PacketType>>,   // None for every type
+    end:                Option<SeekTarget>,         // Where the capture is taken to end
+    has_current:        bool,                       // Whether advance has found a record yet
+    datagram:           Vec<u8>,                    // The current record
+    received_us:        u64,
+    position:           u64,                        // Where the current record starts in the capture
+    clock:              Option<(Instant, u64)>,     // When a record received at the given time is due
+    paused_at:          Option<Instant>,
+}
+
+impl<R: Read + Seek> Replayer<R> {
+    // At real time, once through and every packet type. Call advance for the first record.
+    pub fn new(reader: CaptureReader<R>) -> Self {
+        let position = reader.position();
+        Replayer {
+            reader,
+            index: None,
+            speed: 1.0,
+            looped: false,
+            packets: None,
+            end: None,
+            has_current: false,
+            datagram: Vec::new(),
+            received_us: 0,
+            position,
+            clock: None,
+            paused_at: None,
+        }
+    }
+
+    // Multiplier on the original timing, 2.0 plays twice as fast. Takes effect from the current
+    // record, which stays due when it was.
+    pub fn set_speed(&mut self, speed: f64) {
+        assert!(speed > 0.0, "replay speed must be positive");
+        if self.clock.is_some() {
+            self.clock = Some((self.due(), self.received_us));
+        }
+        self.speed = speed;
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    // Starts again from the first record after the last rather than ending
+    pub fn set_loop(&mut self, looped: bool) {
+        self.looped = looped;
+    }
+
+    // Index of the capture for seeking, built by CaptureIndex::build or read from beside it
+    pub fn set_index(&mut self, index: Option<CaptureIndex>) {
+        self.index = index;
+    }
+
+    // Only these packet types, datagrams without a readable header are skipped. None for every type
+    pub fn set_packets(&mut self, packets: Option<Vec<PacketType>>) {
+        self.packets = packets;
+    }
+
+    // Ends the capture before the first record reaching the target, where looping starts again
+    pub fn set_end(&mut self, end: Option<SeekTarget>) {
+        self.end = end;
+    }
+
+    // Moves to the next record wanted, false at the end of the capture
+    pub fn advance(&mut self) -> io::Result<bool> {
+        let mut wrapped = false;
+        loop {
+            let position = self.reader.position();
+            let end = self.end;
+            let record = match self.reader.next_record()? {
+                Some(record) if !end.is_some_and(|end| end.reached(record.datagram)) => record,
+                // Give up after a whole pass without a record wanted
+                _ if self.looped && !wrapped => {
+                    self.reader.rewind()?;
+                    self.clock = None;
+                    wrapped = true;
+                    continue;
+                },
+                _ => return Ok(false),
+            };
+            if !wanted(&self.packets, &record) {
+                continue;
+            }
+            let received_us = record.received_us;
+            self.datagram.clear();
+            self.datagram.extend_from_slice(record.datagram);
+            self.received_us = received_us;
+            self.position = position;
+            self.has_current = true;
+            // The first record, or the first after looping or seeking, is due straight away
+            if self.clock.is_none() {
+                self.clock = Some((Instant::now(), received_us));
+            }
+            return Ok(true);
+        }
+    }
+
+    pub fn datagram(&self) -> &[u8] {
+        &self.datagram
+    }
+
+    // When the current record was received
+    pub fn received_us(&self) -> u64 {
+        self.received_us
+    }
+
+    // When the current record should be sent. Records received out of order are due at once.
+    pub fn due(&self) -> Instant {
+        match self.clock {
+            Some((started, first_us)) => {
+                let elapsed_us = self.received_us.saturating_sub(first_us) as f64 / self.speed;
+                started + Duration::from_micros(elapsed_us as u64)
+            },
+            None => Instant::now(),
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.paused_at.get_or_insert_with(Instant::now);
+    }
+
+    // Picks up where it left off, the current record due as far off as it was when paused
+    pub fn resume(&mut self) {
+        if let (Some(paused_at), Some((started, first_us))) = (self.paused_at.take(), self.clock) {
+            self.clock = Some((started + paused_at.elapsed(), first_us));
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    // Moves to the first record wanted at or after the target, due straight away. If the capture
+    // never reaches the target the current record is kept and false returned.
+    pub fn seek(&mut self, target: SeekTarget) -> io::Result<bool> {
+        if !index::seek(&mut self.reader, self.index.as_ref(), target)? {
+            self.restore()?;
+            return Ok(false);
+        }
+        let clock = self.clock.take();
+        let looped = self.looped;
+        self.looped = false;
+        let found = self.advance();
+        self.looped = looped;
+        match found {
+            Ok(true) => {
+                if self.is_paused() {
+                    self.paused_at = Some(Instant::now());
+                }
+                Ok(true)
+            },
+            // Nothing wanted from the target to the end
+            Ok(false) => {
+                self.clock = clock;
+                self.restore()?;
+                Ok(false)
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    // Back to just after the current record, or the start if there isn't one yet
+    fn restore(&mut self) -> io::Result<()> {
+        self.reader.seek_to(self.position)?;
+        if self.has_current {
+            self.reader.next_record()?;
+        }
+        Ok(())
+    }
+}
+
+fn wanted(packets: &Option<Vec<PacketType>>, record: &CaptureRecord) -> bool {
+    match packets {
+        Some(packets) => record.header().is_ok_and(|header| packets.contains(&header.packet_type)),
+        None => true,
+    }
+}
@@ -0,0 +1,215 @@
+// Keeps recent packets in memory with a limit per packet type, so a long session doesn't grow
+// without bound. Each type has a Retention, e.g. the last 120s of motion but the whole session
+// of lap data. Packets pushed out are handed to the eviction handler if one is set, otherwise
+// dropped. Windows are measured in session_time from the packet headers rather than wall clock,
+// so a recording fed through gives the same result as the live session.
+
+use std::collections::VecDeque;
+use std::mem;
+
+use crate::f1_2019_net::PacketType;
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::CarMotion;
+use crate::f1_2019_net::WeatherForecastSample;
+use crate::f1_2019_net::LapData;
+use crate::f1_2019_net::ParticipantData;
+use crate::f1_2019_net::CarSetupData;
+use crate::f1_2019_net::CarTelemetry;
+use crate::f1_2019_net::CarStatus;
+use crate::f1_2019_net::FinalClassificationData;
+use crate::f1_2019_net::LobbyInfoData;
+
+// Packet type ids run 0 - 9
+pub const NUM_PACKET_TYPES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Retention {
+    All,            // Never evicted
+    Latest(usize),  // Most recent n packets
+    Window(f32),    // Packets within this many seconds of session_time of the newest, from its session
+    Session,        // Packets from the same session as the newest
+}
+
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    default:    Retention,
+    overrides:  Vec<(PacketType, Retention)>,
+}
+
+impl RetentionPolicy {
+    // Every type kept the same way until overridden with with
+    pub fn new(default: Retention) -> Self {
+        RetentionPolicy { default, overrides: Vec::new() }
+    }
+
+    pub fn with(mut self, packet_type: PacketType, retention: Retention) -> Self {
+        self.overrides.retain(|(overridden, _)| *overridden != packet_type);
+        self.overrides.push((packet_type, retention));
+        self
+    }
+
+    pub fn get(&self, packet_type: PacketType) -> Retention {
+        self.overrides.iter()
+            .find(|(overridden, _)| *overridden == packet_type)
+            .map_or(self.default, |(_, retention)| *retention)
+    }
+}
+
+// Two minutes of the 20-60Hz packets, the latest session data and the rest for the session
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy::new(Retention::Session)
+            .with(PacketType::Motion, Retention::Window(120.0))
+            .with(PacketType::Telemetry, Retention::Window(120.0))
+            .with(PacketType::CarStatus, Retention::Window(120.0))
+            .with(PacketType::Session, Retention::Latest(1))
+    }
+}
+
+// Rough memory held, the packets themselves plus their per car arrays
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub packets:    usize,
+    pub bytes:      usize,
+}
+
+impl std::ops::Add for MemoryUsage {
+    type Output = MemoryUsage;
+
+    fn add(self, other: MemoryUsage) -> MemoryUsage {
+        MemoryUsage { packets: self.packets + other.packets, bytes: self.bytes + other.bytes }
+    }
+}
+
+pub struct PacketStore {
+    policy:     RetentionPolicy,
+    queues:     [VecDeque<Packet>; NUM_PACKET_TYPES],   // Indexed by packet type id, oldest first
+    on_evict:   Option<Box<dyn FnMut(Packet)>>,
+}
+
+impl PacketStore {
+    pub fn new(policy: RetentionPolicy) -> Self {
+        PacketStore {
+            policy,
+            queues: Default::default(),
+            on_evict: None,
+        }
+    }
+
+    // Called with each packet as it's evicted, e.g. to write it out before it's dropped
+    pub fn set_evict_handler<F>(&mut self, handler: F)
+        where F: FnMut(Packet) + 'static {
+        self.on_evict = Some(Box::new(handler));
+    }
+
+    pub fn policy(&self) -> &RetentionPolicy {
+        &self.policy
+    }
+
+    // Adds a packet then evicts whatever its type's retention no longer covers
+    pub fn push(&mut self, packet: Packet) {
+        let index = queue_index(packet.packet_type());
+        self.queues[index].push_back(packet);
+        self.evict(index);
+    }
+
+    // Oldest first
+    pub fn packets(&self, packet_type: PacketType) -> impl Iterator<Item = &Packet> {
+        self.queue(packet_type).into_iter().flatten()
+    }
+
+    pub fn latest(&self, packet_type: PacketType) -> Option<&Packet> {
+        self.queue(packet_type).and_then(VecDeque::back)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+
+    pub fn memory_usage(&self, packet_type: PacketType) -> MemoryUsage {
+        match self.queue(packet_type) {
+            Some(queue) => MemoryUsage {
+                packets: queue.len(),
+                bytes: queue.capacity()*mem::size_of::<Packet>() + queue.iter().map(heap_size).sum::<usize>(),
+            },
+            None => MemoryUsage::default(),
+        }
+    }
+
+    pub fn total_memory_usage(&self) -> MemoryUsage {
+        (0..NUM_PACKET_TYPES as u8)
+            .filter_map(|id| crate::parser::get_packet_type(id).ok())
+            .map(|packet_type| self.memory_usage(packet_type))
+            .fold(MemoryUsage::default(), |total, usage| total + usage)
+    }
+
+    // Evicts every packet, oldest first by type, e.g. at the end of a session
+    pub fn drain(&mut self) {
+        for index in 0..NUM_PACKET_TYPES {
+            while let Some(packet) = self.queues[index].pop_front() {
+                self.evicted(packet);
+            }
+        }
+    }
+
+    fn queue(&self, packet_type: PacketType) -> Option<&VecDeque<Packet>> {
+        match packet_type {
+            PacketType::InvalidPacket => None,
+            _ => Some(&self.queues[queue_index(packet_type)]),
+        }
+    }
+
+    fn evict(&mut self, index: usize) {
+        while let Some(oldest) = self.queues[index].front() {
+            let newest = self.queues[index].back().expect("queue has a front").header();
+            let expired = match self.policy.get(oldest.packet_type()) {
+                Retention::All => false,
+                Retention::Latest(count) => self.queues[index].len() > count,
+                Retention::Window(seconds) => oldest.header().session_id != newest.session_id
+                    || oldest.header().session_time < newest.session_time - seconds,
+                Retention::Session => oldest.header().session_id != newest.session_id,
+            };
+            if !expired {
+                break;
+            }
+            let packet = self.queues[index].pop_front().expect("queue has a front");
+            self.evicted(packet);
+        }
+    }
+
+    fn evicted(&mut self, packet: Packet) {
+        if let Some(on_evict) = self.on_evict.as_mut() {
+            on_evict(packet);
+        }
+    }
+}
+
+impl Default for PacketStore {
+    fn default() -> Self {
+        PacketStore::new(RetentionPolicy::default())
+    }
+}
+
+fn queue_index(packet_type: PacketType) -> usize {
+    packet_type.id() as usize
+}
+
+// Bytes held by a packet's per car arrays
+fn heap_size(packet: &Packet) -> usize {
+    match packet {
+        Packet::Motion(p)       => p.car_motion_data.capacity()*mem::size_of::<CarMotion>(),
+        Packet::Session(p)      => p.weather_forecast_samples.capacity()*mem::size_of::<WeatherForecastSample>(),
+        Packet::Lap(p)          => p.lap_data.capacity()*mem::size_of::<LapData>(),
+        Packet::Event(_)        => 0,
+        Packet::Participants(p) => p.participant_data.capacity()*mem::size_of::<ParticipantData>(),
+        Packet::CarSetups(p)    => p.car_setups.capacity()*mem::size_of::<CarSetupData>(),
+        Packet::Telemetry(p)    => p.car_telemetry_data.capacity()*mem::size_of::<CarTelemetry>(),
+        Packet::CarStatus(p)    => p.car_status_data.capacity()*mem::size_of::<CarStatus>(),
+        Packet::FinalClassification(p) => p.classification_data.capacity()*mem::size_of::<FinalClassificationData>(),
+        Packet::LobbyInfo(p)    => p.lobby_players.capacity()*mem::size_of::<LobbyInfoData>(),
+    }
+}
@@ -0,0 +1,126 @@
+// Somewhere for decoded packets to go. A PacketSink gets a call per packet type, each defaulting
+// to doing nothing so a sink only implements the ones it cares about, with on_header called
+// first for every packet. Sinks wanting the whole packet whatever its type can override
+// on_packet instead. A Dispatcher holds the sinks set up at startup and hands each packet to
+// every one of them in the order they were added.
+
+use std::io;
+
+use crate::f1_2019_net::Packet;
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::MotionData;
+use crate::f1_2019_net::SessionData;
+use crate::f1_2019_net::Lap;
+use crate::f1_2019_net::Event;
+use crate::f1_2019_net::Participants;
+use crate::f1_2019_net::CarSetups;
+use crate::f1_2019_net::Telemetry;
+use crate::f1_2019_net::CarStatusData;
+use crate::f1_2019_net::FinalClassification;
+use crate::f1_2019_net::LobbyInfo;
+
+pub trait PacketSink {
+    fn on_header(&mut self, _header: &PacketHeader) -> io::Result<()> { Ok(()) }
+    fn on_motion(&mut self, _packet: &MotionData) -> io::Result<()> { Ok(()) }
+    fn on_session(&mut self, _packet: &SessionData) -> io::Result<()> { Ok(()) }
+    fn on_lap(&mut self, _packet: &Lap) -> io::Result<()> { Ok(()) }
+    fn on_event(&mut self, _packet: &Event) -> io::Result<()> { Ok(()) }
+    fn on_participants(&mut self, _packet: &Participants) -> io::Result<()> { Ok(()) }
+    fn on_car_setups(&mut self, _packet: &CarSetups) -> io::Result<()> { Ok(()) }
+    fn on_telemetry(&mut self, _packet: &Telemetry) -> io::Result<()> { Ok(()) }
+    fn on_car_status(&mut self, _packet: &CarStatusData) -> io::Result<()> { Ok(()) }
+    fn on_final_classification(&mut self, _packet: &FinalClassification) -> io::Result<()> { Ok(()) }
+    fn on_lobby_info(&mut self, _packet: &LobbyInfo) -> io::Result<()> { Ok(()) }
+
+    // on_header then the call for the packet's type
+    fn on_packet(&mut self, packet: &Packet) -> io::Result<()> {
+        self.on_header(packet.header())?;
+        match packet {
+            Packet::Motion(p)       => self.on_motion(p),
+            Packet::Session(p)      => self.on_session(p),
+            Packet::Lap(p)          => self.on_lap(p),
+            Packet::Event(p)        => self.on_event(p),
+            Packet::Participants(p) => self.on_participants(p),
+            Packet::CarSetups(p)    => self.on_car_setups(p),
+            Packet::Telemetry(p)    => self.on_telemetry(p),
+            Packet::CarStatus(p)    => self.on_car_status(p),
+            Packet::FinalClassification(p) => self.on_final_classification(p),
+            Packet::LobbyInfo(p)    => self.on_lobby_info(p),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+
+    // No more packets are coming. Flushes by default
+    fn close(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+#[derive(Default)]
+pub struct Dispatcher {
+    sinks: Vec<Box<dyn PacketSink>>,
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Dispatcher::default()
+    }
+
+    pub fn add<S: PacketSink + 'static>(&mut self, sink: S) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    pub fn with<S: PacketSink + 'static>(mut self, sink: S) -> Self {
+        self.add(sink);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    // Every sink gets the packet even if an earlier one fails, the first error is returned
+    pub fn dispatch(&mut self, packet: &Packet) -> io::Result<()> {
+        self.each(|sink| sink.on_packet(packet))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.each(|sink| sink.flush())
+    }
+
+    pub fn close(&mut self) -> io::Result<()> {
+        self.each(|sink| sink.close())
+    }
+
+    fn each<F>(&mut self, mut call: F) -> io::Result<()>
+        where F: FnMut(&mut dyn PacketSink) -> io::Result<()> {
+        let mut result = Ok(());
+        for sink in self.sinks.iter_mut() {
+            let sink_result = call(sink.as_mut());
+            if result.is_ok() {
+                result = sink_result;
+            }
+        }
+        result
+    }
+}
+
+// A dispatcher is itself a sink so one can be registered inside another
+impl PacketSink for Dispatcher {
+    fn on_packet(&mut self, packet: &Packet) -> io::Result<()> {
+        self.dispatch(packet)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Dispatcher::flush(self)
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        Dispatcher::close(self)
+    }
+}
@@ -0,0 +1,145 @@
+// Where datagrams come from. A PacketSource yields each datagram with when it was received, in
+// microseconds since the unix epoch, so the same code can run on packets live from the game, from
+// a recording or from memory. Sources read one datagram at a time into their own buffer, the
+// slice handed back is only good until the next call.
+
+use std::io;
+use std::net::SocketAddr;
+use std::net::ToSocketAddrs;
+use std::net::UdpSocket;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::capture::micros_since_epoch;
+
+// Largest UDP payload. Anything can turn up on the port, reading into a buffer of this size means
+// a datagram longer than any packet is still recorded whole rather than cut short.
+pub const MAX_DATAGRAM_SIZE: usize = 65535;
+
+pub trait PacketSource {
+    // Next datagram and when it was received, None once there are no more
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>>;
+
+    // Who sent the last datagram, if the source knows
+    fn source_address(&self) -> Option<SocketAddr> {
+        None
+    }
+}
+
+// Datagrams live from the game, stamped as they arrive. Never runs out, a read timeout if set
+// comes back as a WouldBlock or TimedOut error, see is_timeout.
+pub struct UdpSource {
+    socket: UdpSocket,
+    buf:    Vec<u8>,
+    source: Option<SocketAddr>,
+}
+
+impl UdpSource {
+    pub fn new(socket: UdpSocket) -> Self {
+        UdpSource { socket, buf: vec![0u8; MAX_DATAGRAM_SIZE], source: None }
+    }
+
+    pub fn bind<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        Ok(UdpSource::new(UdpSocket::bind(address)?))
+    }
+
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.socket.set_read_timeout(timeout)
+    }
+
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+}
+
+impl PacketSource for UdpSource {
+    // Windows reports an earlier send being refused on the next recv, those are skipped over.
+    // Any other error is returned.
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        loop {
+            match self.socket.recv_from(&mut self.buf) {
+                Ok((num_bytes, source)) => {
+                    self.source = Some(source);
+                    return Ok(Some((micros_since_epoch(SystemTime::now()), &self.buf[..num_bytes])));
+                },
+                Err(e) if is_refused(&e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn source_address(&self) -> Option<SocketAddr> {
+        self.source
+    }
+}
+
+// Whether an error from a source with a read timeout is just the timeout
+pub fn is_timeout(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut
+}
+
+fn is_refused(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::ConnectionReset || e.kind() == io::ErrorKind::ConnectionRefused
+}
+
+// Datagrams held in memory, e.g. for tests or packets gathered elsewhere
+#[derive(Debug, Default, Clone)]
+pub struct MemorySource {
+    datagrams:  Vec<(u64, Vec<u8>)>,
+    next:       usize,
+}
+
+impl MemorySource {
+    pub fn new(datagrams: Vec<(u64, Vec<u8>)>) -> Self {
+        MemorySource { datagrams, next: 0 }
+    }
+
+    pub fn push(&mut self, received_us: u64, datagram: Vec<u8>) {
+        self.datagrams.push((received_us, datagram));
+    }
+
+    // Starts again from the first datagram
+    pub fn rewind(&mut self) {
+        self.next = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.datagrams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.datagrams.is_empty()
+    }
+}
+
+impl PacketSource for MemorySource {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        match self.datagrams.get(self.next) {
+            Some((received_us, datagram)) => {
+                self.next += 1;
+                Ok(Some((*received_us, datagram)))
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+impl<S: PacketSource + ?Sized> PacketSource for Box<S> {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        (**self).next_datagram()
+    }
+
+    fn source_address(&self) -> Option<SocketAddr> {
+        (**self).source_address()
+    }
+}
+
+impl<S: PacketSource + ?Sized> PacketSource for &mut S {
+    fn next_datagram(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        (**self).next_datagram()
+    }
+
+    fn source_address(&self) -> Option<SocketAddr> {
+        (**self).source_address()
+    }
+}
@@ -0,0 +1,89 @@
+// Packet counts for the stats command, by type, with what couldn't be parsed and how long the
+// datagrams span by receive time.
+
+use std::io;
+use std::io::Write;
+
+use crate::f1_2019_net::PacketHeader;
+use crate::f1_2019_net::PacketType;
+use crate::ParseError;
+
+use crate::cli::packet_type_key;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct TypeCount {
+    packets:    u64,
+    bytes:      u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    by_type:        Vec<(PacketType, TypeCount)>,   // In order first seen
+    bad_packets:    u64,
+    bad_bytes:      u64,
+    sessions:       Vec<u64>,
+    earliest_us:    Option<u64>,    // Receive times can go backwards, e.g. captures merged out of order
+    latest_us:      u64,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats::default()
+    }
+
+    // Counts a datagram given the result of parse_header on it
+    pub fn add(&mut self, received_us: u64, len: usize, header: Result<&PacketHeader, &ParseError>) {
+        let earliest_us = self.earliest_us.get_or_insert(received_us);
+        *earliest_us = (*earliest_us).min(received_us);
+        self.latest_us = self.latest_us.max(received_us);
+        let header = match header {
+            Ok(header) => header,
+            Err(_) => {
+                self.bad_packets += 1;
+                self.bad_bytes += len as u64;
+                return;
+            },
+        };
+        if !self.sessions.contains(&header.session_id) {
+            self.sessions.push(header.session_id);
+        }
+        let count = match self.by_type.iter_mut().find(|(packet_type, _)| *packet_type == header.packet_type) {
+            Some((_, count)) => count,
+            None => {
+                self.by_type.push((header.packet_type, TypeCount::default()));
+                &mut self.by_type.last_mut().unwrap().1
+            },
+        };
+        count.packets += 1;
+        count.bytes += len as u64;
+    }
+
+    // Seconds between the earliest and latest datagram
+    pub fn duration(&self) -> f64 {
+        match self.earliest_us {
+            Some(earliest_us) => (self.latest_us - earliest_us) as f64 / 1_000_000.0,
+            None => 0.0,
+        }
+    }
+
+    pub fn write(&self, out: &mut dyn Write) -> io::Result<()> {
+        let duration = self.duration();
+        let rate = |count: u64| if duration > 0.0 { count as f64 / duration } else { 0.0 };
+        writeln!(out, "{:<14} {:>10} {:>12} {:>10}", "type", "packets", "bytes", "per sec")?;
+        let mut by_type = self.by_type.clone();
+        by_type.sort_by_key(|(packet_type, _)| packet_type.id());
+        let mut total = TypeCount::default();
+        for (packet_type, count) in by_type.iter() {
+            writeln!(out, "{:<14} {:>10} {:>12} {:>10.1}", packet_type_key(*packet_type), count.packets, count.bytes, rate(count.packets))?;
+            total.packets += count.packets;
+            total.bytes += count.bytes;
+        }
+        if self.bad_packets > 0 {
+            writeln!(out, "{:<14} {:>10} {:>12} {:>10.1}", "unparsable", self.bad_packets, self.bad_bytes, rate(self.bad_packets))?;
+            total.packets += self.bad_packets;
+            total.bytes += self.bad_bytes;
+        }
+        writeln!(out, "{:<14} {:>10} {:>12} {:>10.1}", "total", total.packets, total.bytes, rate(total.packets))?;
+        writeln!(out, "{} session(s) over {:.1}s", self.sessions.len(), duration)
+    }
+}
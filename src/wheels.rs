@@ -0,0 +1,161 @@
+// One value per wheel. Packets send per wheel values as four in a row in the order rear left,
+// rear right, front left, front right, Wheels keeps that order so it maps straight onto the wire.
+
+use std::ops::Index;
+use std::ops::IndexMut;
+
+use crate::parser::NUM_WHEELS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wheel {
+    RearLeft = 0,
+    RearRight = 1,
+    FrontLeft = 2,
+    FrontRight = 3,
+}
+
+impl Wheel {
+    // Wire order
+    pub const ALL: [Wheel; NUM_WHEELS] = [Wheel::RearLeft, Wheel::RearRight, Wheel::FrontLeft, Wheel::FrontRight];
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wheels<T>([T; NUM_WHEELS]);
+
+impl<T> Wheels<T> {
+    pub fn new(rear_left: T, rear_right: T, front_left: T, front_right: T) -> Self {
+        Wheels([rear_left, rear_right, front_left, front_right])
+    }
+
+    pub fn as_array(&self) -> &[T; NUM_WHEELS] {
+        &self.0
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.0.iter_mut()
+    }
+
+    // Same wheel order, each value converted
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> Wheels<U> {
+        Wheels(self.0.map(f))
+    }
+}
+
+impl<T: Copy> Wheels<T> {
+    pub fn rear_left(&self) -> T {
+        self.0[Wheel::RearLeft as usize]
+    }
+
+    pub fn rear_right(&self) -> T {
+        self.0[Wheel::RearRight as usize]
+    }
+
+    pub fn front_left(&self) -> T {
+        self.0[Wheel::FrontLeft as usize]
+    }
+
+    pub fn front_right(&self) -> T {
+        self.0[Wheel::FrontRight as usize]
+    }
+
+    // Pairs are [left, right] for an axle and [rear, front] for a side
+    pub fn front(&self) -> [T; 2] {
+        [self.front_left(), self.front_right()]
+    }
+
+    pub fn rear(&self) -> [T; 2] {
+        [self.rear_left(), self.rear_right()]
+    }
+
+    pub fn left(&self) -> [T; 2] {
+        [self.rear_left(), self.front_left()]
+    }
+
+    pub fn right(&self) -> [T; 2] {
+        [self.rear_right(), self.front_right()]
+    }
+}
+
+impl<T: Copy + PartialOrd> Wheels<T> {
+    pub fn min(&self) -> T {
+        self.0.iter().copied().fold(self.0[0], |min, value| if value < min { value } else { min })
+    }
+
+    pub fn max(&self) -> T {
+        self.0.iter().copied().fold(self.0[0], |max, value| if value > max { value } else { max })
+    }
+}
+
+impl<T: Copy + Into<f32>> Wheels<T> {
+    pub fn mean(&self) -> f32 {
+        mean(&self.0)
+    }
+
+    pub fn front_mean(&self) -> f32 {
+        mean(&self.front())
+    }
+
+    pub fn rear_mean(&self) -> f32 {
+        mean(&self.rear())
+    }
+
+    pub fn left_mean(&self) -> f32 {
+        mean(&self.left())
+    }
+
+    pub fn right_mean(&self) -> f32 {
+        mean(&self.right())
+    }
+}
+
+fn mean<T: Copy + Into<f32>>(values: &[T]) -> f32 {
+    values.iter().map(|value| (*value).into()).sum::<f32>() / values.len() as f32
+}
+
+impl<T> From<[T; NUM_WHEELS]> for Wheels<T> {
+    fn from(array: [T; NUM_WHEELS]) -> Self {
+        Wheels(array)
+    }
+}
+
+impl<T> From<Wheels<T>> for [T; NUM_WHEELS] {
+    fn from(wheels: Wheels<T>) -> Self {
+        wheels.0
+    }
+}
+
+impl<T> Index<Wheel> for Wheels<T> {
+    type Output = T;
+
+    fn index(&self, wheel: Wheel) -> &T {
+        &self.0[wheel as usize]
+    }
+}
+
+impl<T> IndexMut<Wheel> for Wheels<T> {
+    fn index_mut(&mut self, wheel: Wheel) -> &mut T {
+        &mut self.0[wheel as usize]
+    }
+}
+
+impl<T> IntoIterator for Wheels<T> {
+    type Item = T;
+    type IntoIter = std::array::IntoIter<T, NUM_WHEELS>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.0)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Wheels<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
@@ -0,0 +1,189 @@
+// Checks archives give back every datagram byte for byte and the packets in them field for field,
+// across chunks and when not finished, and come out smaller than a compressed capture of the same
+// session. Editions without columns are kept as received.
+
+mod common;
+
+use std::io::Cursor;
+
+use f12019_data_logger::archive::ArchiveReader;
+use f12019_data_logger::archive::ArchiveWriter;
+use f12019_data_logger::archive::CHUNK_DATAGRAMS;
+use f12019_data_logger::archive::CHUNK_SPAN_US;
+use f12019_data_logger::capture::CaptureWriter;
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::parser::f1_2020;
+use f12019_data_logger::parser::parse_packet;
+use f12019_data_logger::source::PacketSource;
+use f12019_data_logger::Encode;
+use f12019_data_logger::PacketFormat;
+
+use common::header;
+use common::SESSION_ID;
+use common::SESSION_START_US;
+
+// Lap, telemetry and status from the shared session with an event now and then and the odd
+// datagram that isn't a packet
+fn session(frames: u32) -> Vec<(u64, Vec<u8>)> {
+    let mut datagrams = Vec::new();
+    for (frame, packets) in (0..frames).zip(common::session(frames).chunks(4)) {
+        let received_us = packets[0].0;
+        datagrams.extend_from_slice(&packets[1..]);
+        if frame % 100 == 0 {
+            let event = Event { header: header(PacketType::Event, SESSION_ID, frame as f32 / 60.0, frame), event_type: EventType::DrsEnabled };
+            datagrams.push((received_us + 400, event.to_bytes().unwrap()));
+        }
+        if frame % 250 == 7 {
+            datagrams.push((received_us + 500, b"not a packet".to_vec()));
+        }
+    }
+    datagrams
+}
+
+// 2020 motion, lap, telemetry and status at 60 Hz, each byte counting up a frame at a time past
+// a valid header
+fn session_2020(frames: u32) -> Vec<(u64, Vec<u8>)> {
+    let mut datagrams = Vec::new();
+    for frame in 0..frames {
+        let received_us = SESSION_START_US + frame as u64 * 16_667;
+        for (n, &packet_type) in [PacketType::Motion, PacketType::Lap, PacketType::Telemetry, PacketType::CarStatus].iter().enumerate() {
+            let size = PacketFormat::F2020.packet_size(packet_type).unwrap();
+            let mut datagram: Vec<u8> = (0..size).map(|i| ((i as u32 + frame) % 251) as u8).collect();
+            datagram[..2].copy_from_slice(&2020u16.to_le_bytes());
+            datagram[f1_2020::PACKET_TYPE_OFFSET] = packet_type.id();
+            datagrams.push((received_us + n as u64 * 100, datagram));
+        }
+    }
+    datagrams
+}
+
+fn archive(datagrams: &[(u64, Vec<u8>)]) -> Vec<u8> {
+    let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+    for (received_us, datagram) in datagrams {
+        writer.write(*received_us, datagram).unwrap();
+    }
+    writer.finish().unwrap();
+    writer.into_inner()
+}
+
+fn read_back(archive: Vec<u8>) -> Vec<(u64, Vec<u8>)> {
+    let mut reader = ArchiveReader::new(Cursor::new(archive)).unwrap();
+    let mut datagrams = Vec::new();
+    while let Some((received_us, datagram)) = reader.next_datagram().unwrap() {
+        datagrams.push((received_us, datagram.to_vec()));
+    }
+    datagrams
+}
+
+#[test]
+fn datagrams_come_back_as_recorded() {
+    let datagrams = session(200);
+    let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+    for (received_us, datagram) in &datagrams {
+        writer.write(*received_us, datagram).unwrap();
+    }
+    writer.finish().unwrap();
+    assert_eq!(writer.columnar_count(), 3 * 200);
+    assert_eq!(read_back(writer.into_inner()), datagrams);
+
+    assert_eq!(read_back(archive(&[])), Vec::new());
+}
+
+#[test]
+fn packets_come_back_field_for_field() {
+    let datagrams = session(50);
+    let mut reader = ArchiveReader::new(Cursor::new(archive(&datagrams))).unwrap();
+    // Ones that aren't packets are skipped, Debug prints NaN so it compares
+    let expected: Vec<String> = datagrams.iter().filter_map(|(_, datagram)| parse_packet(datagram).ok()).map(|packet| format!("{:?}", packet)).collect();
+    let mut packets = Vec::new();
+    while let Some((_, packet)) = reader.next_packet().unwrap() {
+        packets.push(format!("{:?}", packet));
+    }
+    assert_eq!(packets, expected);
+}
+
+#[test]
+fn reads_across_chunks() {
+    // Chunks ending on time, then on a chunk's worth of datagrams received together
+    let mut datagrams = session(20 * 60);
+    let (last_us, _) = datagrams[datagrams.len() - 1];
+    datagrams.extend((0..CHUNK_DATAGRAMS + 10).map(|n| (last_us + 1000, (n as u32).to_le_bytes().to_vec())));
+    assert_eq!(read_back(archive(&datagrams)), datagrams);
+}
+
+#[test]
+fn recording_stopped_without_finishing_keeps_all_but_the_last_seconds() {
+    let datagrams = session(12 * 60);
+    let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+    for (received_us, datagram) in &datagrams {
+        writer.write(*received_us, datagram).unwrap();
+    }
+    // As left by a record command that was killed
+    let read = read_back(writer.into_inner());
+    let (last_us, _) = datagrams[datagrams.len() - 1];
+    assert!(!read.is_empty());
+    assert_eq!(read[..], datagrams[..read.len()]);
+    assert!(datagrams[read.len()].0 + CHUNK_SPAN_US > last_us);
+}
+
+#[test]
+fn smaller_than_a_compressed_capture() {
+    let datagrams = session(600);
+    let mut capture = CaptureWriter::compressed(Vec::new()).unwrap();
+    for (received_us, datagram) in &datagrams {
+        capture.write(*received_us, None, datagram).unwrap();
+    }
+    capture.finish().unwrap();
+    let capture = capture.into_inner();
+    let archive = archive(&datagrams);
+    assert!(archive.len() * 4 < capture.len(), "archive {} bytes, compressed capture {}", archive.len(), capture.len());
+}
+
+#[test]
+fn rejects_what_is_not_an_archive() {
+    let file = archive(&session(5));
+    assert!(ArchiveReader::new(Cursor::new(&file[..10])).is_err());
+
+    let mut bad_magic = file.clone();
+    bad_magic[0] = b'X';
+    assert!(ArchiveReader::new(Cursor::new(bad_magic)).is_err());
+
+    let mut truncated = ArchiveReader::new(Cursor::new(&file[..file.len() - 1])).unwrap();
+    assert!(truncated.next_datagram().is_err());
+
+    // A compressed length no chunk could need, 4 GiB if read in
+    let mut too_long = file.clone();
+    too_long[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(ArchiveReader::new(Cursor::new(too_long)).unwrap().next_datagram().is_err());
+
+    // The chunk's decompressed length one short
+    let mut wrong_length = file;
+    wrong_length[20] = wrong_length[20].wrapping_sub(1);
+    assert!(ArchiveReader::new(Cursor::new(wrong_length)).unwrap().next_datagram().is_err());
+}
+
+#[test]
+fn other_editions_are_kept_as_received() {
+    let mut datagrams = session(50);
+    datagrams.extend(session_2020(50));
+    let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+    for (received_us, datagram) in &datagrams {
+        writer.write(*received_us, datagram).unwrap();
+    }
+    writer.finish().unwrap();
+    // Only the 2019 packets go in columns
+    assert_eq!(writer.columnar_count(), 3 * 50);
+    let archive = writer.into_inner();
+    assert_eq!(read_back(archive.clone()), datagrams);
+
+    let mut reader = ArchiveReader::new(Cursor::new(archive)).unwrap();
+    let expected: Vec<String> = datagrams.iter().filter_map(|(_, datagram)| parse_packet(datagram).ok()).map(|packet| format!("{:?}", packet)).collect();
+    assert_eq!(expected.len(), 3 * 50 + 1 + 4 * 50);
+    let mut packets = Vec::new();
+    while let Some((_, packet)) = reader.next_packet().unwrap() {
+        packets.push(format!("{:?}", packet));
+    }
+    assert_eq!(packets, expected);
+}
@@ -0,0 +1,81 @@
+// Checks button presses and releases are picked out between two button states, with buttons
+// held across both reported as neither, and that names and combinations follow the appendix.
+
+use f12019_data_logger::f1_2019_net::ButtonEvent;
+use f12019_data_logger::f1_2019_net::ButtonStatus;
+use f12019_data_logger::f1_2019_net::Telemetry;
+
+fn telemetry(button_status: ButtonStatus) -> Telemetry {
+    Telemetry {
+        header: Default::default(),
+        car_telemetry_data: Vec::new(),
+        button_status,
+        mfd_panel_index: 255,
+        mfd_panel_index_secondary: 255,
+        suggested_gear: 0,
+    }
+}
+
+#[test]
+fn pressed_released_and_held() {
+    let before = ButtonStatus::CROSS | ButtonStatus::L1;
+    let after = ButtonStatus::CROSS | ButtonStatus::R2;
+
+    // Cross is held in both so it's neither pressed nor released
+    assert_eq!(after.pressed_since(before), ButtonStatus::R2);
+    assert_eq!(after.released_since(before), ButtonStatus::L1);
+    assert_eq!(before.pressed_since(before), ButtonStatus::NONE);
+    assert_eq!(before.released_since(before), ButtonStatus::NONE);
+
+    // From nothing everything is pressed, to nothing everything is released
+    assert_eq!(after.pressed_since(ButtonStatus::NONE), after);
+    assert_eq!(ButtonStatus::NONE.released_since(after), after);
+    assert!(ButtonStatus::NONE.pressed_since(after).is_empty());
+}
+
+#[test]
+fn changes_presses_first_in_bit_order() {
+    let before = ButtonStatus::TRIANGLE | ButtonStatus::DPAD_UP | ButtonStatus::OPTIONS;
+    let after = ButtonStatus::CROSS | ButtonStatus::OPTIONS | ButtonStatus::RIGHT_STICK_CLICK;
+    let changes: Vec<ButtonEvent> = after.changes_since(before).collect();
+    assert_eq!(changes, [
+        ButtonEvent::Pressed(ButtonStatus::CROSS),
+        ButtonEvent::Pressed(ButtonStatus::RIGHT_STICK_CLICK),
+        ButtonEvent::Released(ButtonStatus::TRIANGLE),
+        ButtonEvent::Released(ButtonStatus::DPAD_UP),
+    ]);
+    assert_eq!(after.changes_since(after).count(), 0);
+}
+
+#[test]
+fn unlisted_bits_make_no_events() {
+    // Bit 15 up isn't in the appendix
+    let before = ButtonStatus(0x8000);
+    let after = ButtonStatus(0x1_0000) | ButtonStatus::SQUARE;
+    assert_eq!(after.pressed_since(before).bits(), 0x1_0008);
+    assert_eq!(after.changes_since(before).collect::<Vec<_>>(), [ButtonEvent::Pressed(ButtonStatus::SQUARE)]);
+}
+
+#[test]
+fn between_telemetry_packets() {
+    let first = telemetry(ButtonStatus::NONE);
+    let second = telemetry(ButtonStatus::A | ButtonStatus::RT);
+    let third = telemetry(ButtonStatus::RT);
+
+    let pressed: Vec<ButtonEvent> = second.button_changes(&first).collect();
+    assert_eq!(pressed, [ButtonEvent::Pressed(ButtonStatus::A), ButtonEvent::Pressed(ButtonStatus::RT)]);
+    let released: Vec<ButtonEvent> = third.button_changes(&second).collect();
+    assert_eq!(released, [ButtonEvent::Released(ButtonStatus::A)]);
+    assert_eq!(third.button_changes(&third).count(), 0);
+}
+
+#[test]
+fn names_and_combinations() {
+    assert_eq!(ButtonStatus::A, ButtonStatus::CROSS);
+    assert_eq!(ButtonStatus::CROSS.name(), Some("Cross or A"));
+    assert_eq!((ButtonStatus::CROSS | ButtonStatus::L2).name(), None);
+    assert!((ButtonStatus::CROSS | ButtonStatus::L2).contains(ButtonStatus::L2));
+    assert!(!ButtonStatus::CROSS.contains(ButtonStatus::CROSS | ButtonStatus::L2));
+    assert_eq!((ButtonStatus::CROSS | ButtonStatus::L2).to_string(), "Cross or A, L2 or LT");
+    assert_eq!(ButtonStatus::NONE.to_string(), "none");
+}
@@ -0,0 +1,125 @@
+// Checks the capture file layout matches what capture.rs documents, that records come back with
+// their receive time and source address, and that the reader refuses what isn't a capture.
+
+use std::io::Cursor;
+use std::net::SocketAddr;
+
+use f12019_data_logger::capture::CaptureReader;
+use f12019_data_logger::capture::CaptureWriter;
+use f12019_data_logger::capture::FILE_HEADER_SIZE;
+use f12019_data_logger::capture::MAGIC;
+use f12019_data_logger::capture::RECORD_HEADER_SIZE;
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::PacketHeader;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::source::PacketSource;
+use f12019_data_logger::Encode;
+use f12019_data_logger::Packet;
+
+fn event_datagram(frame_id: u32) -> Vec<u8> {
+    let header = PacketHeader { packet_format: 2019, packet_version: 1, packet_type: PacketType::Event, session_id: 7, frame_id, ..PacketHeader::default() };
+    Event { header, event_type: EventType::FastestLap { vehicle_idx: 3, lap_time: 81.5 } }.to_bytes().unwrap()
+}
+
+fn capture(records: &[(u64, Option<SocketAddr>, Vec<u8>)]) -> Vec<u8> {
+    let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+    for (received_us, source, datagram) in records {
+        writer.write(*received_us, *source, datagram).unwrap();
+    }
+    assert_eq!(writer.position() as usize, writer.get_ref().len());
+    writer.into_inner()
+}
+
+#[test]
+fn layout_matches_the_documented_format() {
+    let source: SocketAddr = "192.168.1.20:50123".parse().unwrap();
+    let file = capture(&[(0x0102_0304_0506_0708, Some(source), vec![0xaa, 0xbb])]);
+    assert_eq!(file.len(), FILE_HEADER_SIZE + RECORD_HEADER_SIZE + 2);
+    assert_eq!(&file[..8], &MAGIC);
+    assert_eq!(&file[8..12], &[1, 0, 16, 0]);
+    assert_eq!(&file[12..16], &[0, 0, 0, 0]);
+
+    let record = &file[FILE_HEADER_SIZE..];
+    assert_eq!(&record[..8], &[8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(record[8], 4);
+    assert_eq!(&record[9..13], &[192, 168, 1, 20]);
+    assert!(record[13..25].iter().all(|b| *b == 0));
+    assert_eq!(&record[25..27], &50123u16.to_le_bytes());
+    assert_eq!(&record[27..29], &[2, 0]);
+    assert_eq!(&record[29..], &[0xaa, 0xbb]);
+}
+
+#[test]
+fn records_round_trip_with_their_source() {
+    let records = vec![
+        (1_000, Some("10.0.0.2:20777".parse().unwrap()), event_datagram(1)),
+        (2_000, Some("[fe80::1]:20777".parse().unwrap()), event_datagram(2)),
+        (3_000, None, vec![1, 2, 3]),
+    ];
+    let mut reader = CaptureReader::new(Cursor::new(capture(&records))).unwrap();
+    assert_eq!(reader.version(), 1);
+    for (received_us, source, datagram) in records.iter() {
+        let record = reader.next_record().unwrap().unwrap();
+        assert_eq!(record.received_us, *received_us);
+        assert_eq!(record.source, *source);
+        assert_eq!(record.datagram, &datagram[..]);
+    }
+    assert!(reader.next_record().unwrap().is_none());
+}
+
+#[test]
+fn records_feed_the_parsers() {
+    let file = capture(&[(1, None, event_datagram(42)), (2, None, vec![0; 10])]);
+    let mut reader = CaptureReader::new(Cursor::new(file)).unwrap();
+
+    let record = reader.next_record().unwrap().unwrap();
+    assert_eq!(record.header().unwrap().frame_id, 42);
+    match record.packet().unwrap() {
+        Packet::Event(event) => assert_eq!(event.event_type, EventType::FastestLap { vehicle_idx: 3, lap_time: 81.5 }),
+        packet => panic!("expected an event, got {:?}", packet.packet_type()),
+    }
+
+    // Unparsable datagrams are kept as received
+    let record = reader.next_record().unwrap().unwrap();
+    assert!(record.header().is_err());
+    assert_eq!(record.datagram, &[0; 10]);
+}
+
+#[test]
+fn reader_works_as_a_packet_source() {
+    let source: SocketAddr = "10.0.0.2:20777".parse().unwrap();
+    let file = capture(&[(5, Some(source), event_datagram(1))]);
+    let mut reader: Box<dyn PacketSource> = Box::new(CaptureReader::new(Cursor::new(file)).unwrap());
+    assert_eq!(reader.next_datagram().unwrap().map(|(received_us, _)| received_us), Some(5));
+    assert_eq!(reader.source_address(), Some(source));
+    assert!(reader.next_datagram().unwrap().is_none());
+}
+
+#[test]
+fn longer_headers_are_skipped() {
+    let mut file = capture(&[(9, None, vec![1, 2])]);
+    file[10] = 20;
+    file.splice(FILE_HEADER_SIZE..FILE_HEADER_SIZE, [0xff; 4]);
+    let mut reader = CaptureReader::new(Cursor::new(file)).unwrap();
+    assert_eq!(reader.position(), 20);
+    assert_eq!(reader.next_record().unwrap().unwrap().datagram, &[1, 2]);
+}
+
+#[test]
+fn rejects_what_is_not_a_capture() {
+    let file = capture(&[(1, None, vec![1, 2, 3, 4])]);
+
+    let mut bad_magic = file.clone();
+    bad_magic[0] = b'X';
+    assert!(CaptureReader::new(Cursor::new(bad_magic)).is_err());
+
+    let mut bad_version = file.clone();
+    bad_version[8] = 3;
+    assert!(CaptureReader::new(Cursor::new(bad_version)).is_err());
+
+    assert!(CaptureReader::new(Cursor::new(&file[..10])).is_err());
+
+    let mut truncated = CaptureReader::new(Cursor::new(&file[..file.len() - 1])).unwrap();
+    assert!(truncated.next_record().is_err());
+}
@@ -0,0 +1,158 @@
+// Checks the binary's command line: options and their defaults, values that are refused,
+// combinations a command can't run with, --retain limits and what's typed while replaying.
+
+use std::path::PathBuf;
+
+use f12019_data_logger::cli::parse_args;
+use f12019_data_logger::cli::parse_replay_control;
+use f12019_data_logger::cli::parse_retention;
+use f12019_data_logger::cli::CliError;
+use f12019_data_logger::cli::Command;
+use f12019_data_logger::cli::Options;
+use f12019_data_logger::cli::OutputFormat;
+use f12019_data_logger::cli::ReplayControl;
+use f12019_data_logger::edit::SplitBy;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::replay::SeekTarget;
+use f12019_data_logger::retention::Retention;
+use f12019_data_logger::retention::RetentionPolicy;
+
+fn parse(line: &str) -> Result<Options, CliError> {
+    parse_args(line.split_whitespace().map(str::to_string))
+}
+
+fn invalid(option: &'static str, value: &str) -> CliError {
+    CliError::InvalidValue { option, value: value.to_string() }
+}
+
+#[test]
+fn defaults() {
+    let options = parse("listen").unwrap();
+    assert_eq!(options.command, Command::Listen);
+    assert_eq!(options.bind_address(), "0.0.0.0:20777");
+    assert_eq!(options.format, OutputFormat::Text);
+    assert_eq!(options.input, None);
+    assert_eq!(options.packets, None);
+    assert!(options.wants(PacketType::Motion));
+    assert_eq!(options.speed, 1.0);
+    assert_eq!(options.split_by, SplitBy::Session);
+    assert_eq!(options.replay_target().unwrap(), "127.0.0.1:20777".parse().unwrap());
+    assert_eq!(options.retention.get(PacketType::Motion), Retention::Window(120.0));
+}
+
+#[test]
+fn options_and_values() {
+    let options = parse("export -p 20778 --format csv -o out --packets lap,telemetry --player-only race.cap").unwrap();
+    assert_eq!(options.command, Command::Export);
+    assert_eq!(options.port, 20778);
+    assert_eq!(options.format, OutputFormat::Csv);
+    assert_eq!(options.output, Some(PathBuf::from("out")));
+    assert_eq!(options.input, Some(PathBuf::from("race.cap")));
+    assert_eq!(options.packets, Some(vec![PacketType::Lap, PacketType::Telemetry]));
+    assert!(options.player_only);
+    assert_eq!(options.replay_target().unwrap(), "127.0.0.1:20778".parse().unwrap());
+
+    let options = parse("replay -i race.cap --from-lap 3 --to-lap 5 --speed 2.5 --loop -t 10.0.0.2:9999").unwrap();
+    assert_eq!(options.start, Some(SeekTarget::Lap(3)));
+    // Stopping once lap 5 is finished is stopping when lap 6 starts
+    assert_eq!(options.end, Some(SeekTarget::Lap(6)));
+    assert_eq!(options.speed, 2.5);
+    assert!(options.looped);
+    assert_eq!(options.replay_target().unwrap(), "10.0.0.2:9999".parse().unwrap());
+
+    let options = parse("trim race.cap -o short.cap --from-session 0xDEADbeef --to 90.5").unwrap();
+    assert_eq!(options.start, Some(SeekTarget::Session(0xdead_beef)));
+    assert_eq!(options.end, Some(SeekTarget::SessionTime(90.5)));
+
+    let options = parse("merge a.cap b.cap c.cap -o all.cap").unwrap();
+    assert_eq!(options.input, Some(PathBuf::from("a.cap")));
+    assert_eq!(options.inputs, [PathBuf::from("b.cap"), PathBuf::from("c.cap")]);
+
+    assert_eq!(parse("split race.cap --by lap").unwrap().split_by, SplitBy::Lap);
+}
+
+#[test]
+fn drop_leaves_out_types() {
+    let options = parse("record -o race.cap --drop motion,telemetry").unwrap();
+    let packets = options.packets.unwrap();
+    assert_eq!(packets.len(), 8);
+    assert!(!packets.contains(&PacketType::Motion) && !packets.contains(&PacketType::Telemetry));
+
+    let options = parse("listen --packets lap,motion --drop motion").unwrap();
+    assert_eq!(options.packets, Some(vec![PacketType::Lap]));
+}
+
+#[test]
+fn refused_arguments() {
+    assert_eq!(parse("").unwrap_err(), CliError::MissingCommand);
+    assert_eq!(parse("--help").unwrap_err(), CliError::Help);
+    assert_eq!(parse("listen -h").unwrap_err(), CliError::Help);
+    assert_eq!(parse("play").unwrap_err(), CliError::UnknownCommand("play".to_string()));
+    assert_eq!(parse("listen --verbose").unwrap_err(), CliError::UnknownOption("--verbose".to_string()));
+    assert_eq!(parse("listen --port").unwrap_err(), CliError::MissingValue("--port"));
+    assert_eq!(parse("listen -p 70000").unwrap_err(), invalid("--port", "70000"));
+    assert_eq!(parse("listen -f xml").unwrap_err(), invalid("--format", "xml"));
+    assert_eq!(parse("listen --packets lap,tyres").unwrap_err(), invalid("--packets", "tyres"));
+    assert_eq!(parse("replay a.cap --speed 0").unwrap_err(), invalid("--speed", "0"));
+    assert_eq!(parse("replay a.cap --speed inf").unwrap_err(), invalid("--speed", "inf"));
+    assert_eq!(parse("replay a.cap --to-lap 255").unwrap_err(), invalid("--to-lap", "255"));
+    assert_eq!(parse("replay a.cap -t localhost").unwrap_err(), invalid("--target", "localhost"));
+    assert_eq!(parse("split a.cap --by stint").unwrap_err(), invalid("--by", "stint"));
+    assert_eq!(parse("inspect a.cap b.cap").unwrap_err(), CliError::UnexpectedArgument("b.cap".to_string()));
+}
+
+#[test]
+fn combinations_commands_cant_run() {
+    assert_eq!(parse("replay").unwrap_err(), CliError::MissingInput(Command::Replay));
+    assert_eq!(parse("listen --from 10").unwrap_err(), CliError::MissingInput(Command::Listen));
+    assert_eq!(parse("record").unwrap_err(), CliError::MissingOutput(Command::Record));
+    assert_eq!(parse("merge a.cap -o b.cap").unwrap_err(), CliError::TooFewInputs(Command::Merge));
+    assert_eq!(parse("listen -f pcap").unwrap_err(), invalid("--format", "pcap for listen"));
+    assert_eq!(parse("record -o a.csv -f csv").unwrap_err(), invalid("--format", "csv for record"));
+    assert_eq!(parse("export a.cap -f csv").unwrap_err(), invalid("--format", "csv without --output, the directory to write to"));
+    assert_eq!(parse("record -o a.cap -f archive --compress").unwrap_err(), invalid("--format", "archive with --compress"));
+    assert!(parse("record -o a.cap --compress").is_ok());
+}
+
+#[test]
+fn retention_limits() {
+    let policy = parse_retention(RetentionPolicy::new(Retention::All), "motion=30s, lap=session,event=all,session=2").unwrap();
+    assert_eq!(policy.get(PacketType::Motion), Retention::Window(30.0));
+    assert_eq!(policy.get(PacketType::Lap), Retention::Session);
+    assert_eq!(policy.get(PacketType::Event), Retention::All);
+    assert_eq!(policy.get(PacketType::Session), Retention::Latest(2));
+    // Types not named keep what they had, later limits replace earlier ones
+    assert_eq!(policy.get(PacketType::Telemetry), Retention::All);
+    let policy = parse_retention(policy, "motion=1,").unwrap();
+    assert_eq!(policy.get(PacketType::Motion), Retention::Latest(1));
+
+    let retain = |value: &str| parse_retention(RetentionPolicy::default(), value).unwrap_err();
+    assert_eq!(retain("motion"), invalid("--retain", "motion"));
+    assert_eq!(retain("motion=-5s"), invalid("--retain", "motion=-5s"));
+    assert_eq!(retain("motion=forever"), invalid("--retain", "motion=forever"));
+    assert_eq!(retain("tyres=1"), invalid("--retain", "tyres"));
+
+    let options = parse("listen --retain telemetry=10s").unwrap();
+    assert_eq!(options.retention.get(PacketType::Telemetry), Retention::Window(10.0));
+    assert_eq!(options.retention.get(PacketType::Session), Retention::Latest(1));
+}
+
+#[test]
+fn replay_controls() {
+    assert_eq!(parse_replay_control("pause").unwrap(), ReplayControl::Pause);
+    assert_eq!(parse_replay_control(" p \n").unwrap(), ReplayControl::Pause);
+    assert_eq!(parse_replay_control("resume").unwrap(), ReplayControl::Resume);
+    assert_eq!(parse_replay_control("r").unwrap(), ReplayControl::Resume);
+    assert_eq!(parse_replay_control("seek 95.5").unwrap(), ReplayControl::Seek(SeekTarget::SessionTime(95.5)));
+    assert_eq!(parse_replay_control("lap 4").unwrap(), ReplayControl::Seek(SeekTarget::Lap(4)));
+    assert_eq!(parse_replay_control("session 1f").unwrap(), ReplayControl::Seek(SeekTarget::Session(0x1f)));
+    assert_eq!(parse_replay_control("speed 0.5").unwrap(), ReplayControl::Speed(0.5));
+
+    assert_eq!(parse_replay_control("").unwrap_err(), CliError::UnexpectedArgument(String::new()));
+    assert_eq!(parse_replay_control("stop").unwrap_err(), CliError::UnexpectedArgument("stop".to_string()));
+    assert_eq!(parse_replay_control("seek").unwrap_err(), CliError::UnexpectedArgument("seek".to_string()));
+    assert_eq!(parse_replay_control("pause now").unwrap_err(), CliError::UnexpectedArgument("pause now".to_string()));
+    assert_eq!(parse_replay_control("lap 4 5").unwrap_err(), CliError::UnexpectedArgument("5".to_string()));
+    assert_eq!(parse_replay_control("lap 300").unwrap_err(), invalid("lap", "300"));
+    assert_eq!(parse_replay_control("speed -1").unwrap_err(), invalid("speed", "-1"));
+}
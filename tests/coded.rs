@@ -0,0 +1,68 @@
+// Checks coded values turn into the numbers sent and back, and that a number outside the spec is
+// kept as Other rather than failing the packet it came in.
+
+use f12019_data_logger::f1_2019_net::*;
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::parser::f1_2019;
+use f12019_data_logger::Encode;
+use f12019_data_logger::Packet;
+
+#[test]
+fn numbers_both_ways() {
+    assert_eq!(Weather::from(4), Weather::HeavyRain);
+    assert_eq!(SessionType::from(0), SessionType::Unknown);
+    assert_eq!(SessionType::from(10), SessionType::Race);
+    assert_eq!(SafetyCarStatus::from(2), SafetyCarStatus::Virtual);
+    assert_eq!(TyreCompound::from(18), TyreCompound::C3);
+    assert_eq!(VisualTyreCompound::from(16), VisualTyreCompound::Soft);
+    assert_eq!(FiaFlag::from(-1), FiaFlag::Unknown);
+
+    for value in 0..=u8::MAX {
+        assert_eq!(u8::from(Weather::from(value)), value);
+        assert_eq!(u8::from(ResultStatus::from(value)), value);
+        assert_eq!(u8::from(VisualTyreCompound::from(value)), value);
+    }
+    for value in i8::MIN..=i8::MAX {
+        assert_eq!(i8::from(FiaFlag::from(value)), value);
+    }
+}
+
+#[test]
+fn numbers_outside_the_spec_are_kept() {
+    assert_eq!(Weather::from(6), Weather::Other(6));
+    assert_eq!(PitStatus::from(3), PitStatus::Other(3));
+    assert_eq!(VisualTyreCompound::from(3), VisualTyreCompound::Other(3));
+    assert_eq!(FiaFlag::from(-2), FiaFlag::Other(-2));
+    assert_eq!(TyreCompound::from(200).to_string(), "other (200)");
+    assert_eq!(FiaFlag::Other(-2).to_string(), "other (-2)");
+    assert_eq!(FiaFlag::Unknown.to_string(), "unknown");
+}
+
+#[test]
+fn edition_numbering() {
+    assert_eq!(ErsDeployMode::from_2020(1), ErsDeployMode::Medium);
+    assert_eq!(ErsDeployMode::from_2020(3), ErsDeployMode::Hotlap);
+    assert_eq!(ErsDeployMode::from_2020(4), ErsDeployMode::Other(4));
+    assert_eq!(VisualTyreCompound::from_2018(4), VisualTyreCompound::Medium);
+    assert_eq!(VisualTyreCompound::from_2018(7), VisualTyreCompound::Inter);
+    assert_eq!(VisualTyreCompound::from_2018(99), VisualTyreCompound::Other(99));
+}
+
+#[test]
+fn one_bad_car_doesnt_fail_the_packet() {
+    let lap = Lap {
+        header: PacketHeader { packet_format: 2019, packet_version: 1, packet_type: PacketType::Lap, secondary_player_car_index: 255, ..PacketHeader::default() },
+        lap_data: vec![LapData { pit_status: PitStatus::InPitArea, ..LapData::default() }; 20],
+    };
+    let mut bytes = Packet::Lap(lap).to_bytes().unwrap();
+    let pit_status = f1_2019::CAR_LAP_LAYOUT.offset_of("pit_status").unwrap();
+    bytes[f1_2019::HEADER_SIZE + 5 * f1_2019::CAR_LAP_SIZE + pit_status] = 200;
+
+    let decoded = match parse_packet(&bytes).unwrap() {
+        Packet::Lap(lap) => lap,
+        other => panic!("decoded a {:?}", other.packet_type()),
+    };
+    assert_eq!(decoded.lap_data[5].pit_status, PitStatus::Other(200));
+    assert!(decoded.lap_data.iter().enumerate().all(|(car, data)| car == 5 || data.pit_status == PitStatus::InPitArea));
+    assert_eq!(Packet::Lap(decoded).to_bytes().unwrap(), bytes);
+}
@@ -0,0 +1,141 @@
+// Helpers shared by the tests, each test file pulls in the ones it needs.
+
+#![allow(dead_code)]
+
+use f12019_data_logger::f1_2019_net::ButtonStatus;
+use f12019_data_logger::f1_2019_net::CarMotion;
+use f12019_data_logger::f1_2019_net::CarStatus;
+use f12019_data_logger::f1_2019_net::CarStatusData;
+use f12019_data_logger::f1_2019_net::CarTelemetry;
+use f12019_data_logger::f1_2019_net::FiaFlag;
+use f12019_data_logger::f1_2019_net::Lap;
+use f12019_data_logger::f1_2019_net::LapData;
+use f12019_data_logger::f1_2019_net::MotionData;
+use f12019_data_logger::f1_2019_net::PacketHeader;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::f1_2019_net::Telemetry;
+use f12019_data_logger::f1_2019_net::TyreCompound;
+use f12019_data_logger::wheels::Wheels;
+use f12019_data_logger::Encode;
+
+pub const SESSION_ID: u64 = 0x1234_5678_9abc_def0;
+
+// When session's first frame is received, in microseconds since the unix epoch
+pub const SESSION_START_US: u64 = 1_600_000_000_000_000;
+
+// A 2019 header, the rest of its fields zeroed
+pub fn header(packet_type: PacketType, session_id: u64, session_time: f32, frame_id: u32) -> PacketHeader {
+    PacketHeader {
+        packet_format: 2019,
+        packet_version: 1,
+        packet_type,
+        session_id,
+        session_time,
+        frame_id,
+        ..PacketHeader::default()
+    }
+}
+
+// Motion, lap, telemetry and status in that order each frame at 60 Hz, for 20 cars going round,
+// received with the timing the game sends them. The odd value is one a packet can carry but
+// that's easily mishandled: NaN, reverse gear, a flag outside the usual ones.
+pub fn session(frames: u32) -> Vec<(u64, Vec<u8>)> {
+    let mut datagrams = Vec::new();
+    for frame in 0..frames {
+        let time = frame as f32 / 60.0;
+        let received_us = SESSION_START_US + frame as u64 * 16_667;
+        let header = |packet_type: PacketType| header(packet_type, SESSION_ID, time, frame);
+        let cars = 0..20u8;
+        let angle = |car: u8| time * 0.08 + car as f32 * 0.05;
+        let motion = MotionData {
+            header: header(PacketType::Motion),
+            car_motion_data: cars.clone().map(|car| CarMotion {
+                world_pos_x: 600.0 * angle(car).cos(),
+                world_pos_y: 2.0 * (angle(car) * 3.0).sin(),
+                world_pos_z: 400.0 * angle(car).sin(),
+                world_vel_x: -48.0 * angle(car).sin(),
+                world_vel_z: 32.0 * angle(car).cos(),
+                world_fwd_dir_x: (32767.0 * angle(car).sin()) as i16,
+                world_fwd_dir_z: (32767.0 * angle(car).cos()) as i16,
+                lateral_g: 2.5 * (time * 1.3 + car as f32).sin(),
+                longitudinal_g: (time * 0.7 + car as f32).cos(),
+                yaw: angle(car),
+                ..CarMotion::default()
+            }).collect(),
+            suspension_pos: Wheels::new(1.0, 1.1, 0.9, 0.95).map(|pos| pos + (time * 9.0).sin()),
+            suspension_vel: Wheels::default(),
+            suspension_acc: Wheels::default(),
+            wheel_speed: Wheels::new(60.0, 60.1, 59.9, 60.0).map(|speed| speed + (time * 0.5).sin() * 20.0),
+            wheel_slip: Wheels::default(),
+            local_vel_x: 0.0,
+            local_vel_y: 0.0,
+            local_vel_z: 58.0 + (time * 0.5).sin() * 20.0,
+            angular_vel_x: 0.0,
+            angular_vel_y: 0.08,
+            angular_vel_z: 0.0,
+            angular_acc_x: 0.0,
+            angular_acc_y: 0.0,
+            angular_acc_z: 0.0,
+            front_wheels_angle: 0.1 * (time * 1.3).sin(),
+        };
+        datagrams.push((received_us, motion.to_bytes().unwrap()));
+
+        let lap = Lap {
+            header: header(PacketType::Lap),
+            lap_data: cars.clone().map(|car| LapData {
+                last_lap: 88.123,
+                current_lap: time + car as f32 * 0.2,
+                best_lap: 87.9,
+                lap_distance: 400.0 * angle(car),
+                total_distance: 400.0 * angle(car),
+                safety_car_delta: f32::NAN,
+                position: car + 1,
+                lap_num: 1 + (time / 90.0) as u8,
+                grid_position: car + 1,
+                ..LapData::default()
+            }).collect(),
+        };
+        datagrams.push((received_us + 100, lap.to_bytes().unwrap()));
+
+        let telemetry = Telemetry {
+            header: header(PacketType::Telemetry),
+            car_telemetry_data: cars.clone().map(|car| CarTelemetry {
+                car_speed: (230.0 + 70.0 * (time * 0.5 + car as f32).sin()) as u16,
+                throttle_pos: 0.5 + 0.5 * (time * 0.5 + car as f32).sin(),
+                steering_pos: 0.1 * (time * 1.3 + car as f32).sin(),
+                gear: if frame % 300 < 10 { -1 } else { 6 },
+                engine_rpm: (10500.0 + 1000.0 * (time * 3.0 + car as f32).sin()) as u16,
+                brake_temps: Wheels::new(500, 500, 520, 520),
+                tyre_surface_temps: Wheels::new(95, 95, 98, 98),
+                tyre_inner_temps: Wheels::new(100, 100, 101, 101),
+                engine_temp: 110,
+                tyre_pressures: Wheels::new(21.5, 21.5, 23.0, 23.0),
+                ..CarTelemetry::default()
+            }).collect(),
+            button_status: if frame % 120 < 5 { ButtonStatus::CROSS } else { ButtonStatus::NONE },
+            mfd_panel_index: 255,
+            mfd_panel_index_secondary: 255,
+            suggested_gear: 0,
+        };
+        datagrams.push((received_us + 200, telemetry.to_bytes().unwrap()));
+
+        let status = CarStatusData {
+            header: header(PacketType::CarStatus),
+            car_status_data: cars.map(|car| CarStatus {
+                fuel_in_tank: 100.0 - time * 0.02 - car as f32 * 0.1,
+                fuel_capacity: 110.0,
+                fuel_remaining_laps: 30.0 - time * 0.006,
+                max_rpm: 12000,
+                idle_rpm: 4000,
+                max_gears: 8,
+                tyres_wear: Wheels::new(3, 3, 4, car % 5),
+                actual_tyre_compound: TyreCompound::C3,
+                vehicle_fia_flags: if car == 7 { FiaFlag::Unknown } else { FiaFlag::None },
+                ers_store_energy: 4_000_000.0 - time * 1000.0,
+                ..CarStatus::default()
+            }).collect(),
+        };
+        datagrams.push((received_us + 300, status.to_bytes().unwrap()));
+    }
+    datagrams
+}
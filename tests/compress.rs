@@ -0,0 +1,165 @@
+// Checks block compression round trips, rejects corrupt blocks, and that compressed captures read
+// back the same records as uncompressed ones, seek through their index and come out smaller on a
+// session shaped like the game's.
+
+mod common;
+
+use std::io::Cursor;
+
+use f12019_data_logger::capture::CaptureReader;
+use f12019_data_logger::capture::CaptureWriter;
+use f12019_data_logger::capture::BLOCK_SIZE;
+use f12019_data_logger::capture::FILE_HEADER_SIZE;
+use f12019_data_logger::capture::RECORD_HEADER_SIZE;
+use f12019_data_logger::compress::compress;
+use f12019_data_logger::compress::decompress;
+use f12019_data_logger::compress::max_compressed_len;
+use f12019_data_logger::index::CaptureIndex;
+use f12019_data_logger::index;
+use f12019_data_logger::replay::SeekTarget;
+
+use common::session;
+
+fn round_trip(input: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    compress(input, &mut compressed);
+    assert!(compressed.len() <= max_compressed_len(input.len()));
+    let mut output = vec![1, 2, 3];     // Appended to, not replaced
+    decompress(&compressed, &mut output, input.len()).unwrap();
+    assert_eq!(&output[..3], &[1, 2, 3]);
+    output.split_off(3)
+}
+
+// Noise that won't compress, xorshift
+fn noise(len: usize) -> Vec<u8> {
+    let mut state: u32 = 2_463_534_242;
+    (0..len).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state as u8
+    }).collect()
+}
+
+#[test]
+fn compression_round_trips() {
+    let mut inputs: Vec<Vec<u8>> = vec![Vec::new(), vec![7], vec![0; 3], vec![0; 100_000], noise(70_000)];
+    inputs.extend((1..40).map(|len| (0..len).map(|byte| (byte % 5) as u8).collect()));
+    // Literal and match lengths needing extra length bytes, and matches 65535 back
+    let mut long = noise(300);
+    long.extend(vec![9u8; 1000]);
+    long.extend(noise(65535 - 1300));
+    long.extend_from_slice(&long.clone()[..600]);
+    inputs.push(long);
+    for input in inputs {
+        assert_eq!(round_trip(&input), input, "length {}", input.len());
+    }
+
+    let mut compressed = Vec::new();
+    compress(&vec![0; 100_000], &mut compressed);
+    assert!(compressed.len() < 500);
+}
+
+#[test]
+fn corrupt_blocks_are_rejected() {
+    let input: Vec<u8> = b"abcdabcdabcdabcd and some more".to_vec();
+    let mut compressed = Vec::new();
+    compress(&input, &mut compressed);
+    let fails = |compressed: &[u8], len: usize| decompress(compressed, &mut Vec::new(), len).is_err();
+    assert!(fails(&compressed, input.len() - 1));
+    assert!(fails(&compressed, input.len() + 1));
+    assert!(fails(&compressed[..compressed.len() - 1], input.len()));
+    assert!(fails(&compressed[..6], input.len()));
+    // A match from before the start of the block
+    assert!(fails(&[0x10, b'a', 2, 0], 5));
+    assert!(fails(&[0x10, b'a', 0, 0], 5));
+}
+
+fn write(datagrams: &[(u64, Vec<u8>)], mut writer: CaptureWriter<Vec<u8>>) -> (Vec<u8>, Vec<u64>) {
+    let mut positions = Vec::new();
+    for (received_us, datagram) in datagrams {
+        positions.push(writer.position());
+        writer.write(*received_us, None, datagram).unwrap();
+    }
+    writer.finish().unwrap();
+    (writer.into_inner(), positions)
+}
+
+fn read(capture: Vec<u8>) -> Vec<(u64, u64, Vec<u8>)> {
+    let mut reader = CaptureReader::new(Cursor::new(capture)).unwrap();
+    let mut records = Vec::new();
+    loop {
+        let position = reader.position();
+        match reader.next_record().unwrap() {
+            Some(record) => records.push((position, record.received_us, record.datagram.to_vec())),
+            None => return records,
+        }
+    }
+}
+
+#[test]
+fn compressed_captures_read_back_smaller() {
+    let datagrams = session(5 * 60);
+    let (plain, _) = write(&datagrams, CaptureWriter::new(Vec::new()).unwrap());
+    let (compressed, positions) = write(&datagrams, CaptureWriter::compressed(Vec::new()).unwrap());
+    assert!(compressed.len() * 2 < plain.len(), "compressed {} of {} bytes", compressed.len(), plain.len());
+
+    let records = read(compressed);
+    assert_eq!(records.len(), datagrams.len());
+    for ((position, received_us, datagram), (expected_position, (expected_us, expected))) in records.iter().zip(positions.iter().zip(&datagrams)) {
+        assert_eq!((position, received_us, datagram), (expected_position, expected_us, expected));
+        assert!((position & 0xffff) < BLOCK_SIZE as u64);
+    }
+    assert!(CaptureReader::new(Cursor::new(Vec::new())).is_err());
+}
+
+#[test]
+fn blocks_longer_than_they_can_compress_to_are_rejected() {
+    let (mut capture, _) = write(&session(60), CaptureWriter::compressed(Vec::new()).unwrap());
+    // Read in whole, a length this long would be 4 GiB
+    capture[FILE_HEADER_SIZE..FILE_HEADER_SIZE + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let mut reader = CaptureReader::new(Cursor::new(capture)).unwrap();
+    assert!(reader.next_record().is_err());
+}
+
+#[test]
+fn compressed_captures_seek() {
+    let datagrams = session(3 * 60);
+    let (capture, positions) = write(&datagrams, CaptureWriter::compressed(Vec::new()).unwrap());
+    let mut reader = CaptureReader::new(Cursor::new(capture.clone())).unwrap();
+    assert!(reader.is_compressed());
+    let index = CaptureIndex::build(&mut reader).unwrap();
+    assert_eq!(index.entries().iter().map(|entry| entry.position).collect::<Vec<u64>>(), positions);
+
+    // Back and forth, within a block and across them
+    for &(target, record) in &[(2.0, 480), (0.5, 120), (0.51, 124), (2.98, 716), (0.0, 0)] {
+        assert!(index::seek(&mut reader, Some(&index), SeekTarget::SessionTime(target)).unwrap());
+        assert_eq!(reader.position(), positions[record]);
+        assert_eq!(reader.next_record().unwrap().unwrap().datagram, &datagrams[record].1[..]);
+        assert_eq!(reader.position(), positions[record + 1]);
+    }
+    assert!(!index::seek(&mut reader, None, SeekTarget::SessionTime(3.0)).unwrap());
+    reader.rewind().unwrap();
+    assert_eq!(reader.next_record().unwrap().unwrap().datagram, &datagrams[0].1[..]);
+}
+
+#[test]
+fn blocks_end_when_full_or_a_second_old() {
+    let mut writer = CaptureWriter::compressed(Vec::new()).unwrap();
+    let start = writer.written_position();
+    writer.write(0, None, &[0; 1000]).unwrap();
+    writer.write(999_999, None, &[0; 1000]).unwrap();
+    assert_eq!(writer.written_position(), start);
+    writer.write(1_000_000, None, &[0; 1000]).unwrap();
+    assert!(writer.written_position() > start);
+    assert_eq!(writer.position() & 0xffff, 0);
+
+    let written = writer.written_position();
+    // The record taking the block to BLOCK_SIZE ends it
+    for _ in 0..(BLOCK_SIZE / (RECORD_HEADER_SIZE + 1000)) {
+        writer.write(2_000_000, None, &[1; 1000]).unwrap();
+    }
+    assert_eq!(writer.written_position(), written);
+    writer.write(2_000_000, None, &[1; 1000]).unwrap();
+    assert!(writer.written_position() > written);
+}
@@ -0,0 +1,189 @@
+// Checks the CSV sink writes a file per packet type with a row per car under a line of column
+// names, splits per wheel values into a column per wheel, keeps what's sent for the player alone
+// to the player's row and quotes what needs it.
+
+mod common;
+
+use f12019_data_logger::csv;
+use f12019_data_logger::csv::CsvSink;
+use f12019_data_logger::f1_2019_net::CarMotion;
+use f12019_data_logger::f1_2019_net::CarStatus;
+use f12019_data_logger::f1_2019_net::CarStatusData;
+use f12019_data_logger::f1_2019_net::CarTelemetry;
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::Lap;
+use f12019_data_logger::f1_2019_net::LapData;
+use f12019_data_logger::f1_2019_net::MotionData;
+use f12019_data_logger::f1_2019_net::PacketHeader;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::f1_2019_net::ParticipantData;
+use f12019_data_logger::f1_2019_net::Participants;
+use f12019_data_logger::f1_2019_net::Telemetry;
+use f12019_data_logger::f1_2019_net::TyreCompound;
+use f12019_data_logger::sink::PacketSink;
+use f12019_data_logger::wheels::Wheels;
+use f12019_data_logger::Packet;
+
+use common::header;
+
+// The shared header at 4 Hz with the player in car 3
+fn player_header(packet_type: PacketType, frame_id: u32) -> PacketHeader {
+    PacketHeader { player_car_index: 3, ..header(packet_type, 0x0123_4567_89ab_cdef, frame_id as f32 / 4.0, frame_id) }
+}
+
+fn sink() -> CsvSink<Vec<u8>> {
+    CsvSink::new(|_| Ok(Vec::new()))
+}
+
+// Each line split on commas, for files without quoted cells
+fn lines(sink: &CsvSink<Vec<u8>>, name: &str) -> Vec<Vec<String>> {
+    let file = String::from_utf8(sink.table(name).expect("file not written").clone()).unwrap();
+    file.lines().map(|line| line.split(',').map(str::to_string).collect()).collect()
+}
+
+fn cell<'a>(lines: &'a [Vec<String>], row: usize, column: &str) -> &'a str {
+    let index = lines[0].iter().position(|name| name == column).unwrap_or_else(|| panic!("no column {}", column));
+    &lines[row + 1][index]
+}
+
+fn lap(frame_id: u32) -> Packet {
+    Packet::Lap(Lap {
+        header: player_header(PacketType::Lap, frame_id),
+        lap_data: (0..20u8).map(|car| LapData { position: car + 1, lap_num: 2, last_lap: 91.25, ..LapData::default() }).collect(),
+    })
+}
+
+fn motion() -> Packet {
+    Packet::Motion(MotionData {
+        header: player_header(PacketType::Motion, 8),
+        car_motion_data: (0..20).map(|car| CarMotion { world_pos_x: car as f32 * 10.0, ..CarMotion::default() }).collect(),
+        suspension_pos: Wheels::default(),
+        suspension_vel: Wheels::default(),
+        suspension_acc: Wheels::default(),
+        wheel_speed: Wheels::new(60.0, 60.5, 59.5, 61.0),
+        wheel_slip: Wheels::default(),
+        local_vel_x: 0.0,
+        local_vel_y: 0.0,
+        local_vel_z: 58.0,
+        angular_vel_x: 0.0,
+        angular_vel_y: 0.0,
+        angular_vel_z: 0.0,
+        angular_acc_x: 0.0,
+        angular_acc_y: 0.0,
+        angular_acc_z: 0.0,
+        front_wheels_angle: 0.125,
+    })
+}
+
+#[test]
+fn a_row_per_car_under_the_column_names() {
+    let mut sink = sink();
+    sink.on_packet(&lap(10)).unwrap();
+    sink.on_packet(&lap(11)).unwrap();
+    sink.close().unwrap();
+
+    let lines = lines(&sink, csv::LAP);
+    assert_eq!(lines.len(), 1 + 2 * 20);
+    assert_eq!(lines[0][..6], ["session_id", "session_time", "frame_id", "car_index", "last_lap", "current_lap"]);
+    assert_eq!(lines[0].last().unwrap(), "result_status");
+    assert!(lines.iter().all(|line| line.len() == lines[0].len()));
+
+    assert_eq!(cell(&lines, 0, "session_id"), "0123456789abcdef");
+    assert_eq!(cell(&lines, 0, "session_time"), "2.5");
+    assert_eq!(cell(&lines, 7, "car_index"), "7");
+    assert_eq!(cell(&lines, 7, "position"), "8");
+    assert_eq!(cell(&lines, 7, "last_lap"), "91.25");
+    assert_eq!(cell(&lines, 20, "frame_id"), "11");
+    assert_eq!(cell(&lines, 20, "car_index"), "0");
+}
+
+#[test]
+fn wheels_get_a_column_each() {
+    let mut sink = sink();
+    let telemetry = Telemetry {
+        header: player_header(PacketType::Telemetry, 4),
+        car_telemetry_data: vec![CarTelemetry { brake_temps: Wheels::new(480, 481, 530, 529), ..CarTelemetry::default() }; 20],
+        button_status: Default::default(),
+        mfd_panel_index: 255,
+        mfd_panel_index_secondary: 255,
+        suggested_gear: 0,
+    };
+    sink.on_packet(&Packet::Telemetry(telemetry)).unwrap();
+    let status = CarStatusData {
+        header: player_header(PacketType::CarStatus, 4),
+        car_status_data: vec![CarStatus { actual_tyre_compound: TyreCompound::C3, ..CarStatus::default() }; 20],
+    };
+    sink.on_packet(&Packet::CarStatus(status)).unwrap();
+
+    let lines = lines(&sink, csv::TELEMETRY);
+    assert_eq!(cell(&lines, 0, "brake_temps_rl"), "480");
+    assert_eq!(cell(&lines, 0, "brake_temps_rr"), "481");
+    assert_eq!(cell(&lines, 0, "brake_temps_fl"), "530");
+    assert_eq!(cell(&lines, 0, "brake_temps_fr"), "529");
+    assert!(lines[0].iter().all(|name| name != "brake_temps"));
+
+    // Coded values by name
+    let lines = self::lines(&sink, csv::CAR_STATUS);
+    assert_eq!(cell(&lines, 0, "actual_tyre_compound"), "C3");
+    assert_eq!(cell(&lines, 0, "tyres_wear_fr"), "0");
+}
+
+#[test]
+fn player_values_on_the_players_row() {
+    let mut sink = sink();
+    sink.on_packet(&motion()).unwrap();
+    let lines = lines(&sink, csv::MOTION);
+    assert_eq!(lines.len(), 1 + 20);
+    assert_eq!(cell(&lines, 3, "world_pos_x"), "30");
+    assert_eq!(cell(&lines, 3, "wheel_speed_rr"), "60.5");
+    assert_eq!(cell(&lines, 3, "front_wheels_angle"), "0.125");
+    assert_eq!(cell(&lines, 2, "wheel_speed_rr"), "");
+    assert_eq!(cell(&lines, 2, "front_wheels_angle"), "");
+
+    let mut sink = self::sink();
+    sink.set_player_only(true);
+    sink.on_packet(&motion()).unwrap();
+    let lines = self::lines(&sink, csv::MOTION);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(cell(&lines, 0, "car_index"), "3");
+}
+
+#[test]
+fn files_only_for_the_packets_wanted() {
+    let mut sink = sink();
+    sink.set_packets(Some(vec![PacketType::Event]));
+    sink.on_packet(&lap(1)).unwrap();
+    sink.on_packet(&motion()).unwrap();
+    sink.on_packet(&Packet::Event(Event { header: player_header(PacketType::Event, 2), event_type: EventType::DrsEnabled })).unwrap();
+    let fastest = EventType::FastestLap { vehicle_idx: 5, lap_time: 88.5 };
+    sink.on_packet(&Packet::Event(Event { header: player_header(PacketType::Event, 3), event_type: fastest })).unwrap();
+
+    assert!(sink.table(csv::LAP).is_none());
+    assert!(sink.table(csv::MOTION).is_none());
+    let lines = lines(&sink, csv::EVENTS);
+    assert_eq!(lines[0], ["session_id", "session_time", "frame_id", "car_index", "code", "lap_time"]);
+    assert_eq!(lines[1][2..], ["2", "", "DRSE", ""]);
+    assert_eq!(lines[2][2..], ["3", "5", "FTLP", "88.5"]);
+}
+
+#[test]
+fn quotes_what_needs_it() {
+    let mut sink = sink();
+    let mut participants = Participants {
+        header: player_header(PacketType::Participant, 1),
+        num_cars_active: 2,
+        participant_data: vec![ParticipantData::new(); 2],
+    };
+    let name = b"Smith, \"Speedy\" J";
+    participants.participant_data[1].name[..name.len()].copy_from_slice(name);
+    participants.participant_data[1].race_number = 44;
+    sink.on_packet(&Packet::Participants(participants)).unwrap();
+
+    let file = String::from_utf8(sink.table(csv::PARTICIPANTS).unwrap().clone()).unwrap();
+    let lines: Vec<&str> = file.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with(",num_cars_active,ai_controlled,driver_id,team_id,race_number,nationality,name,priv_telemetry"));
+    assert!(lines[2].ends_with(",2,0,0,0,44,0,\"Smith, \"\"Speedy\"\" J\",0"), "{}", lines[2]);
+    assert!(lines[1].ends_with(",2,0,0,0,0,0,,0"), "{}", lines[1]);
+}
@@ -0,0 +1,104 @@
+// Checks recordings end where asked, split into the right pieces, and merge keeping one of each
+// packet.
+
+mod common;
+
+use f12019_data_logger::edit::Merger;
+use f12019_data_logger::edit::Piece;
+use f12019_data_logger::edit::SplitBy;
+use f12019_data_logger::edit::Splitter;
+use f12019_data_logger::edit::Until;
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::Lap;
+use f12019_data_logger::f1_2019_net::LapData;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::parser::parse_header;
+use f12019_data_logger::replay::SeekTarget;
+use f12019_data_logger::source::MemorySource;
+use f12019_data_logger::source::PacketSource;
+use f12019_data_logger::Encode;
+
+use common::header;
+
+fn lap(session_id: u64, frame_id: u32, lap_num: u8) -> Vec<u8> {
+    let mut lap_data = vec![LapData::default(); 20];
+    lap_data[0].lap_num = lap_num;
+    Lap { header: header(PacketType::Lap, session_id, frame_id as f32, frame_id), lap_data }.to_bytes().unwrap()
+}
+
+fn event(session_id: u64, frame_id: u32) -> Vec<u8> {
+    Event { header: header(PacketType::Event, session_id, frame_id as f32, frame_id), event_type: EventType::DrsEnabled }.to_bytes().unwrap()
+}
+
+// (frame_id, packet type) of everything left in the source
+fn frames<S: PacketSource>(mut source: S) -> Vec<(u32, PacketType)> {
+    let mut frames = Vec::new();
+    while let Some((_, datagram)) = source.next_datagram().unwrap() {
+        let header = parse_header(datagram).unwrap();
+        frames.push((header.frame_id, header.packet_type));
+    }
+    frames
+}
+
+#[test]
+fn until_stops_before_the_end() {
+    let datagrams = vec![
+        (0, event(1, 0)), (1, lap(1, 1, 1)), (2, event(1, 2)), (3, lap(1, 3, 2)), (4, event(1, 4)), (5, lap(1, 5, 3)),
+    ];
+    let until = Until::new(MemorySource::new(datagrams.clone()), SeekTarget::Lap(2));
+    assert_eq!(frames(until), vec![(0, PacketType::Event), (1, PacketType::Lap), (2, PacketType::Event)]);
+    let until = Until::new(MemorySource::new(datagrams.clone()), SeekTarget::SessionTime(4.0));
+    assert_eq!(frames(until).len(), 4);
+    let until = Until::new(MemorySource::new(datagrams), SeekTarget::Session(2));
+    assert_eq!(frames(until).len(), 6);
+}
+
+#[test]
+fn splits_by_session_and_lap() {
+    let datagrams = [
+        event(1, 0), lap(1, 1, 1), event(1, 2), b"not a packet".to_vec(), lap(1, 3, 2), event(1, 4),
+        event(2, 0), lap(2, 1, 1),
+    ];
+    let mut by_session = Splitter::new(SplitBy::Session);
+    let pieces: Vec<Option<Piece>> = datagrams.iter().map(|datagram| by_session.piece(datagram)).collect();
+    let session = |session_id| Some(Piece { session_id, lap: None });
+    assert_eq!(pieces, vec![session(1), session(1), session(1), session(1), session(1), session(1), session(2), session(2)]);
+
+    let mut by_lap = Splitter::new(SplitBy::Lap);
+    let pieces: Vec<Option<Piece>> = datagrams.iter().map(|datagram| by_lap.piece(datagram)).collect();
+    let lap = |session_id, lap| Some(Piece { session_id, lap: Some(lap) });
+    assert_eq!(pieces, vec![lap(1, 0), lap(1, 1), lap(1, 1), lap(1, 1), lap(1, 2), lap(1, 2), lap(2, 0), lap(2, 1)]);
+    assert_eq!(lap(1, 2).unwrap().to_string(), "session 0000000000000001 lap 2");
+
+    // Nothing to put a datagram with before the first packet
+    assert_eq!(Splitter::new(SplitBy::Lap).piece(b"not a packet"), None);
+}
+
+#[test]
+fn merge_keeps_one_of_each_packet() {
+    // Both machines got frames 1 and 2, only the first got frame 0 and only the second frame 3
+    let first = MemorySource::new(vec![(100, event(1, 0)), (200, event(1, 1)), (300, lap(1, 2, 1))]);
+    let second = MemorySource::new(vec![(205, event(1, 1)), (295, lap(1, 2, 1)), (405, event(1, 3))]);
+    let mut merger = Merger::new(vec![first, second]).unwrap();
+    let mut merged = Vec::new();
+    while let Some((received_us, datagram)) = merger.next_datagram().unwrap() {
+        merged.push((received_us, parse_header(datagram).unwrap().frame_id));
+    }
+    assert_eq!(merged, vec![(100, 0), (200, 1), (295, 2), (405, 3)]);
+    assert_eq!(merger.duplicates(), 2);
+}
+
+#[test]
+fn merge_keeps_repeats_within_a_recording() {
+    // A flashback sends frames 1 and 2 again, the second machine only catching the first time
+    let first = MemorySource::new(vec![(0, event(1, 1)), (1, event(1, 2)), (2, event(1, 1)), (3, event(1, 2))]);
+    let second = MemorySource::new(vec![(0, event(1, 1)), (1, event(1, 2)), (4, b"not a packet".to_vec())]);
+    let mut merger = Merger::new(vec![first, second]).unwrap();
+    let mut merged = Vec::new();
+    while let Some((_, datagram)) = merger.next_datagram().unwrap() {
+        merged.push(parse_header(datagram).map(|header| header.frame_id).ok());
+    }
+    assert_eq!(merged, vec![Some(1), Some(2), Some(1), Some(2), None]);
+    assert_eq!(merger.duplicates(), 2);
+}
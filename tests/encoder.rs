@@ -0,0 +1,282 @@
+// Checks every 2019 packet type encodes to its spec size and decodes back to the packet it was
+// made from, and that packets the 2019 layout can't hold are refused rather than written.
+
+use f12019_data_logger::f1_2019_net::*;
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::parser::f1_2019;
+use f12019_data_logger::wheels::Wheels;
+use f12019_data_logger::Encode;
+use f12019_data_logger::EncodeError;
+
+fn header(packet_type: PacketType) -> PacketHeader {
+    PacketHeader {
+        packet_format: 2019,
+        maj_version: 1,
+        min_version: 22,
+        packet_version: 1,
+        packet_type,
+        session_id: 0x0011_2233_4455_6677,
+        session_time: 1234.5,
+        frame_id: 74_070,
+        player_car_index: 7,
+        secondary_player_car_index: 255,   // What 2019 packets decode with
+    }
+}
+
+// Encodes the packet, checks the size and decodes it again. Debug prints every field and NaN
+// compares equal there, so it stands in for PartialEq
+fn round_trip(packet: Packet, size: usize) {
+    let bytes = packet.to_bytes().unwrap();
+    assert_eq!(bytes.len(), size, "{:?} size", packet.packet_type());
+    let decoded = parse_packet(&bytes).unwrap();
+    assert_eq!(format!("{:?}", decoded), format!("{:?}", packet));
+    assert_eq!(decoded.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn motion_round_trips() {
+    let motion = MotionData {
+        header: header(PacketType::Motion),
+        car_motion_data: (0..20).map(|car| CarMotion {
+            world_pos_x: car as f32 * 10.5,
+            world_pos_y: -1.25,
+            world_pos_z: 300.0 - car as f32,
+            world_vel_x: 55.5,
+            world_fwd_dir_x: 32767,
+            world_fwd_dir_z: -car,
+            world_right_dir_y: -32767,
+            lateral_g: 2.5,
+            yaw: f32::NAN,
+            roll: -0.01,
+            ..CarMotion::default()
+        }).collect(),
+        suspension_pos: Wheels::new(1.0, 2.0, 3.0, 4.0),
+        suspension_vel: Wheels::new(-1.0, -2.0, -3.0, -4.0),
+        suspension_acc: Wheels::new(0.5, 0.25, 0.125, 0.0625),
+        wheel_speed: Wheels::new(60.0, 61.0, 62.0, 63.0),
+        wheel_slip: Wheels::new(0.01, 0.02, 0.03, 0.04),
+        local_vel_x: 1.5,
+        local_vel_y: 2.5,
+        local_vel_z: 58.0,
+        angular_vel_x: 0.1,
+        angular_vel_y: 0.2,
+        angular_vel_z: 0.3,
+        angular_acc_x: -0.1,
+        angular_acc_y: -0.2,
+        angular_acc_z: -0.3,
+        front_wheels_angle: 0.125,
+    };
+    round_trip(Packet::Motion(motion), f1_2019::MOTION_SIZE);
+}
+
+#[test]
+fn session_round_trips() {
+    let mut zones = [MarshalZone::default(); 21];
+    for (index, zone) in zones.iter_mut().enumerate() {
+        zone.zone_start = index as f32 / 21.0;
+        zone.flag = if index == 4 { FiaFlag::Yellow } else { FiaFlag::Green };
+    }
+    let session = SessionData {
+        header: header(PacketType::Session),
+        weather: Weather::LightRain,
+        track_temp: 31,
+        air_temp: -2,
+        total_laps: 52,
+        track_len: 5891,
+        session_type: SessionType::Race,
+        track_id: 7,
+        formual: Formula::F1Modern,
+        session_ttl: 7200,
+        session_len: 7200,
+        pit_spd_lim: 80,
+        is_paused: 0,
+        is_spectating: 1,
+        spectator_car: 12,
+        sli_native: 0,
+        num_zones: 17,
+        zones,
+        safety_car: SafetyCarStatus::Virtual,
+        is_network_game: 1,
+        weather_forecast_samples: Vec::new(),
+    };
+    round_trip(Packet::Session(session), f1_2019::SESSION_SIZE);
+}
+
+#[test]
+fn lap_round_trips() {
+    let lap = Lap {
+        header: header(PacketType::Lap),
+        lap_data: (0..20u8).map(|car| LapData {
+            last_lap: 91.25,
+            current_lap: 12.0 + car as f32,
+            best_lap: 90.5,
+            best_sec_1: 28.0,
+            best_sec_2: 31.5,
+            lap_distance: -15.0,
+            total_distance: 10_000.0,
+            safety_car_delta: -0.5,
+            position: car + 1,
+            lap_num: 3,
+            pit_status: PitStatus::Pitting,
+            sector: 2,
+            is_lap_valid: 1,
+            penalties: 5,
+            grid_position: 20 - car,
+            driver_status: DriverStatus::FlyingLap,
+            result_status: ResultStatus::Active,
+        }).collect(),
+    };
+    round_trip(Packet::Lap(lap), f1_2019::LAP_SIZE);
+}
+
+#[test]
+fn events_round_trip() {
+    let events = [
+        EventType::SessionStarted,
+        EventType::SessionEnded,
+        EventType::FastestLap { vehicle_idx: 4, lap_time: 88.125 },
+        EventType::Retirement { vehicle_idx: 19 },
+        EventType::DrsEnabled,
+        EventType::DrsDisabled,
+        EventType::TeamMateInPits { vehicle_idx: 2 },
+        EventType::ChequeredFlag,
+        EventType::RaceWinner { vehicle_idx: 0 },
+        EventType::Unknown(*b"ABCD"),
+    ];
+    for event_type in events {
+        round_trip(Packet::Event(Event { header: header(PacketType::Event), event_type }), f1_2019::EVENT_SIZE);
+    }
+}
+
+#[test]
+fn participants_round_trip() {
+    let mut participants = Participants {
+        header: header(PacketType::Participant),
+        num_cars_active: 18,
+        participant_data: vec![ParticipantData::new(); 20],
+    };
+    for (index, car) in participants.participant_data.iter_mut().enumerate() {
+        car.ai_controlled = (index != 7) as u8;
+        car.driver_id = index as u8;
+        car.team_id = index as u8 / 2;
+        car.race_number = index as u8 + 2;
+        car.nationality = 10;
+        car.priv_telemetry = 1;
+        let name = format!("Driver {}", index);
+        car.name[..name.len()].copy_from_slice(name.as_bytes());
+    }
+    round_trip(Packet::Participants(participants), f1_2019::PARTICIPANTS_SIZE);
+}
+
+#[test]
+fn setups_round_trip() {
+    let setups = CarSetups {
+        header: header(PacketType::Setup),
+        car_setups: (0..20u8).map(|car| CarSetupData {
+            front_wing: 5,
+            rear_wing: 7,
+            on_throttle: 60,
+            off_throttle: 55,
+            front_camber: -3.5,
+            rear_camber: -2.0,
+            front_toe: 0.05,
+            rear_toe: 0.2,
+            front_suspension: 4,
+            rear_suspension: 3,
+            front_anti_roll_bar: 6,
+            rear_anti_roll_bar: 2,
+            front_suspension_height: 3,
+            rear_suspension_height: 6,
+            brake_pressure: 100,
+            brake_bias: 56,
+            front_tyre_pressure: 23.0,
+            rear_tyre_pressure: 21.5,
+            ballast: car % 10,
+            fuel_load: 15.5 + car as f32,
+        }).collect(),
+    };
+    round_trip(Packet::CarSetups(setups), f1_2019::CARSETUPS_SIZE);
+}
+
+#[test]
+fn telemetry_round_trips() {
+    let telemetry = Telemetry {
+        header: header(PacketType::Telemetry),
+        car_telemetry_data: (0..20u16).map(|car| CarTelemetry {
+            car_speed: 300 + car,
+            throttle_pos: 1.0,
+            steering_pos: -0.25,
+            brake_pos: 0.0,
+            clutch_pos: 0,
+            gear: if car == 0 { -1 } else { 8 },
+            engine_rpm: 11_500,
+            drs_active: 1,
+            change_light_perc: 95,
+            brake_temps: Wheels::new(400, 410, 420, 430),
+            tyre_surface_temps: Wheels::new(90, 91, 92, 93),
+            tyre_inner_temps: Wheels::new(100, 101, 102, 103),
+            engine_temp: 105,
+            tyre_pressures: Wheels::new(21.5, 21.6, 23.0, 23.1),
+            tyre_contact_types: Wheels::new(0, 0, 1, 7),
+        }).collect(),
+        button_status: ButtonStatus::CROSS | ButtonStatus::R2,
+        // Not sent before 2020, decoded 2019 packets carry these
+        mfd_panel_index: 255,
+        mfd_panel_index_secondary: 255,
+        suggested_gear: 0,
+    };
+    round_trip(Packet::Telemetry(telemetry), f1_2019::TELEMETY_SIZE);
+}
+
+#[test]
+fn car_status_round_trips() {
+    let status = CarStatusData {
+        header: header(PacketType::CarStatus),
+        car_status_data: (0..20u8).map(|car| CarStatus {
+            traction_control: 2,
+            anti_lock_brakes: 1,
+            fuel_mix: FuelMix::Rich,
+            front_brake_bias: 58,
+            pit_limiter_status: 0,
+            fuel_in_tank: 45.5,
+            fuel_capacity: 110.0,
+            fuel_remaining_laps: 12.25,
+            max_rpm: 12_000,
+            idle_rpm: 4000,
+            max_gears: 8,
+            drs_allowed: 1,
+            tyres_wear: Wheels::new(10, 11, 12, car),
+            actual_tyre_compound: TyreCompound::C2,
+            tyre_visual_compound: VisualTyreCompound::Hard,
+            tyres_damage: Wheels::new(1, 2, 3, 4),
+            front_left_wing_damage: 5,
+            front_right_wing_damage: 0,
+            rear_wing_damage: 10,
+            engine_damage: 20,
+            gear_box_damage: 15,
+            vehicle_fia_flags: FiaFlag::Blue,
+            ers_store_energy: 3_500_000.0,
+            ers_deploy_mode: ErsDeployMode::Overtake,
+            ers_harvested_this_lap_mguk: 100_000.0,
+            ers_harvested_this_lap_mguh: 200_000.0,
+            ers_deployed_this_lap: 300_000.0,
+            ..CarStatus::default()
+        }).collect(),
+    };
+    round_trip(Packet::CarStatus(status), f1_2019::STATUS_SIZE);
+}
+
+#[test]
+fn refuses_what_the_2019_layout_cant_hold() {
+    let lap = |packet_format, cars| Packet::Lap(Lap {
+        header: PacketHeader { packet_format, ..header(PacketType::Lap) },
+        lap_data: vec![LapData::default(); cars],
+    });
+    assert_eq!(lap(2018, 20).to_bytes().unwrap_err(), EncodeError::UnsupportedFormat(2018));
+    assert_eq!(lap(2020, 20).to_bytes().unwrap_err(), EncodeError::UnsupportedFormat(2020));
+    assert_eq!(lap(2019, 22).to_bytes().unwrap_err(), EncodeError::CarCountMismatch { expected: 20, got: 22 });
+
+    let mut short = vec![0u8; f1_2019::LAP_SIZE - 1];
+    assert_eq!(lap(2019, 20).write_into(&mut short).unwrap_err(),
+        EncodeError::BufferTooSmall { needed: f1_2019::LAP_SIZE, got: f1_2019::LAP_SIZE - 1 });
+}
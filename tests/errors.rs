@@ -0,0 +1,58 @@
+// Checks datagrams that can't be decoded come back as the matching ParseError rather than a
+// panic: too short for a header, a format or packet type that isn't read, a size other than the
+// spec's and an event cut off before its code or details.
+
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::ParseError;
+
+// A 2019 header for the packet type, zero padded or cut to len bytes
+fn datagram(packet_type: u8, len: usize) -> Vec<u8> {
+    let mut datagram = vec![0u8; len.max(23)];
+    datagram[..2].copy_from_slice(&2019u16.to_le_bytes());
+    datagram[5] = packet_type;
+    datagram.truncate(len);
+    datagram
+}
+
+fn error(datagram: &[u8]) -> Option<ParseError> {
+    parse_packet(datagram).err()
+}
+
+#[test]
+fn too_short_for_a_header() {
+    // Too short to hold packet_format, then too short for the 2019 header it names
+    assert_eq!(error(&[]), Some(ParseError::TooShort { expected: 2, got: 0 }));
+    assert_eq!(error(&datagram(0, 22)), Some(ParseError::TooShort { expected: 23, got: 22 }));
+}
+
+#[test]
+fn formats_and_packet_types_not_read() {
+    for &format in &[2017u16, 2021, 0xffff] {
+        let mut datagram = datagram(0, 1343);
+        datagram[..2].copy_from_slice(&format.to_le_bytes());
+        assert_eq!(error(&datagram), Some(ParseError::UnsupportedFormat(format)));
+    }
+    assert_eq!(error(&datagram(8, 1343)), Some(ParseError::UnknownPacketType(8)));
+    assert_eq!(error(&datagram(255, 23)), Some(ParseError::UnknownPacketType(255)));
+}
+
+#[test]
+fn sizes_other_than_the_spec() {
+    // Motion, session, lap, participants, setups, telemetry and status
+    let sizes = [(0, 1343), (1, 149), (2, 843), (4, 1104), (5, 843), (6, 1347), (7, 1143)];
+    for &(packet_type, size) in &sizes {
+        for &len in &[23, size - 1, size + 1] {
+            assert_eq!(error(&datagram(packet_type, len)), Some(ParseError::SizeMismatch { expected: size, got: len }));
+        }
+        assert!(parse_packet(&datagram(packet_type, size)).is_ok(), "packet type {} at its size", packet_type);
+    }
+}
+
+#[test]
+fn events_cut_short() {
+    assert_eq!(error(&datagram(3, 26)), Some(ParseError::TooShort { expected: 27, got: 26 }));
+    // Fastest lap sends a car index and a lap time after the code
+    let mut fastest_lap = datagram(3, 29);
+    fastest_lap[23..27].copy_from_slice(b"FTLP");
+    assert_eq!(error(&fastest_lap), Some(ParseError::TooShort { expected: 32, got: 29 }));
+}
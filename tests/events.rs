@@ -0,0 +1,78 @@
+// Checks each event code decodes to its EventType with the details sent after it, that codes
+// outside the spec are kept as sent, and that the code and car come back out as they went in.
+
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::PacketHeader;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::parser::f1_2019;
+use f12019_data_logger::Encode;
+use f12019_data_logger::Packet;
+use f12019_data_logger::ParseError;
+
+// A 2019 event datagram with the code and detail bytes given, padded to full size
+fn datagram(code: &[u8; 4], details: &[u8]) -> Vec<u8> {
+    let header = PacketHeader { packet_format: 2019, packet_version: 1, packet_type: PacketType::Event, ..PacketHeader::default() };
+    let mut buf = header.to_bytes().unwrap();
+    buf.extend_from_slice(code);
+    buf.extend_from_slice(details);
+    buf.resize(f1_2019::EVENT_SIZE, 0);
+    buf
+}
+
+fn decode(code: &[u8; 4], details: &[u8]) -> EventType {
+    match parse_packet(&datagram(code, details)).unwrap() {
+        Packet::Event(Event { event_type, .. }) => event_type,
+        other => panic!("decoded a {:?}", other.packet_type()),
+    }
+}
+
+#[test]
+fn codes_without_details() {
+    assert_eq!(decode(b"SSTA", &[]), EventType::SessionStarted);
+    assert_eq!(decode(b"SEND", &[]), EventType::SessionEnded);
+    assert_eq!(decode(b"DRSE", &[]), EventType::DrsEnabled);
+    assert_eq!(decode(b"DRSD", &[]), EventType::DrsDisabled);
+    assert_eq!(decode(b"CHQF", &[]), EventType::ChequeredFlag);
+}
+
+#[test]
+fn codes_naming_a_car() {
+    let mut fastest = vec![12];
+    fastest.extend_from_slice(&87.625f32.to_le_bytes());
+    assert_eq!(decode(b"FTLP", &fastest), EventType::FastestLap { vehicle_idx: 12, lap_time: 87.625 });
+    assert_eq!(decode(b"RTMT", &[4]), EventType::Retirement { vehicle_idx: 4 });
+    assert_eq!(decode(b"TMPT", &[17]), EventType::TeamMateInPits { vehicle_idx: 17 });
+    assert_eq!(decode(b"RCWN", &[0]), EventType::RaceWinner { vehicle_idx: 0 });
+
+    assert_eq!(EventType::Retirement { vehicle_idx: 4 }.vehicle_idx(), Some(4));
+    assert_eq!(EventType::FastestLap { vehicle_idx: 12, lap_time: 87.625 }.vehicle_idx(), Some(12));
+    assert_eq!(EventType::ChequeredFlag.vehicle_idx(), None);
+}
+
+#[test]
+fn unknown_codes_are_kept() {
+    // Later games' codes and anything else, matched exactly so case matters
+    assert_eq!(decode(b"SPTP", &[3, 1, 2]), EventType::Unknown(*b"SPTP"));
+    assert_eq!(decode(b"ssta", &[]), EventType::Unknown(*b"ssta"));
+    assert_eq!(decode(&[0xff, 0, 0x10, b'A'], &[]), EventType::Unknown([0xff, 0, 0x10, b'A']));
+    assert_eq!(EventType::Unknown(*b"SPTP").code(), *b"SPTP");
+    assert_eq!(EventType::Unknown(*b"SPTP").vehicle_idx(), None);
+}
+
+#[test]
+fn codes_come_back_as_sent() {
+    for code in [b"SSTA", b"SEND", b"FTLP", b"RTMT", b"DRSE", b"DRSD", b"TMPT", b"CHQF", b"RCWN", b"XXXX"] {
+        assert_eq!(decode(code, &[1, 0, 0, 0, 0]).code(), *code);
+    }
+}
+
+#[test]
+fn details_cut_short_are_an_error() {
+    // Header and code with nothing after, the car the event names is missing
+    let header_and_code = f1_2019::HEADER_SIZE + 4;
+    let short = &datagram(b"RTMT", &[])[..header_and_code];
+    assert!(matches!(parse_packet(short), Err(ParseError::TooShort { .. })));
+    assert_eq!(parse_packet(&datagram(b"SEND", &[])[..header_and_code]).map(|packet| packet.packet_type()), Ok(PacketType::Event));
+}
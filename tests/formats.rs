@@ -0,0 +1,168 @@
+// Checks 2018 and 2020 datagrams decode into the common model: fields an edition sends
+// differently are converted to the 2019 meaning, fields it doesn't send are left at their
+// defaults and packet types or sizes from another edition are refused.
+
+use f12019_data_logger::f1_2019_net::*;
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::parser::f1_2018;
+use f12019_data_logger::parser::f1_2020;
+use f12019_data_logger::wheels::Wheels;
+use f12019_data_logger::ParseError;
+
+// A header for the edition, zero padded to len bytes
+fn datagram(packet_format: u16, packet_type: u8, len: usize) -> Vec<u8> {
+    let mut datagram = vec![0u8; len];
+    put(&mut datagram, 0, &packet_format.to_le_bytes());
+    // 2018 has no game version bytes in front of the packet type
+    datagram[if packet_format == 2018 { 3 } else { 5 }] = packet_type;
+    datagram
+}
+
+fn put(datagram: &mut [u8], offset: usize, bytes: &[u8]) {
+    datagram[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+#[test]
+fn headers() {
+    let mut datagram_2018 = datagram(2018, 2, f1_2018::LAP_SIZE);
+    put(&mut datagram_2018, 4, &0xabcdu64.to_le_bytes());
+    datagram_2018[20] = 3;
+    let header = *parse_packet(&datagram_2018).unwrap().header();
+    assert_eq!((header.packet_format, header.maj_version, header.min_version), (2018, 0, 0));
+    assert_eq!(header.packet_type, PacketType::Lap);
+    assert_eq!(header.session_id, 0xabcd);
+    assert_eq!(header.player_car_index, 3);
+    assert_eq!(header.secondary_player_car_index, 255);
+
+    let mut datagram_2020 = datagram(2020, 2, f1_2020::LAP_SIZE);
+    put(&mut datagram_2020, 2, &[1, 18]);
+    datagram_2020[22] = 0;
+    datagram_2020[23] = 1;
+    let header = *parse_packet(&datagram_2020).unwrap().header();
+    assert_eq!((header.packet_format, header.maj_version, header.min_version), (2020, 1, 18));
+    assert_eq!((header.player_car_index, header.secondary_player_car_index), (0, 1));
+}
+
+#[test]
+fn telemetry_2018_scales_pedals_and_steering() {
+    let mut buf = datagram(2018, 6, f1_2018::TELEMETRY_SIZE);
+    let car = f1_2018::HEADER_SIZE + f1_2018::CAR_TELEMETRY_SIZE;
+    put(&mut buf, car + 2, &[50, (-25i8) as u8, 100]);
+    let telemetry = match parse_packet(&buf) {
+        Ok(Packet::Telemetry(telemetry)) => telemetry,
+        _ => panic!("not decoded as telemetry"),
+    };
+    assert_eq!(telemetry.car_telemetry_data.len(), 20);
+    let car = &telemetry.car_telemetry_data[1];
+    assert_eq!((car.throttle_pos, car.steering_pos, car.brake_pos), (0.5, -0.25, 1.0));
+    assert_eq!(telemetry.mfd_panel_index, 255);
+}
+
+#[test]
+fn car_status_2018_has_one_compound() {
+    let mut buf = datagram(2018, 7, f1_2018::STATUS_SIZE);
+    buf[f1_2018::HEADER_SIZE + 23] = 4;
+    let status = match parse_packet(&buf) {
+        Ok(Packet::CarStatus(status)) => status,
+        _ => panic!("not decoded as car status"),
+    };
+    let car = &status.car_status_data[0];
+    assert_eq!((car.actual_tyre_compound, car.tyre_visual_compound), (TyreCompound::Medium, VisualTyreCompound::Medium));
+    assert_eq!(car.fuel_remaining_laps, 0.0);
+}
+
+#[test]
+fn participants_2018_have_telemetry_public() {
+    let buf = datagram(2018, 4, f1_2018::PARTICIPANTS_SIZE);
+    let participants = match parse_packet(&buf) {
+        Ok(Packet::Participants(participants)) => participants,
+        _ => panic!("not decoded as participants"),
+    };
+    assert!(participants.participant_data.iter().all(|car| car.priv_telemetry == 1));
+}
+
+#[test]
+fn laps_2020_have_22_cars_and_sectors_in_seconds() {
+    let mut buf = datagram(2020, 2, f1_2020::LAP_SIZE);
+    let last_car = f1_2020::HEADER_SIZE + 21 * f1_2020::CAR_LAP_SIZE;
+    put(&mut buf, last_car + 8, &28_250u16.to_le_bytes());
+    buf[last_car + 44] = 22;
+    let lap = match parse_packet(&buf) {
+        Ok(Packet::Lap(lap)) => lap,
+        _ => panic!("not decoded as lap"),
+    };
+    assert_eq!(lap.lap_data.len(), 22);
+    assert_eq!(lap.lap_data[21].best_sec_1, 28.25);
+    assert_eq!(lap.lap_data[21].position, 22);
+}
+
+#[test]
+fn session_2020_keeps_forecasts() {
+    let mut buf = datagram(2020, 1, f1_2020::SESSION_SIZE);
+    let forecasts = f1_2020::HEADER_SIZE + 126;
+    buf[forecasts] = 2;
+    put(&mut buf, forecasts + 1 + f1_2020::WEATHER_FORECAST_SAMPLE_SIZE, &[10, 15, 3, 28, (-1i8) as u8]);
+    let session = match parse_packet(&buf) {
+        Ok(Packet::Session(session)) => session,
+        _ => panic!("not decoded as session"),
+    };
+    assert_eq!(session.weather_forecast_samples.len(), 2);
+    let sample = session.weather_forecast_samples[1];
+    assert_eq!((sample.session_type, sample.time_offset, sample.weather), (SessionType::Race, 15, Weather::LightRain));
+    assert_eq!((sample.track_temp, sample.air_temp), (28, -1));
+}
+
+#[test]
+fn setups_2020_average_tyre_pressures_per_axle() {
+    let mut buf = datagram(2020, 5, f1_2020::CARSETUPS_SIZE);
+    let pressures = f1_2020::HEADER_SIZE + 28;
+    for (wheel, pressure) in [21.0f32, 22.0, 23.0, 24.0].iter().enumerate() {
+        put(&mut buf, pressures + wheel * 4, &pressure.to_le_bytes());
+    }
+    let setups = match parse_packet(&buf) {
+        Ok(Packet::CarSetups(setups)) => setups,
+        _ => panic!("not decoded as setups"),
+    };
+    // Sent rear left, rear right, front left, front right
+    assert_eq!(setups.car_setups[0].front_tyre_pressure, 23.5);
+    assert_eq!(setups.car_setups[0].rear_tyre_pressure, 21.5);
+}
+
+#[test]
+fn telemetry_2020_widens_tyre_temps_and_keeps_extras() {
+    let mut buf = datagram(2020, 6, f1_2020::TELEMETRY_SIZE);
+    put(&mut buf, f1_2020::HEADER_SIZE + 28, &[90, 91, 92, 250]);
+    let extras = f1_2020::HEADER_SIZE + 22 * f1_2020::CAR_TELEMETRY_SIZE;
+    put(&mut buf, extras + 4, &[2, 255, 7]);
+    let telemetry = match parse_packet(&buf) {
+        Ok(Packet::Telemetry(telemetry)) => telemetry,
+        _ => panic!("not decoded as telemetry"),
+    };
+    assert_eq!(telemetry.car_telemetry_data[0].tyre_surface_temps, Wheels::new(90, 91, 92, 250));
+    assert_eq!((telemetry.mfd_panel_index, telemetry.mfd_panel_index_secondary, telemetry.suggested_gear), (2, 255, 7));
+}
+
+#[test]
+fn packets_only_2020_sends() {
+    let mut buf = datagram(2020, 8, f1_2020::FINAL_CLASSIFICATION_SIZE);
+    buf[f1_2020::HEADER_SIZE] = 20;
+    match parse_packet(&buf) {
+        Ok(Packet::FinalClassification(classification)) => {
+            assert_eq!(classification.num_cars, 20);
+            assert_eq!(classification.classification_data.len(), 22);
+        },
+        _ => panic!("not decoded as final classification"),
+    }
+    let buf = datagram(2020, 9, f1_2020::LOBBY_INFO_SIZE);
+    assert_eq!(parse_packet(&buf).unwrap().packet_type(), PacketType::LobbyInfo);
+
+    assert_eq!(parse_packet(&datagram(2018, 8, f1_2020::FINAL_CLASSIFICATION_SIZE)).err(), Some(ParseError::UnknownPacketType(8)));
+    assert_eq!(parse_packet(&datagram(2019, 9, f1_2020::LOBBY_INFO_SIZE)).err(), Some(ParseError::UnknownPacketType(9)));
+}
+
+#[test]
+fn sizes_are_the_editions_own() {
+    // 2019's lap size is neither 2018's nor 2020's
+    assert_eq!(parse_packet(&datagram(2018, 2, 843)).err(), Some(ParseError::SizeMismatch { expected: f1_2018::LAP_SIZE, got: 843 }));
+    assert_eq!(parse_packet(&datagram(2020, 2, 843)).err(), Some(ParseError::SizeMismatch { expected: f1_2020::LAP_SIZE, got: 843 }));
+}
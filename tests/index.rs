@@ -0,0 +1,186 @@
+// Checks seeks through an index land on the same record as reading through the capture, that
+// index files read back what was written, and that an index stopping short or not matching its
+// capture is handled.
+
+mod common;
+
+use std::io::Cursor;
+
+use f12019_data_logger::capture::CaptureReader;
+use f12019_data_logger::capture::CaptureWriter;
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::Lap;
+use f12019_data_logger::f1_2019_net::LapData;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::index;
+use f12019_data_logger::index::CaptureIndex;
+use f12019_data_logger::index::IndexWriter;
+use f12019_data_logger::index::ENTRY_SIZE;
+use f12019_data_logger::index::FILE_HEADER_SIZE;
+use f12019_data_logger::replay::SeekTarget;
+use f12019_data_logger::Encode;
+
+use common::header;
+
+// Two sessions of a lap packet and an event each second, the player starting a lap every 30s.
+// The second session's clock goes back 5s at 40s, as it does after a flashback.
+fn capture() -> Vec<u8> {
+    let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+    let mut frame_id = 0;
+    for (session_id, seconds) in [(1u64, 90u32), (2, 120)] {
+        for second in 0..seconds {
+            let session_time = if session_id == 2 && second >= 40 { second as f32 - 5.0 } else { second as f32 };
+            let mut lap_data = vec![LapData::default(); 20];
+            lap_data[0].lap_num = 1 + (second / 30) as u8;
+            let lap = Lap { header: header(PacketType::Lap, session_id, session_time, frame_id), lap_data };
+            writer.write(second as u64 * 1_000_000, None, &lap.to_bytes().unwrap()).unwrap();
+            let event = Event { header: header(PacketType::Event, session_id, session_time, frame_id + 1), event_type: EventType::DrsEnabled };
+            writer.write(second as u64 * 1_000_000 + 500_000, None, &event.to_bytes().unwrap()).unwrap();
+            frame_id += 2;
+        }
+        // A datagram that isn't a packet isn't indexed
+        writer.write(seconds as u64 * 1_000_000, None, b"not a packet").unwrap();
+    }
+    writer.into_inner()
+}
+
+fn reader(capture: &[u8]) -> CaptureReader<Cursor<Vec<u8>>> {
+    CaptureReader::new(Cursor::new(capture.to_vec())).unwrap()
+}
+
+// Where a seek leaves the reader, None if the capture never reaches the target
+fn seek(capture: &[u8], index: Option<&CaptureIndex>, target: SeekTarget) -> Option<u64> {
+    let mut reader = reader(capture);
+    if index::seek(&mut reader, index, target).unwrap() { Some(reader.position()) } else { None }
+}
+
+fn targets() -> Vec<SeekTarget> {
+    let mut targets = vec![SeekTarget::Session(1), SeekTarget::Session(2), SeekTarget::Session(3), SeekTarget::SessionTime(f32::MAX)];
+    targets.extend((0..=6).map(SeekTarget::Lap));
+    targets.extend([-1.0, 0.0, 0.25, 29.5, 34.0, 35.5, 89.0, 89.5, 100.0, 114.0, 115.0].iter().map(|&seconds| SeekTarget::SessionTime(seconds)));
+    targets
+}
+
+#[test]
+fn index_finds_what_reading_through_finds() {
+    let capture = capture();
+    let index = CaptureIndex::build(&mut reader(&capture)).unwrap();
+    assert_eq!(index.len(), 2 * (90 + 120));
+    assert_eq!(index.sessions().collect::<Vec<u64>>(), vec![1, 2]);
+    for target in targets() {
+        let found = seek(&capture, None, target);
+        assert_eq!(index.find(target).map(|entry| entry.position), found, "{}", target);
+        assert_eq!(seek(&capture, Some(&index), target), found, "{}", target);
+    }
+}
+
+#[test]
+fn entries_follow_the_player_lap() {
+    let capture = capture();
+    let index = CaptureIndex::build(&mut reader(&capture)).unwrap();
+    let entry = index.find(SeekTarget::Lap(2)).unwrap();
+    assert_eq!((entry.session_id, entry.session_time, entry.frame_id, entry.packet_type, entry.player_lap), (1, 30.0, 60, PacketType::Lap, 2));
+    // The event after carries the lap on, and a new session starts again from 0
+    assert_eq!(index.entries()[61].player_lap, 2);
+    let entry = index.find(SeekTarget::Session(2)).unwrap();
+    assert_eq!((entry.frame_id, entry.player_lap), (180, 1));
+    assert_eq!(index.entries().iter().filter(|entry| entry.player_lap == 0).count(), 0);
+}
+
+#[test]
+fn written_index_reads_back() {
+    let capture = capture();
+    let mut reader = reader(&capture);
+    let mut writer = IndexWriter::new(Vec::new()).unwrap();
+    loop {
+        let position = reader.position();
+        match reader.next_record().unwrap() {
+            Some(record) => writer.add(position, record.datagram),
+            None => break,
+        }
+    }
+    assert_eq!(writer.len(), 2 * (90 + 120));
+    writer.write_up_to(reader.position()).unwrap();
+    let mut file = writer.into_inner();
+    assert_eq!(file.len(), FILE_HEADER_SIZE + 2 * (90 + 120) * ENTRY_SIZE);
+
+    let built = CaptureIndex::build(&mut self::reader(&capture)).unwrap();
+    assert_eq!(CaptureIndex::read(&file[..]).unwrap().entries(), built.entries());
+
+    // A record command stopped part way through an entry
+    file.truncate(file.len() - 10);
+    assert_eq!(CaptureIndex::read(&file[..]).unwrap().entries(), &built.entries()[..built.len() - 1]);
+
+    assert!(CaptureIndex::read(&capture[..]).is_err());
+}
+
+#[test]
+fn reads_on_past_an_index_stopping_short() {
+    let capture = capture();
+    let built = CaptureIndex::build(&mut reader(&capture)).unwrap();
+    let mut partial = CaptureIndex::new();
+    for entry in &built.entries()[..100] {
+        partial.push(*entry);
+    }
+    for target in targets() {
+        assert_eq!(seek(&capture, Some(&partial), target), seek(&capture, None, target), "{}", target);
+    }
+}
+
+#[test]
+fn index_for_another_capture_is_an_error() {
+    let capture = capture();
+    let index = CaptureIndex::build(&mut reader(&capture)).unwrap();
+    let mut other = CaptureWriter::new(Vec::new()).unwrap();
+    other.write(0, None, b"a shorter capture").unwrap();
+    let mut reader = reader(&other.into_inner());
+    assert!(index::seek(&mut reader, Some(&index), SeekTarget::Lap(2)).is_err());
+}
+
+#[test]
+fn capture_and_index_carry_on_where_they_left_off() {
+    let records: Vec<(u64, Vec<u8>)> = {
+        let capture = capture();
+        let mut reader = reader(&capture);
+        let mut records = Vec::new();
+        while let Some(record) = reader.next_record().unwrap() {
+            records.push((record.received_us, record.datagram.to_vec()));
+        }
+        records
+    };
+    for compressed in [false, true] {
+        // Stopped after the lap packet starting lap 2, the event after it is still on lap 2
+        let (first, rest) = records.split_at(61);
+        let mut capture = if compressed { CaptureWriter::compressed(Vec::new()) } else { CaptureWriter::new(Vec::new()) }.unwrap();
+        let mut index = IndexWriter::new(Vec::new()).unwrap();
+        for (received_us, datagram) in first {
+            index.add(capture.position(), datagram);
+            capture.write(*received_us, None, datagram).unwrap();
+        }
+        capture.finish().unwrap();
+        index.finish().unwrap();
+
+        let tracker = index.tracker().clone();
+        let len = capture.get_ref().len() as u64;
+        let mut capture = CaptureWriter::append(capture.into_inner(), len, compressed);
+        let mut index = IndexWriter::append(index.into_inner(), tracker);
+        for (received_us, datagram) in rest {
+            index.add(capture.position(), datagram);
+            capture.write(*received_us, None, datagram).unwrap();
+        }
+        capture.finish().unwrap();
+        index.finish().unwrap();
+
+        let capture = capture.into_inner();
+        let mut read = reader(&capture);
+        assert_eq!(read.is_compressed(), compressed);
+        for (received_us, datagram) in &records {
+            let record = read.next_record().unwrap().unwrap();
+            assert_eq!((record.received_us, record.datagram), (*received_us, &datagram[..]));
+        }
+        assert!(read.next_record().unwrap().is_none());
+        let built = CaptureIndex::build(&mut reader(&capture)).unwrap();
+        assert_eq!(CaptureIndex::read(&index.into_inner()[..]).unwrap().entries(), built.entries());
+    }
+}
@@ -0,0 +1,770 @@
+// Checks the wire layouts against the offsets in the spec, then checks each parser reads every
+// field from where its layout says it is.
+//
+// The parser tests fill a packet with a byte pattern so every field holds a different value,
+// overwrite the coded fields with a valid code and compare what was decoded against the bytes
+// at the layout's offset for that field.
+
+use std::convert::TryFrom;
+
+use f12019_data_logger::encoder::Encode;
+use f12019_data_logger::f1_2019_net::*;
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::parser::f1_2018;
+use f12019_data_logger::parser::f1_2019;
+use f12019_data_logger::parser::f1_2020;
+use f12019_data_logger::parser::layout::Layout;
+use f12019_data_logger::PacketFormat;
+
+// Asserts every field of the layout is listed, in order, at the spec's offset
+fn assert_offsets(layout: &Layout, spec: &[(&str, usize)]) {
+    let fields: Vec<(usize, &str)> = layout.iter().map(|(offset, field)| (offset, field.name)).collect();
+    let spec: Vec<(usize, &str)> = spec.iter().map(|(name, offset)| (*offset, *name)).collect();
+    assert_eq!(fields, spec, "{} layout", layout.name);
+}
+
+#[test]
+fn header_offsets() {
+    assert_offsets(&f1_2018::HEADER_LAYOUT, &[
+        ("packet_format", 0), ("packet_version", 2), ("packet_type", 3), ("session_id", 4),
+        ("session_time", 12), ("frame_id", 16), ("player_car_index", 20),
+    ]);
+    assert_offsets(&f1_2019::HEADER_LAYOUT, &[
+        ("packet_format", 0), ("maj_version", 2), ("min_version", 3), ("packet_version", 4),
+        ("packet_type", 5), ("session_id", 6), ("session_time", 14), ("frame_id", 18),
+        ("player_car_index", 22),
+    ]);
+    assert_offsets(&f1_2020::HEADER_LAYOUT, &[
+        ("packet_format", 0), ("maj_version", 2), ("min_version", 3), ("packet_version", 4),
+        ("packet_type", 5), ("session_id", 6), ("session_time", 14), ("frame_id", 18),
+        ("player_car_index", 22), ("secondary_player_car_index", 23),
+    ]);
+    assert_eq!(f1_2019::PACKET_TYPE_OFFSET, 5);
+    assert_eq!(f1_2019::PLAYER_CAR_INDEX_OFFESET, 22);
+    assert_eq!(f1_2018::PLAYER_CAR_INDEX_OFFSET, 20);
+    assert_eq!(f1_2020::SECONDARY_PLAYER_CAR_INDEX_OFFSET, 23);
+}
+
+#[test]
+fn motion_offsets() {
+    assert_offsets(&f1_2019::CAR_MOTION_LAYOUT, &[
+        ("world_pos_x", 0), ("world_pos_y", 4), ("world_pos_z", 8), ("world_vel_x", 12),
+        ("world_vel_y", 16), ("world_vel_z", 20), ("world_fwd_dir_x", 24), ("world_fwd_dir_y", 26),
+        ("world_fwd_dir_z", 28), ("world_right_dir_x", 30), ("world_right_dir_y", 32),
+        ("world_right_dir_z", 34), ("lateral_g", 36), ("longitudinal_g", 40), ("vertical_g", 44),
+        ("yaw", 48), ("pitch", 52), ("roll", 56),
+    ]);
+    assert_offsets(&f1_2019::MOTION_EXTRAS_LAYOUT, &[
+        ("suspension_pos", 0), ("suspension_vel", 16), ("suspension_acc", 32), ("wheel_speed", 48),
+        ("wheel_slip", 64), ("local_vel_x", 80), ("local_vel_y", 84), ("local_vel_z", 88),
+        ("angular_vel_x", 92), ("angular_vel_y", 96), ("angular_vel_z", 100), ("angular_acc_x", 104),
+        ("angular_acc_y", 108), ("angular_acc_z", 112), ("front_wheels_angle", 116),
+    ]);
+}
+
+#[test]
+fn session_offsets() {
+    assert_offsets(&f1_2019::MARSHAL_ZONE_LAYOUT, &[("zone_start", 0), ("flag", 4)]);
+    assert_offsets(&f1_2019::SESSION_LAYOUT, &[
+        ("weather", 0), ("track_temp", 1), ("air_temp", 2), ("total_laps", 3), ("track_len", 4),
+        ("session_type", 6), ("track_id", 7), ("formual", 8), ("session_ttl", 9), ("session_len", 11),
+        ("pit_spd_lim", 13), ("is_paused", 14), ("is_spectating", 15), ("spectator_car", 16),
+        ("sli_native", 17), ("num_zones", 18), ("zones", 19), ("safety_car", 124),
+        ("is_network_game", 125),
+    ]);
+    assert_offsets(&f1_2020::WEATHER_FORECAST_SAMPLE_LAYOUT, &[
+        ("session_type", 0), ("time_offset", 1), ("weather", 2), ("track_temp", 3), ("air_temp", 4),
+    ]);
+    assert_offsets(&f1_2020::SESSION_EXTRAS_LAYOUT, &[
+        ("num_weather_forecast_samples", 0), ("weather_forecast_samples", 1),
+    ]);
+}
+
+#[test]
+fn lap_offsets() {
+    assert_offsets(&f1_2019::CAR_LAP_LAYOUT, &[
+        ("last_lap", 0), ("current_lap", 4), ("best_lap", 8), ("best_sec_1", 12), ("best_sec_2", 16),
+        ("lap_distance", 20), ("total_distance", 24), ("safety_car_delta", 28), ("position", 32),
+        ("lap_num", 33), ("pit_status", 34), ("sector", 35), ("is_lap_valid", 36), ("penalties", 37),
+        ("grid_position", 38), ("driver_status", 39), ("result_status", 40),
+    ]);
+    assert_offsets(&f1_2020::CAR_LAP_LAYOUT, &[
+        ("last_lap", 0), ("current_lap", 4), ("best_sec_1", 8), ("best_sec_2", 10), ("best_lap", 12),
+        ("best_lap_num", 16), ("best_lap_sector1_time_ms", 17), ("best_lap_sector2_time_ms", 19),
+        ("best_lap_sector3_time_ms", 21), ("best_overall_sector1_time_ms", 23),
+        ("best_overall_sector1_lap_num", 25), ("best_overall_sector2_time_ms", 26),
+        ("best_overall_sector2_lap_num", 28), ("best_overall_sector3_time_ms", 29),
+        ("best_overall_sector3_lap_num", 31), ("lap_distance", 32), ("total_distance", 36),
+        ("safety_car_delta", 40), ("position", 44), ("lap_num", 45), ("pit_status", 46), ("sector", 47),
+        ("is_lap_valid", 48), ("penalties", 49), ("grid_position", 50), ("driver_status", 51),
+        ("result_status", 52),
+    ]);
+}
+
+#[test]
+fn participant_offsets() {
+    assert_offsets(&f1_2018::PARTICIPANT_LAYOUT, &[
+        ("ai_controlled", 0), ("driver_id", 1), ("team_id", 2), ("race_number", 3), ("nationality", 4),
+        ("name", 5),
+    ]);
+    assert_offsets(&f1_2019::PARTICIPANT_LAYOUT, &[
+        ("ai_controlled", 0), ("driver_id", 1), ("team_id", 2), ("race_number", 3), ("nationality", 4),
+        ("name", 5), ("priv_telemetry", 53),
+    ]);
+    assert_offsets(&f1_2020::LOBBY_INFO_DATA_LAYOUT, &[
+        ("ai_controlled", 0), ("team_id", 1), ("nationality", 2), ("name", 3), ("ready_status", 51),
+    ]);
+}
+
+#[test]
+fn setup_offsets() {
+    let shared = [
+        ("front_wing", 0), ("rear_wing", 1), ("on_throttle", 2), ("off_throttle", 3), ("front_camber", 4),
+        ("rear_camber", 8), ("front_toe", 12), ("rear_toe", 16), ("front_suspension", 20),
+        ("rear_suspension", 21), ("front_anti_roll_bar", 22), ("rear_anti_roll_bar", 23),
+        ("front_suspension_height", 24), ("rear_suspension_height", 25), ("brake_pressure", 26),
+        ("brake_bias", 27),
+    ];
+    let mut spec_2019 = shared.to_vec();
+    spec_2019.extend_from_slice(&[("front_tyre_pressure", 28), ("rear_tyre_pressure", 32), ("ballast", 36), ("fuel_load", 37)]);
+    assert_offsets(&f1_2019::CAR_SETUP_LAYOUT, &spec_2019);
+    let mut spec_2020 = shared.to_vec();
+    spec_2020.extend_from_slice(&[("tyre_pressures", 28), ("ballast", 44), ("fuel_load", 45)]);
+    assert_offsets(&f1_2020::CAR_SETUP_LAYOUT, &spec_2020);
+}
+
+#[test]
+fn telemetry_offsets() {
+    assert_offsets(&f1_2018::CAR_TELEMETRY_LAYOUT, &[
+        ("car_speed", 0), ("throttle_pos", 2), ("steering_pos", 3), ("brake_pos", 4), ("clutch_pos", 5),
+        ("gear", 6), ("engine_rpm", 7), ("drs_active", 9), ("change_light_perc", 10), ("brake_temps", 11),
+        ("tyre_surface_temps", 19), ("tyre_inner_temps", 27), ("engine_temp", 35), ("tyre_pressures", 37),
+    ]);
+    assert_offsets(&f1_2019::CAR_TELEMETRY_LAYOUT, &[
+        ("car_speed", 0), ("throttle_pos", 2), ("steering_pos", 6), ("brake_pos", 10), ("clutch_pos", 14),
+        ("gear", 15), ("engine_rpm", 16), ("drs_active", 18), ("change_light_perc", 19), ("brake_temps", 20),
+        ("tyre_surface_temps", 28), ("tyre_inner_temps", 36), ("engine_temp", 44), ("tyre_pressures", 46),
+        ("tyre_contact_types", 62),
+    ]);
+    assert_offsets(&f1_2020::CAR_TELEMETRY_LAYOUT, &[
+        ("car_speed", 0), ("throttle_pos", 2), ("steering_pos", 6), ("brake_pos", 10), ("clutch_pos", 14),
+        ("gear", 15), ("engine_rpm", 16), ("drs_active", 18), ("change_light_perc", 19), ("brake_temps", 20),
+        ("tyre_surface_temps", 28), ("tyre_inner_temps", 32), ("engine_temp", 36), ("tyre_pressures", 38),
+        ("tyre_contact_types", 54),
+    ]);
+    assert_offsets(&f1_2019::TELEMETRY_EXTRAS_LAYOUT, &[("button_status", 0)]);
+    assert_offsets(&f1_2020::TELEMETRY_EXTRAS_LAYOUT, &[
+        ("button_status", 0), ("mfd_panel_index", 4), ("mfd_panel_index_secondary", 5), ("suggested_gear", 6),
+    ]);
+}
+
+#[test]
+fn status_offsets() {
+    assert_offsets(&f1_2018::CAR_STATUS_LAYOUT, &[
+        ("traction_control", 0), ("anti_lock_brakes", 1), ("fuel_mix", 2), ("front_brake_bias", 3),
+        ("pit_limiter_status", 4), ("fuel_in_tank", 5), ("fuel_capacity", 9), ("max_rpm", 13),
+        ("idle_rpm", 15), ("max_gears", 17), ("drs_allowed", 18), ("tyres_wear", 19), ("tyre_compound", 23),
+        ("tyres_damage", 24), ("front_left_wing_damage", 28), ("front_right_wing_damage", 29),
+        ("rear_wing_damage", 30), ("engine_damage", 31), ("gear_box_damage", 32), ("exhaust_damage", 33),
+        ("vehicle_fia_flags", 34), ("ers_store_energy", 35), ("ers_deploy_mode", 39),
+        ("ers_harvested_this_lap_mguk", 40), ("ers_harvested_this_lap_mguh", 44), ("ers_deployed_this_lap", 48),
+    ]);
+    assert_offsets(&f1_2019::CAR_STATUS_LAYOUT, &[
+        ("traction_control", 0), ("anti_lock_brakes", 1), ("fuel_mix", 2), ("front_brake_bias", 3),
+        ("pit_limiter_status", 4), ("fuel_in_tank", 5), ("fuel_capacity", 9), ("fuel_remaining_laps", 13),
+        ("max_rpm", 17), ("idle_rpm", 19), ("max_gears", 21), ("drs_allowed", 22), ("tyres_wear", 23),
+        ("actual_tyre_compound", 27), ("tyre_visual_compound", 28), ("tyres_damage", 29),
+        ("front_left_wing_damage", 33), ("front_right_wing_damage", 34), ("rear_wing_damage", 35),
+        ("engine_damage", 36), ("gear_box_damage", 37), ("vehicle_fia_flags", 38), ("ers_store_energy", 39),
+        ("ers_deploy_mode", 43), ("ers_harvested_this_lap_mguk", 44), ("ers_harvested_this_lap_mguh", 48),
+        ("ers_deployed_this_lap", 52),
+    ]);
+    assert_offsets(&f1_2020::CAR_STATUS_LAYOUT, &[
+        ("traction_control", 0), ("anti_lock_brakes", 1), ("fuel_mix", 2), ("front_brake_bias", 3),
+        ("pit_limiter_status", 4), ("fuel_in_tank", 5), ("fuel_capacity", 9), ("fuel_remaining_laps", 13),
+        ("max_rpm", 17), ("idle_rpm", 19), ("max_gears", 21), ("drs_allowed", 22),
+        ("drs_activation_distance", 23), ("tyres_wear", 25), ("actual_tyre_compound", 29),
+        ("tyre_visual_compound", 30), ("tyres_age_laps", 31), ("tyres_damage", 32),
+        ("front_left_wing_damage", 36), ("front_right_wing_damage", 37), ("rear_wing_damage", 38),
+        ("drs_fault", 39), ("engine_damage", 40), ("gear_box_damage", 41), ("vehicle_fia_flags", 42),
+        ("ers_store_energy", 43), ("ers_deploy_mode", 47), ("ers_harvested_this_lap_mguk", 48),
+        ("ers_harvested_this_lap_mguh", 52), ("ers_deployed_this_lap", 56),
+    ]);
+}
+
+#[test]
+fn final_classification_offsets() {
+    assert_offsets(&f1_2020::FINAL_CLASSIFICATION_DATA_LAYOUT, &[
+        ("position", 0), ("num_laps", 1), ("grid_position", 2), ("points", 3), ("num_pit_stops", 4),
+        ("result_status", 5), ("best_lap_time", 6), ("total_race_time", 10), ("penalties_time", 18),
+        ("num_penalties", 19), ("num_tyre_stints", 20), ("tyre_stints_actual", 21), ("tyre_stints_visual", 29),
+    ]);
+}
+
+// Sizes from the spec's "Size:" lines
+#[test]
+fn packet_sizes() {
+    let spec = [
+        (2018, [1341, 147, 841, 25, 1082, 841, 1085, 1061, 0, 0]),
+        (2019, [1343, 149, 843, 32, 1104, 843, 1347, 1143, 0, 0]),
+        (2020, [1464, 251, 1190, 35, 1213, 1102, 1307, 1344, 839, 1169]),
+    ];
+    for (year, sizes) in spec.iter() {
+        let format = PacketFormat::try_from(*year).unwrap();
+        for (id, size) in sizes.iter().enumerate() {
+            let packet_type = f12019_data_logger::parser::get_packet_type(id as u8).unwrap();
+            let expected = if *size == 0 { None } else { Some(*size) };
+            assert_eq!(format.packet_size(packet_type), expected, "{} {:?}", year, packet_type);
+        }
+    }
+    assert_eq!(f1_2018::HEADER_SIZE, 21);
+    assert_eq!(f1_2019::HEADER_SIZE, 23);
+    assert_eq!(f1_2020::HEADER_SIZE, 24);
+}
+
+//-------------Parsers against the layouts
+
+// Every byte differs from its neighbours and the pattern doesn't line up with any struct size
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+fn packet(format: PacketFormat, packet_type: u8) -> Vec<u8> {
+    let mut buf = pattern(format.packet_size(f12019_data_logger::parser::get_packet_type(packet_type).unwrap()).unwrap());
+    buf[0..2].copy_from_slice(&format.year().to_le_bytes());
+    let type_offset = match format {
+        PacketFormat::F2018 => f1_2018::PACKET_TYPE_OFFSET,
+        PacketFormat::F2019 => f1_2019::PACKET_TYPE_OFFSET,
+        PacketFormat::F2020 => f1_2020::PACKET_TYPE_OFFSET,
+    };
+    buf[type_offset] = packet_type;
+    buf
+}
+
+// Writes value into the named fields of count records of layout starting at base
+fn set_coded(buf: &mut [u8], base: usize, layout: &Layout, count: usize, fields: &[&str], value: u8) {
+    for index in 0..count {
+        for name in fields {
+            buf[base + index * layout.size() + layout.offset_of(name).unwrap()] = value;
+        }
+    }
+}
+
+// Reads the value at the named field of record index so it can be compared with the decoded one
+struct Record<'a> {
+    buf: &'a [u8],
+    start: usize,
+    layout: &'a Layout,
+}
+
+impl<'a> Record<'a> {
+    fn new(buf: &'a [u8], base: usize, layout: &'a Layout, index: usize) -> Self {
+        Record { buf, start: base + index * layout.size(), layout }
+    }
+
+    fn bytes<const N: usize>(&self, name: &str, skip: usize) -> [u8; N] {
+        let offset = self.start + self.layout.offset_of(name).unwrap_or_else(|| panic!("no field {}", name)) + skip;
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&self.buf[offset..offset + N]);
+        bytes
+    }
+
+    fn u8(&self, name: &str) -> u8 { self.bytes::<1>(name, 0)[0] }
+    fn i8(&self, name: &str) -> i8 { self.bytes::<1>(name, 0)[0] as i8 }
+    fn u16(&self, name: &str) -> u16 { u16::from_le_bytes(self.bytes(name, 0)) }
+    fn i16(&self, name: &str) -> i16 { i16::from_le_bytes(self.bytes(name, 0)) }
+    fn u32(&self, name: &str) -> u32 { u32::from_le_bytes(self.bytes(name, 0)) }
+    fn u64(&self, name: &str) -> u64 { u64::from_le_bytes(self.bytes(name, 0)) }
+    // Floats are compared as bits as the pattern makes some of them NaN
+    fn f32(&self, name: &str) -> u32 { u32::from_le_bytes(self.bytes(name, 0)) }
+    fn f64(&self, name: &str) -> u64 { u64::from_le_bytes(self.bytes(name, 0)) }
+
+    fn wheels_u8(&self, name: &str) -> [u8; 4] { self.bytes(name, 0) }
+    fn wheels_u16(&self, name: &str) -> [u16; 4] {
+        [0, 1, 2, 3].map(|wheel| u16::from_le_bytes(self.bytes(name, wheel * 2)))
+    }
+    fn wheels_f32(&self, name: &str) -> [u32; 4] {
+        [0, 1, 2, 3].map(|wheel| u32::from_le_bytes(self.bytes(name, wheel * 4)))
+    }
+}
+
+fn bits(wheels: &f12019_data_logger::wheels::Wheels<f32>) -> [u32; 4] {
+    (*wheels.as_array()).map(f32::to_bits)
+}
+
+fn check_header(header: &PacketHeader, buf: &[u8], layout: &Layout) {
+    let wire = Record::new(buf, 0, layout, 0);
+    assert_eq!(header.packet_format, wire.u16("packet_format"));
+    assert_eq!(header.packet_version, wire.u8("packet_version"));
+    assert_eq!(header.packet_type.id(), wire.u8("packet_type"));
+    assert_eq!(header.session_id, wire.u64("session_id"));
+    assert_eq!(header.session_time.to_bits(), wire.f32("session_time"));
+    assert_eq!(header.frame_id, wire.u32("frame_id"));
+    assert_eq!(header.player_car_index, wire.u8("player_car_index"));
+    if layout.field("maj_version").is_some() {
+        assert_eq!(header.maj_version, wire.u8("maj_version"));
+        assert_eq!(header.min_version, wire.u8("min_version"));
+    }
+    if layout.field("secondary_player_car_index").is_some() {
+        assert_eq!(header.secondary_player_car_index, wire.u8("secondary_player_car_index"));
+    }
+}
+
+// 2019 packets decoded from the pattern must encode back to the same bytes
+fn check_round_trip(packet: &Packet, buf: &[u8]) {
+    if packet.header().packet_format == 2019 {
+        assert_eq!(packet.to_bytes().unwrap(), buf, "{:?} round trip", packet.packet_type());
+    }
+}
+
+fn check_motion(format: PacketFormat, header_layout: &Layout, num_cars: usize) {
+    let buf = packet(format, 0);
+    let packet = parse_packet(&buf).unwrap();
+    let motion = match &packet {
+        Packet::Motion(motion) => motion,
+        _ => panic!("not motion"),
+    };
+    check_header(&motion.header, &buf, header_layout);
+    let base = header_layout.size();
+    assert_eq!(motion.car_motion_data.len(), num_cars);
+    for index in [0, num_cars - 1] {
+        let car = &motion.car_motion_data[index];
+        let wire = Record::new(&buf, base, &f1_2019::CAR_MOTION_LAYOUT, index);
+        assert_eq!(car.world_pos_x.to_bits(), wire.f32("world_pos_x"));
+        assert_eq!(car.world_pos_y.to_bits(), wire.f32("world_pos_y"));
+        assert_eq!(car.world_pos_z.to_bits(), wire.f32("world_pos_z"));
+        assert_eq!(car.world_vel_x.to_bits(), wire.f32("world_vel_x"));
+        assert_eq!(car.world_vel_y.to_bits(), wire.f32("world_vel_y"));
+        assert_eq!(car.world_vel_z.to_bits(), wire.f32("world_vel_z"));
+        assert_eq!(car.world_fwd_dir_x, wire.i16("world_fwd_dir_x"));
+        assert_eq!(car.world_fwd_dir_y, wire.i16("world_fwd_dir_y"));
+        assert_eq!(car.world_fwd_dir_z, wire.i16("world_fwd_dir_z"));
+        assert_eq!(car.world_right_dir_x, wire.i16("world_right_dir_x"));
+        assert_eq!(car.world_right_dir_y, wire.i16("world_right_dir_y"));
+        assert_eq!(car.world_right_dir_z, wire.i16("world_right_dir_z"));
+        assert_eq!(car.lateral_g.to_bits(), wire.f32("lateral_g"));
+        assert_eq!(car.longitudinal_g.to_bits(), wire.f32("longitudinal_g"));
+        assert_eq!(car.vertical_g.to_bits(), wire.f32("vertical_g"));
+        assert_eq!(car.yaw.to_bits(), wire.f32("yaw"));
+        assert_eq!(car.pitch.to_bits(), wire.f32("pitch"));
+        assert_eq!(car.roll.to_bits(), wire.f32("roll"));
+    }
+    let extras = Record::new(&buf, base + num_cars * f1_2019::CAR_MOTION_SIZE, &f1_2019::MOTION_EXTRAS_LAYOUT, 0);
+    assert_eq!(bits(&motion.suspension_pos), extras.wheels_f32("suspension_pos"));
+    assert_eq!(bits(&motion.suspension_vel), extras.wheels_f32("suspension_vel"));
+    assert_eq!(bits(&motion.suspension_acc), extras.wheels_f32("suspension_acc"));
+    assert_eq!(bits(&motion.wheel_speed), extras.wheels_f32("wheel_speed"));
+    assert_eq!(bits(&motion.wheel_slip), extras.wheels_f32("wheel_slip"));
+    assert_eq!(motion.local_vel_x.to_bits(), extras.f32("local_vel_x"));
+    assert_eq!(motion.local_vel_y.to_bits(), extras.f32("local_vel_y"));
+    assert_eq!(motion.local_vel_z.to_bits(), extras.f32("local_vel_z"));
+    assert_eq!(motion.angular_vel_x.to_bits(), extras.f32("angular_vel_x"));
+    assert_eq!(motion.angular_vel_y.to_bits(), extras.f32("angular_vel_y"));
+    assert_eq!(motion.angular_vel_z.to_bits(), extras.f32("angular_vel_z"));
+    assert_eq!(motion.angular_acc_x.to_bits(), extras.f32("angular_acc_x"));
+    assert_eq!(motion.angular_acc_y.to_bits(), extras.f32("angular_acc_y"));
+    assert_eq!(motion.angular_acc_z.to_bits(), extras.f32("angular_acc_z"));
+    assert_eq!(motion.front_wheels_angle.to_bits(), extras.f32("front_wheels_angle"));
+    check_round_trip(&packet, &buf);
+}
+
+#[test]
+fn motion_fields() {
+    check_motion(PacketFormat::F2018, &f1_2018::HEADER_LAYOUT, f1_2018::NUM_CARS);
+    check_motion(PacketFormat::F2019, &f1_2019::HEADER_LAYOUT, f1_2019::NUM_CARS);
+    check_motion(PacketFormat::F2020, &f1_2020::HEADER_LAYOUT, f1_2020::NUM_CARS);
+}
+
+fn check_session(format: PacketFormat, header_layout: &Layout) {
+    let mut buf = packet(format, 1);
+    let base = header_layout.size();
+    let session_layout = &f1_2019::SESSION_LAYOUT;
+    set_coded(&mut buf, base, session_layout, 1, &["weather", "session_type", "formual", "safety_car"], 1);
+    let zones = base + session_layout.offset_of("zones").unwrap();
+    set_coded(&mut buf, zones, &f1_2019::MARSHAL_ZONE_LAYOUT, 21, &["flag"], 3);
+    let forecasts = base + session_layout.size();
+    if format == PacketFormat::F2020 {
+        buf[forecasts] = 5;
+        let samples = forecasts + f1_2020::SESSION_EXTRAS_LAYOUT.offset_of("weather_forecast_samples").unwrap();
+        set_coded(&mut buf, samples, &f1_2020::WEATHER_FORECAST_SAMPLE_LAYOUT, 20, &["session_type", "weather"], 2);
+    }
+    let packet = parse_packet(&buf).unwrap();
+    let session = match &packet {
+        Packet::Session(session) => session,
+        _ => panic!("not session"),
+    };
+    check_header(&session.header, &buf, header_layout);
+    let wire = Record::new(&buf, base, session_layout, 0);
+    assert_eq!(session.weather, Weather::from(wire.u8("weather")));
+    assert_eq!(session.track_temp, wire.i8("track_temp"));
+    assert_eq!(session.air_temp, wire.i8("air_temp"));
+    assert_eq!(session.total_laps, wire.u8("total_laps"));
+    assert_eq!(session.track_len, wire.u16("track_len"));
+    assert_eq!(session.session_type, SessionType::from(wire.u8("session_type")));
+    assert_eq!(session.track_id, wire.i8("track_id"));
+    assert_eq!(session.formual, Formula::from(wire.u8("formual")));
+    assert_eq!(session.session_ttl, wire.u16("session_ttl"));
+    assert_eq!(session.session_len, wire.u16("session_len"));
+    assert_eq!(session.pit_spd_lim, wire.u8("pit_spd_lim"));
+    assert_eq!(session.is_paused, wire.u8("is_paused"));
+    assert_eq!(session.is_spectating, wire.u8("is_spectating"));
+    assert_eq!(session.spectator_car, wire.u8("spectator_car"));
+    assert_eq!(session.sli_native, wire.u8("sli_native"));
+    assert_eq!(session.num_zones, wire.u8("num_zones"));
+    for index in [0, 20] {
+        let zone = Record::new(&buf, zones, &f1_2019::MARSHAL_ZONE_LAYOUT, index);
+        assert_eq!(session.zones[index].zone_start.to_bits(), zone.f32("zone_start"));
+        assert_eq!(session.zones[index].flag, FiaFlag::Yellow);
+    }
+    assert_eq!(session.safety_car, SafetyCarStatus::from(wire.u8("safety_car")));
+    assert_eq!(session.is_network_game, wire.u8("is_network_game"));
+    if format == PacketFormat::F2020 {
+        let samples = forecasts + f1_2020::SESSION_EXTRAS_LAYOUT.offset_of("weather_forecast_samples").unwrap();
+        assert_eq!(session.weather_forecast_samples.len(), 5);
+        for (index, sample) in session.weather_forecast_samples.iter().enumerate() {
+            let wire = Record::new(&buf, samples, &f1_2020::WEATHER_FORECAST_SAMPLE_LAYOUT, index);
+            assert_eq!(sample.session_type, SessionType::from(wire.u8("session_type")));
+            assert_eq!(sample.time_offset, wire.u8("time_offset"));
+            assert_eq!(sample.weather, Weather::from(wire.u8("weather")));
+            assert_eq!(sample.track_temp, wire.i8("track_temp"));
+            assert_eq!(sample.air_temp, wire.i8("air_temp"));
+        }
+    } else {
+        assert!(session.weather_forecast_samples.is_empty());
+    }
+    check_round_trip(&packet, &buf);
+}
+
+#[test]
+fn session_fields() {
+    check_session(PacketFormat::F2018, &f1_2018::HEADER_LAYOUT);
+    check_session(PacketFormat::F2019, &f1_2019::HEADER_LAYOUT);
+    check_session(PacketFormat::F2020, &f1_2020::HEADER_LAYOUT);
+}
+
+fn check_lap(format: PacketFormat, header_layout: &Layout, lap_layout: &Layout, num_cars: usize) {
+    let mut buf = packet(format, 2);
+    let base = header_layout.size();
+    set_coded(&mut buf, base, lap_layout, num_cars, &["pit_status", "driver_status", "result_status"], 1);
+    let packet = parse_packet(&buf).unwrap();
+    let lap = match &packet {
+        Packet::Lap(lap) => lap,
+        _ => panic!("not lap"),
+    };
+    check_header(&lap.header, &buf, header_layout);
+    assert_eq!(lap.lap_data.len(), num_cars);
+    for index in [0, num_cars - 1] {
+        let car = &lap.lap_data[index];
+        let wire = Record::new(&buf, base, lap_layout, index);
+        assert_eq!(car.last_lap.to_bits(), wire.f32("last_lap"));
+        assert_eq!(car.current_lap.to_bits(), wire.f32("current_lap"));
+        assert_eq!(car.best_lap.to_bits(), wire.f32("best_lap"));
+        if format == PacketFormat::F2020 {
+            assert_eq!(car.best_sec_1, f32::from(wire.u16("best_sec_1")) / 1000.0);
+            assert_eq!(car.best_sec_2, f32::from(wire.u16("best_sec_2")) / 1000.0);
+        } else {
+            assert_eq!(car.best_sec_1.to_bits(), wire.f32("best_sec_1"));
+            assert_eq!(car.best_sec_2.to_bits(), wire.f32("best_sec_2"));
+        }
+        assert_eq!(car.lap_distance.to_bits(), wire.f32("lap_distance"));
+        assert_eq!(car.total_distance.to_bits(), wire.f32("total_distance"));
+        assert_eq!(car.safety_car_delta.to_bits(), wire.f32("safety_car_delta"));
+        assert_eq!(car.position, wire.u8("position"));
+        assert_eq!(car.lap_num, wire.u8("lap_num"));
+        assert_eq!(car.pit_status, PitStatus::from(wire.u8("pit_status")));
+        assert_eq!(car.sector, wire.u8("sector"));
+        assert_eq!(car.is_lap_valid, wire.u8("is_lap_valid"));
+        assert_eq!(car.penalties, wire.u8("penalties"));
+        assert_eq!(car.grid_position, wire.u8("grid_position"));
+        assert_eq!(car.driver_status, DriverStatus::from(wire.u8("driver_status")));
+        assert_eq!(car.result_status, ResultStatus::from(wire.u8("result_status")));
+    }
+    check_round_trip(&packet, &buf);
+}
+
+#[test]
+fn lap_fields() {
+    check_lap(PacketFormat::F2018, &f1_2018::HEADER_LAYOUT, &f1_2019::CAR_LAP_LAYOUT, f1_2018::NUM_CARS);
+    check_lap(PacketFormat::F2019, &f1_2019::HEADER_LAYOUT, &f1_2019::CAR_LAP_LAYOUT, f1_2019::NUM_CARS);
+    check_lap(PacketFormat::F2020, &f1_2020::HEADER_LAYOUT, &f1_2020::CAR_LAP_LAYOUT, f1_2020::NUM_CARS);
+}
+
+fn check_participants(format: PacketFormat, header_layout: &Layout, participant_layout: &Layout, num_cars: usize) {
+    let buf = packet(format, 4);
+    let base = header_layout.size();
+    let packet = parse_packet(&buf).unwrap();
+    let participants = match &packet {
+        Packet::Participants(participants) => participants,
+        _ => panic!("not participants"),
+    };
+    check_header(&participants.header, &buf, header_layout);
+    assert_eq!(participants.num_cars_active, buf[base]);
+    assert_eq!(participants.participant_data.len(), num_cars);
+    for index in [0, num_cars - 1] {
+        let car = &participants.participant_data[index];
+        let wire = Record::new(&buf, base + 1, participant_layout, index);
+        assert_eq!(car.ai_controlled, wire.u8("ai_controlled"));
+        assert_eq!(car.driver_id, wire.u8("driver_id"));
+        assert_eq!(car.team_id, wire.u8("team_id"));
+        assert_eq!(car.race_number, wire.u8("race_number"));
+        assert_eq!(car.nationality, wire.u8("nationality"));
+        assert_eq!(car.name, wire.bytes::<48>("name", 0));
+        if participant_layout.field("priv_telemetry").is_some() {
+            assert_eq!(car.priv_telemetry, wire.u8("priv_telemetry"));
+        }
+    }
+    check_round_trip(&packet, &buf);
+}
+
+#[test]
+fn participant_fields() {
+    check_participants(PacketFormat::F2018, &f1_2018::HEADER_LAYOUT, &f1_2018::PARTICIPANT_LAYOUT, f1_2018::NUM_CARS);
+    check_participants(PacketFormat::F2019, &f1_2019::HEADER_LAYOUT, &f1_2019::PARTICIPANT_LAYOUT, f1_2019::NUM_CARS);
+    check_participants(PacketFormat::F2020, &f1_2020::HEADER_LAYOUT, &f1_2019::PARTICIPANT_LAYOUT, f1_2020::NUM_CARS);
+}
+
+fn check_setups(format: PacketFormat, header_layout: &Layout, setup_layout: &Layout, num_cars: usize) {
+    let buf = packet(format, 5);
+    let base = header_layout.size();
+    let packet = parse_packet(&buf).unwrap();
+    let setups = match &packet {
+        Packet::CarSetups(setups) => setups,
+        _ => panic!("not setups"),
+    };
+    check_header(&setups.header, &buf, header_layout);
+    assert_eq!(setups.car_setups.len(), num_cars);
+    for index in [0, num_cars - 1] {
+        let car = &setups.car_setups[index];
+        let wire = Record::new(&buf, base, setup_layout, index);
+        assert_eq!(car.front_wing, wire.u8("front_wing"));
+        assert_eq!(car.rear_wing, wire.u8("rear_wing"));
+        assert_eq!(car.on_throttle, wire.u8("on_throttle"));
+        assert_eq!(car.off_throttle, wire.u8("off_throttle"));
+        assert_eq!(car.front_camber.to_bits(), wire.f32("front_camber"));
+        assert_eq!(car.rear_camber.to_bits(), wire.f32("rear_camber"));
+        assert_eq!(car.front_toe.to_bits(), wire.f32("front_toe"));
+        assert_eq!(car.rear_toe.to_bits(), wire.f32("rear_toe"));
+        assert_eq!(car.front_suspension, wire.u8("front_suspension"));
+        assert_eq!(car.rear_suspension, wire.u8("rear_suspension"));
+        assert_eq!(car.front_anti_roll_bar, wire.u8("front_anti_roll_bar"));
+        assert_eq!(car.rear_anti_roll_bar, wire.u8("rear_anti_roll_bar"));
+        assert_eq!(car.front_suspension_height, wire.u8("front_suspension_height"));
+        assert_eq!(car.rear_suspension_height, wire.u8("rear_suspension_height"));
+        assert_eq!(car.brake_pressure, wire.u8("brake_pressure"));
+        assert_eq!(car.brake_bias, wire.u8("brake_bias"));
+        if format == PacketFormat::F2020 {
+            let pressures = wire.wheels_f32("tyre_pressures").map(f32::from_bits);
+            assert_eq!(car.front_tyre_pressure.to_bits(), ((pressures[2] + pressures[3]) / 2.0).to_bits());
+            assert_eq!(car.rear_tyre_pressure.to_bits(), ((pressures[0] + pressures[1]) / 2.0).to_bits());
+        } else {
+            assert_eq!(car.front_tyre_pressure.to_bits(), wire.f32("front_tyre_pressure"));
+            assert_eq!(car.rear_tyre_pressure.to_bits(), wire.f32("rear_tyre_pressure"));
+        }
+        assert_eq!(car.ballast, wire.u8("ballast"));
+        assert_eq!(car.fuel_load.to_bits(), wire.f32("fuel_load"));
+    }
+    check_round_trip(&packet, &buf);
+}
+
+#[test]
+fn setup_fields() {
+    check_setups(PacketFormat::F2018, &f1_2018::HEADER_LAYOUT, &f1_2019::CAR_SETUP_LAYOUT, f1_2018::NUM_CARS);
+    check_setups(PacketFormat::F2019, &f1_2019::HEADER_LAYOUT, &f1_2019::CAR_SETUP_LAYOUT, f1_2019::NUM_CARS);
+    check_setups(PacketFormat::F2020, &f1_2020::HEADER_LAYOUT, &f1_2020::CAR_SETUP_LAYOUT, f1_2020::NUM_CARS);
+}
+
+fn check_telemetry(format: PacketFormat, header_layout: &Layout, telemetry_layout: &Layout, extras_layout: &Layout, num_cars: usize) {
+    let buf = packet(format, 6);
+    let base = header_layout.size();
+    let packet = parse_packet(&buf).unwrap();
+    let telemetry = match &packet {
+        Packet::Telemetry(telemetry) => telemetry,
+        _ => panic!("not telemetry"),
+    };
+    check_header(&telemetry.header, &buf, header_layout);
+    assert_eq!(telemetry.car_telemetry_data.len(), num_cars);
+    for index in [0, num_cars - 1] {
+        let car = &telemetry.car_telemetry_data[index];
+        let wire = Record::new(&buf, base, telemetry_layout, index);
+        assert_eq!(car.car_speed, wire.u16("car_speed"));
+        if format == PacketFormat::F2018 {
+            assert_eq!(car.throttle_pos, f32::from(wire.u8("throttle_pos")) / 100.0);
+            assert_eq!(car.steering_pos, f32::from(wire.i8("steering_pos")) / 100.0);
+            assert_eq!(car.brake_pos, f32::from(wire.u8("brake_pos")) / 100.0);
+        } else {
+            assert_eq!(car.throttle_pos.to_bits(), wire.f32("throttle_pos"));
+            assert_eq!(car.steering_pos.to_bits(), wire.f32("steering_pos"));
+            assert_eq!(car.brake_pos.to_bits(), wire.f32("brake_pos"));
+        }
+        assert_eq!(car.clutch_pos, wire.u8("clutch_pos"));
+        assert_eq!(car.gear, wire.i8("gear"));
+        assert_eq!(car.engine_rpm, wire.u16("engine_rpm"));
+        assert_eq!(car.drs_active, wire.u8("drs_active"));
+        assert_eq!(car.change_light_perc, wire.u8("change_light_perc"));
+        assert_eq!(*car.brake_temps.as_array(), wire.wheels_u16("brake_temps"));
+        if format == PacketFormat::F2020 {
+            assert_eq!(*car.tyre_surface_temps.as_array(), wire.wheels_u8("tyre_surface_temps").map(u16::from));
+            assert_eq!(*car.tyre_inner_temps.as_array(), wire.wheels_u8("tyre_inner_temps").map(u16::from));
+        } else {
+            assert_eq!(*car.tyre_surface_temps.as_array(), wire.wheels_u16("tyre_surface_temps"));
+            assert_eq!(*car.tyre_inner_temps.as_array(), wire.wheels_u16("tyre_inner_temps"));
+        }
+        assert_eq!(car.engine_temp, wire.u16("engine_temp"));
+        assert_eq!(bits(&car.tyre_pressures), wire.wheels_f32("tyre_pressures"));
+        if format == PacketFormat::F2018 {
+            assert_eq!(*car.tyre_contact_types.as_array(), [0; 4]);
+        } else {
+            assert_eq!(*car.tyre_contact_types.as_array(), wire.wheels_u8("tyre_contact_types"));
+        }
+    }
+    let extras = Record::new(&buf, base + num_cars * telemetry_layout.size(), extras_layout, 0);
+    assert_eq!(telemetry.button_status.bits(), extras.u32("button_status"));
+    if format == PacketFormat::F2020 {
+        assert_eq!(telemetry.mfd_panel_index, extras.u8("mfd_panel_index"));
+        assert_eq!(telemetry.mfd_panel_index_secondary, extras.u8("mfd_panel_index_secondary"));
+        assert_eq!(telemetry.suggested_gear, extras.i8("suggested_gear"));
+    }
+    check_round_trip(&packet, &buf);
+}
+
+#[test]
+fn telemetry_fields() {
+    check_telemetry(PacketFormat::F2018, &f1_2018::HEADER_LAYOUT, &f1_2018::CAR_TELEMETRY_LAYOUT, &f1_2019::TELEMETRY_EXTRAS_LAYOUT, f1_2018::NUM_CARS);
+    check_telemetry(PacketFormat::F2019, &f1_2019::HEADER_LAYOUT, &f1_2019::CAR_TELEMETRY_LAYOUT, &f1_2019::TELEMETRY_EXTRAS_LAYOUT, f1_2019::NUM_CARS);
+    check_telemetry(PacketFormat::F2020, &f1_2020::HEADER_LAYOUT, &f1_2020::CAR_TELEMETRY_LAYOUT, &f1_2020::TELEMETRY_EXTRAS_LAYOUT, f1_2020::NUM_CARS);
+}
+
+fn check_status(format: PacketFormat, header_layout: &Layout, status_layout: &Layout, num_cars: usize) {
+    let mut buf = packet(format, 7);
+    let base = header_layout.size();
+    let compounds: &[&str] = if format == PacketFormat::F2018 {
+        &["tyre_compound"]
+    } else {
+        &["actual_tyre_compound", "tyre_visual_compound"]
+    };
+    set_coded(&mut buf, base, status_layout, num_cars, &["fuel_mix", "vehicle_fia_flags", "ers_deploy_mode"], 1);
+    set_coded(&mut buf, base, status_layout, num_cars, compounds, 7);
+    let packet = parse_packet(&buf).unwrap();
+    let status = match &packet {
+        Packet::CarStatus(status) => status,
+        _ => panic!("not car status"),
+    };
+    check_header(&status.header, &buf, header_layout);
+    assert_eq!(status.car_status_data.len(), num_cars);
+    for index in [0, num_cars - 1] {
+        let car = &status.car_status_data[index];
+        let wire = Record::new(&buf, base, status_layout, index);
+        assert_eq!(car.traction_control, wire.u8("traction_control"));
+        assert_eq!(car.anti_lock_brakes, wire.u8("anti_lock_brakes"));
+        assert_eq!(car.fuel_mix, FuelMix::Standard);
+        assert_eq!(car.front_brake_bias, wire.u8("front_brake_bias"));
+        assert_eq!(car.pit_limiter_status, wire.u8("pit_limiter_status"));
+        assert_eq!(car.fuel_in_tank.to_bits(), wire.f32("fuel_in_tank"));
+        assert_eq!(car.fuel_capacity.to_bits(), wire.f32("fuel_capacity"));
+        assert_eq!(car.max_rpm, wire.u16("max_rpm"));
+        assert_eq!(car.idle_rpm, wire.u16("idle_rpm"));
+        assert_eq!(car.max_gears, wire.u8("max_gears"));
+        assert_eq!(car.drs_allowed, wire.u8("drs_allowed"));
+        assert_eq!(*car.tyres_wear.as_array(), wire.wheels_u8("tyres_wear"));
+        assert_eq!(car.actual_tyre_compound, TyreCompound::Inter);
+        assert_eq!(car.tyre_visual_compound, VisualTyreCompound::Inter);
+        assert_eq!(*car.tyres_damage.as_array(), wire.wheels_u8("tyres_damage"));
+        assert_eq!(car.front_left_wing_damage, wire.u8("front_left_wing_damage"));
+        assert_eq!(car.front_right_wing_damage, wire.u8("front_right_wing_damage"));
+        assert_eq!(car.rear_wing_damage, wire.u8("rear_wing_damage"));
+        assert_eq!(car.engine_damage, wire.u8("engine_damage"));
+        assert_eq!(car.gear_box_damage, wire.u8("gear_box_damage"));
+        assert_eq!(car.vehicle_fia_flags, FiaFlag::Green);
+        assert_eq!(car.ers_store_energy.to_bits(), wire.f32("ers_store_energy"));
+        assert_eq!(car.ers_harvested_this_lap_mguk.to_bits(), wire.f32("ers_harvested_this_lap_mguk"));
+        assert_eq!(car.ers_harvested_this_lap_mguh.to_bits(), wire.f32("ers_harvested_this_lap_mguh"));
+        assert_eq!(car.ers_deployed_this_lap.to_bits(), wire.f32("ers_deployed_this_lap"));
+        match format {
+            PacketFormat::F2018 => {
+                assert_eq!(car.fuel_remaining_laps, 0.0);
+                assert_eq!(car.ers_deploy_mode, ErsDeployMode::Low);
+            },
+            PacketFormat::F2019 => {
+                assert_eq!(car.fuel_remaining_laps.to_bits(), wire.f32("fuel_remaining_laps"));
+                assert_eq!(car.ers_deploy_mode, ErsDeployMode::Low);
+            },
+            PacketFormat::F2020 => {
+                assert_eq!(car.fuel_remaining_laps.to_bits(), wire.f32("fuel_remaining_laps"));
+                assert_eq!(car.ers_deploy_mode, ErsDeployMode::Medium);
+                assert_eq!(car.drs_activation_distance, wire.u16("drs_activation_distance"));
+                assert_eq!(car.tyres_age_laps, wire.u8("tyres_age_laps"));
+                assert_eq!(car.drs_fault, wire.u8("drs_fault"));
+            },
+        }
+    }
+    check_round_trip(&packet, &buf);
+}
+
+#[test]
+fn status_fields() {
+    check_status(PacketFormat::F2018, &f1_2018::HEADER_LAYOUT, &f1_2018::CAR_STATUS_LAYOUT, f1_2018::NUM_CARS);
+    check_status(PacketFormat::F2019, &f1_2019::HEADER_LAYOUT, &f1_2019::CAR_STATUS_LAYOUT, f1_2019::NUM_CARS);
+    check_status(PacketFormat::F2020, &f1_2020::HEADER_LAYOUT, &f1_2020::CAR_STATUS_LAYOUT, f1_2020::NUM_CARS);
+}
+
+#[test]
+fn final_classification_fields() {
+    let mut buf = packet(PacketFormat::F2020, 8);
+    let base = f1_2020::HEADER_SIZE + 1;
+    let layout = &f1_2020::FINAL_CLASSIFICATION_DATA_LAYOUT;
+    set_coded(&mut buf, base, layout, f1_2020::NUM_CARS, &["result_status"], 3);
+    let classification = match parse_packet(&buf).unwrap() {
+        Packet::FinalClassification(classification) => classification,
+        _ => panic!("not final classification"),
+    };
+    check_header(&classification.header, &buf, &f1_2020::HEADER_LAYOUT);
+    assert_eq!(classification.num_cars, buf[f1_2020::HEADER_SIZE]);
+    for index in [0, f1_2020::NUM_CARS - 1] {
+        let car = &classification.classification_data[index];
+        let wire = Record::new(&buf, base, layout, index);
+        assert_eq!(car.position, wire.u8("position"));
+        assert_eq!(car.num_laps, wire.u8("num_laps"));
+        assert_eq!(car.grid_position, wire.u8("grid_position"));
+        assert_eq!(car.points, wire.u8("points"));
+        assert_eq!(car.num_pit_stops, wire.u8("num_pit_stops"));
+        assert_eq!(car.result_status, ResultStatus::Finished);
+        assert_eq!(car.best_lap_time.to_bits(), wire.f32("best_lap_time"));
+        assert_eq!(car.total_race_time.to_bits(), wire.f64("total_race_time"));
+        assert_eq!(car.penalties_time, wire.u8("penalties_time"));
+        assert_eq!(car.num_penalties, wire.u8("num_penalties"));
+        assert_eq!(car.num_tyre_stints, wire.u8("num_tyre_stints"));
+        assert_eq!(car.tyre_stints_actual, wire.bytes::<8>("tyre_stints_actual", 0));
+        assert_eq!(car.tyre_stints_visual, wire.bytes::<8>("tyre_stints_visual", 0));
+    }
+}
+
+#[test]
+fn lobby_info_fields() {
+    let buf = packet(PacketFormat::F2020, 9);
+    let base = f1_2020::HEADER_SIZE + 1;
+    let layout = &f1_2020::LOBBY_INFO_DATA_LAYOUT;
+    let lobby = match parse_packet(&buf).unwrap() {
+        Packet::LobbyInfo(lobby) => lobby,
+        _ => panic!("not lobby info"),
+    };
+    check_header(&lobby.header, &buf, &f1_2020::HEADER_LAYOUT);
+    assert_eq!(lobby.num_players, buf[f1_2020::HEADER_SIZE]);
+    for index in [0, f1_2020::NUM_CARS - 1] {
+        let player = &lobby.lobby_players[index];
+        let wire = Record::new(&buf, base, layout, index);
+        assert_eq!(player.ai_controlled, wire.u8("ai_controlled"));
+        assert_eq!(player.team_id, wire.u8("team_id"));
+        assert_eq!(player.nationality, wire.u8("nationality"));
+        assert_eq!(player.name, wire.bytes::<48>("name", 0));
+        assert_eq!(player.ready_status, wire.u8("ready_status"));
+    }
+}
+
+#[test]
+fn truncated_packets_are_rejected() {
+    for format in [PacketFormat::F2018, PacketFormat::F2019, PacketFormat::F2020] {
+        let buf = packet(format, 6);
+        assert!(parse_packet(&buf[..buf.len() - 1]).is_err());
+        assert!(parse_packet(&buf[..format.header_size() - 1]).is_err());
+    }
+}
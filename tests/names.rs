@@ -0,0 +1,72 @@
+// Checks name fields decode up to their terminator, that a name filling the whole field loses a
+// character cut off at the end rather than failing, and that bytes that aren't UTF-8 become U+FFFD.
+
+use std::borrow::Cow;
+
+use f12019_data_logger::f1_2019_net::decode_name;
+use f12019_data_logger::f1_2019_net::ParticipantData;
+use f12019_data_logger::parser::f1_2019;
+
+// A name field holding bytes, the rest zeroed
+fn field(bytes: &[u8]) -> [u8; f1_2019::NAME_SIZE] {
+    let mut field = [0u8; f1_2019::NAME_SIZE];
+    field[..bytes.len()].copy_from_slice(bytes);
+    field
+}
+
+#[test]
+fn up_to_the_terminator() {
+    assert_eq!(decode_name(&field(b"Lewis Hamilton")), "Lewis Hamilton");
+    assert_eq!(decode_name(&field(b"")), "");
+    // Anything after the first NUL is ignored
+    assert_eq!(decode_name(&field(b"Kimi\0Raikkonen")), "Kimi");
+    assert!(matches!(decode_name(&field("Kimi Räikkönen".as_bytes())), Cow::Borrowed("Kimi Räikkönen")));
+}
+
+#[test]
+fn no_terminator() {
+    let full = [b'x'; f1_2019::NAME_SIZE];
+    assert_eq!(decode_name(&full), "x".repeat(f1_2019::NAME_SIZE));
+    assert_eq!(decode_name(b"Sainz"), "Sainz");
+}
+
+#[test]
+fn character_cut_at_the_end() {
+    // 46 bytes then the 3 byte … with its last byte cut off by the end of the field
+    let mut full = [b'a'; f1_2019::NAME_SIZE];
+    full[46..].copy_from_slice(&"\u{2026}".as_bytes()[..2]);
+    assert_eq!(decode_name(&full), "a".repeat(46));
+
+    // 47 bytes then the first byte of ä
+    full[46] = b'a';
+    full[47] = "ä".as_bytes()[0];
+    assert_eq!(decode_name(&full), "a".repeat(47));
+
+    // Cut before the terminator as well
+    assert_eq!(decode_name(&field(&"Pérez".as_bytes()[..2])), "P");
+}
+
+#[test]
+fn invalid_utf8() {
+    assert_eq!(decode_name(&field(b"Ver\xffstappen")), "Ver\u{fffd}stappen");
+    assert_eq!(decode_name(&field(b"\x80\x80")), "\u{fffd}\u{fffd}");
+    // A lone lead byte followed by more text isn't a cut character
+    assert_eq!(decode_name(&field(b"Le\xc3 Clerc")), "Le\u{fffd} Clerc");
+}
+
+#[test]
+fn truncated_names() {
+    let mut car = ParticipantData::new();
+    car.name = field("Alexander Albon-Ansley-Fortescue Smythe\u{2026}".as_bytes());
+    assert!(car.is_name_truncated());
+    assert!(car.name().ends_with("Smythe\u{2026}"));
+
+    car.name = field(b"Alexander Albon");
+    assert!(!car.is_name_truncated());
+
+    // Filling the field with the … cut off isn't spotted, there's nothing left to show it
+    let mut full = [b'a'; f1_2019::NAME_SIZE];
+    full[46..].copy_from_slice(&"\u{2026}".as_bytes()[..2]);
+    car.name = full;
+    assert!(!car.is_name_truncated());
+}
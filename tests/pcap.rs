@@ -0,0 +1,269 @@
+// Checks the pcap reader pulls the F1 datagrams out of hand built pcap and pcapng files in each
+// byte order, timestamp resolution and link type it takes, and that what the writer produces
+// reads back with valid checksums.
+
+use std::io::Cursor;
+use std::net::SocketAddr;
+
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::PacketHeader;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::pcap::is_pcap;
+use f12019_data_logger::pcap::PcapReader;
+use f12019_data_logger::pcap::PcapWriter;
+use f12019_data_logger::source::PacketSource;
+use f12019_data_logger::Encode;
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::Packet;
+
+const F1_PORT: u16 = 20777;
+
+fn event_datagram(frame_id: u32) -> Vec<u8> {
+    let header = PacketHeader { packet_format: 2019, packet_version: 1, packet_type: PacketType::Event, frame_id, ..PacketHeader::default() };
+    Event { header, event_type: EventType::SessionStarted }.to_bytes().unwrap()
+}
+
+fn udp(source_port: u16, port: u16, payload: &[u8]) -> Vec<u8> {
+    let mut udp = Vec::new();
+    udp.extend_from_slice(&source_port.to_be_bytes());
+    udp.extend_from_slice(&port.to_be_bytes());
+    udp.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+    udp.extend_from_slice(&[0, 0]);
+    udp.extend_from_slice(payload);
+    udp
+}
+
+fn ipv4(source: [u8; 4], fragment: u16, udp: &[u8]) -> Vec<u8> {
+    let mut ip = vec![0x45, 0];
+    ip.extend_from_slice(&((20 + udp.len()) as u16).to_be_bytes());
+    ip.extend_from_slice(&[0, 1]);
+    ip.extend_from_slice(&fragment.to_be_bytes());
+    ip.extend_from_slice(&[64, 17, 0, 0]);
+    ip.extend_from_slice(&source);
+    ip.extend_from_slice(&[192, 168, 1, 2]);
+    ip.extend_from_slice(udp);
+    ip
+}
+
+fn ipv6(source: [u8; 16], udp: &[u8]) -> Vec<u8> {
+    let mut ip = vec![0x60, 0, 0, 0];
+    ip.extend_from_slice(&(udp.len() as u16).to_be_bytes());
+    ip.extend_from_slice(&[17, 64]);
+    ip.extend_from_slice(&source);
+    ip.extend_from_slice(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2]);
+    ip.extend_from_slice(udp);
+    ip
+}
+
+fn ethernet(vlan: bool, ethertype: u16, ip: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0xff; 12];
+    if vlan {
+        frame.extend_from_slice(&[0x81, 0x00, 0x00, 0x05]);
+    }
+    frame.extend_from_slice(&ethertype.to_be_bytes());
+    frame.extend_from_slice(ip);
+    frame
+}
+
+fn linux_sll2(ip: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x08, 0x00];
+    frame.extend_from_slice(&[0; 18]);
+    frame.extend_from_slice(ip);
+    frame
+}
+
+fn u16_bytes(value: u16, big_endian: bool) -> [u8; 2] {
+    if big_endian { value.to_be_bytes() } else { value.to_le_bytes() }
+}
+
+fn u32_bytes(value: u32, big_endian: bool) -> [u8; 4] {
+    if big_endian { value.to_be_bytes() } else { value.to_le_bytes() }
+}
+
+// A pcap file of (seconds, fraction, frame, captured length)
+fn pcap(big_endian: bool, nanos: bool, link_type: u32, frames: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+    let mut file = Vec::new();
+    file.extend_from_slice(&u32_bytes(if nanos { 0xa1b2_3c4d } else { 0xa1b2_c3d4 }, big_endian));
+    file.extend_from_slice(&u16_bytes(2, big_endian));
+    file.extend_from_slice(&u16_bytes(4, big_endian));
+    file.extend_from_slice(&[0; 8]);
+    file.extend_from_slice(&u32_bytes(65535, big_endian));
+    file.extend_from_slice(&u32_bytes(link_type, big_endian));
+    for (seconds, fraction, frame) in frames {
+        file.extend_from_slice(&u32_bytes(*seconds, big_endian));
+        file.extend_from_slice(&u32_bytes(*fraction, big_endian));
+        file.extend_from_slice(&u32_bytes(frame.len() as u32, big_endian));
+        file.extend_from_slice(&u32_bytes(frame.len() as u32, big_endian));
+        file.extend_from_slice(frame);
+    }
+    file
+}
+
+fn pcapng_block(big_endian: bool, block_type: u32, body: &[u8]) -> Vec<u8> {
+    let padded = body.len().div_ceil(4) * 4;
+    let len = (12 + padded) as u32;
+    let mut block = Vec::new();
+    block.extend_from_slice(&u32_bytes(block_type, big_endian));
+    block.extend_from_slice(&u32_bytes(len, big_endian));
+    block.extend_from_slice(body);
+    block.resize(8 + padded, 0);
+    block.extend_from_slice(&u32_bytes(len, big_endian));
+    block
+}
+
+fn section_header(big_endian: bool) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&u32_bytes(0x1a2b_3c4d, big_endian));
+    body.extend_from_slice(&u16_bytes(1, big_endian));
+    body.extend_from_slice(&u16_bytes(0, big_endian));
+    body.extend_from_slice(&[0xff; 8]);
+    pcapng_block(big_endian, 0x0a0d_0d0a, &body)
+}
+
+fn interface(big_endian: bool, link_type: u16, tsresol: Option<u8>) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&u16_bytes(link_type, big_endian));
+    body.extend_from_slice(&[0, 0]);
+    body.extend_from_slice(&u32_bytes(65535, big_endian));
+    if let Some(tsresol) = tsresol {
+        body.extend_from_slice(&u16_bytes(9, big_endian));
+        body.extend_from_slice(&u16_bytes(1, big_endian));
+        body.extend_from_slice(&[tsresol, 0, 0, 0]);
+    }
+    body.extend_from_slice(&[0; 4]);
+    pcapng_block(big_endian, 1, &body)
+}
+
+fn enhanced_packet(big_endian: bool, interface: u32, timestamp: u64, frame: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&u32_bytes(interface, big_endian));
+    body.extend_from_slice(&u32_bytes((timestamp >> 32) as u32, big_endian));
+    body.extend_from_slice(&u32_bytes(timestamp as u32, big_endian));
+    body.extend_from_slice(&u32_bytes(frame.len() as u32, big_endian));
+    body.extend_from_slice(&u32_bytes(frame.len() as u32, big_endian));
+    body.extend_from_slice(frame);
+    pcapng_block(big_endian, 6, &body)
+}
+
+// Receive time, source and datagram of everything the reader finds
+fn read_all(file: Vec<u8>) -> Vec<(u64, Option<SocketAddr>, Vec<u8>)> {
+    let mut reader = PcapReader::new(Cursor::new(file), F1_PORT).unwrap();
+    let mut read = Vec::new();
+    while let Some(record) = reader.next_record().unwrap() {
+        read.push((record.received_us, record.source, record.datagram.to_vec()));
+    }
+    read
+}
+
+#[test]
+fn reads_pcap_ethernet_in_microseconds() {
+    let frames = vec![
+        (100, 250, ethernet(false, 0x0800, &ipv4([192, 168, 1, 10], 0, &udp(50000, F1_PORT, &event_datagram(1))))),
+        (100, 500, ethernet(false, 0x0800, &ipv4([192, 168, 1, 10], 0, &udp(50001, 53, b"not f1")))),
+        (101, 0, ethernet(true, 0x0800, &ipv4([192, 168, 1, 11], 0, &udp(50002, F1_PORT, &event_datagram(2))))),
+        (102, 0, ethernet(false, 0x0806, &[0; 28])),
+    ];
+    let read = read_all(pcap(false, false, 1, &frames));
+    assert_eq!(read, vec![
+        (100_000_250, Some("192.168.1.10:50000".parse().unwrap()), event_datagram(1)),
+        (101_000_000, Some("192.168.1.11:50002".parse().unwrap()), event_datagram(2)),
+    ]);
+    match parse_packet(&read[1].2).unwrap() {
+        Packet::Event(event) => assert_eq!(event.header.frame_id, 2),
+        packet => panic!("expected an event, got {:?}", packet.packet_type()),
+    }
+}
+
+#[test]
+fn reads_big_endian_nanosecond_pcap_of_bare_ipv6() {
+    let mut source = [0u8; 16];
+    source[0] = 0xfe;
+    source[1] = 0x80;
+    source[15] = 1;
+    let frames = vec![(5, 123_456_789, ipv6(source, &udp(40000, F1_PORT, &event_datagram(3))))];
+    let read = read_all(pcap(true, true, 101, &frames));
+    assert_eq!(read, vec![(5_123_456, Some("[fe80::1]:40000".parse().unwrap()), event_datagram(3))]);
+}
+
+#[test]
+fn reads_pcapng_in_either_byte_order() {
+    for big_endian in [false, true] {
+        let mut file = section_header(big_endian);
+        file.extend(interface(big_endian, 1, Some(9)));        // Ethernet, nanoseconds
+        file.extend(interface(big_endian, 276, None));         // Linux cooked v2, microseconds
+        file.extend(pcapng_block(big_endian, 4, &[0; 8]));     // Name resolution, skipped
+        file.extend(enhanced_packet(big_endian, 0, 7_000_000_000, &ethernet(false, 0x0800, &ipv4([10, 0, 0, 1], 0, &udp(1, F1_PORT, &event_datagram(1))))));
+        file.extend(enhanced_packet(big_endian, 1, 8_000_001, &linux_sll2(&ipv4([10, 0, 0, 2], 0, &udp(2, F1_PORT, &event_datagram(2))))));
+
+        // A second section starts over with its own interfaces
+        file.extend(section_header(!big_endian));
+        file.extend(interface(!big_endian, 101, Some(0x80 | 10)));     // Raw IP, 1/1024ths
+        file.extend(enhanced_packet(!big_endian, 0, 1024 * 9 + 512, &ipv4([10, 0, 0, 3], 0, &udp(3, F1_PORT, &event_datagram(3)))));
+
+        let read = read_all(file);
+        assert_eq!(read, vec![
+            (7_000_000, Some("10.0.0.1:1".parse().unwrap()), event_datagram(1)),
+            (8_000_001, Some("10.0.0.2:2".parse().unwrap()), event_datagram(2)),
+            (9_500_000, Some("10.0.0.3:3".parse().unwrap()), event_datagram(3)),
+        ]);
+    }
+}
+
+#[test]
+fn skips_fragments_and_truncated_datagrams() {
+    let whole = udp(1, F1_PORT, &event_datagram(1));
+    let mut truncated = ethernet(false, 0x0800, &ipv4([10, 0, 0, 1], 0, &whole));
+    truncated.truncate(40);
+    let frames = vec![
+        (1, 0, ethernet(false, 0x0800, &ipv4([10, 0, 0, 1], 0x2000, &whole))),     // More fragments
+        (2, 0, ethernet(false, 0x0800, &ipv4([10, 0, 0, 1], 0x0010, &whole))),     // A later fragment
+        (3, 0, truncated),
+        (4, 0, ethernet(false, 0x0800, &ipv4([10, 0, 0, 1], 0x4000, &whole))),     // Don't fragment is fine
+    ];
+    let read = read_all(pcap(false, false, 1, &frames));
+    assert_eq!(read.len(), 1);
+    assert_eq!(read[0].0, 4_000_000);
+}
+
+#[test]
+fn written_pcap_reads_back() {
+    let destination: SocketAddr = "127.0.0.1:20777".parse().unwrap();
+    let sources: [Option<SocketAddr>; 3] = [Some("192.168.1.10:50000".parse().unwrap()), Some("[fe80::1]:50001".parse().unwrap()), None];
+    let mut writer = PcapWriter::new(Vec::new(), destination).unwrap();
+    for (index, source) in sources.iter().enumerate() {
+        writer.write(1_600_000_000_000_000 + index as u64, *source, &event_datagram(index as u32)).unwrap();
+    }
+    let file = writer.into_inner();
+    assert!(is_pcap(&file));
+
+    let mut reader = PcapReader::new(Cursor::new(file.clone()), F1_PORT).unwrap();
+    for (index, source) in sources.iter().enumerate() {
+        let (received_us, datagram) = reader.next_datagram().unwrap().unwrap();
+        assert_eq!(received_us, 1_600_000_000_000_000 + index as u64);
+        assert_eq!(datagram, &event_datagram(index as u32)[..]);
+        let expected = source.unwrap_or_else(|| "0.0.0.0:0".parse().unwrap());
+        assert_eq!(reader.source_address(), Some(expected));
+    }
+    assert!(reader.next_datagram().unwrap().is_none());
+
+    // The first frame's IPv4 header and UDP checksums add up
+    let frame_len = u32::from_le_bytes([file[24 + 8], file[24 + 9], file[24 + 10], file[24 + 11]]) as usize;
+    let frame = &file[24 + 16..24 + 16 + frame_len];
+    let sum = |bytes: &[u8]| {
+        let total = bytes.chunks(2).map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32).sum::<u32>();
+        (total & 0xffff) + (total >> 16)
+    };
+    assert_eq!(sum(&frame[..20]), 0xffff);
+    let udp_len = frame_len - 20;
+    let pseudo = sum(&frame[12..20]) + 17 + udp_len as u32;
+    let total = pseudo + sum(&frame[20..20 + udp_len]);
+    assert_eq!((total & 0xffff) + (total >> 16), 0xffff);
+}
+
+#[test]
+fn rejects_other_files() {
+    assert!(!is_pcap(b"F1UDPCAP"));
+    assert!(!is_pcap(&[0xd4]));
+    assert!(PcapReader::new(Cursor::new(b"F1UDPCAP\x01\x00".to_vec()), F1_PORT).is_err());
+}
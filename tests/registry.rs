@@ -0,0 +1,75 @@
+// Checks ids from the spec appendices look up the entry with that id, and that ids the appendix
+// doesn't list, gaps and out of range ones alike, come back as None.
+
+use f12019_data_logger::f1_2019_net::ParticipantData;
+use f12019_data_logger::registry::Driver;
+use f12019_data_logger::registry::Nationality;
+use f12019_data_logger::registry::SurfaceType;
+use f12019_data_logger::registry::Team;
+use f12019_data_logger::registry::Track;
+
+#[test]
+fn known_ids() {
+    assert_eq!(Team::from_id(0).unwrap().name, "Mercedes");
+    assert_eq!(Team::from_id(65).unwrap().name, "Ferrari 2010");
+    assert_eq!(Driver::from_id(0).unwrap().name, "Carlos Sainz");
+    assert_eq!(Driver::from_id(89).unwrap().name, "Ralph Boschung");
+    assert_eq!(Nationality::from_id(1).unwrap().name, "American");
+    assert_eq!(Nationality::from_id(86).unwrap().name, "Welsh");
+    assert_eq!(SurfaceType::from_id(0).unwrap().name, "Tarmac");
+    assert_eq!(SurfaceType::from_id(11).unwrap().name, "Ridged");
+    assert_eq!(Track::from_id(0).unwrap().name, "Melbourne");
+    assert_eq!(Track::from_id(7).unwrap().to_string(), "Silverstone");
+}
+
+#[test]
+fn hanoi_and_zandvoort() {
+    let hanoi = Track::from_id(25).unwrap();
+    assert_eq!((hanoi.name, hanoi.country, hanoi.length_m), ("Hanoi", "Vietnam", Some(5607)));
+    let zandvoort = Track::from_id(26).unwrap();
+    assert_eq!((zandvoort.name, zandvoort.country, zandvoort.length_m), ("Zandvoort", "Netherlands", Some(4259)));
+    assert_eq!(Track::from_id(24).unwrap().length_m, None);
+}
+
+#[test]
+fn ids_not_in_the_appendix() {
+    // Past the end of each table
+    assert_eq!(Team::from_id(66), None);
+    assert_eq!(Driver::from_id(90), None);
+    assert_eq!(Nationality::from_id(87), None);
+    assert_eq!(SurfaceType::from_id(12), None);
+    assert_eq!(Track::from_id(27), None);
+    // Gaps, nationality 0 and the -1 sent when the track isn't known
+    assert_eq!(Team::from_id(20), None);
+    assert_eq!(Driver::from_id(3), None);
+    assert_eq!(Nationality::from_id(0), None);
+    assert_eq!(Track::from_id(-1), None);
+    assert_eq!(Track::from_id(i8::MIN), None);
+    // Human drivers online
+    assert_eq!(Driver::from_id(255), None);
+    assert_eq!(Team::from_id(255), None);
+}
+
+#[test]
+fn lookups_find_their_own_id() {
+    for id in 0..=u8::MAX {
+        assert!(Team::from_id(id).is_none_or(|team| team.id == id));
+        assert!(Driver::from_id(id).is_none_or(|driver| driver.id == id));
+        assert!(Nationality::from_id(id).is_none_or(|nationality| nationality.id == id));
+        assert!(SurfaceType::from_id(id).is_none_or(|surface| surface.id == id));
+    }
+    for id in i8::MIN..=i8::MAX {
+        assert!(Track::from_id(id).is_none_or(|track| track.id == id));
+    }
+}
+
+#[test]
+fn participants_look_up_their_ids() {
+    let mut car = ParticipantData::new();
+    car.team_id = 1;
+    car.driver_id = 255;
+    car.nationality = 86;
+    assert_eq!(car.team().unwrap().name, "Ferrari");
+    assert_eq!(car.driver(), None);
+    assert_eq!(car.nationality().unwrap().name, "Welsh");
+}
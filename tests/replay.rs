@@ -0,0 +1,170 @@
+// Checks replay timing follows the capture scaled by the speed, and that filtering, looping,
+// seeking and pausing pick the records they should.
+
+mod common;
+
+use std::io::Cursor;
+use std::thread;
+use std::time::Duration;
+
+use f12019_data_logger::capture::CaptureReader;
+use f12019_data_logger::capture::CaptureWriter;
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::Lap;
+use f12019_data_logger::f1_2019_net::LapData;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::index::CaptureIndex;
+use f12019_data_logger::parser::parse_header;
+use f12019_data_logger::replay::Replayer;
+use f12019_data_logger::replay::SeekTarget;
+use f12019_data_logger::Encode;
+
+use common::header;
+
+// A lap packet then an event each second, the player starting lap 2 at 30s and lap 3 at 60s
+fn capture() -> Vec<u8> {
+    let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+    for second in 0..90u32 {
+        let session_time = second as f32;
+        let received_us = 1_000_000_000 + second as u64 * 1_000_000;
+        let player = LapData { lap_num: 1 + (second / 30) as u8, ..LapData::default() };
+        let mut lap_data = vec![LapData::default(); 20];
+        lap_data[0] = player;
+        let lap = Lap { header: header(PacketType::Lap, 1, session_time, second * 2), lap_data };
+        writer.write(received_us, None, &lap.to_bytes().unwrap()).unwrap();
+        let event = Event { header: header(PacketType::Event, 1, session_time, second * 2 + 1), event_type: EventType::DrsEnabled };
+        writer.write(received_us + 500_000, None, &event.to_bytes().unwrap()).unwrap();
+    }
+    writer.into_inner()
+}
+
+fn replayer() -> Replayer<Cursor<Vec<u8>>> {
+    Replayer::new(CaptureReader::new(Cursor::new(capture())).unwrap())
+}
+
+fn frame(replayer: &Replayer<Cursor<Vec<u8>>>) -> u32 {
+    parse_header(replayer.datagram()).unwrap().frame_id
+}
+
+#[test]
+fn timing_scales_with_speed() {
+    let mut replayer = replayer();
+    assert!(replayer.advance().unwrap());
+    let first = replayer.due();
+    assert!(replayer.advance().unwrap());
+    assert_eq!(replayer.due() - first, Duration::from_millis(500));
+
+    replayer.set_speed(4.0);
+    let second = replayer.due();
+    assert!(replayer.advance().unwrap());
+    assert_eq!(replayer.due() - second, Duration::from_millis(125));
+}
+
+#[test]
+fn filters_and_ends() {
+    let mut replayer = replayer();
+    replayer.set_packets(Some(vec![PacketType::Event]));
+    let mut frames = Vec::new();
+    while replayer.advance().unwrap() {
+        frames.push(frame(&replayer));
+    }
+    assert_eq!(frames, (0..90).map(|second| second * 2 + 1).collect::<Vec<u32>>());
+}
+
+#[test]
+fn loops_back_to_the_start() {
+    let mut replayer = replayer();
+    replayer.set_loop(true);
+    for _ in 0..180 {
+        assert!(replayer.advance().unwrap());
+    }
+    assert_eq!(frame(&replayer), 179);
+    assert!(replayer.advance().unwrap());
+    assert_eq!(frame(&replayer), 0);
+}
+
+#[test]
+fn ends_and_loops_at_the_end_target() {
+    let mut replayer = replayer();
+    replayer.set_end(Some(SeekTarget::Lap(2)));
+    let mut frames = 0;
+    while replayer.advance().unwrap() {
+        frames += 1;
+    }
+    assert_eq!(frames, 60);
+
+    replayer.set_loop(true);
+    assert!(replayer.seek(SeekTarget::SessionTime(29.0)).unwrap());
+    assert_eq!(frame(&replayer), 58);
+    assert!(replayer.advance().unwrap());
+    assert!(replayer.advance().unwrap());
+    assert_eq!(frame(&replayer), 0);
+}
+
+#[test]
+fn seeks_by_session_time_and_lap() {
+    let mut replayer = replayer();
+    assert!(replayer.advance().unwrap());
+
+    assert!(replayer.seek(SeekTarget::SessionTime(45.5)).unwrap());
+    assert_eq!(frame(&replayer), 92);
+    assert!(replayer.advance().unwrap());
+    assert_eq!(frame(&replayer), 93);
+
+    // Backwards as well as forwards
+    assert!(replayer.seek(SeekTarget::Lap(2)).unwrap());
+    assert_eq!(frame(&replayer), 60);
+    assert!(replayer.seek(SeekTarget::Lap(3)).unwrap());
+    assert_eq!(frame(&replayer), 120);
+}
+
+#[test]
+fn seeks_through_an_index() {
+    let mut replayer = replayer();
+    replayer.set_index(Some(CaptureIndex::build(&mut CaptureReader::new(Cursor::new(capture())).unwrap()).unwrap()));
+    replayer.set_packets(Some(vec![PacketType::Event]));
+    assert!(replayer.seek(SeekTarget::Lap(3)).unwrap());
+    assert_eq!(frame(&replayer), 121);
+    assert!(replayer.seek(SeekTarget::SessionTime(10.5)).unwrap());
+    assert_eq!(frame(&replayer), 23);
+    assert!(replayer.seek(SeekTarget::Session(1)).unwrap());
+    assert_eq!(frame(&replayer), 1);
+    assert!(!replayer.seek(SeekTarget::Session(2)).unwrap());
+    assert_eq!(frame(&replayer), 1);
+}
+
+#[test]
+fn failed_seek_keeps_the_current_record() {
+    let mut replayer = replayer();
+    for _ in 0..10 {
+        replayer.advance().unwrap();
+    }
+    assert!(!replayer.seek(SeekTarget::Lap(4)).unwrap());
+    assert!(!replayer.seek(SeekTarget::SessionTime(1000.0)).unwrap());
+    assert_eq!(frame(&replayer), 9);
+    assert!(replayer.advance().unwrap());
+    assert_eq!(frame(&replayer), 10);
+}
+
+#[test]
+fn seek_respects_the_filter() {
+    let mut replayer = replayer();
+    replayer.set_packets(Some(vec![PacketType::Event]));
+    assert!(replayer.seek(SeekTarget::Lap(2)).unwrap());
+    assert_eq!(frame(&replayer), 61);
+}
+
+#[test]
+fn pause_holds_the_current_record_back() {
+    let mut replayer = replayer();
+    replayer.advance().unwrap();
+    replayer.advance().unwrap();
+    let due = replayer.due();
+    replayer.pause();
+    assert!(replayer.is_paused());
+    thread::sleep(Duration::from_millis(50));
+    replayer.resume();
+    assert!(!replayer.is_paused());
+    assert!(replayer.due() - due >= Duration::from_millis(50));
+}
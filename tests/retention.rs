@@ -0,0 +1,138 @@
+// Checks each retention limit evicts the right packets, hands them to the eviction handler in
+// order, and that memory use follows what's held.
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::Lap;
+use f12019_data_logger::f1_2019_net::LapData;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::retention::MemoryUsage;
+use f12019_data_logger::retention::PacketStore;
+use f12019_data_logger::retention::Retention;
+use f12019_data_logger::retention::RetentionPolicy;
+use f12019_data_logger::Packet;
+
+use common::header;
+
+fn event(session_id: u64, session_time: f32, frame_id: u32) -> Packet {
+    Packet::Event(Event { header: header(PacketType::Event, session_id, session_time, frame_id), event_type: EventType::DrsEnabled })
+}
+
+fn lap(session_id: u64, session_time: f32, frame_id: u32) -> Packet {
+    Packet::Lap(Lap { header: header(PacketType::Lap, session_id, session_time, frame_id), lap_data: vec![LapData::default(); 20] })
+}
+
+fn frames(store: &PacketStore, packet_type: PacketType) -> Vec<u32> {
+    store.packets(packet_type).map(|packet| packet.header().frame_id).collect()
+}
+
+// Frame ids of the packets handed to the eviction handler, in order
+fn record_evictions(store: &mut PacketStore) -> Rc<RefCell<Vec<u32>>> {
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let handler_evicted = Rc::clone(&evicted);
+    store.set_evict_handler(move |packet| handler_evicted.borrow_mut().push(packet.header().frame_id));
+    evicted
+}
+
+#[test]
+fn latest_keeps_the_newest_count() {
+    let mut store = PacketStore::new(RetentionPolicy::new(Retention::Latest(3)));
+    let evicted = record_evictions(&mut store);
+    for frame in 0..5 {
+        store.push(event(1, frame as f32, frame));
+    }
+    assert_eq!(frames(&store, PacketType::Event), vec![2, 3, 4]);
+    assert_eq!(*evicted.borrow(), vec![0, 1]);
+    assert_eq!(store.latest(PacketType::Event).map(|packet| packet.header().frame_id), Some(4));
+}
+
+#[test]
+fn window_keeps_recent_session_time() {
+    let mut store = PacketStore::new(RetentionPolicy::new(Retention::Window(10.0)));
+    let evicted = record_evictions(&mut store);
+    for frame in 0..30 {
+        store.push(lap(1, frame as f32, frame));
+    }
+    assert_eq!(frames(&store, PacketType::Lap), (19..30).collect::<Vec<u32>>());
+    assert_eq!(*evicted.borrow(), (0..19).collect::<Vec<u32>>());
+
+    // Nothing from an earlier session is within the window
+    store.push(lap(2, 100.0, 100));
+    assert_eq!(frames(&store, PacketType::Lap), vec![100]);
+}
+
+#[test]
+fn session_keeps_until_the_session_changes() {
+    let mut store = PacketStore::new(RetentionPolicy::new(Retention::Session));
+    let evicted = record_evictions(&mut store);
+    for frame in 0..50 {
+        store.push(lap(1, frame as f32 * 100.0, frame));
+    }
+    assert_eq!(store.len(), 50);
+    assert!(evicted.borrow().is_empty());
+
+    store.push(lap(2, 0.0, 0));
+    assert_eq!(frames(&store, PacketType::Lap), vec![0]);
+    assert_eq!(evicted.borrow().len(), 50);
+}
+
+#[test]
+fn limits_apply_per_type() {
+    let policy = RetentionPolicy::new(Retention::All)
+        .with(PacketType::Event, Retention::Latest(1))
+        .with(PacketType::Lap, Retention::Window(5.0));
+    assert_eq!(policy.get(PacketType::Motion), Retention::All);
+    assert_eq!(policy.get(PacketType::Event), Retention::Latest(1));
+
+    let mut store = PacketStore::new(policy);
+    for frame in 0..20 {
+        store.push(event(1, frame as f32, frame));
+        store.push(lap(1, frame as f32, frame));
+    }
+    assert_eq!(frames(&store, PacketType::Event), vec![19]);
+    assert_eq!(frames(&store, PacketType::Lap), (14..20).collect::<Vec<u32>>());
+    assert!(store.packets(PacketType::Motion).next().is_none());
+}
+
+#[test]
+fn drain_evicts_everything() {
+    let mut store = PacketStore::new(RetentionPolicy::new(Retention::All));
+    let evicted = record_evictions(&mut store);
+    store.push(lap(1, 0.0, 1));
+    store.push(event(1, 0.0, 2));
+    store.push(lap(1, 0.0, 3));
+    store.drain();
+    assert!(store.is_empty());
+    assert_eq!(*evicted.borrow(), vec![1, 3, 2]);
+    assert_eq!(store.total_memory_usage().packets, 0);
+}
+
+#[test]
+fn memory_usage_follows_what_is_held() {
+    let mut store = PacketStore::new(RetentionPolicy::new(Retention::Latest(4)));
+    assert_eq!(store.total_memory_usage(), MemoryUsage::default());
+
+    store.push(lap(1, 0.0, 0));
+    let one = store.memory_usage(PacketType::Lap);
+    assert_eq!(one.packets, 1);
+    assert!(one.bytes >= 20*std::mem::size_of::<LapData>());
+
+    for frame in 1..10 {
+        store.push(lap(1, frame as f32, frame));
+    }
+    let bounded = store.memory_usage(PacketType::Lap);
+    assert_eq!(bounded.packets, 4);
+    assert!(bounded.bytes > one.bytes);
+
+    // Stays put once the limit is reached
+    for frame in 10..1000 {
+        store.push(lap(1, frame as f32, frame));
+    }
+    assert_eq!(store.memory_usage(PacketType::Lap), bounded);
+    assert_eq!(store.total_memory_usage(), bounded);
+}
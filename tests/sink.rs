@@ -0,0 +1,121 @@
+// Checks the dispatcher hands every packet to every sink through the call for its type, keeps
+// going past a failing sink, and that close reaches each sink.
+
+mod common;
+
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+
+use f12019_data_logger::f1_2019_net::Event;
+use f12019_data_logger::f1_2019_net::EventType;
+use f12019_data_logger::f1_2019_net::Lap;
+use f12019_data_logger::f1_2019_net::LapData;
+use f12019_data_logger::f1_2019_net::PacketHeader;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::sink::Dispatcher;
+use f12019_data_logger::sink::PacketSink;
+use f12019_data_logger::Packet;
+
+use common::header;
+
+fn event(frame_id: u32) -> Packet {
+    Packet::Event(Event { header: header(PacketType::Event, 0, 0.0, frame_id), event_type: EventType::ChequeredFlag })
+}
+
+fn lap(frame_id: u32) -> Packet {
+    Packet::Lap(Lap { header: header(PacketType::Lap, 0, 0.0, frame_id), lap_data: vec![LapData::default(); 20] })
+}
+
+// Writes what it was called with to a log shared with the test
+struct LogSink {
+    name:   &'static str,
+    log:    Rc<RefCell<Vec<String>>>,
+    fail:   bool,
+}
+
+impl LogSink {
+    fn log(&self, entry: String) -> io::Result<()> {
+        self.log.borrow_mut().push(format!("{} {}", self.name, entry));
+        match self.fail {
+            true => Err(io::Error::other(format!("{} failed", self.name))),
+            false => Ok(()),
+        }
+    }
+}
+
+impl PacketSink for LogSink {
+    fn on_header(&mut self, header: &PacketHeader) -> io::Result<()> {
+        self.log.borrow_mut().push(format!("{} header {}", self.name, header.frame_id));
+        Ok(())
+    }
+
+    fn on_lap(&mut self, packet: &Lap) -> io::Result<()> {
+        self.log(format!("lap {} cars", packet.lap_data.len()))
+    }
+
+    fn on_event(&mut self, packet: &Event) -> io::Result<()> {
+        self.log(format!("event {:?}", packet.event_type))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.log("flush".to_string())
+    }
+}
+
+fn log_sink(name: &'static str, log: &Rc<RefCell<Vec<String>>>, fail: bool) -> LogSink {
+    LogSink { name, log: Rc::clone(log), fail }
+}
+
+#[test]
+fn every_sink_gets_every_packet() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut dispatcher = Dispatcher::new()
+        .with(log_sink("a", &log, false))
+        .with(log_sink("b", &log, false));
+    assert_eq!(dispatcher.len(), 2);
+
+    dispatcher.dispatch(&lap(1)).unwrap();
+    dispatcher.dispatch(&event(2)).unwrap();
+    assert_eq!(*log.borrow(), vec![
+        "a header 1", "a lap 20 cars", "b header 1", "b lap 20 cars",
+        "a header 2", "a event ChequeredFlag", "b header 2", "b event ChequeredFlag",
+    ]);
+}
+
+#[test]
+fn unimplemented_calls_do_nothing() {
+    struct Nothing;
+    impl PacketSink for Nothing {}
+
+    let mut dispatcher = Dispatcher::new().with(Nothing);
+    dispatcher.dispatch(&lap(1)).unwrap();
+    dispatcher.close().unwrap();
+}
+
+#[test]
+fn a_failing_sink_does_not_stop_the_rest() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut dispatcher = Dispatcher::new()
+        .with(log_sink("a", &log, true))
+        .with(log_sink("b", &log, false));
+
+    let error = dispatcher.dispatch(&event(1)).unwrap_err();
+    assert_eq!(error.to_string(), "a failed");
+    assert!(log.borrow().contains(&"b event ChequeredFlag".to_string()));
+}
+
+#[test]
+fn close_flushes_every_sink_and_nests() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let inner = Dispatcher::new().with(log_sink("inner", &log, false));
+    let mut dispatcher = Dispatcher::new()
+        .with(log_sink("outer", &log, false))
+        .with(inner);
+
+    dispatcher.dispatch(&event(1)).unwrap();
+    dispatcher.close().unwrap();
+    let log = log.borrow();
+    assert!(log.contains(&"inner event ChequeredFlag".to_string()));
+    assert_eq!(&log[log.len() - 2..], ["outer flush", "inner flush"]);
+}
@@ -0,0 +1,81 @@
+// Checks each packet source yields the datagrams it was given, in order and with their receive
+// times, so code reading from one reads the same from any of them.
+
+use std::io::Cursor;
+use std::net::UdpSocket;
+use std::time::Duration;
+
+use f12019_data_logger::capture::CaptureReader;
+use f12019_data_logger::capture::CaptureWriter;
+use f12019_data_logger::source::is_timeout;
+use f12019_data_logger::source::MemorySource;
+use f12019_data_logger::source::PacketSource;
+use f12019_data_logger::source::UdpSource;
+
+fn datagrams() -> Vec<(u64, Vec<u8>)> {
+    vec![
+        (1_000_000, vec![1, 2, 3]),
+        (1_016_667, (0..200).collect()),
+        (1_033_333, vec![]),
+        (2_000_000, vec![255; 1464]),
+        // Longer than any packet
+        (2_016_667, (0..9000).map(|byte| byte as u8).collect()),
+    ]
+}
+
+// Everything left in a source
+fn drain(source: &mut dyn PacketSource) -> Vec<(u64, Vec<u8>)> {
+    let mut read = Vec::new();
+    while let Some((received_us, datagram)) = source.next_datagram().unwrap() {
+        read.push((received_us, datagram.to_vec()));
+    }
+    read
+}
+
+#[test]
+fn memory_source_yields_in_order() {
+    let mut source = MemorySource::new(datagrams());
+    assert_eq!(drain(&mut source), datagrams());
+    assert!(source.next_datagram().unwrap().is_none());
+
+    source.rewind();
+    source.push(3_000_000, vec![9]);
+    let mut expected = datagrams();
+    expected.push((3_000_000, vec![9]));
+    assert_eq!(drain(&mut source), expected);
+}
+
+#[test]
+fn capture_source_matches_memory_source() {
+    let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+    for (received_us, datagram) in datagrams() {
+        writer.write(received_us, None, &datagram).unwrap();
+    }
+    let file = writer.into_inner();
+    let mut recorded: Box<dyn PacketSource> = Box::new(CaptureReader::new(Cursor::new(file)).unwrap());
+    let mut memory: Box<dyn PacketSource> = Box::new(MemorySource::new(datagrams()));
+    assert_eq!(drain(&mut recorded), drain(&mut memory));
+}
+
+#[test]
+fn udp_source_stamps_received_datagrams() {
+    let mut source = UdpSource::bind("127.0.0.1:0").unwrap();
+    source.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let address = source.socket().local_addr().unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for (_, datagram) in datagrams().iter().filter(|(_, datagram)| !datagram.is_empty()) {
+        sender.send_to(datagram, address).unwrap();
+    }
+
+    let mut last_us = 0;
+    for (_, sent) in datagrams().iter().filter(|(_, datagram)| !datagram.is_empty()) {
+        let (received_us, datagram) = source.next_datagram().unwrap().unwrap();
+        assert_eq!(datagram, &sent[..]);
+        assert!(received_us >= last_us);
+        last_us = received_us;
+        assert_eq!(source.source_address(), Some(sender.local_addr().unwrap()));
+    }
+
+    source.set_read_timeout(Some(Duration::from_millis(10))).unwrap();
+    assert!(is_timeout(&source.next_datagram().unwrap_err()));
+}
@@ -0,0 +1,44 @@
+// Checks the stats command's counts and that the span covers the earliest to latest receive
+// time even when datagrams arrive out of order.
+
+mod common;
+
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::stats::Stats;
+use f12019_data_logger::ParseError;
+
+use common::header;
+
+#[test]
+fn counts_by_type() {
+    let mut stats = Stats::new();
+    stats.add(0, 1343, Ok(&header(PacketType::Motion, 1, 0.0, 0)));
+    stats.add(500_000, 1343, Ok(&header(PacketType::Motion, 1, 0.0, 0)));
+    stats.add(1_000_000, 149, Ok(&header(PacketType::Session, 2, 0.0, 0)));
+    stats.add(2_000_000, 10, Err(&ParseError::UnknownPacketType(12)));
+    assert_eq!(stats.duration(), 2.0);
+
+    let mut out = Vec::new();
+    stats.write(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<Vec<&str>> = out.lines().map(|line| line.split_whitespace().collect()).collect();
+    assert_eq!(lines[1], ["motion", "2", "2686", "1.0"]);
+    assert_eq!(lines[2], ["session", "1", "149", "0.5"]);
+    assert_eq!(lines[3], ["unparsable", "1", "10", "0.5"]);
+    assert_eq!(lines[4], ["total", "4", "2845", "2.0"]);
+    assert_eq!(lines[5].join(" "), "2 session(s) over 2.0s");
+}
+
+#[test]
+fn out_of_order_receive_times() {
+    let mut stats = Stats::new();
+    assert_eq!(stats.duration(), 0.0);
+    stats.add(5_000_000, 1343, Ok(&header(PacketType::Motion, 1, 0.0, 0)));
+    assert_eq!(stats.duration(), 0.0);
+    // Earlier than the first seen, then between the two
+    stats.add(2_000_000, 1343, Ok(&header(PacketType::Motion, 1, 0.0, 0)));
+    stats.add(3_000_000, 1343, Ok(&header(PacketType::Motion, 1, 0.0, 0)));
+    assert_eq!(stats.duration(), 3.0);
+    stats.add(1_500_000, 10, Err(&ParseError::UnknownPacketType(12)));
+    assert_eq!(stats.duration(), 3.5);
+}
@@ -0,0 +1,96 @@
+// Checks vector maths and the car frame built from the motion packet's direction vectors:
+// headings for cars facing each way down the world axes, and world to car and back again.
+
+use std::f32::consts::FRAC_PI_2;
+use std::f32::consts::PI;
+
+use f12019_data_logger::f1_2019_net::CarMotion;
+use f12019_data_logger::vector::Vec3;
+use f12019_data_logger::vector::NORMALISED_SCALE;
+
+fn close(a: Vec3, b: Vec3) -> bool {
+    a.distance(b) < 1e-3
+}
+
+// A car pointing along forward with right to its right, both sent as normalised i16
+fn car(forward: Vec3, right: Vec3) -> CarMotion {
+    let normalise = |value: f32| (value * NORMALISED_SCALE).round() as i16;
+    CarMotion {
+        world_fwd_dir_x: normalise(forward.x),
+        world_fwd_dir_y: normalise(forward.y),
+        world_fwd_dir_z: normalise(forward.z),
+        world_right_dir_x: normalise(right.x),
+        world_right_dir_y: normalise(right.y),
+        world_right_dir_z: normalise(right.z),
+        ..CarMotion::default()
+    }
+}
+
+#[test]
+fn cross_product() {
+    let x = Vec3::new(1.0, 0.0, 0.0);
+    let y = Vec3::new(0.0, 1.0, 0.0);
+    let z = Vec3::new(0.0, 0.0, 1.0);
+    assert_eq!(y.cross(z), x);
+    assert_eq!(z.cross(x), y);
+    assert_eq!(x.cross(y), z);
+    assert_eq!(z.cross(y), -x);
+    assert_eq!(x.cross(x), Vec3::ZERO);
+
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::new(-4.0, 0.5, 2.0);
+    assert_eq!(a.cross(b), Vec3::new(2.5, -14.0, 8.5));
+    assert_eq!(a.cross(b).dot(a), 0.0);
+    assert_eq!(a.cross(b).dot(b), 0.0);
+}
+
+#[test]
+fn headings_down_each_axis() {
+    let north = car(Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 0.0));
+    let east = car(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+    let south = car(Vec3::new(0.0, 0.0, -1.0), Vec3::new(-1.0, 0.0, 0.0));
+    let west = car(Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+
+    // Clockwise from world +z looking down
+    assert_eq!(north.heading(), 0.0);
+    assert_eq!(east.heading(), FRAC_PI_2);
+    assert_eq!(south.heading(), PI);
+    assert_eq!(west.heading(), -FRAC_PI_2);
+
+    // Up comes out as world up whichever way the car faces
+    for car in [north, east, south, west] {
+        assert!(close(car.up(), Vec3::new(0.0, 1.0, 0.0)), "{}", car.up());
+    }
+}
+
+#[test]
+fn local_axes() {
+    let east = car(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+    // Moving down world +x is straight ahead for a car facing that way
+    assert!(close(east.to_local(Vec3::new(10.0, 0.0, 0.0)), Vec3::new(0.0, 0.0, 10.0)));
+    // and world -z is to its right
+    assert!(close(east.to_local(Vec3::new(0.0, 0.0, -3.0)), Vec3::new(3.0, 0.0, 0.0)));
+    assert!(close(east.to_world(Vec3::new(0.0, 2.0, 0.0)), Vec3::new(0.0, 2.0, 0.0)));
+
+    let moving = CarMotion { world_vel_x: 50.0, world_vel_z: 5.0, ..east };
+    assert!(close(moving.local_velocity(), Vec3::new(-5.0, 0.0, 50.0)));
+}
+
+#[test]
+fn world_to_car_and_back() {
+    let (sin, cos) = 30f32.to_radians().sin_cos();
+    let (pitch_sin, pitch_cos) = 10f32.to_radians().sin_cos();
+    let cars = [
+        car(Vec3::new(sin, 0.0, cos), Vec3::new(cos, 0.0, -sin)),
+        // Nose up a hill
+        car(Vec3::new(0.0, pitch_sin, pitch_cos), Vec3::new(1.0, 0.0, 0.0)),
+        car(Vec3::new(-cos, 0.0, sin), Vec3::new(sin, 0.0, cos)),
+    ];
+    let vectors = [Vec3::new(1.0, 2.0, 3.0), Vec3::new(-40.5, 0.25, 12.0), Vec3::ZERO];
+    for car in &cars {
+        for &world in &vectors {
+            assert!(close(car.to_world(car.to_local(world)), world), "{} through {:?}", world, car);
+            assert!((car.to_local(world).length() - world.length()).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// Checks the borrowed views read the same values the owned parsers decode, for every car in
+// every edition, that fields an edition doesn't send come back as None rather than a default,
+// and that a view refuses datagrams of the wrong type or size.
+
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::parser::f1_2018;
+use f12019_data_logger::parser::f1_2019;
+use f12019_data_logger::parser::f1_2020;
+use f12019_data_logger::parser::layout::Layout;
+use f12019_data_logger::parser::view::CarStatusDataView;
+use f12019_data_logger::parser::view::LapView;
+use f12019_data_logger::parser::view::MotionDataView;
+use f12019_data_logger::parser::view::TelemetryView;
+use f12019_data_logger::PacketFormat;
+use f12019_data_logger::ParseError;
+
+const FORMATS: [PacketFormat; 3] = [PacketFormat::F2018, PacketFormat::F2019, PacketFormat::F2020];
+
+// Byte pattern so every field holds a different value, with a valid header
+fn packet(format: PacketFormat, packet_type: PacketType) -> Vec<u8> {
+    let size = format.packet_size(packet_type).unwrap();
+    let mut buf: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+    buf[0..2].copy_from_slice(&format.year().to_le_bytes());
+    let type_offset = match format {
+        PacketFormat::F2018 => f1_2018::PACKET_TYPE_OFFSET,
+        PacketFormat::F2019 => f1_2019::PACKET_TYPE_OFFSET,
+        PacketFormat::F2020 => f1_2020::PACKET_TYPE_OFFSET,
+    };
+    buf[type_offset] = packet_type.id();
+    let player_offset = type_offset + 1 + 8 + 4 + 4;
+    buf[player_offset] = 3;
+    buf
+}
+
+// Writes value into the named fields of every car, skipping names the layout doesn't have
+fn set_coded(buf: &mut [u8], format: PacketFormat, layout: &Layout, fields: &[&str], value: u8) {
+    for index in 0..format.num_cars() {
+        for name in fields {
+            if let Some(offset) = layout.offset_of(name) {
+                buf[format.header_size() + index*layout.size() + offset] = value;
+            }
+        }
+    }
+}
+
+#[test]
+fn motion_view_matches_parser() {
+    for format in FORMATS {
+        let buf = packet(format, PacketType::Motion);
+        let view = MotionDataView::new(&buf).unwrap();
+        let owned = view.decode().unwrap();
+        assert_eq!(view.num_cars(), owned.car_motion_data.len());
+        assert_eq!(view.cars().count(), owned.car_motion_data.len());
+        for (car, expected) in view.cars().zip(owned.car_motion_data.iter()) {
+            assert_eq!(car.position(), expected.position());
+            assert_eq!(car.velocity(), expected.velocity());
+            assert_eq!(car.world_fwd_dir_x(), expected.world_fwd_dir_x);
+            assert_eq!(car.world_fwd_dir_y(), expected.world_fwd_dir_y);
+            assert_eq!(car.world_fwd_dir_z(), expected.world_fwd_dir_z);
+            assert_eq!(car.world_right_dir_x(), expected.world_right_dir_x);
+            assert_eq!(car.world_right_dir_y(), expected.world_right_dir_y);
+            assert_eq!(car.world_right_dir_z(), expected.world_right_dir_z);
+            assert_eq!(car.lateral_g().to_bits(), expected.lateral_g.to_bits());
+            assert_eq!(car.longitudinal_g().to_bits(), expected.longitudinal_g.to_bits());
+            assert_eq!(car.vertical_g().to_bits(), expected.vertical_g.to_bits());
+            assert_eq!(car.yaw().to_bits(), expected.yaw.to_bits());
+            assert_eq!(car.pitch().to_bits(), expected.pitch.to_bits());
+            assert_eq!(car.roll().to_bits(), expected.roll.to_bits());
+        }
+        let bits = |wheels: f12019_data_logger::wheels::Wheels<f32>| wheels.map(f32::to_bits);
+        assert_eq!(bits(view.suspension_pos()), bits(owned.suspension_pos));
+        assert_eq!(bits(view.suspension_vel()), bits(owned.suspension_vel));
+        assert_eq!(bits(view.suspension_acc()), bits(owned.suspension_acc));
+        assert_eq!(bits(view.wheel_speed()), bits(owned.wheel_speed));
+        assert_eq!(bits(view.wheel_slip()), bits(owned.wheel_slip));
+        assert_eq!(view.local_velocity(), owned.local_velocity());
+        assert_eq!(view.angular_velocity(), owned.angular_velocity());
+        assert_eq!(view.angular_acceleration(), owned.angular_acceleration());
+        assert_eq!(view.local_vel_z().to_bits(), owned.local_vel_z.to_bits());
+        assert_eq!(view.front_wheels_angle().to_bits(), owned.front_wheels_angle.to_bits());
+        assert_eq!(view.player_car().unwrap().position(), owned.car_motion_data[3].position());
+        assert!(view.car(view.num_cars()).is_none());
+    }
+}
+
+#[test]
+fn lap_view_matches_parser() {
+    for format in FORMATS {
+        let mut buf = packet(format, PacketType::Lap);
+        let layout = match format {
+            PacketFormat::F2020 => &f1_2020::CAR_LAP_LAYOUT,
+            _ => &f1_2019::CAR_LAP_LAYOUT,
+        };
+        set_coded(&mut buf, format, layout, &["pit_status", "driver_status", "result_status"], 1);
+        let view = LapView::new(&buf).unwrap();
+        let owned = view.decode().unwrap();
+        assert_eq!(view.cars().count(), owned.lap_data.len());
+        for (car, expected) in view.cars().zip(owned.lap_data.iter()) {
+            assert_eq!(car.last_lap().to_bits(), expected.last_lap.to_bits());
+            assert_eq!(car.current_lap().to_bits(), expected.current_lap.to_bits());
+            assert_eq!(car.best_lap().to_bits(), expected.best_lap.to_bits());
+            assert_eq!(car.best_sec_1().to_bits(), expected.best_sec_1.to_bits());
+            assert_eq!(car.best_sec_2().to_bits(), expected.best_sec_2.to_bits());
+            assert_eq!(car.lap_distance().to_bits(), expected.lap_distance.to_bits());
+            assert_eq!(car.total_distance().to_bits(), expected.total_distance.to_bits());
+            assert_eq!(car.safety_car_delta().to_bits(), expected.safety_car_delta.to_bits());
+            assert_eq!(car.position(), expected.position);
+            assert_eq!(car.lap_num(), expected.lap_num);
+            assert_eq!(car.pit_status(), expected.pit_status);
+            assert_eq!(car.sector(), expected.sector);
+            assert_eq!(car.is_lap_valid(), expected.is_lap_valid);
+            assert_eq!(car.penalties(), expected.penalties);
+            assert_eq!(car.grid_position(), expected.grid_position);
+            assert_eq!(car.driver_status(), expected.driver_status);
+            assert_eq!(car.result_status(), expected.result_status);
+        }
+    }
+}
+
+#[test]
+fn telemetry_view_matches_parser() {
+    for format in FORMATS {
+        let buf = packet(format, PacketType::Telemetry);
+        let (since_2019, is_2020) = (format != PacketFormat::F2018, format == PacketFormat::F2020);
+        let view = TelemetryView::new(&buf).unwrap();
+        let owned = view.decode().unwrap();
+        assert_eq!(view.cars().count(), owned.car_telemetry_data.len());
+        for (car, expected) in view.cars().zip(owned.car_telemetry_data.iter()) {
+            assert_eq!(car.car_speed(), expected.car_speed);
+            assert_eq!(car.throttle_pos().to_bits(), expected.throttle_pos.to_bits());
+            assert_eq!(car.steering_pos().to_bits(), expected.steering_pos.to_bits());
+            assert_eq!(car.brake_pos().to_bits(), expected.brake_pos.to_bits());
+            assert_eq!(car.clutch_pos(), expected.clutch_pos);
+            assert_eq!(car.gear(), expected.gear);
+            assert_eq!(car.engine_rpm(), expected.engine_rpm);
+            assert_eq!(car.drs_active(), expected.drs_active);
+            assert_eq!(car.change_light_perc(), expected.change_light_perc);
+            assert_eq!(car.brake_temps(), expected.brake_temps);
+            assert_eq!(car.tyre_surface_temps(), expected.tyre_surface_temps);
+            assert_eq!(car.tyre_inner_temps(), expected.tyre_inner_temps);
+            assert_eq!(car.engine_temp(), expected.engine_temp);
+            assert_eq!(car.tyre_pressures().map(f32::to_bits), expected.tyre_pressures.map(f32::to_bits));
+            assert_eq!(car.tyre_contact_types(), since_2019.then_some(expected.tyre_contact_types));
+        }
+        assert_eq!(view.button_status(), owned.button_status);
+        assert_eq!(view.mfd_panel_index(), is_2020.then_some(owned.mfd_panel_index));
+        assert_eq!(view.mfd_panel_index_secondary(), is_2020.then_some(owned.mfd_panel_index_secondary));
+        assert_eq!(view.suggested_gear(), is_2020.then_some(owned.suggested_gear));
+        assert_eq!(view.player_car().unwrap().car_speed(), owned.car_telemetry_data[3].car_speed);
+    }
+}
+
+#[test]
+fn car_status_view_matches_parser() {
+    for format in FORMATS {
+        let mut buf = packet(format, PacketType::CarStatus);
+        let layout = match format {
+            PacketFormat::F2018 => &f1_2018::CAR_STATUS_LAYOUT,
+            PacketFormat::F2019 => &f1_2019::CAR_STATUS_LAYOUT,
+            PacketFormat::F2020 => &f1_2020::CAR_STATUS_LAYOUT,
+        };
+        set_coded(&mut buf, format, layout, &["fuel_mix", "vehicle_fia_flags", "ers_deploy_mode"], 1);
+        set_coded(&mut buf, format, layout, &["tyre_compound", "actual_tyre_compound", "tyre_visual_compound"], 7);
+        let (since_2019, is_2020) = (format != PacketFormat::F2018, format == PacketFormat::F2020);
+        let view = CarStatusDataView::new(&buf).unwrap();
+        let owned = view.decode().unwrap();
+        assert_eq!(view.cars().count(), owned.car_status_data.len());
+        for (car, expected) in view.cars().zip(owned.car_status_data.iter()) {
+            assert_eq!(car.traction_control(), expected.traction_control);
+            assert_eq!(car.anti_lock_brakes(), expected.anti_lock_brakes);
+            assert_eq!(car.fuel_mix(), expected.fuel_mix);
+            assert_eq!(car.front_brake_bias(), expected.front_brake_bias);
+            assert_eq!(car.pit_limiter_status(), expected.pit_limiter_status);
+            assert_eq!(car.fuel_in_tank().to_bits(), expected.fuel_in_tank.to_bits());
+            assert_eq!(car.fuel_capacity().to_bits(), expected.fuel_capacity.to_bits());
+            assert_eq!(car.fuel_remaining_laps().map(f32::to_bits), since_2019.then_some(expected.fuel_remaining_laps.to_bits()));
+            assert_eq!(car.max_rpm(), expected.max_rpm);
+            assert_eq!(car.idle_rpm(), expected.idle_rpm);
+            assert_eq!(car.max_gears(), expected.max_gears);
+            assert_eq!(car.drs_allowed(), expected.drs_allowed);
+            assert_eq!(car.drs_activation_distance(), is_2020.then_some(expected.drs_activation_distance));
+            assert_eq!(car.tyres_wear(), expected.tyres_wear);
+            assert_eq!(car.actual_tyre_compound(), expected.actual_tyre_compound);
+            assert_eq!(car.tyre_visual_compound(), expected.tyre_visual_compound);
+            assert_eq!(car.tyres_age_laps(), is_2020.then_some(expected.tyres_age_laps));
+            assert_eq!(car.tyres_damage(), expected.tyres_damage);
+            assert_eq!(car.front_left_wing_damage(), expected.front_left_wing_damage);
+            assert_eq!(car.front_right_wing_damage(), expected.front_right_wing_damage);
+            assert_eq!(car.rear_wing_damage(), expected.rear_wing_damage);
+            assert_eq!(car.drs_fault(), is_2020.then_some(expected.drs_fault));
+            assert_eq!(car.engine_damage(), expected.engine_damage);
+            assert_eq!(car.gear_box_damage(), expected.gear_box_damage);
+            assert_eq!(car.vehicle_fia_flags(), expected.vehicle_fia_flags);
+            assert_eq!(car.ers_store_energy().to_bits(), expected.ers_store_energy.to_bits());
+            assert_eq!(car.ers_deploy_mode(), expected.ers_deploy_mode);
+            assert_eq!(car.ers_harvested_this_lap_mguk().to_bits(), expected.ers_harvested_this_lap_mguk.to_bits());
+            assert_eq!(car.ers_harvested_this_lap_mguh().to_bits(), expected.ers_harvested_this_lap_mguh.to_bits());
+            assert_eq!(car.ers_deployed_this_lap().to_bits(), expected.ers_deployed_this_lap.to_bits());
+        }
+    }
+}
+
+#[test]
+fn views_reject_other_packets() {
+    for format in FORMATS {
+        let buf = packet(format, PacketType::Motion);
+        assert_eq!(
+            TelemetryView::new(&buf).unwrap_err(),
+            ParseError::WrongPacketType { expected: PacketType::Telemetry, got: PacketType::Motion }
+        );
+        let buf = packet(format, PacketType::Telemetry);
+        assert!(matches!(TelemetryView::new(&buf[..buf.len() - 1]), Err(ParseError::SizeMismatch { .. })));
+        assert!(LapView::new(&buf[..4]).is_err());
+    }
+}
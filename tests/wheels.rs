@@ -0,0 +1,80 @@
+// Checks Wheels keeps the wire order rear left, rear right, front left, front right, so each
+// accessor and pair picks the wheel it's named for, and the min, max and means over them.
+
+use f12019_data_logger::f1_2019_net::CarTelemetry;
+use f12019_data_logger::f1_2019_net::PacketHeader;
+use f12019_data_logger::f1_2019_net::PacketType;
+use f12019_data_logger::f1_2019_net::Telemetry;
+use f12019_data_logger::parse_packet;
+use f12019_data_logger::parser::f1_2019;
+use f12019_data_logger::wheels::Wheel;
+use f12019_data_logger::wheels::Wheels;
+use f12019_data_logger::Encode;
+use f12019_data_logger::Packet;
+
+#[test]
+fn rear_left_rear_right_front_left_front_right() {
+    let wheels = Wheels::new(1, 2, 3, 4);
+    assert_eq!(wheels.rear_left(), 1);
+    assert_eq!(wheels.rear_right(), 2);
+    assert_eq!(wheels.front_left(), 3);
+    assert_eq!(wheels.front_right(), 4);
+    assert_eq!(Wheel::ALL.map(|wheel| wheels[wheel]), [1, 2, 3, 4]);
+    assert_eq!(*wheels.as_array(), [1, 2, 3, 4]);
+    assert_eq!(Wheels::from([1, 2, 3, 4]), wheels);
+    assert_eq!(wheels.into_iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+    assert_eq!(wheels.map(|value| value * 10), Wheels::new(10, 20, 30, 40));
+
+    let mut wheels = wheels;
+    wheels[Wheel::FrontLeft] = 30;
+    assert_eq!(<[i32; 4]>::from(wheels), [1, 2, 30, 4]);
+}
+
+#[test]
+fn axles_and_sides() {
+    let wheels = Wheels::new(1, 2, 3, 4);
+    // [left, right] for an axle, [rear, front] for a side
+    assert_eq!(wheels.front(), [3, 4]);
+    assert_eq!(wheels.rear(), [1, 2]);
+    assert_eq!(wheels.left(), [1, 3]);
+    assert_eq!(wheels.right(), [2, 4]);
+}
+
+#[test]
+fn min_max_and_means() {
+    let temps = Wheels::new(95u8, 90, 101, 99);
+    assert_eq!(temps.min(), 90);
+    assert_eq!(temps.max(), 101);
+    assert_eq!(temps.mean(), 96.25);
+    assert_eq!(temps.rear_mean(), 92.5);
+    assert_eq!(temps.front_mean(), 100.0);
+    assert_eq!(temps.left_mean(), 98.0);
+    assert_eq!(temps.right_mean(), 94.5);
+
+    let pressures = Wheels::new(21.5f32, 21.5, -1.0, 23.0);
+    assert_eq!(pressures.min(), -1.0);
+    assert_eq!(pressures.max(), 23.0);
+    assert_eq!(Wheels::new(7u16, 7, 7, 7).min(), 7);
+}
+
+#[test]
+fn wire_order() {
+    // Four u16s in a row, first on the wire is the rear left
+    let telemetry = Telemetry {
+        header: PacketHeader { packet_format: 2019, packet_version: 1, packet_type: PacketType::Telemetry, ..PacketHeader::default() },
+        car_telemetry_data: vec![CarTelemetry { brake_temps: Wheels::new(100, 200, 300, 400), ..CarTelemetry::default() }; 20],
+        button_status: Default::default(),
+        mfd_panel_index: 255,
+        mfd_panel_index_secondary: 255,
+        suggested_gear: 0,
+    };
+    let bytes = Packet::Telemetry(telemetry).to_bytes().unwrap();
+    let start = f1_2019::HEADER_SIZE + f1_2019::CAR_TELEMETRY_LAYOUT.offset_of("brake_temps").unwrap();
+    let sent: Vec<u16> = bytes[start..start + 8].chunks(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+    assert_eq!(sent, [100, 200, 300, 400]);
+
+    match parse_packet(&bytes).unwrap() {
+        Packet::Telemetry(telemetry) => assert_eq!(telemetry.car_telemetry_data[0].brake_temps.rear_left(), 100),
+        other => panic!("decoded a {:?}", other.packet_type()),
+    }
+}